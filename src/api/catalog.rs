@@ -0,0 +1,125 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// User distro catalogs
+//
+// A catalog is an HTTP(S) URL or a local folder serving `MirrorListResponse` JSON,
+// either bare or wrapped in the wslui `ApiResponse` envelope, or Microsoft's
+// DistributionInfo.json manifest. A folder may hold any number of *.json files.
+// All catalogs are merged into one list; a distro listed by several catalogs keeps
+// the mirrors of all of them, each labelled with its catalog. Those mirrors may serve
+// different files, so a download only moves between them when the SHA-256 or ETag
+// matches. Blocking, call from spawn_blocking.
+
+use std::path::Path;
+use tracing::{info, warn};
+use crate::api::client::{ApiResponse, WslUiClient};
+use crate::api::distribution_info::DistributionManifest;
+use crate::api::models::{DistroInfo, MirrorListResponse};
+use crate::config::CatalogSource;
+
+const CATALOG_TIMEOUT_MS: u64 = 10000;
+
+pub fn is_remote(location: &str) -> bool {
+    let lower = location.trim().to_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+fn parse(text: &str) -> Result<MirrorListResponse, String> {
+    let text = text.trim_start_matches('\u{FEFF}').trim();
+    if let Some(manifest) = DistributionManifest::parse(text) {
+        return Ok(manifest.into_mirror_list());
+    }
+    if let Ok(wrapped) = serde_json::from_str::<ApiResponse<MirrorListResponse>>(text) {
+        if wrapped.err != 0 {
+            return Err(format!("err={}, msg={}", wrapped.err, wrapped.msg));
+        }
+        return Ok(wrapped.data);
+    }
+    serde_json::from_str::<MirrorListResponse>(text).map_err(|e| e.to_string())
+}
+
+fn load_folder(dir: &Path) -> Result<MirrorListResponse, String> {
+    let mut files: Vec<_> = std::fs::read_dir(dir)
+        .map_err(|e| e.to_string())?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("json")))
+        .collect();
+    files.sort();
+    if files.is_empty() {
+        return Err(format!("No .json files in '{}'", dir.display()));
+    }
+
+    let mut response = MirrorListResponse::default();
+    for file in files {
+        let content = std::fs::read_to_string(&file).map_err(|e| format!("{}: {}", file.display(), e))?;
+        let part = parse(&content).map_err(|e| format!("{}: {}", file.display(), e))?;
+        response.distros.extend(part.distros);
+    }
+    Ok(response)
+}
+
+// Load a single catalog from a URL, a folder or a single JSON file
+pub fn load(location: &str) -> Result<MirrorListResponse, String> {
+    let location = location.trim();
+    if is_remote(location) {
+        let text = WslUiClient::new().fetch_text(location, Some(CATALOG_TIMEOUT_MS))?;
+        return parse(&text);
+    }
+    let path = Path::new(location);
+    if path.is_dir() {
+        load_folder(path)
+    } else if path.is_file() {
+        let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+        parse(&content)
+    } else {
+        Err(format!("'{}' does not exist", location))
+    }
+}
+
+// Fold `distros` into `merged`, labelling every entry and mirror with the catalog it came from
+pub fn merge(merged: &mut Vec<DistroInfo>, distros: Vec<DistroInfo>, label: &str) {
+    for mut distro in distros {
+        if distro.sources.is_empty() {
+            continue;
+        }
+        for source in distro.sources.iter_mut() {
+            source.catalog = label.to_string();
+        }
+        match merged.iter_mut().find(|d| d.name == distro.name && d.version == distro.version) {
+            Some(existing) => {
+                for source in distro.sources {
+                    if !existing.sources.iter().any(|s| s.url == source.url) {
+                        existing.sources.push(source);
+                    }
+                }
+                if !existing.catalogs.iter().any(|c| c == label) {
+                    existing.catalogs.push(label.to_string());
+                }
+            }
+            None => {
+                distro.catalogs = vec![label.to_string()];
+                merged.push(distro);
+            }
+        }
+    }
+}
+
+// Load every enabled user catalog. Failing catalogs are skipped and reported as (name, error).
+pub fn load_sources(sources: &[CatalogSource], merged: &mut Vec<DistroInfo>) -> Vec<(String, String)> {
+    let mut failures = Vec::new();
+    for source in sources.iter().filter(|s| s.enabled) {
+        match load(&source.location) {
+            Ok(response) => {
+                info!("Catalog '{}': {} distros from '{}'", source.name, response.distros.len(), source.location);
+                merge(merged, response.distros, &source.name);
+            }
+            Err(e) => {
+                warn!("Catalog '{}' ({}) could not be loaded: {}", source.name, source.location, e);
+                failures.push((source.name.clone(), e));
+            }
+        }
+    }
+    failures
+}
@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use serde::{Deserialize, Serialize};
-use ureq;
 use std::time::Duration;
 use std::thread;
 use tracing::{trace, debug, error};
+use crate::network::http;
 
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -18,27 +18,13 @@ pub struct ApiResponse<T> {
 pub struct WslUiClient {
     api1_url: String,
     api2_url: String,
-    agent: ureq::Agent,
 }
 
 impl WslUiClient {
     pub fn new() -> Self {
-        let mut builder = ureq::builder();
-        if cfg!(debug_assertions) {
-            if let Ok(tls_connector) = native_tls::TlsConnector::builder()
-                .danger_accept_invalid_certs(true)
-                .build()
-            {
-                builder = builder.tls_connector(std::sync::Arc::new(tls_connector));
-                trace!("Developer mode: HTTPS certificate verification is disabled (-k)");
-            }
-        }
-        let agent = builder.build();
-
         Self {
             api1_url: crate::app::API1_URL.to_string(),
             api2_url: crate::app::API2_URL.to_string(),
-            agent,
         }
     }
 
@@ -81,6 +67,18 @@ impl WslUiClient {
         self.request_url(method, url, body, timeout_ms)
     }
 
+    // Plain GET returning the raw body, for endpoints outside the wslui API envelope
+    pub fn fetch_text(&self, url: &str, timeout_ms: Option<u64>) -> Result<String, String> {
+        let timeout = Duration::from_millis(timeout_ms.unwrap_or(5000));
+        trace!("HTTP GET: url={}", url);
+        let resp = http::get(url)
+            .timeout(timeout)
+            .set("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36")
+            .call()
+            .map_err(|e| e.to_string())?;
+        resp.into_string().map_err(|e| e.to_string())
+    }
+
     fn request_url<T>(&self, method: &str, url: &str, body: Option<serde_json::Value>, timeout_ms: Option<u64>) -> Result<(ApiResponse<T>, Option<String>), String>
     where
         T: for<'de> Deserialize<'de> + std::fmt::Debug,
@@ -90,8 +88,8 @@ impl WslUiClient {
             trace!("WSLUI API Request: method={}, url={}, body={:?}", method, url, body);
             
             let mut req = match method.to_uppercase().as_str() {
-                "POST" => self.agent.post(url),
-                _ => self.agent.get(url),
+                "POST" => http::api_request("POST", url),
+                _ => http::api_request("GET", url),
             };
 
             req = req.timeout(timeout)
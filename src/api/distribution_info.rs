@@ -0,0 +1,151 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Microsoft's WSL distribution manifest (DistributionInfo.json)
+//
+// This is the list `wsl --install` and `wsl -l -o` read. `ModernDistributions` holds
+// .wsl images with per-architecture URLs and SHA-256, the legacy `Distributions` list
+// holds Store appx bundles. Both are converted into mirror list entries so they install
+// through the regular download and import pipeline.
+
+use std::collections::BTreeMap;
+use serde::Deserialize;
+use crate::api::models::{DistroInfo, MirrorListResponse, MirrorSource};
+
+pub const OFFICIAL_MANIFEST_URL: &str = "https://raw.githubusercontent.com/microsoft/WSL/master/distributions/DistributionInfo.json";
+
+// Mirror name shown for manifest downloads
+const MIRROR_NAME: &str = "Microsoft";
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(rename_all = "PascalCase")]
+pub struct DistributionManifest {
+    #[serde(default)]
+    pub distributions: Vec<LegacyDistribution>,
+    #[serde(default)]
+    pub modern_distributions: BTreeMap<String, Vec<ModernDistribution>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct LegacyDistribution {
+    pub name: String,
+    #[serde(default)]
+    pub amd64: bool,
+    #[serde(default)]
+    pub arm64: bool,
+    #[serde(default)]
+    pub amd64_package_url: Option<String>,
+    #[serde(default)]
+    pub arm64_package_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ModernDistribution {
+    pub name: String,
+    #[serde(default)]
+    pub amd64_url: Option<ManifestUrl>,
+    #[serde(default)]
+    pub arm64_url: Option<ManifestUrl>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ManifestUrl {
+    pub url: String,
+    #[serde(default)]
+    pub sha256: Option<String>,
+}
+
+fn is_arm64() -> bool {
+    std::env::consts::ARCH == "aarch64"
+}
+
+// "Ubuntu-24.04" -> ("Ubuntu", "24.04"), "OracleLinux_9_1" -> ("OracleLinux", "9.1").
+// Unversioned names like "kali-linux" always point at the latest release.
+fn split_name(name: &str) -> (String, String) {
+    let bytes = name.as_bytes();
+    let split = (0..bytes.len().saturating_sub(1))
+        .find(|&i| (bytes[i] == b'-' || bytes[i] == b'_') && bytes[i + 1].is_ascii_digit());
+    match split {
+        Some(i) => (name[..i].to_string(), name[i + 1..].replace('_', ".")),
+        None => (name.to_string(), "latest".to_string()),
+    }
+}
+
+fn format_of(url: &str) -> String {
+    let lower = url.to_lowercase();
+    if lower.ends_with(".appx") || lower.ends_with(".appxbundle") || lower.ends_with(".msixbundle") {
+        "appx".to_string()
+    } else if lower.ends_with(".tar.xz") {
+        "tar.xz".to_string()
+    } else if lower.ends_with(".tar.gz") {
+        "tar.gz".to_string()
+    } else {
+        "wsl".to_string()
+    }
+}
+
+fn entry(name: &str, url: String, sha256: Option<String>) -> DistroInfo {
+    let (name, version) = split_name(name);
+    DistroInfo {
+        name,
+        version,
+        sources: vec![MirrorSource {
+            format: format_of(&url),
+            url,
+            mirror: MIRROR_NAME.to_string(),
+            last_modified: None,
+            sha256,
+            catalog: String::new(),
+        }],
+        catalogs: Vec::new(),
+    }
+}
+
+impl DistributionManifest {
+    // A JSON document is taken for a manifest when it has either distribution list
+    pub fn parse(text: &str) -> Option<Self> {
+        let value: serde_json::Value = serde_json::from_str(text).ok()?;
+        let object = value.as_object()?;
+        if !object.contains_key("ModernDistributions") && !object.contains_key("Distributions") {
+            return None;
+        }
+        serde_json::from_value(value).ok()
+    }
+
+    // Entries installable on this machine's architecture. Modern images win over the
+    // legacy appx of the same distro.
+    pub fn into_mirror_list(self) -> MirrorListResponse {
+        let arm64 = is_arm64();
+        let mut distros: Vec<DistroInfo> = Vec::new();
+
+        for (_family, versions) in self.modern_distributions {
+            for d in versions {
+                let url = if arm64 { d.arm64_url } else { d.amd64_url };
+                if let Some(url) = url {
+                    distros.push(entry(&d.name, url.url, url.sha256));
+                }
+            }
+        }
+
+        for d in self.distributions {
+            let url = if arm64 {
+                d.arm64_package_url.filter(|_| d.arm64)
+            } else {
+                d.amd64_package_url.filter(|_| d.amd64)
+            };
+            let Some(url) = url.filter(|u| !u.is_empty()) else { continue; };
+            let legacy = entry(&d.name, url, None);
+            if !distros.iter().any(|m| m.name.eq_ignore_ascii_case(&legacy.name) && m.version == legacy.version) {
+                distros.push(legacy);
+            }
+        }
+
+        MirrorListResponse {
+            update_time: String::new(),
+            distros,
+        }
+    }
+}
@@ -2,6 +2,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 pub mod cache;
+pub mod catalog;
 pub mod client;
 pub mod common;
+pub mod distribution_info;
 pub mod models;
@@ -1,7 +1,7 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct ReleaseData {
@@ -136,13 +136,21 @@ pub struct HelperHomeLink {
 }
 
 // 5. wslui_helper_mirrors data structure
-#[derive(Debug, Deserialize, Clone, Default)]
+// Serialize: download jobs persist their sources
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct MirrorSource {
     pub url: String,
     pub mirror: String,
     pub format: String,
     #[allow(dead_code)]
     pub last_modified: Option<String>,
+    // Expected SHA-256 of the file, checked after download when present
+    #[serde(default)]
+    pub sha256: Option<String>,
+    // Label of the catalog listing this mirror, empty for the built-in list.
+    // Filled in when catalogs are merged.
+    #[serde(default)]
+    pub catalog: String,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
@@ -150,11 +158,15 @@ pub struct DistroInfo {
     pub name: String,
     pub version: String,
     pub sources: Vec<MirrorSource>,
+    // Labels of the catalogs listing this distro, filled in when catalogs are merged
+    #[serde(skip)]
+    pub catalogs: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Clone, Default)]
 pub struct MirrorListResponse {
     #[allow(dead_code)]
+    #[serde(default)]
     pub update_time: String,
     pub distros: Vec<DistroInfo>,
 }
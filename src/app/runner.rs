@@ -137,6 +137,9 @@ pub async fn run_app(config_manager: ConfigManager, logging_system: LoggingSyste
     // 8. Refresh initial data (distro list)
     ui::data::refresh_data(app.as_weak(), app_state.clone()).await;
 
+    // Roll back moves interrupted by a crash or power loss in the previous session
+    tokio::spawn(ui::handlers::distro::move_logic::recover_interrupted_moves(app.as_weak(), app_state.clone()));
+
     // 9. Start background tasks (WSL/USB status monitoring)
     crate::app::tasks::spawn_wsl_monitor(app.as_weak(), app_state.clone());
     crate::app::tasks::spawn_usb_monitor(app.as_weak());
@@ -172,6 +175,15 @@ pub async fn run_app(config_manager: ConfigManager, logging_system: LoggingSyste
             // Version expiry check (3s delay, runs in all modes)
             scheduler.register(crate::app::tasks::VersionExpiryTask { app_state: state_clone.clone() });
 
+            // Sandbox cleanup (removes expired disposable distros)
+            scheduler.register(crate::app::tasks::SandboxCleanupTask { app_state: state_clone.clone() });
+
+            // Recycle bin purge (retention period and size cap)
+            scheduler.register(crate::app::tasks::RecycleBinPurgeTask { app_state: state_clone.clone() });
+
+            // wsl.conf preset drift of running distros, for the distro list
+            scheduler.register(crate::app::tasks::PresetDriftTask { app_state: state_clone.clone() });
+
             // Update check (5s delay, runs in all modes)
             scheduler.register(crate::app::tasks::UpdateCheckTask { app_state: state_clone });
 
@@ -0,0 +1,41 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// wsl.conf preset drift task
+//
+// Re-reads the wsl.conf of running distros that follow a preset, so the distro
+// list can flag the ones that no longer match it. Stopped distros are not started.
+
+use crate::AppWindow;
+use crate::app::task_scheduler::{ScheduledTask, TaskInterval};
+
+pub struct PresetDriftTask {
+    pub app_state: std::sync::Arc<tokio::sync::Mutex<crate::AppState>>,
+}
+
+#[async_trait::async_trait]
+impl ScheduledTask for PresetDriftTask {
+    fn name(&self) -> &str {
+        "preset_drift"
+    }
+
+    fn interval(&self) -> TaskInterval {
+        TaskInterval::FiveMinutes
+    }
+
+    fn requires_window_visible(&self) -> bool {
+        true
+    }
+
+    async fn execute(&self, _app_handle: &slint::Weak<AppWindow>) -> Result<(), String> {
+        let (dashboard, presets) = {
+            let state = self.app_state.lock().await;
+            (state.wsl_dashboard.clone(), crate::ui::handlers::wsl_presets::followed_presets(&state.config_manager))
+        };
+
+        if crate::wsl::ops::lint::check_preset_drift(dashboard.executor(), &presets).await {
+            dashboard.state_changed().notify_one();
+        }
+        Ok(())
+    }
+}
@@ -7,6 +7,9 @@ pub mod message_handler;
 pub mod expiry_task;
 pub mod update_task;
 pub mod compat_task;
+pub mod sandbox_task;
+pub mod recycle_task;
+pub mod drift_task;
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -19,6 +22,9 @@ pub use sync_task::{PopupSyncTask, MessageSyncTask};
 pub use expiry_task::VersionExpiryTask;
 pub use update_task::UpdateCheckTask;
 pub use compat_task::WslCompatTask;
+pub use sandbox_task::SandboxCleanupTask;
+pub use recycle_task::RecycleBinPurgeTask;
+pub use drift_task::PresetDriftTask;
 
 // Start WSL status monitoring task
 pub fn spawn_wsl_monitor(app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
@@ -12,7 +12,7 @@ use crate::AppWindow;
 // Download and decode popup image (runs on a blocking thread)
 fn load_popup_image_pixels(url: &str) -> Result<(Vec<u8>, u32, u32), String> {
     use std::io::Read;
-    let resp = ureq::get(url)
+    let resp = crate::network::http::get(url)
         .timeout(std::time::Duration::from_secs(10))
         .call()
         .map_err(|e| e.to_string())?;
@@ -0,0 +1,48 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Recycle bin purge task
+//
+// Permanently removes recycle bin entries past the retention period and
+// trims the oldest ones while the bin exceeds its size cap.
+
+use tracing::{info, warn};
+use crate::AppWindow;
+use crate::app::task_scheduler::{ScheduledTask, TaskInterval};
+
+pub struct RecycleBinPurgeTask {
+    pub app_state: std::sync::Arc<tokio::sync::Mutex<crate::AppState>>,
+}
+
+#[async_trait::async_trait]
+impl ScheduledTask for RecycleBinPurgeTask {
+    fn name(&self) -> &str {
+        "recycle_bin_purge"
+    }
+
+    fn interval(&self) -> TaskInterval {
+        TaskInterval::FiveMinutes
+    }
+
+    fn requires_window_visible(&self) -> bool {
+        false
+    }
+
+    async fn execute(&self, _app_handle: &slint::Weak<AppWindow>) -> Result<(), String> {
+        let config_manager = {
+            let state = self.app_state.lock().await;
+            state.config_manager.clone()
+        };
+
+        let result = tokio::task::spawn_blocking(move || crate::wsl::ops::recycle::purge_expired(&config_manager))
+            .await
+            .map_err(|e| e.to_string())?;
+        if !result.purged.is_empty() {
+            info!("recycle_bin_purge: removed {} entries: {}", result.purged.len(), result.purged.join(", "));
+        }
+        if result.over_cap {
+            warn!("recycle_bin_purge: the newest entry alone exceeds the size cap, keeping it");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,43 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Sandbox cleanup task
+//
+// Unregisters sandbox distros whose expiry time has passed, or which were
+// stopped after being used when marked "delete on stop", and removes their folders.
+
+use tracing::info;
+use crate::AppWindow;
+use crate::app::task_scheduler::{ScheduledTask, TaskInterval};
+
+pub struct SandboxCleanupTask {
+    pub app_state: std::sync::Arc<tokio::sync::Mutex<crate::AppState>>,
+}
+
+#[async_trait::async_trait]
+impl ScheduledTask for SandboxCleanupTask {
+    fn name(&self) -> &str {
+        "sandbox_cleanup"
+    }
+
+    fn interval(&self) -> TaskInterval {
+        TaskInterval::ThirtySeconds
+    }
+
+    fn requires_window_visible(&self) -> bool {
+        false // expired sandboxes must be removed even while minimized to tray
+    }
+
+    async fn execute(&self, _app_handle: &slint::Weak<AppWindow>) -> Result<(), String> {
+        let (dashboard, config_manager) = {
+            let state = self.app_state.lock().await;
+            (state.wsl_dashboard.clone(), state.config_manager.clone())
+        };
+
+        let removed = crate::wsl::ops::sandbox::cleanup_sandboxes(&dashboard, &config_manager).await;
+        if !removed.is_empty() {
+            info!("sandbox_cleanup: removed {} sandbox(es): {}", removed.len(), removed.join(", "));
+        }
+        Ok(())
+    }
+}
@@ -14,7 +14,7 @@ use once_cell::sync::Lazy;
 
 // TrayIcon from tray-icon crate is not Send/Sync on some platforms (like Windows due to Rc/RefCell)
 // But we are only accessing it from the main UI thread via Slint/Winit anyway.
-struct TrayIconWrapper(#[allow(dead_code)] tray_icon::TrayIcon);
+struct TrayIconWrapper(tray_icon::TrayIcon);
 unsafe impl Send for TrayIconWrapper {}
 unsafe impl Sync for TrayIconWrapper {}
 
@@ -47,7 +47,7 @@ impl SystemTray {
         let tray = TrayIconBuilder::new()
             .with_menu(Box::new(tray_menu))
             .with_menu_on_left_click(false)
-            .with_tooltip(format!("{} v{}", crate::app::constants::APP_NAME, env!("CARGO_PKG_VERSION")))
+            .with_tooltip(Self::base_tooltip())
             .with_icon(icon)
             .build()?;
             
@@ -111,4 +111,26 @@ impl SystemTray {
 
         Ok(())
     }
+
+    fn base_tooltip() -> String {
+        format!("{} v{}", crate::app::constants::APP_NAME, env!("CARGO_PKG_VERSION"))
+    }
+
+    // List protected distros in the tray tooltip, must be called on the UI thread
+    pub fn set_protected(names: &[String]) {
+        let mut tooltip = Self::base_tooltip();
+        if !names.is_empty() {
+            tooltip.push('\n');
+            tooltip.push_str(&i18n::tr("tray.protected", &[names.join(", ")]));
+        }
+        // Windows caps tray tooltips at 127 characters
+        if tooltip.chars().count() > 127 {
+            tooltip = tooltip.chars().take(124).collect::<String>() + "...";
+        }
+        if let Ok(global_tray) = TRAY_ICON.lock() {
+            if let Some(wrapper) = global_tray.as_ref() {
+                let _ = wrapper.0.set_tooltip(Some(tooltip));
+            }
+        }
+    }
 }
@@ -79,16 +79,19 @@ async fn cleanup_network_rules() {
                     info!("Deleting port proxy: {}:{} -> {}:{}", rule.listen_address, rule.listen_port, rule.distro_name, rule.target_port);
                     
                     // 1. Delete netsh rules
-                    let cmd = format!("netsh interface portproxy delete v4tov4 listenaddress={} listenport={}", 
-                        rule.listen_address, rule.listen_port);
+                    let cmd = crate::network::port_proxy::delete_command(rule.proxy_type, &rule.listen_address, rule.listen_port);
                     let _ = system::run_invisible_elevated_command(&cmd);
 
-                    // 2. Delete firewall rule (if exists)
-                    // The logic here references how rule_name is generated in src/network/port_proxy.rs
-                    let rule_name = format!("WSL_Dashboard_{}_{}", rule.distro_name, rule.listen_port);
-                    info!("Attempting to delete firewall rule: {}", rule_name);
-                    let fw_cmd = format!("netsh advfirewall firewall delete rule name=\"{}\"", rule_name);
-                    let _ = system::run_invisible_elevated_command(&fw_cmd);
+                    // 2. Delete firewall rule (if exists), under its current and its older name
+                    let rule_names = [
+                        crate::network::port_proxy::firewall_rule_name(&rule.distro_name, rule.proxy_type, &rule.listen_address, rule.listen_port),
+                        crate::network::port_proxy::legacy_firewall_rule_name(&rule.distro_name, rule.listen_port),
+                    ];
+                    for rule_name in rule_names {
+                        info!("Attempting to delete firewall rule: {}", rule_name);
+                        let fw_cmd = format!("netsh advfirewall firewall delete rule name=\"{}\"", rule_name);
+                        let _ = system::run_invisible_elevated_command(&fw_cmd);
+                    }
                 }
                 info!("Port forwarding and firewall rules cleanup completed.");
             }
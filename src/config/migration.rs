@@ -83,6 +83,21 @@ pub fn migrate_config(config: &mut Config) {
         config.settings.hide_pin = false;
     }
 
+    // v7 -> v8 logic
+    if old_version < 8 {
+        info!("Upgrading to v8: adding [settings] recycle-bin (default false),recycle-days,recycle-max-gb");
+        config.settings.recycle_bin = false;
+        config.settings.recycle_days = 7;
+        config.settings.recycle_max_gb = 50;
+    }
+
+    // v8 -> v9 logic
+    if old_version < 9 {
+        info!("Upgrading to v9: adding [settings] image-cache (default true),image-cache-max-gb");
+        config.settings.image_cache = true;
+        config.settings.image_cache_max_gb = 20;
+    }
+
     config.application.setting_version = SETTINGS_VERSION as u8;
     info!("Configuration migration complete, current version: v{}", SETTINGS_VERSION);
 }
@@ -99,6 +114,14 @@ pub fn migrate_instances_config(container: &mut super::InstancesContainer) {
     if old_version < 2 {
         info!("Upgrading instances config to v2: adding terminal-proxy field (default enabled)");
     }
+
+    if old_version < 3 {
+        info!("Upgrading instances config to v3: adding sandbox fields");
+    }
+
+    if old_version < 4 {
+        info!("Upgrading instances config to v4: adding protected field");
+    }
     
     container.common.setting_version = super::INSTANCES_VERSION;
     info!("Instances configuration migration complete, current version: v{}", super::INSTANCES_VERSION);
@@ -39,9 +39,15 @@ impl ConfigManager {
         home_dir.join(".wsldashboard").join("network.toml")
     }
 
+    fn get_templates_path() -> PathBuf {
+        let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        home_dir.join(".wsldashboard").join("templates.toml")
+    }
+
     // Initialize configuration manager
     pub async fn new() -> Self {
         let config_path = Self::get_config_path();
+        crate::network::http::configure(&Self::load_network_config().proxy);
         
         // Check if configuration file exists
         if config_path.exists() {
@@ -247,10 +253,71 @@ impl ConfigManager {
         network.common.modify_time = chrono::Utc::now().timestamp_millis().to_string();
         let rule_count = network.port_proxies.len();
         Self::save_network_config(&network)?;
+        crate::network::http::configure(&network.proxy);
         info!("Network configuration ({} rules) saved successfully to network.toml", rule_count);
         Ok(())
     }
 
+    // --- Templates Config Management ---
+
+    fn load_templates_config() -> TemplatesConfig {
+        let path = Self::get_templates_path();
+        if path.exists() {
+            if let Ok(content) = fs::read_to_string(&path) {
+                match toml::from_str::<TemplatesConfig>(&content) {
+                    Ok(config) => return config,
+                    Err(e) => {
+                        error!("Failed to parse templates.toml, falling back to default: {}", e);
+                    }
+                }
+            }
+        }
+        TemplatesConfig::default()
+    }
+
+    fn save_templates_config(config: &mut TemplatesConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        config.modify_time = chrono::Utc::now().timestamp_millis().to_string();
+        let path = Self::get_templates_path();
+        if let Some(parent) = path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let toml_string = toml::to_string_pretty(config)?;
+        fs::write(path, toml_string)?;
+        Ok(())
+    }
+
+    pub fn get_templates(&self) -> Vec<DistroTemplate> {
+        Self::load_templates_config().templates
+    }
+
+    pub fn get_template(&self, name: &str) -> Option<DistroTemplate> {
+        Self::load_templates_config().templates.into_iter().find(|t| t.name == name)
+    }
+
+    // Add a template, replacing an existing one with the same name
+    pub fn save_template(&self, template: DistroTemplate) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut config = Self::load_templates_config();
+        let name = template.name.clone();
+        match config.templates.iter_mut().find(|t| t.name == name) {
+            Some(existing) => *existing = template,
+            None => config.templates.push(template),
+        }
+        Self::save_templates_config(&mut config)?;
+        info!("Template '{}' saved successfully to templates.toml", name);
+        Ok(())
+    }
+
+    pub fn remove_template(&self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let mut config = Self::load_templates_config();
+        let before = config.templates.len();
+        config.templates.retain(|t| t.name != name);
+        if config.templates.len() != before {
+            Self::save_templates_config(&mut config)?;
+            info!("Removed template '{}'", name);
+        }
+        Ok(())
+    }
+
     // --- Instances Config Management ---
 
     fn load_instances() -> InstancesContainer {
@@ -298,6 +365,26 @@ impl ConfigManager {
         Ok(())
     }
 
+    // All instances marked as sandbox (read-only, does not create missing entries)
+    pub fn get_sandbox_instances(&self) -> Vec<(String, DistroInstanceConfig)> {
+        Self::load_instances().instances.into_iter()
+            .filter(|(_, c)| c.sandbox)
+            .collect()
+    }
+
+    // Names of all protected instances
+    pub fn get_protected_instances(&self) -> Vec<String> {
+        Self::load_instances().instances.into_iter()
+            .filter(|(_, c)| c.protected)
+            .map(|(name, _)| name)
+            .collect()
+    }
+
+    // Reads instances.toml directly so it can be used without a manager, e.g. by the executor
+    pub fn is_protected(distro_name: &str) -> bool {
+        Self::load_instances().instances.get(distro_name).is_some_and(|c| c.protected)
+    }
+
     pub fn get_cached_distros(&self) -> Vec<CachedDistro> {
         let container = Self::load_instances();
         container.last_distros
@@ -318,7 +405,6 @@ impl ConfigManager {
         &self.config.usb
     }
 
-    #[allow(dead_code)]
     pub fn update_usb_config(&mut self, usb: UsbConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
         self.config.usb = usb;
         Self::save_config(&self.config_path, &mut self.config)?;
@@ -326,6 +412,114 @@ impl ConfigManager {
         Ok(())
     }
 
+    // --- Catalog Config Management ---
+
+    pub fn get_catalog_config(&self) -> &CatalogConfig {
+        &self.config.catalog
+    }
+
+    pub fn update_catalog_config(&mut self, catalog: CatalogConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.config.catalog = catalog;
+        Self::save_config(&self.config_path, &mut self.config)?;
+        info!("Catalog configuration saved successfully");
+        Ok(())
+    }
+
+    // --- Download Config Management ---
+
+    pub fn get_download_config(&self) -> &DownloadConfig {
+        &self.config.download
+    }
+
+    pub fn update_download_config(&mut self, download: DownloadConfig) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        self.config.download = download;
+        Self::save_config(&self.config_path, &mut self.config)?;
+        info!("Download configuration saved successfully");
+        Ok(())
+    }
+
+    // --- wsl.conf Presets ---
+
+    pub fn get_wsl_conf_presets(&self) -> &[WslConfPreset] {
+        &self.config.wsl_conf_presets
+    }
+
+    // Add a preset, replacing an existing one with the same name
+    pub fn save_wsl_conf_preset(&mut self, preset: WslConfPreset) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let name = preset.name.clone();
+        match self.config.wsl_conf_presets.iter_mut().find(|p| p.name == name) {
+            Some(existing) => *existing = preset,
+            None => self.config.wsl_conf_presets.push(preset),
+        }
+        Self::save_config(&self.config_path, &mut self.config)?;
+        info!("wsl.conf preset '{}' saved successfully", name);
+        Ok(())
+    }
+
+    pub fn remove_wsl_conf_preset(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let before = self.config.wsl_conf_presets.len();
+        self.config.wsl_conf_presets.retain(|p| p.name != name);
+        if self.config.wsl_conf_presets.len() != before {
+            Self::save_config(&self.config_path, &mut self.config)?;
+            info!("Removed wsl.conf preset '{}'", name);
+        }
+        Ok(())
+    }
+
+    // (distro, preset) for every instance that follows a preset
+    pub fn get_wsl_conf_preset_followers(&self) -> Vec<(String, String)> {
+        Self::load_instances().instances.into_iter()
+            .filter(|(_, c)| !c.wsl_conf_preset.is_empty())
+            .map(|(name, c)| (name, c.wsl_conf_preset))
+            .collect()
+    }
+
+    // --- Custom kernels ---
+
+    pub fn get_kernels(&self) -> &KernelConfig {
+        &self.config.kernels
+    }
+
+    // Register a kernel, replacing an existing one with the same name
+    pub fn save_kernel(&mut self, kernel: KernelImage) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let name = kernel.name.clone();
+        match self.config.kernels.images.iter_mut().find(|k| k.name == name) {
+            Some(existing) => *existing = kernel,
+            None => self.config.kernels.images.push(kernel),
+        }
+        Self::save_config(&self.config_path, &mut self.config)?;
+        info!("Kernel '{}' registered", name);
+        Ok(())
+    }
+
+    pub fn remove_kernel(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let before = self.config.kernels.images.len();
+        self.config.kernels.images.retain(|k| k.name != name);
+        if self.config.kernels.images.len() != before {
+            Self::save_config(&self.config_path, &mut self.config)?;
+            info!("Removed kernel '{}'", name);
+        }
+        Ok(())
+    }
+
+    pub fn set_kernel_restart_pending(&mut self, pending: bool, boot_id: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let kernels = &mut self.config.kernels;
+        if kernels.restart_pending == pending && kernels.boot_id == boot_id {
+            return Ok(());
+        }
+        kernels.restart_pending = pending;
+        kernels.boot_id = boot_id.to_string();
+        Self::save_config(&self.config_path, &mut self.config)
+    }
+
+    pub fn set_kernel_saved_command_line(&mut self, value: Option<String>) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        if self.config.kernels.saved_command_line == value {
+            return Ok(());
+        }
+        self.config.kernels.saved_command_line = value;
+        Self::save_config(&self.config_path, &mut self.config)
+    }
+
     // --- Debug Config ---
 
     // Load `~/.wsldashboard/debug.toml` (best-effort, never panics).
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 // Configuration file version constant
-pub const SETTINGS_VERSION: u32 = 7;
+pub const SETTINGS_VERSION: u32 = 9;
 
 // Application configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -66,6 +66,16 @@ pub struct UserSettings {
     pub mail: bool,
     #[serde(rename = "hide-pin", default)]
     pub hide_pin: bool,
+    #[serde(rename = "recycle-bin", default)]
+    pub recycle_bin: bool,
+    #[serde(rename = "recycle-days", default = "default_recycle_days")]
+    pub recycle_days: u16,
+    #[serde(rename = "recycle-max-gb", default = "default_recycle_max_gb")]
+    pub recycle_max_gb: u32,
+    #[serde(rename = "image-cache", default = "default_true")]
+    pub image_cache: bool,
+    #[serde(rename = "image-cache-max-gb", default = "default_image_cache_max_gb")]
+    pub image_cache_max_gb: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -93,6 +103,9 @@ impl Default for TraySettings {
 pub fn default_log_level() -> u8 { 4 }
 pub fn default_log_days() -> u8 { 7 }
 pub fn default_check_update() -> u8 { 7 }
+pub fn default_recycle_days() -> u16 { 7 }
+pub fn default_recycle_max_gb() -> u32 { 50 }
+pub fn default_image_cache_max_gb() -> u32 { 20 }
 
 // Complete configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -106,6 +119,14 @@ pub struct Config {
     pub usb: UsbConfig,
     #[serde(default)]
     pub sidebar: SidebarConfig,
+    #[serde(default)]
+    pub catalog: CatalogConfig,
+    #[serde(default)]
+    pub download: DownloadConfig,
+    #[serde(rename = "wsl-conf-presets", default)]
+    pub wsl_conf_presets: Vec<WslConfPreset>,
+    #[serde(default)]
+    pub kernels: KernelConfig,
 }
 
 impl Config {
@@ -155,11 +176,20 @@ impl Config {
                 colorful_icons: true,
                 mail: true,
                 hide_pin: false,
+                recycle_bin: false,
+                recycle_days: 7,
+                recycle_max_gb: 50,
+                image_cache: true,
+                image_cache_max_gb: 20,
             },
 
             tray: TraySettings::default(),
             usb: UsbConfig::default(),
             sidebar: SidebarConfig::default(),
+            catalog: CatalogConfig::default(),
+            download: DownloadConfig::default(),
+            wsl_conf_presets: Vec::new(),
+            kernels: KernelConfig::default(),
         }
     }
 }
@@ -207,6 +237,101 @@ pub struct UsbConfig {
     pub auto_attach_list: Vec<UsbAutoAttachDevice>,
 }
 
+// --- Distro Catalog Configuration ---
+
+// A user catalog: an HTTP(S) URL or a local folder serving `MirrorListResponse` JSON
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogSource {
+    pub name: String,
+    pub location: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CatalogConfig {
+    // Whether the online catalog published by wslui is listed as well
+    #[serde(rename = "include-builtin", default = "default_true")]
+    pub include_builtin: bool,
+    // Microsoft's DistributionInfo.json, for installs where the Store is blocked
+    #[serde(rename = "include-microsoft", default)]
+    pub include_microsoft: bool,
+    #[serde(default)]
+    pub sources: Vec<CatalogSource>,
+}
+
+impl Default for CatalogConfig {
+    fn default() -> Self {
+        Self {
+            include_builtin: true,
+            include_microsoft: false,
+            sources: Vec::new(),
+        }
+    }
+}
+
+// --- Download Configuration ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadConfig {
+    // Parallel range requests per download
+    #[serde(default = "default_download_connections")]
+    pub connections: u8,
+    // Shared cap for all downloads in MB/s, 0 for unlimited
+    #[serde(rename = "bandwidth-limit-mb", default)]
+    pub bandwidth_limit_mb: u32,
+}
+
+pub fn default_download_connections() -> u8 { 4 }
+
+impl Default for DownloadConfig {
+    fn default() -> Self {
+        Self {
+            connections: default_download_connections(),
+            bandwidth_limit_mb: 0,
+        }
+    }
+}
+
+// --- wsl.conf presets ---
+
+// Named partial wsl.conf, only the keys it sets are written to a distro
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WslConfPreset {
+    pub name: String,
+    #[serde(default)]
+    pub settings: crate::wsl::ops::config::WslConf,
+}
+
+// --- Custom kernels ---
+
+// A kernel image on the Windows side, with its optional modules VHD and arguments
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KernelImage {
+    pub name: String,
+    pub path: String,
+    #[serde(default)]
+    pub modules: String,
+    #[serde(rename = "command-line", default)]
+    pub command_line: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct KernelConfig {
+    #[serde(default)]
+    pub images: Vec<KernelImage>,
+    // Set when the kernel keys of .wslconfig changed while the WSL VM was up
+    #[serde(rename = "restart-pending", default)]
+    pub restart_pending: bool,
+    // boot_id of that VM, empty when none was running
+    #[serde(rename = "boot-id", default)]
+    pub boot_id: String,
+    // kernelCommandLine from before a custom kernel replaced it, put back on revert.
+    // Empty when there was none, None when nothing was replaced.
+    #[serde(rename = "saved-command-line", default)]
+    pub saved_command_line: Option<String>,
+}
+
 pub const NETWORK_VERSION: u32 = 1;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -263,7 +388,7 @@ pub struct UsbAutoAttachDevice {
 
 // --- Instance-specific configuration (instances.toml) ---
 
-pub const INSTANCES_VERSION: u32 = 2;
+pub const INSTANCES_VERSION: u32 = 4;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CachedDistro {
@@ -294,6 +419,26 @@ pub struct DistroInstanceConfig {
     pub startup_script: String,
     #[serde(rename = "terminal-proxy", default = "default_true")]
     pub terminal_proxy: bool,
+    // Disposable instance, removed automatically by the sandbox cleanup task
+    #[serde(default)]
+    pub sandbox: bool,
+    // Unix timestamp (ms) after which the sandbox is removed, empty for no deadline
+    #[serde(rename = "expire-time", default)]
+    pub expire_time: String,
+    #[serde(rename = "delete-on-stop", default)]
+    pub delete_on_stop: bool,
+    // Set once the sandbox has been seen running, so delete-on-stop does not fire before first use
+    #[serde(rename = "sandbox-started", default)]
+    pub sandbox_started: bool,
+    // Folder the dashboard created for the sandbox, the only one removed with it
+    #[serde(rename = "sandbox-dir", default)]
+    pub sandbox_dir: String,
+    // Blocks delete, move, compress and version conversion until lifted
+    #[serde(default)]
+    pub protected: bool,
+    // wsl.conf preset last applied to the distro, empty for none
+    #[serde(rename = "wsl-conf-preset", default)]
+    pub wsl_conf_preset: String,
 }
 
 pub fn default_terminal_dir() -> String { "~".to_string() }
@@ -307,6 +452,13 @@ impl Default for DistroInstanceConfig {
             auto_startup: false,
             startup_script: String::new(),
             terminal_proxy: true,
+            sandbox: false,
+            expire_time: String::new(),
+            delete_on_stop: false,
+            sandbox_started: false,
+            sandbox_dir: String::new(),
+            protected: false,
+            wsl_conf_preset: String::new(),
         }
     }
 }
@@ -331,3 +483,101 @@ impl InstancesContainer {
         }
     }
 }
+
+// --- Distro templates (templates.toml) ---
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TemplateParameter {
+    pub key: String,
+    pub label: String,
+    #[serde(default)]
+    pub default: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvisionStep {
+    pub name: String,
+    // Shell script run inside the new distro, `{{key}}` is replaced by parameter values
+    pub script: String,
+    #[serde(default = "default_step_user")]
+    pub user: String,
+}
+
+pub fn default_step_user() -> String { "root".to_string() }
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DistroTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    // Exported archive (.tar, .tar.gz, .wsl) or .vhdx the template is imported from
+    pub file: String,
+    #[serde(rename = "is-vhdx", default)]
+    pub is_vhdx: bool,
+    #[serde(rename = "create-time", default = "default_modify_time")]
+    pub create_time: String,
+    #[serde(default)]
+    pub parameters: Vec<TemplateParameter>,
+    #[serde(default)]
+    pub steps: Vec<ProvisionStep>,
+    // Login user written to wsl.conf once the steps ran, `{{key}}` is replaced by
+    // parameter values. Nothing is written when it renders empty.
+    #[serde(rename = "default-user", default)]
+    pub default_user: String,
+}
+
+impl DistroTemplate {
+    // New template with the common dev-box parameters: user, git e-mail and extra packages
+    pub fn with_default_provisioning(name: &str, description: &str, file: &str) -> Self {
+        let lower = file.to_lowercase();
+        Self {
+            name: name.to_string(),
+            description: description.to_string(),
+            file: file.to_string(),
+            is_vhdx: lower.ends_with(".vhdx"),
+            create_time: default_modify_time(),
+            parameters: vec![
+                TemplateParameter { key: "username".into(), label: "Username".into(), default: String::new() },
+                TemplateParameter { key: "git_email".into(), label: "Git e-mail".into(), default: String::new() },
+                TemplateParameter { key: "packages".into(), label: "Packages".into(), default: String::new() },
+            ],
+            steps: vec![
+                ProvisionStep {
+                    name: "Create default user".into(),
+                    script: "if [ -n \"{{username}}\" ]; then \
+                        id -u \"{{username}}\" >/dev/null 2>&1 || useradd -m -s /bin/bash \"{{username}}\" 2>/dev/null || adduser -D \"{{username}}\"; \
+                        fi".into(),
+                    user: default_step_user(),
+                },
+                ProvisionStep {
+                    name: "Configure git".into(),
+                    script: "if [ -n \"{{git_email}}\" ] && command -v git >/dev/null 2>&1; then \
+                        git config --system user.email \"{{git_email}}\"; \
+                        fi".into(),
+                    user: default_step_user(),
+                },
+                ProvisionStep {
+                    name: "Install packages".into(),
+                    script: "if [ -n \"{{packages}}\" ]; then \
+                        if command -v apt-get >/dev/null 2>&1; then apt-get update && DEBIAN_FRONTEND=noninteractive apt-get install -y {{packages}}; \
+                        elif command -v dnf >/dev/null 2>&1; then dnf install -y {{packages}}; \
+                        elif command -v zypper >/dev/null 2>&1; then zypper --non-interactive install {{packages}}; \
+                        elif command -v pacman >/dev/null 2>&1; then pacman -Sy --noconfirm {{packages}}; \
+                        elif command -v apk >/dev/null 2>&1; then apk add {{packages}}; \
+                        else echo 'No supported package manager found' >&2; exit 1; fi; \
+                        fi".into(),
+                    user: default_step_user(),
+                },
+            ],
+            default_user: "{{username}}".into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct TemplatesConfig {
+    #[serde(rename = "modify-time", default)]
+    pub modify_time: String,
+    #[serde(default)]
+    pub templates: Vec<DistroTemplate>,
+}
@@ -5,7 +5,7 @@
 // --- Netsh Implementation (for batch execution with PortProxy) ---
 
 pub fn get_add_rule_cmd_netsh(rule_name: &str, port: u16, listen_addr: &str) -> String {
-    let local_ip_param = if listen_addr == "0.0.0.0" || listen_addr.is_empty() { 
+    let local_ip_param = if listen_addr == "0.0.0.0" || listen_addr == "::" || listen_addr.is_empty() { 
         "localip=any" 
     } else { 
         &format!("localip={}", listen_addr) 
@@ -0,0 +1,435 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Shared HTTP clients
+//
+// Every outbound request of the dashboard is made with a client from here, so they
+// all follow the same network settings: the proxy configured on the network page
+// (with its credentials and bypass list), otherwise the Windows or environment
+// proxy, and an optional CA bundle for proxies that intercept TLS.
+
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use base64::Engine;
+use once_cell::sync::Lazy;
+use tracing::{info, trace, warn};
+use super::models::HttpProxyConfig;
+
+const PEM_BEGIN: &str = "-----BEGIN CERTIFICATE-----";
+const PEM_END: &str = "-----END CERTIFICATE-----";
+
+#[derive(Debug, Clone)]
+struct ResolvedProxy {
+    // Proxy URL, credentials embedded
+    url: String,
+    // Lowercase bypass entries, from `no_proxy` or the system settings
+    bypass: Vec<String>,
+}
+
+struct HttpSettings {
+    proxy: Option<ResolvedProxy>,
+    // PEM blocks of the custom CA bundle
+    ca_certs: Vec<String>,
+    // ureq agents through the proxy and direct, shared until the next `configure`
+    proxied_agent: ureq::Agent,
+    direct_agent: ureq::Agent,
+    // Same for the wslui API client, which skips certificate checks in debug builds
+    api_proxied_agent: ureq::Agent,
+    api_direct_agent: ureq::Agent,
+    // ureq only authenticates CONNECT tunnels, plain http requests carry it themselves
+    proxy_authorization: Option<String>,
+}
+
+impl HttpSettings {
+    fn new(proxy: Option<ResolvedProxy>, ca_certs: Vec<String>) -> Self {
+        let proxy_url = proxy.as_ref().map(|p| p.url.as_str());
+        let proxied_agent = build_agent(proxy_url, &ca_certs, false);
+        let direct_agent = build_agent(None, &ca_certs, false);
+        let dev_insecure = cfg!(debug_assertions);
+        let api_proxied_agent = build_agent(proxy_url, &ca_certs, dev_insecure);
+        let api_direct_agent = build_agent(None, &ca_certs, dev_insecure);
+        let proxy_authorization = proxy.as_ref().and_then(|p| proxy_authorization(&p.url));
+        Self { proxy, ca_certs, proxied_agent, direct_agent, api_proxied_agent, api_direct_agent, proxy_authorization }
+    }
+}
+
+static SETTINGS: Lazy<RwLock<HttpSettings>> = Lazy::new(|| {
+    let config = HttpProxyConfig::default();
+    RwLock::new(HttpSettings::new(resolve_proxy(&config), Vec::new()))
+});
+
+// Apply the saved network settings to all clients created from now on
+pub fn configure(config: &HttpProxyConfig) {
+    let proxy = resolve_proxy(config);
+    match proxy {
+        Some(ref p) => info!("HTTP clients: using proxy {}", redact(&p.url)),
+        None => info!("HTTP clients: direct connection"),
+    }
+
+    let ca_certs = if config.ca_bundle.trim().is_empty() {
+        Vec::new()
+    } else {
+        match load_ca_bundle(config.ca_bundle.trim()) {
+            Ok(certs) => {
+                info!("HTTP clients: trusting {} certificate(s) from {}", certs.len(), config.ca_bundle);
+                certs
+            }
+            Err(e) => {
+                warn!("HTTP clients: ignoring CA bundle {}: {}", config.ca_bundle, e);
+                Vec::new()
+            }
+        }
+    };
+
+    *SETTINGS.write().unwrap() = HttpSettings::new(proxy, ca_certs);
+}
+
+// Certificates of a PEM bundle, checked so a bad file is reported when it is chosen
+pub fn load_ca_bundle(path: &str) -> Result<Vec<String>, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let mut certs = Vec::new();
+    let mut rest = text.as_str();
+    while let Some(start) = rest.find(PEM_BEGIN) {
+        let Some(len) = rest[start..].find(PEM_END) else {
+            break;
+        };
+        let end = start + len + PEM_END.len();
+        let pem = &rest[start..end];
+        native_tls::Certificate::from_pem(pem.as_bytes()).map_err(|e| e.to_string())?;
+        certs.push(pem.to_string());
+        rest = &rest[end..];
+    }
+    if certs.is_empty() {
+        return Err("no PEM certificate found".to_string());
+    }
+    Ok(certs)
+}
+
+// ureq request, sent directly when the host is on the bypass list
+pub fn request(method: &str, url: &str) -> ureq::Request {
+    agent_request(method, url, false)
+}
+
+// Request of the wslui API client, the only one that skips certificate checks in debug builds
+pub fn api_request(method: &str, url: &str) -> ureq::Request {
+    agent_request(method, url, true)
+}
+
+fn agent_request(method: &str, url: &str, api: bool) -> ureq::Request {
+    let settings = SETTINGS.read().unwrap();
+    let (proxied, direct) = if api {
+        (&settings.api_proxied_agent, &settings.api_direct_agent)
+    } else {
+        (&settings.proxied_agent, &settings.direct_agent)
+    };
+    match settings.proxy {
+        Some(ref proxy) if !bypasses(&proxy.bypass, url) => {
+            with_proxy_authorization(proxied.request(method, url), url, settings.proxy_authorization.as_deref())
+        }
+        _ => direct.request(method, url),
+    }
+}
+
+pub fn get(url: &str) -> ureq::Request {
+    request("GET", url)
+}
+
+pub fn head(url: &str) -> ureq::Request {
+    request("HEAD", url)
+}
+
+// GET that always goes through the given proxy, for testing it before it is used
+pub fn get_via(config: &HttpProxyConfig, url: &str) -> Result<ureq::Request, String> {
+    let proxy_url = config.proxy_url().ok_or_else(|| "proxy host or port missing".to_string())?;
+    ureq::Proxy::new(ureq_proxy_url(&proxy_url)).map_err(|e| e.to_string())?;
+    let ca_certs = SETTINGS.read().unwrap().ca_certs.clone();
+    let agent = build_agent(Some(&proxy_url), &ca_certs, false);
+    Ok(with_proxy_authorization(agent.get(url), url, proxy_authorization(&proxy_url).as_deref()))
+}
+
+fn with_proxy_authorization(request: ureq::Request, url: &str, authorization: Option<&str>) -> ureq::Request {
+    match authorization {
+        Some(value) if url.starts_with("http://") => request.set("Proxy-Authorization", value),
+        _ => request,
+    }
+}
+
+// reqwest builder with the proxy and CA bundle applied, callers add their own options
+pub fn client_builder() -> reqwest::ClientBuilder {
+    let settings = SETTINGS.read().unwrap();
+    // Proxies are resolved here, keep reqwest from picking its own from the environment
+    let mut builder = reqwest::Client::builder().no_proxy();
+    if let Some(ref proxy) = settings.proxy {
+        match reqwest::Url::parse(&proxy.url) {
+            Ok(proxy_url) => {
+                let bypass = proxy.bypass.clone();
+                builder = builder.proxy(reqwest::Proxy::custom(move |url| {
+                    (!bypasses(&bypass, url.as_str())).then(|| proxy_url.clone())
+                }));
+            }
+            Err(e) => warn!("HTTP clients: invalid proxy {}: {}", redact(&proxy.url), e),
+        }
+    }
+    for pem in &settings.ca_certs {
+        if let Ok(cert) = reqwest::Certificate::from_pem(pem.as_bytes()) {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+    builder
+}
+
+fn build_agent(proxy_url: Option<&str>, ca_certs: &[String], accept_invalid_certs: bool) -> ureq::Agent {
+    let mut builder = ureq::AgentBuilder::new();
+    if let Some(url) = proxy_url {
+        match ureq::Proxy::new(ureq_proxy_url(url)) {
+            Ok(proxy) => builder = builder.proxy(proxy),
+            Err(e) => warn!("HTTP clients: invalid proxy {}: {}", redact(url), e),
+        }
+    }
+
+    let mut tls = native_tls::TlsConnector::builder();
+    for pem in ca_certs {
+        if let Ok(cert) = native_tls::Certificate::from_pem(pem.as_bytes()) {
+            tls.add_root_certificate(cert);
+        }
+    }
+    if accept_invalid_certs {
+        tls.danger_accept_invalid_certs(true);
+        trace!("Developer mode: HTTPS certificate verification is disabled (-k)");
+    }
+    match tls.build() {
+        Ok(connector) => builder = builder.tls_connector(Arc::new(connector)),
+        Err(e) => warn!("HTTP clients: failed to set up TLS: {}", e),
+    }
+    builder.build()
+}
+
+// Credentials embedded in a proxy URL, percent-decoded. A user without a password
+// still authenticates, with an empty one.
+fn proxy_credentials(url: &str) -> Option<(reqwest::Url, String, String)> {
+    let parsed = reqwest::Url::parse(url).ok()?;
+    if parsed.username().is_empty() {
+        return None;
+    }
+    let decode = |s: &str| urlencoding::decode(s).map(|d| d.into_owned()).unwrap_or_else(|_| s.to_string());
+    let (username, password) = (decode(parsed.username()), decode(parsed.password().unwrap_or_default()));
+    Some((parsed, username, password))
+}
+
+fn proxy_authorization(url: &str) -> Option<String> {
+    let (_, username, password) = proxy_credentials(url)?;
+    let token = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+    Some(format!("Basic {}", token))
+}
+
+// ureq takes the credentials verbatim, so they go in decoded
+fn ureq_proxy_url(url: &str) -> String {
+    let Some((parsed, username, password)) = proxy_credentials(url) else {
+        return url.to_string();
+    };
+    let host = parsed.host_str().unwrap_or_default();
+    let port = parsed.port_or_known_default().map(|p| format!(":{}", p)).unwrap_or_default();
+    format!("{}://{}:{}@{}{}", parsed.scheme(), username, password, host, port)
+}
+
+fn resolve_proxy(config: &HttpProxyConfig) -> Option<ResolvedProxy> {
+    if let Some(url) = config.proxy_url() {
+        return Some(ResolvedProxy { url, bypass: split_bypass(&config.no_proxy) });
+    }
+    // An incomplete proxy means "direct" just like the "No proxy" choice
+    if config.is_enabled || config.ignore_system_proxy {
+        return None;
+    }
+    env_proxy().or_else(system_proxy)
+}
+
+// HTTPS_PROXY and friends, as used by command line tools
+fn env_proxy() -> Option<ResolvedProxy> {
+    let var = |names: &[&str]| {
+        names.iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.trim().is_empty())
+    };
+    let url = var(&["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy", "HTTP_PROXY", "http_proxy"])?;
+    let bypass = var(&["NO_PROXY", "no_proxy"]).unwrap_or_default();
+    Some(ResolvedProxy { url: with_scheme(url.trim()), bypass: split_bypass(&bypass) })
+}
+
+// Manual proxy from the Windows Internet Options. A proxy auto-config script is not
+// evaluated, set the proxy on the network page in that case.
+fn system_proxy() -> Option<ResolvedProxy> {
+    let (server, overrides) = crate::utils::registry::get_system_proxy()?;
+    // Either "host:port" for all protocols or "http=host:port;https=host:port;..."
+    let url = if server.contains('=') {
+        let by_protocol = |protocol: &str| {
+            server.split(';')
+                .filter_map(|s| s.trim().split_once('='))
+                .find(|(p, _)| p.eq_ignore_ascii_case(protocol))
+                .map(|(_, address)| address.trim().to_string())
+        };
+        by_protocol("https").or_else(|| by_protocol("http"))?
+    } else {
+        server.trim().to_string()
+    };
+    Some(ResolvedProxy { url: with_scheme(&url), bypass: split_bypass(&overrides) })
+}
+
+fn with_scheme(url: &str) -> String {
+    if url.contains("://") {
+        url.to_string()
+    } else {
+        format!("http://{}", url)
+    }
+}
+
+// Proxy URL without its credentials, for the log
+fn redact(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(mut parsed) => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        Err(_) => "<invalid>".to_string(),
+    }
+}
+
+// `no_proxy` uses commas, the Windows override list semicolons
+fn split_bypass(list: &str) -> Vec<String> {
+    list.split([',', ';', ' '])
+        .map(|entry| entry.trim().to_lowercase())
+        .filter(|entry| !entry.is_empty())
+        .collect()
+}
+
+fn bypasses(bypass: &[String], url: &str) -> bool {
+    let Ok(parsed) = reqwest::Url::parse(url) else {
+        return false;
+    };
+    let Some(host) = parsed.host_str() else {
+        return false;
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']').to_lowercase();
+    bypass.iter().any(|entry| entry_matches(entry, &host))
+}
+
+// Entries: `*`, `<local>` (names without a dot), `example.com` / `.example.com` /
+// `*.example.com` (the domain and its subdomains), wildcards like `192.168.*` and
+// CIDR ranges like `10.0.0.0/8`
+fn entry_matches(entry: &str, host: &str) -> bool {
+    if entry == "*" {
+        return true;
+    }
+    if entry == "<local>" {
+        return !host.contains('.') && !host.contains(':');
+    }
+    if let Some((network, prefix)) = entry.split_once('/') {
+        return match (network.parse::<IpAddr>(), prefix.parse::<u32>(), host.parse::<IpAddr>()) {
+            (Ok(network), Ok(prefix), Ok(ip)) => in_network(ip, network, prefix),
+            _ => false,
+        };
+    }
+    let domain = entry.trim_start_matches("*.").trim_start_matches('.');
+    if !domain.contains('*') {
+        return host == domain || host.ends_with(&format!(".{}", domain));
+    }
+    wildcard_matches(entry, host)
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u32) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+// `*` matches any run of characters
+fn wildcard_matches(pattern: &str, text: &str) -> bool {
+    let parts: Vec<&str> = pattern.split('*').collect();
+    let (first, last) = (parts[0], parts[parts.len() - 1]);
+    if !text.starts_with(first) || text.len() < first.len() + last.len() || !text.ends_with(last) {
+        return false;
+    }
+    let mut rest = &text[first.len()..text.len() - last.len()];
+    for part in &parts[1..parts.len() - 1] {
+        match rest.find(part) {
+            Some(pos) => rest = &rest[pos + part.len()..],
+            None => return false,
+        }
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(entry: &str, host: &str) -> bool {
+        entry_matches(&entry.to_lowercase(), host)
+    }
+
+    #[test]
+    fn star_matches_everything() {
+        assert!(matches("*", "example.com"));
+        assert!(matches("*", "10.1.2.3"));
+    }
+
+    #[test]
+    fn local_matches_plain_names() {
+        assert!(matches("<local>", "intranet"));
+        assert!(!matches("<local>", "intranet.corp"));
+        assert!(!matches("<local>", "::1"));
+    }
+
+    #[test]
+    fn domain_entries_cover_subdomains() {
+        for entry in ["example.com", ".example.com", "*.example.com"] {
+            assert!(matches(entry, "example.com"), "{}", entry);
+            assert!(matches(entry, "www.example.com"), "{}", entry);
+            assert!(!matches(entry, "badexample.com"), "{}", entry);
+            assert!(!matches(entry, "example.com.evil.org"), "{}", entry);
+        }
+    }
+
+    #[test]
+    fn wildcards_in_the_middle_and_at_the_end() {
+        assert!(matches("192.168.*", "192.168.1.20"));
+        assert!(!matches("192.168.*", "192.169.1.20"));
+        assert!(matches("*.corp.*", "git.corp.local"));
+        assert!(!matches("*.corp.*", "corp.local"));
+        assert!(matches("build-*.ci", "build-42.ci"));
+        assert!(!matches("build-*.ci", "build-42.cd"));
+    }
+
+    #[test]
+    fn cidr_ranges() {
+        assert!(matches("10.0.0.0/8", "10.20.30.40"));
+        assert!(!matches("10.0.0.0/8", "11.0.0.1"));
+        assert!(matches("fd00::/8", "fd12::1"));
+        assert!(!matches("10.0.0.0/8", "example.com"));
+        assert!(!matches("10.0.0.0/33", "10.0.0.1"));
+    }
+
+    #[test]
+    fn bypass_list_checks_the_url_host() {
+        let bypass = split_bypass("localhost; *.internal, 127.0.0.0/8");
+        assert!(bypasses(&bypass, "http://LOCALHOST:8080/"));
+        assert!(bypasses(&bypass, "https://api.internal/v1"));
+        assert!(bypasses(&bypass, "http://127.0.0.1/"));
+        assert!(!bypasses(&bypass, "https://example.com/"));
+    }
+
+    #[test]
+    fn user_without_password_still_authenticates() {
+        let expected = format!("Basic {}", base64::engine::general_purpose::STANDARD.encode("user:"));
+        assert_eq!(proxy_authorization("http://user:@proxy:8080").as_deref(), Some(expected.as_str()));
+        assert_eq!(proxy_authorization("http://proxy:8080"), None);
+    }
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 pub mod models;
+pub mod http;
 pub mod scanner;
 pub mod tracker;
 pub mod port_proxy;
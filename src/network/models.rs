@@ -11,6 +11,43 @@ pub struct NetworkInterface {
     pub is_virtual: bool,
 }
 
+// netsh portproxy table, named after the address family it listens on and connects to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PortProxyType {
+    #[default]
+    V4ToV4,
+    V4ToV6,
+    V6ToV4,
+    V6ToV6,
+}
+
+impl PortProxyType {
+    // In the order of the type selector in the add rule dialog
+    pub const ALL: [PortProxyType; 4] = [Self::V4ToV4, Self::V4ToV6, Self::V6ToV4, Self::V6ToV6];
+
+    pub fn from_index(index: i32) -> Self {
+        usize::try_from(index).ok().and_then(|i| Self::ALL.get(i).copied()).unwrap_or_default()
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::V4ToV4 => "v4tov4",
+            Self::V4ToV6 => "v4tov6",
+            Self::V6ToV4 => "v6tov4",
+            Self::V6ToV6 => "v6tov6",
+        }
+    }
+
+    pub fn listens_on_v6(&self) -> bool {
+        matches!(self, Self::V6ToV4 | Self::V6ToV6)
+    }
+
+    pub fn connects_to_v6(&self) -> bool {
+        matches!(self, Self::V4ToV6 | Self::V6ToV6)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PortProxyRule {
     pub id: String,
@@ -19,6 +56,9 @@ pub struct PortProxyRule {
     pub listen_port: u16,
     pub target_port: u16,
     pub enable_firewall: bool,
+    // Rules saved before IPv6 support are v4tov4
+    #[serde(default)]
+    pub proxy_type: PortProxyType,
 }
 
 
@@ -38,6 +78,30 @@ pub struct HttpProxyConfig {
     pub password: String,
     #[serde(default)]
     pub no_proxy: String,
+    // With the proxy off, connect directly instead of using the system proxy
+    #[serde(default)]
+    pub ignore_system_proxy: bool,
+    // PEM file with extra root certificates, for proxies that intercept TLS
+    #[serde(default)]
+    pub ca_bundle: String,
+}
+
+impl HttpProxyConfig {
+    // Proxy URL with credentials embedded, None when the proxy is off or incomplete
+    pub fn proxy_url(&self) -> Option<String> {
+        let host = self.host.trim();
+        let port = self.port.trim();
+        if !self.is_enabled || host.is_empty() || port.parse::<u16>().is_err() {
+            return None;
+        }
+        if self.auth_enabled {
+            let user_enc = urlencoding::encode(&self.username);
+            let pass_enc = urlencoding::encode(&self.password);
+            Some(format!("http://{}:{}@{}:{}", user_enc, pass_enc, host, port))
+        } else {
+            Some(format!("http://{}:{}", host, port))
+        }
+    }
 }
 
 pub fn default_no_proxy() -> String {
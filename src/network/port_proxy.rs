@@ -5,8 +5,9 @@ use tracing::{info, error};
 use std::process::Command;
 use std::os::windows::process::CommandExt;
 use std::collections::HashSet;
-use super::models::PortProxyRule;
-use super::tracker::{get_distro_ip, is_distro_running};
+use std::net::IpAddr;
+use super::models::{PortProxyRule, PortProxyType};
+use super::tracker::{get_distro_address, is_distro_running};
 
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
@@ -26,10 +27,33 @@ fn execute_netsh(args: &[&str]) -> Result<String, String> {
     }
 }
 
-pub fn get_active_listen_ports() -> Result<HashSet<(String, u16)>, String> {
-    let output = execute_netsh(&["interface", "portproxy", "show", "all"])?;
+// A rule in the netsh tables: table, listen address and listen port
+pub type ListenKey = (PortProxyType, String, u16);
+
+// netsh prints IPv6 addresses in canonical form, which the saved rule may not use
+fn normalize_address(addr: &str) -> String {
+    match addr.trim().parse::<IpAddr>() {
+        Ok(ip) => ip.to_string(),
+        Err(_) => addr.trim().to_lowercase(),
+    }
+}
+
+pub fn listen_key(rule: &PortProxyRule) -> ListenKey {
+    (rule.proxy_type, normalize_address(&rule.listen_address), rule.listen_port)
+}
+
+// Rules currently in all four netsh tables. Each table is queried on its own,
+// since the headers that separate them in "show all" are localized.
+pub fn get_active_listen_ports() -> Result<HashSet<ListenKey>, String> {
     let mut active_sets = HashSet::new();
+    for proxy_type in PortProxyType::ALL {
+        let output = execute_netsh(&["interface", "portproxy", "show", proxy_type.as_str()])?;
+        parse_listen_table(&output, proxy_type, &mut active_sets);
+    }
+    Ok(active_sets)
+}
 
+fn parse_listen_table(output: &str, proxy_type: PortProxyType, active_sets: &mut HashSet<ListenKey>) {
     let mut in_data_section = false;
     for line in output.lines() {
         let line = line.trim();
@@ -47,26 +71,49 @@ pub fn get_active_listen_ports() -> Result<HashSet<(String, u16)>, String> {
             // Parse the first two whitespace-separated parts as address and port
             let parts: Vec<&str> = line.split_whitespace().collect();
             if parts.len() >= 2 {
-                let addr = parts[0].to_string();
                 if let Ok(port) = parts[1].parse::<u16>() {
-                    active_sets.insert((addr, port));
+                    active_sets.insert((proxy_type, normalize_address(parts[0]), port));
                 }
             }
         }
     }
-    
-    Ok(active_sets)
 }
 
+// One firewall rule per portproxy entry, so the IPv4 and IPv6 rule of a port don't share one.
+// The listen address goes in as a hash, IPv6 addresses make unwieldy rule names.
+pub fn firewall_rule_name(distro_name: &str, proxy_type: PortProxyType, listen_addr: &str, listen_port: u16) -> String {
+    let hash = normalize_address(listen_addr).bytes().fold(0xcbf29ce484222325u64, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3));
+    format!("WSL_Dashboard_{}_{}_{}_{:08x}", distro_name, listen_port, proxy_type.as_str(), hash as u32)
+}
 
-pub fn add_port_proxy(listen_addr: &str, listen_port: u16, connect_addr: &str, connect_port: u16) -> Result<(), String> {
+// Rules created before the proxy type and address were part of the name
+pub fn legacy_firewall_rule_name(distro_name: &str, listen_port: u16) -> String {
+    format!("WSL_Dashboard_{}_{}", distro_name, listen_port)
+}
+
+pub fn add_command(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16, connect_addr: &str, connect_port: u16) -> String {
+    format!(
+        "netsh interface portproxy add {} listenaddress={} listenport={} connectaddress={} connectport={}",
+        proxy_type.as_str(), listen_addr, listen_port, connect_addr, connect_port
+    )
+}
+
+pub fn delete_command(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16) -> String {
+    format!(
+        "netsh interface portproxy delete {} listenaddress={} listenport={}",
+        proxy_type.as_str(), listen_addr, listen_port
+    )
+}
+
+
+pub fn add_port_proxy(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16, connect_addr: &str, connect_port: u16) -> Result<(), String> {
     let port_str = listen_port.to_string();
     let conn_port_str = connect_port.to_string();
 
-    info!("Executing PortProxy Mapping: {}:{} -> {}:{}", listen_addr, listen_port, connect_addr, connect_port);
+    info!("Executing PortProxy Mapping ({}): {}:{} -> {}:{}", proxy_type.as_str(), listen_addr, listen_port, connect_addr, connect_port);
 
     execute_netsh(&[
-        "interface", "portproxy", "add", "v4tov4",
+        "interface", "portproxy", "add", proxy_type.as_str(),
         &format!("listenaddress={}", listen_addr),
         &format!("listenport={}", port_str),
         &format!("connectaddress={}", connect_addr),
@@ -75,11 +122,11 @@ pub fn add_port_proxy(listen_addr: &str, listen_port: u16, connect_addr: &str, c
     Ok(())
 }
 
-pub fn delete_port_proxy(listen_addr: &str, listen_port: u16) -> Result<(), String> {
+pub fn delete_port_proxy(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16) -> Result<(), String> {
     let port_str = listen_port.to_string();
     // Ignore deletion failures as the rule might not exist
     let _ = execute_netsh(&[
-        "interface", "portproxy", "delete", "v4tov4",
+        "interface", "portproxy", "delete", proxy_type.as_str(),
         &format!("listenaddress={}", listen_addr),
         &format!("listenport={}", port_str),
     ]);
@@ -87,29 +134,24 @@ pub fn delete_port_proxy(listen_addr: &str, listen_port: u16) -> Result<(), Stri
 }
 
 // Elevated version of add_port_proxy (triggers UAC)
-pub fn add_port_proxy_elevated(listen_addr: &str, listen_port: u16, connect_addr: &str, connect_port: u16, _enable_firewall: bool, _distro_name: &str) -> Result<(), String> {
-    info!("Requesting elevation for netsh add portproxy (Direct: {}:{} -> {}:{})", listen_addr, listen_port, connect_addr, connect_port);
-    let full_command = format!(
-        "netsh interface portproxy add v4tov4 listenaddress={} listenport={} connectaddress={} connectport={}",
-        listen_addr, listen_port, connect_addr, connect_port
-    );
+pub fn add_port_proxy_elevated(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16, connect_addr: &str, connect_port: u16, _enable_firewall: bool, _distro_name: &str) -> Result<(), String> {
+    info!("Requesting elevation for netsh add portproxy (Direct {}: {}:{} -> {}:{})", proxy_type.as_str(), listen_addr, listen_port, connect_addr, connect_port);
+    let full_command = add_command(proxy_type, listen_addr, listen_port, connect_addr, connect_port);
     crate::utils::system::run_invisible_elevated_command(&full_command)
 }
 
 // Elevated version of add_port_proxy (Single cmd /c line, combined with optional firewall)
 pub fn add_port_proxy_and_firewall_elevated(
+    proxy_type: PortProxyType,
     listen_addr: &str, listen_port: u16, 
     connect_addr: &str, connect_port: u16, 
     enable_firewall: bool, distro_name: &str
 ) -> Result<(), String> {
-    let rule_name = format!("WSL_Dashboard_{}_{}", distro_name, listen_port);
+    let rule_name = firewall_rule_name(distro_name, proxy_type, listen_addr, listen_port);
     let mut commands = Vec::new();
 
     // 1. Add portproxy
-    commands.push(format!(
-        "netsh interface portproxy add v4tov4 listenaddress={} listenport={} connectaddress={} connectport={}",
-        listen_addr, listen_port, connect_addr, connect_port
-    ));
+    commands.push(add_command(proxy_type, listen_addr, listen_port, connect_addr, connect_port));
 
     // 2. Firewall (if enabled)
     if enable_firewall {
@@ -121,8 +163,8 @@ pub fn add_port_proxy_and_firewall_elevated(
 }
 
 // Elevated version of adding firewall rule ONLY (used when distro is not started)
-pub fn add_firewall_rule_elevated(listen_addr: &str, listen_port: u16, distro_name: &str) -> Result<(), String> {
-    let rule_name = format!("WSL_Dashboard_{}_{}", distro_name, listen_port);
+pub fn add_firewall_rule_elevated(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16, distro_name: &str) -> Result<(), String> {
+    let rule_name = firewall_rule_name(distro_name, proxy_type, listen_addr, listen_port);
     let mut commands = Vec::new();
     commands.push(super::firewall::get_delete_rule_cmd_netsh(&rule_name));
     commands.push(super::firewall::get_add_rule_cmd_netsh(&rule_name, listen_port, listen_addr));
@@ -132,21 +174,19 @@ pub fn add_firewall_rule_elevated(listen_addr: &str, listen_port: u16, distro_na
 
 
 // Elevated version of delete_port_proxy (triggers UAC) - NO firewall
-pub fn delete_port_proxy_elevated(listen_addr: &str, listen_port: u16, _distro_name: &str) -> Result<(), String> {
-    info!("Requesting elevation to delete portproxy (Direct): {}:{}", listen_addr, listen_port);
-    let full_command = format!(
-        "netsh interface portproxy delete v4tov4 listenaddress={} listenport={}",
-        listen_addr, listen_port
-    );
+pub fn delete_port_proxy_elevated(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16, _distro_name: &str) -> Result<(), String> {
+    info!("Requesting elevation to delete portproxy (Direct {}): {}:{}", proxy_type.as_str(), listen_addr, listen_port);
+    let full_command = delete_command(proxy_type, listen_addr, listen_port);
     crate::utils::system::run_invisible_elevated_command(&full_command)
 }
 
 // Elevated version of delete_port_proxy AND firewall (Physical deletion)
-pub fn delete_port_proxy_and_firewall_elevated(listen_addr: &str, listen_port: u16, distro_name: &str) -> Result<(), String> {
-    let rule_name = format!("WSL_Dashboard_{}_{}", distro_name, listen_port);
+pub fn delete_port_proxy_and_firewall_elevated(proxy_type: PortProxyType, listen_addr: &str, listen_port: u16, distro_name: &str) -> Result<(), String> {
+    let rule_name = firewall_rule_name(distro_name, proxy_type, listen_addr, listen_port);
     let mut commands = Vec::new();
-    commands.push(format!("netsh interface portproxy delete v4tov4 listenaddress={} listenport={}", listen_addr, listen_port));
+    commands.push(delete_command(proxy_type, listen_addr, listen_port));
     commands.push(super::firewall::get_delete_rule_cmd_netsh(&rule_name));
+    commands.push(super::firewall::get_delete_rule_cmd_netsh(&legacy_firewall_rule_name(distro_name, listen_port)));
     
     crate::utils::system::run_invisible_elevated_commands(commands)
 }
@@ -159,8 +199,7 @@ pub fn apply_port_proxies_elevated(rules_with_ips: Vec<(PortProxyRule, String)>)
     let mut all_cmd_parts = Vec::new();
     for (rule, target_ip) in rules_with_ips {
         // Add portproxy only
-        all_cmd_parts.push(format!("netsh interface portproxy add v4tov4 listenaddress={} listenport={} connectaddress={} connectport={}", 
-            rule.listen_address, rule.listen_port, target_ip, rule.target_port));
+        all_cmd_parts.push(add_command(rule.proxy_type, &rule.listen_address, rule.listen_port, &target_ip, rule.target_port));
     }
     
     crate::utils::system::run_invisible_elevated_commands(all_cmd_parts)
@@ -173,8 +212,7 @@ pub fn delete_port_proxies_elevated(rules: Vec<PortProxyRule>) -> Result<(), Str
     info!("Batch deleting {} port proxy rules via elevation", rules.len());
     let mut all_cmd_parts = Vec::new();
     for rule in rules {
-        all_cmd_parts.push(format!("netsh interface portproxy delete v4tov4 listenaddress={} listenport={}", 
-            rule.listen_address, rule.listen_port));
+        all_cmd_parts.push(delete_command(rule.proxy_type, &rule.listen_address, rule.listen_port));
     }
     
     crate::utils::system::run_invisible_elevated_commands(all_cmd_parts)
@@ -189,24 +227,28 @@ pub fn sync_port_proxies(distro_name: &str, rules: &[PortProxyRule]) -> Result<V
     
     // 1. Check if the distribution is running
     let is_running = is_distro_running(distro_name);
-    
-    // 2. Obtain current IP if running
-    let target_ip = if is_running {
-        match get_distro_ip(distro_name) {
-            Ok(ip) => Some(ip),
+    if !is_running {
+        info!("Distro '{}' is NOT running. Skipping rule application (Applying step), but will still clean up existing rules.", distro_name);
+    }
+
+    // 2. Obtain current IPs if running, only for the address families the rules connect to
+    let fetch_ip = |ipv6: bool| -> Option<String> {
+        if !is_running || !rules.iter().any(|r| r.distro_name == distro_name && r.proxy_type.connects_to_v6() == ipv6) {
+            return None;
+        }
+        match get_distro_address(distro_name, ipv6) {
+            Ok(ip) => {
+                info!("Obtained Target IP for {}: {}", distro_name, ip);
+                Some(ip)
+            }
             Err(e) => {
-                error!("Failed to obtain IP for running distro {}: {}", distro_name, e);
+                error!("Failed to obtain {} for running distro {}: {}", if ipv6 { "IPv6" } else { "IP" }, distro_name, e);
                 None
             }
         }
-    } else {
-        info!("Distro '{}' is NOT running. Skipping rule application (Applying step), but will still clean up existing rules.", distro_name);
-        None
     };
-    
-    if let Some(ref ip) = target_ip {
-        info!("Obtained Target IP for {}: {}", distro_name, ip);
-    }
+    let target_ipv4 = fetch_ip(false);
+    let target_ipv6 = fetch_ip(true);
     
     let mut results = Vec::new();
 
@@ -217,13 +259,14 @@ pub fn sync_port_proxies(distro_name: &str, rules: &[PortProxyRule]) -> Result<V
         }
         
         // 3.a Delete existing rules (if any) - Always executed
-        info!("Step 1/2: Deleting existing rule (if any) for {}:{}", rule.listen_address, rule.listen_port);
-        let _ = delete_port_proxy(&rule.listen_address, rule.listen_port);
+        info!("Step 1/2: Deleting existing {} rule (if any) for {}:{}", rule.proxy_type.as_str(), rule.listen_address, rule.listen_port);
+        let _ = delete_port_proxy(rule.proxy_type, &rule.listen_address, rule.listen_port);
         
         // 3.b Add new rule - Executed only if distro is running and IP is available
-        if let Some(ref ip) = target_ip {
+        let target_ip = if rule.proxy_type.connects_to_v6() { &target_ipv6 } else { &target_ipv4 };
+        if let Some(ip) = target_ip {
             info!("Step 2/2: Applying port proxy rule: {}:{} -> {}:{}", rule.listen_address, rule.listen_port, ip, rule.target_port);
-            match add_port_proxy(&rule.listen_address, rule.listen_port, ip, rule.target_port) {
+            match add_port_proxy(rule.proxy_type, &rule.listen_address, rule.listen_port, ip, rule.target_port) {
                 Ok(_) => {
                     info!("Successfully applied rule: {}:{} -> {}:{}", rule.listen_address, rule.listen_port, ip, rule.target_port);
                     results.push(SyncResult {});
@@ -14,9 +14,13 @@ pub fn get_physical_interfaces() -> Vec<NetworkInterface> {
         for iface in ifaces {
             let ip = iface.ip();
             let is_loopback = ip.is_loopback();
-            let is_v4 = ip.is_ipv4();
+            // Link-local IPv6 needs a zone index, which portproxy cannot listen on
+            let is_listenable = match ip {
+                std::net::IpAddr::V4(_) => true,
+                std::net::IpAddr::V6(v6) => !v6.is_unicast_link_local(),
+            };
             
-            if is_v4 {
+            if is_listenable {
                 let name_lower = iface.name.to_lowercase();
                 let is_virtual = name_lower.contains("wsl") 
                     || name_lower.contains("virtual")
@@ -89,6 +89,78 @@ pub fn get_distro_ip(distro_name: &str) -> Result<String, String> {
     ))
 }
 
+const IPV6_ATTEMPTS: u32 = 2;
+
+// Global IPv6 address of the distribution, retried once for address autoconfiguration.
+// NAT networking gives the VM no IPv6 unless IPv6 is enabled for WSL, so this fails fast.
+pub fn get_distro_ipv6(distro_name: &str) -> Result<String, String> {
+    info!("Fetching IPv6 for distro: {}", distro_name);
+
+    let mut last_error = String::new();
+    for attempt in 1..=IPV6_ATTEMPTS {
+        if attempt > 1 {
+            info!("Retrying IPv6 fetch for {} (attempt {}/{})...", distro_name, attempt, IPV6_ATTEMPTS);
+            std::thread::sleep(std::time::Duration::from_secs(1));
+        }
+
+        let output = Command::new("wsl")
+            .env("WSL_UTF8", "1")
+            .args(&["-d", distro_name, "--", "ip", "-6", "-o", "addr", "show", "scope", "global"])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output();
+
+        match output {
+            Ok(out) if out.status.success() => {
+                let stdout = crate::wsl::decoder::decode_output(&out.stdout);
+                if let Some(ip) = pick_ipv6(&stdout) {
+                    info!("Found IPv6 for {} (attempt {}): {}", distro_name, attempt, ip);
+                    return Ok(ip);
+                }
+                last_error = "no global IPv6 address".to_string();
+            }
+            Ok(out) => {
+                last_error = format!("ip -6 addr failed: {}", crate::wsl::decoder::decode_output(&out.stderr).trim());
+            }
+            Err(e) => {
+                last_error = format!("Failed to execute wsl: {}", e);
+            }
+        }
+    }
+
+    Err(format!(
+        "Could not find IPv6 address for {} after {} attempts. Last error: {}",
+        distro_name, IPV6_ATTEMPTS, last_error
+    ))
+}
+
+// From `ip -6 -o addr` lines such as
+// "2: eth0    inet6 fd00::5/64 scope global dynamic mngtmpaddr ...",
+// preferring stable addresses over temporary and deprecated ones
+fn pick_ipv6(output: &str) -> Option<String> {
+    let mut fallback = None;
+    for line in output.lines() {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        let Some(pos) = parts.iter().position(|&p| p == "inet6") else { continue };
+        let Some(ip) = parts.get(pos + 1).and_then(|cidr| cidr.split('/').next()) else { continue };
+        // `scope global` already filters, but a link-local address can't be forwarded to
+        let Ok(addr) = ip.parse::<std::net::Ipv6Addr>() else { continue };
+        if addr.is_unicast_link_local() {
+            continue;
+        }
+        if parts.contains(&"temporary") || parts.contains(&"deprecated") || parts.contains(&"tentative") {
+            fallback.get_or_insert_with(|| ip.to_string());
+            continue;
+        }
+        return Some(ip.to_string());
+    }
+    fallback
+}
+
+// The address a port proxy rule connects to, IPv6 for the v4tov6 and v6tov6 tables
+pub fn get_distro_address(distro_name: &str, ipv6: bool) -> Result<String, String> {
+    if ipv6 { get_distro_ipv6(distro_name) } else { get_distro_ip(distro_name) }
+}
+
 // Check if the distribution is currently running (fast check, won't start it)
 pub fn is_distro_running(distro_name: &str) -> bool {
     let output = Command::new("wsl")
@@ -105,3 +177,31 @@ pub fn is_distro_running(distro_name: &str) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn picks_global_over_link_local() {
+        let output = "2: eth0    inet6 fe80::215:5dff:fe00:1/64 scope link \\       valid_lft forever preferred_lft forever\n\
+                      2: eth0    inet6 2001:db8::5/64 scope global dynamic mngtmpaddr \\       valid_lft 86000sec preferred_lft 14000sec\n";
+        assert_eq!(pick_ipv6(output).as_deref(), Some("2001:db8::5"));
+        assert_eq!(pick_ipv6("2: eth0    inet6 fe80::1/64 scope link\n"), None);
+    }
+
+    #[test]
+    fn prefers_stable_addresses() {
+        let output = "2: eth0    inet6 2001:db8::aaaa/64 scope global temporary dynamic\n\
+                      2: eth0    inet6 2001:db8::1/64 scope global dynamic mngtmpaddr\n";
+        assert_eq!(pick_ipv6(output).as_deref(), Some("2001:db8::1"));
+        let only_temporary = "2: eth0    inet6 2001:db8::aaaa/64 scope global temporary dynamic\n";
+        assert_eq!(pick_ipv6(only_temporary).as_deref(), Some("2001:db8::aaaa"));
+    }
+
+    #[test]
+    fn no_address_under_nat() {
+        assert_eq!(pick_ipv6(""), None);
+        assert_eq!(pick_ipv6("1: lo    inet 127.0.0.1/8 scope host lo\n"), None);
+    }
+}
@@ -114,12 +114,36 @@ pub fn refresh_localized_strings(app: &AppWindow) {
         tab_general: i18n::tr("settings.tab_general", &[]).into(),
         tab_advanced: i18n::tr("settings.tab_advanced", &[]).into(),
         tab_interface: i18n::tr("settings.tab_interface", &[]).into(),
+        tab_wsl: i18n::tr("settings.tab_wsl", &[]).into(),
         sparse_vhd: i18n::tr("settings.sparse_vhd", &[]).into(),
         sparse_vhd_desc: i18n::tr("settings.sparse_vhd_desc", &[]).into(),
+        recycle_bin: i18n::tr("settings.recycle_bin", &[]).into(),
+        recycle_bin_desc: i18n::tr("settings.recycle_bin_desc", &[]).into(),
+        recycle_retention: i18n::tr("settings.recycle_retention", &[]).into(),
+        recycle_max_size: i18n::tr("settings.recycle_max_size", &[]).into(),
+        recycle_open: i18n::tr("settings.recycle_open", &[]).into(),
+        image_cache: i18n::tr("settings.image_cache", &[]).into(),
+        image_cache_desc: i18n::tr("settings.image_cache_desc", &[]).into(),
+        image_cache_max_size: i18n::tr("settings.image_cache_max_size", &[]).into(),
+        image_cache_empty: i18n::tr("settings.image_cache_empty", &[]).into(),
+        downloads: i18n::tr("settings.downloads", &[]).into(),
+        downloads_desc: i18n::tr("settings.downloads_desc", &[]).into(),
+        download_connections: i18n::tr("settings.download_connections", &[]).into(),
+        download_bandwidth: i18n::tr("settings.download_bandwidth", &[]).into(),
+        downloads_empty: i18n::tr("settings.downloads_empty", &[]).into(),
+        mirrors: i18n::tr("settings.mirrors", &[]).into(),
+        mirrors_desc: i18n::tr("settings.mirrors_desc", &[]).into(),
+        mirrors_empty: i18n::tr("settings.mirrors_empty", &[]).into(),
+        catalog_sources: i18n::tr("settings.catalog_sources", &[]).into(),
+        catalog_sources_desc: i18n::tr("settings.catalog_sources_desc", &[]).into(),
+        catalog_builtin: i18n::tr("settings.catalog_builtin", &[]).into(),
+        catalog_microsoft: i18n::tr("settings.catalog_microsoft", &[]).into(),
+        catalog_empty: i18n::tr("settings.catalog_empty", &[]).into(),
         colorful_icons: i18n::tr("settings.colorful_icons", &[]).into(),
         mail_icon_always: i18n::tr("settings.mail_icon_always", &[]).into(),
         hide_pin_icon: i18n::tr("settings.hide_pin_icon", &[]).into(),
         stop_wsl: i18n::tr("settings.stop_wsl", &[]).into(),
+        apply_restart_wsl: i18n::tr("settings.apply_restart_wsl", &[]).into(),
     });
 
     app.set_about_strings(crate::AboutStrings {
@@ -164,16 +188,17 @@ pub fn refresh_localized_strings(app: &AppWindow) {
 
     update_install_sources(app);
 
+    let expiry_labels: Vec<slint::SharedString> = crate::wsl::ops::sandbox::expiry_labels()
+        .into_iter().map(|s| s.into()).collect();
+    app.set_sandbox_expiry_options(slint::ModelRc::from(Rc::new(VecModel::from(expiry_labels))));
+
     // Rebuild mirror distro names from cache with new language translation.
     // The names are pre-formatted strings stored in Slint's model, so they won't
     // update automatically when the language changes — we must rebuild them here.
     if let Ok(cache) = MIRROR_LIST_CACHE.lock() {
         if !cache.is_empty() {
             let names: Vec<slint::SharedString> = cache.iter()
-                .map(|d| {
-                    let count_label = i18n::tr("add.mirror_count", &[d.sources.len().to_string()]);
-                    format!("{} {} ({})", d.name, d.version, count_label).into()
-                })
+                .map(|d| mirror_display_name(d).into())
                 .collect();
             let model = VecModel::from(names);
             app.set_mirror_distro_names(slint::ModelRc::from(Rc::new(model)));
@@ -187,6 +212,8 @@ pub fn update_install_sources(app: &AppWindow) {
         i18n::tr("add.sources.vhdx", &[]),
         i18n::tr("add.sources.store", &[]),
         i18n::tr("add.sources.mirrors", &[]),
+        i18n::tr("add.sources.template", &[]),
+        i18n::tr("add.sources.oci", &[]),
     ];
 
     let shared_sources: Vec<slint::SharedString> = sources.into_iter().map(|s| s.into()).collect();
@@ -243,7 +270,7 @@ pub async fn refresh_data(app_handle: slint::Weak<AppWindow>, app_state: Arc<Mut
 static IS_REFRESHING: AtomicBool = AtomicBool::new(false);
 
 // Global static snapshot to prevent redundant refreshes across all threads
-static LAST_REFRESH_SNAPSHOT: Lazy<std::sync::Mutex<Option<Vec<(String, String, String, bool, Option<&'static str>)>>>> = Lazy::new(|| std::sync::Mutex::new(None));
+static LAST_REFRESH_SNAPSHOT: Lazy<std::sync::Mutex<Option<Vec<(String, String, String, bool, Option<&'static str>, bool, bool)>>>> = Lazy::new(|| std::sync::Mutex::new(None));
 static LAST_INSTALLABLE_SNAPSHOT: Lazy<std::sync::Mutex<Option<Vec<String>>>> = Lazy::new(|| std::sync::Mutex::new(None));
 
 // Refresh UI list of installed distributions
@@ -259,7 +286,8 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
     });
 
     // Acquire all needed data under a single lock
-    let (distros, executor, is_manual_op) = {
+    let drifted = crate::wsl::ops::lint::drifted_distros();
+    let (distros, executor, is_manual_op, sandboxes, protected) = {
         let lock_timeout = std::time::Duration::from_millis(1000);
         match tokio::time::timeout(lock_timeout, app_state.lock()).await {
             Ok(app_state_lock) => {
@@ -277,6 +305,11 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
                     distros,
                     app_state_lock.wsl_dashboard.executor().clone(),
                     app_state_lock.wsl_dashboard.is_manual_operation(),
+                    app_state_lock.config_manager.get_sandbox_instances().into_iter()
+                        .map(|(name, _)| name)
+                        .collect::<std::collections::HashSet<String>>(),
+                    app_state_lock.config_manager.get_protected_instances().into_iter()
+                        .collect::<std::collections::HashSet<String>>(),
                 )
             }
             Err(_) => {
@@ -287,16 +320,24 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
     };
 
     // Quick check: has the actual data changed before we do heavy icon loading?
-    let current_snapshot: Vec<(String, String, String, bool, Option<&'static str>)> = distros.iter().map(|d| {
+    let current_snapshot: Vec<(String, String, String, bool, Option<&'static str>, bool, bool, bool)> = distros.iter().map(|d| {
         (
             d.name.clone(),
             format!("{:?}", d.status),
             format!("{:?}", d.version),
             d.is_default,
-            crate::utils::icon_mapper::map_name_to_icon_key(&d.name)
+            crate::utils::icon_mapper::map_name_to_icon_key(&d.name),
+            sandboxes.contains(&d.name),
+            protected.contains(&d.name),
+            drifted.contains(&d.name),
         )
     }).collect();
 
+    let protected_names: Vec<String> = distros.iter()
+        .filter(|d| protected.contains(&d.name))
+        .map(|d| d.name.clone())
+        .collect();
+
     let data_changed = {
         let mut last = LAST_REFRESH_SNAPSHOT.lock().unwrap();
         if let Some(ref l) = *last {
@@ -344,6 +385,9 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
                 icon_key,
                 crate::utils::icon_mapper::get_initial(&d.name),
                 icon_key.and_then(crate::utils::icon_mapper::load_icon_data),
+                sandboxes.contains(&d.name),
+                protected.contains(&d.name),
+                drifted.contains(&d.name),
             ));
         }
 
@@ -409,7 +453,7 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
 
         if let Some(app) = app_handle.upgrade() {
             if data_changed {
-                let slint_distros: Vec<Distro> = intermediate_distros.into_iter().map(|(name, status, version, is_default, icon_key, initial, preloaded_icon)| {
+                let slint_distros: Vec<Distro> = intermediate_distros.into_iter().map(|(name, status, version, is_default, icon_key, initial, preloaded_icon, is_sandbox, is_protected, preset_drift)| {
                     let mut image = slint::Image::default();
                     let mut has_icon = false;
                     
@@ -430,6 +474,9 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
                         has_icon,
                         initial: initial.into(),
                         distro_display_name: crate::utils::icon_mapper::get_display_name(icon_key).into(),
+                        is_sandbox,
+                        is_protected,
+                        preset_drift,
                     }
                 }).collect();
 
@@ -445,7 +492,10 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
                             if old_distro.name != new_distro.name 
                                 || old_distro.status != new_distro.status
                                 || old_distro.is_default != new_distro.is_default 
-                                || old_distro.has_icon != new_distro.has_icon {
+                                || old_distro.has_icon != new_distro.has_icon
+                                || old_distro.is_sandbox != new_distro.is_sandbox
+                                || old_distro.is_protected != new_distro.is_protected
+                                || old_distro.preset_drift != new_distro.preset_drift {
                                 data_actually_changed = true;
                                 break;
                             }
@@ -461,6 +511,7 @@ pub async fn refresh_distros_ui(app_handle: slint::Weak<AppWindow>, app_state: A
                     let model_rc = ModelRc::from(Rc::new(model));
                     app.set_distros(model_rc);
                 }
+                crate::app::tray::SystemTray::set_protected(&protected_names);
 
                 // Update WSL error state based on distro count
                 let distro_count = app.get_distros().row_count();
@@ -597,7 +648,42 @@ pub async fn refresh_installable_distros(app_handle: slint::Weak<AppWindow>, app
 
 pub static MIRROR_LIST_CACHE: Lazy<std::sync::Mutex<Vec<crate::api::models::DistroInfo>>> = Lazy::new(|| std::sync::Mutex::new(Vec::new()));
 
-pub async fn refresh_mirror_distros(app_handle: slint::Weak<AppWindow>, _app_state: Arc<Mutex<AppState>>) {
+// "<name> <version> (<n> mirrors)", with the catalog labels once user catalogs are in use.
+// The add view strips everything from the last " (" to get the distro id back.
+pub fn mirror_display_name(d: &crate::api::models::DistroInfo) -> String {
+    let count_label = i18n::tr("add.mirror_count", &[d.sources.len().to_string()]);
+    if d.catalogs.iter().all(|c| c.is_empty()) {
+        return format!("{} {} ({})", d.name, d.version, count_label);
+    }
+    let labels: Vec<String> = d.catalogs.iter()
+        .map(|c| if c.is_empty() { i18n::t("catalog.builtin") } else { c.replace(['(', ')'], "") })
+        .collect();
+    format!("{} {} ({} · {})", d.name, d.version, count_label, labels.join(", "))
+}
+
+pub fn set_catalog_sources_to_ui(app: &AppWindow, catalog: &crate::config::CatalogConfig) {
+    let items: Vec<crate::CatalogSourceItem> = catalog.sources.iter()
+        .map(|s| crate::CatalogSourceItem {
+            name: s.name.clone().into(),
+            location: s.location.clone().into(),
+        })
+        .collect();
+    app.set_catalog_builtin(catalog.include_builtin);
+    app.set_catalog_microsoft(catalog.include_microsoft);
+    app.set_catalog_sources(ModelRc::from(Rc::new(VecModel::from(items))));
+}
+
+// Drop the loaded mirror list so the add view fetches it again with the current catalogs
+pub fn reset_mirror_list(app: &AppWindow) {
+    if let Ok(mut cache) = MIRROR_LIST_CACHE.lock() {
+        cache.clear();
+    }
+    app.set_mirror_distro_names(ModelRc::from(Rc::new(VecModel::from(Vec::<slint::SharedString>::new()))));
+    app.set_selected_mirror_distro("".into());
+    app.set_mirror_list_available(false);
+}
+
+pub async fn refresh_mirror_distros(app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
     let ah = app_handle.clone();
     let _ = slint::invoke_from_event_loop(move || {
         if let Some(app) = ah.upgrade() {
@@ -609,16 +695,41 @@ pub async fn refresh_mirror_distros(app_handle: slint::Weak<AppWindow>, _app_sta
         }
     });
 
-    let (response, install_data_opt, err_msg) = tokio::task::spawn_blocking(move || {
-        let install_data = crate::api::common::wslui_helper_install();
-        if let Some(ref online_distros) = install_data.online_distros {
-            let mirror_url = online_distros.url.clone();
-            (crate::api::common::wslui_helper_mirrors(&mirror_url), Some(install_data), None)
-        } else {
-            (crate::api::models::MirrorListResponse::default(), None, Some("Failed to obtain mirror API information".to_string()))
+    let catalog = app_state.lock().await.config_manager.get_catalog_config().clone();
+
+    // The built-in catalog is labelled "" and user catalogs by their name, see mirror_display_name
+    let (distros, source_url, err_msg) = tokio::task::spawn_blocking(move || {
+        let mut merged = Vec::new();
+        let mut source_url = String::new();
+        let mut errors = Vec::new();
+        if catalog.include_builtin {
+            let install_data = crate::api::common::wslui_helper_install();
+            source_url = install_data.online_source.as_ref().map(|o| o.url.clone()).unwrap_or_default();
+            if let Some(ref online_distros) = install_data.online_distros {
+                let response = crate::api::common::wslui_helper_mirrors(&online_distros.url);
+                crate::api::catalog::merge(&mut merged, response.distros, "");
+            } else {
+                errors.push("Failed to obtain mirror API information".to_string());
+            }
         }
-    }).await.unwrap_or_else(|_| (crate::api::models::MirrorListResponse::default(), None, Some("Background task failed".to_string())));
-    
+        if catalog.include_microsoft {
+            match crate::api::catalog::load(crate::api::distribution_info::OFFICIAL_MANIFEST_URL) {
+                Ok(response) => crate::api::catalog::merge(&mut merged, response.distros, "Microsoft"),
+                Err(e) => errors.push(i18n::tr("catalog.load_failed", &["Microsoft".to_string(), e])),
+            }
+        }
+        for (name, e) in crate::api::catalog::load_sources(&catalog.sources, &mut merged) {
+            errors.push(i18n::tr("catalog.load_failed", &[name, e]));
+        }
+        // Partial failures are only logged as long as something could be listed
+        let err_msg = if merged.is_empty() {
+            Some(if errors.is_empty() { i18n::t("catalog.no_sources") } else { errors.join("\n") })
+        } else {
+            None
+        };
+        (merged, source_url, err_msg)
+    }).await.unwrap_or_else(|_| (Vec::new(), String::new(), Some("Background task failed".to_string())));
+
     if let Some(err) = err_msg {
         let ah = app_handle.clone();
         let _ = slint::invoke_from_event_loop(move || {
@@ -633,10 +744,8 @@ pub async fn refresh_mirror_distros(app_handle: slint::Weak<AppWindow>, _app_sta
         return;
     }
 
-    let install_data = install_data_opt.unwrap();
-    
     // Sort by predefined distribution order, then by name in descending order
-    let mut distros = response.distros;
+    let mut distros = distros;
     distros.sort_by(|a, b| {
         let rank_a = get_distro_sort_rank(&a.name);
         let rank_b = get_distro_sort_rank(&b.name);
@@ -655,13 +764,8 @@ pub async fn refresh_mirror_distros(app_handle: slint::Weak<AppWindow>, _app_sta
     }
 
     let names: Vec<slint::SharedString> = distros.iter()
-        .map(|d| {
-            let count_label = i18n::tr("add.mirror_count", &[d.sources.len().to_string()]);
-            format!("{} {} ({})", d.name, d.version, count_label).into()
-        })
+        .map(|d| mirror_display_name(d).into())
         .collect();
-    
-    let source_url = install_data.online_source.as_ref().map(|o| o.url.clone()).unwrap_or_default();
 
     let ah = app_handle.clone();
     let _ = slint::invoke_from_event_loop(move || {
@@ -740,10 +844,7 @@ pub async fn load_local_mirror_distros(app_handle: slint::Weak<AppWindow>, json_
 
                     let names: Vec<slint::SharedString> = distros
                         .iter()
-                        .map(|d| {
-                            let count_label = i18n::tr("add.mirror_count", &[d.sources.len().to_string()]);
-                            format!("{} {} ({})", d.name, d.version, count_label).into()
-                        })
+                        .map(|d| mirror_display_name(d).into())
                         .collect();
 
                     let _ = slint::invoke_from_event_loop(move || {
@@ -810,6 +911,11 @@ pub async fn load_settings_to_ui(app: &AppWindow, app_state: &Arc<Mutex<AppState
     app.global::<crate::Theme>().set_colorful_icons(settings.colorful_icons);
     app.set_mail_icon_always(settings.mail);
     app.set_hide_pin_icon(settings.hide_pin);
+    app.set_recycle_bin(settings.recycle_bin);
+    app.set_recycle_days(settings.recycle_days as i32);
+    app.set_recycle_max_gb(settings.recycle_max_gb as i32);
+    app.set_image_cache(settings.image_cache);
+    app.set_image_cache_max_gb(settings.image_cache_max_gb as i32);
     app.set_sidebar_collapsed(settings.sidebar_collapsed);
     app.set_tray_autostart(tray.autostart);
     app.set_tray_start_minimized(tray.start_minimized);
@@ -824,6 +930,14 @@ pub async fn load_settings_to_ui(app: &AppWindow, app_state: &Arc<Mutex<AppState
     app.set_sidebar_about(sidebar.about);
     app.set_sidebar_donate(sidebar.donate);
 
+    let catalog = app_state.lock().await.config_manager.get_catalog_config().clone();
+    set_catalog_sources_to_ui(app, &catalog);
+
+    let download = app_state.lock().await.config_manager.get_download_config().clone();
+    app.set_download_connections(download.connections as i32);
+    app.set_download_bandwidth_mb(download.bandwidth_limit_mb as i32);
+    crate::ui::handlers::distro::mirror_install::manager::configure(&download);
+
     // Set RTL mode based on current resolved language
     let current_lang = i18n::current_lang();
     app.global::<crate::AppI18n>().set_is_rtl(i18n::is_rtl(&current_lang));
@@ -121,7 +121,7 @@ fn match_group<'a>(
 // Download the image and decode to raw RGBA8 pixel bytes + width/height
 // Returns (rgba_bytes, width, height), all implement Send, can be safely passed across threads
 fn load_image_pixels(url: &str) -> Result<(Vec<u8>, u32, u32), String> {
-    let resp = ureq::get(url)
+    let resp = crate::network::http::get(url)
         .timeout(std::time::Duration::from_secs(10))
         .call()
         .map_err(|e| e.to_string())?;
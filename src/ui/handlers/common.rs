@@ -53,6 +53,13 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 });
             }
 
+            // Tab 5 is "Settings"
+            if tab == 5 {
+                app.invoke_refresh_image_cache();
+                app.invoke_refresh_downloads();
+                app.invoke_refresh_mirror_stats();
+            }
+
             // Tab 6 is "About" — fetch helper about info once on first visit
             if tab == 6 {
                 super::about::trigger_fetch(ah.clone(), as_ptr.clone());
@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Earlier versions of ~/.wslconfig and the distros' wsl.conf, see utils::config_history
+
+use std::rc::Rc;
+use std::sync::Arc;
+use chrono::TimeZone;
+use tokio::sync::Mutex;
+use slint::{Model, VecModel};
+use tracing::{info, error};
+use crate::{AppWindow, AppState, ConfigHistoryItem, i18n};
+use crate::ui::handlers::distro::config_logic::diff_to_ui;
+use crate::utils::config_history::{self, ConfigTarget};
+use crate::utils::text_diff::diff_with_context;
+use crate::utils::wsl_config::restore_wsl_config;
+use crate::wsl::ops::config::restore_wsl_conf;
+
+const DIFF_CONTEXT_LINES: usize = 2;
+
+fn format_time(time: &str) -> String {
+    time.parse::<i64>().ok()
+        .and_then(|ms| chrono::Local.timestamp_millis_opt(ms).single())
+        .map(|t| t.format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_else(|| time.to_string())
+}
+
+// Target by the label shown at `index`, the list on disk may have changed since
+fn target_at(app: &AppWindow, index: i32) -> Option<ConfigTarget> {
+    let label = app.get_config_history_targets().row_data(usize::try_from(index).ok()?)?;
+    ConfigTarget::from_label(&label)
+}
+
+fn versions_to_ui(target: &ConfigTarget) -> Vec<ConfigHistoryItem> {
+    config_history::list(target).iter()
+        .map(|v| ConfigHistoryItem {
+            id: v.id.clone().into(),
+            time: format_time(&v.time).into(),
+            origin: v.origin.as_str().into(),
+        })
+        .collect()
+}
+
+// Reloads the target list, keeping the selected target when it still has a history
+pub fn refresh(ah: slint::Weak<AppWindow>) {
+    let targets = config_history::targets();
+    let labels: Vec<slint::SharedString> = targets.iter().map(|t| t.label().into()).collect();
+    let _ = slint::invoke_from_event_loop(move || {
+        let Some(app) = ah.upgrade() else { return };
+        let index = app.get_config_history_target_index().clamp(0, (targets.len() as i32 - 1).max(0));
+        let versions = targets.get(index as usize).map(versions_to_ui).unwrap_or_default();
+        app.set_config_history_targets(slint::ModelRc::from(Rc::new(VecModel::from(labels))));
+        app.set_config_history_target_index(index);
+        app.set_config_history_versions(slint::ModelRc::from(Rc::new(VecModel::from(versions))));
+        app.set_config_history_selected("".into());
+        app.set_config_history_diff(slint::ModelRc::default());
+    });
+}
+
+// What the version changed compared to the one before it
+fn show_version(app: &AppWindow, target: &ConfigTarget, id: &str) {
+    let versions = config_history::list(target);
+    let Some(pos) = versions.iter().position(|v| v.id == id) else { return };
+    let content = config_history::read(target, id).unwrap_or_default();
+    let previous = versions.get(pos + 1)
+        .and_then(|v| config_history::read(target, &v.id).ok())
+        .unwrap_or_default();
+    let diff = diff_to_ui(diff_with_context(&previous, &content, DIFF_CONTEXT_LINES));
+    app.set_config_history_selected(id.into());
+    app.set_config_history_diff(slint::ModelRc::from(Rc::new(VecModel::from(diff))));
+}
+
+async fn restore(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, target: ConfigTarget, id: String) {
+    let content = match config_history::read(&target, &id) {
+        Ok(content) => content,
+        Err(e) => {
+            error!("Failed to read version {} of {}: {}", id, target.label(), e);
+            show_result(&ah, i18n::tr("history.restore_failed", &[e]));
+            return;
+        }
+    };
+
+    let result = match &target {
+        ConfigTarget::WslConfig => restore_wsl_config(&content),
+        ConfigTarget::WslConf(distro) => {
+            let wsl_dashboard = app_state.lock().await.wsl_dashboard.clone();
+            wsl_dashboard.increment_manual_operation();
+            let dashboard_c = wsl_dashboard.clone();
+            let _op_guard = scopeguard::guard((), move |_| {
+                dashboard_c.decrement_manual_operation();
+            });
+            restore_wsl_conf(wsl_dashboard.executor(), distro, &content).await
+        }
+    };
+
+    let message = match result {
+        Ok(()) => match &target {
+            ConfigTarget::WslConfig => i18n::t("history.restored_wslconfig"),
+            ConfigTarget::WslConf(distro) => i18n::tr("history.restored_wsl_conf", &[distro.clone()]),
+        },
+        Err(e) => {
+            error!("Failed to restore version {} of {}: {}", id, target.label(), e);
+            i18n::tr("history.restore_failed", &[e])
+        }
+    };
+    refresh(ah.clone());
+    show_result(&ah, message);
+}
+
+fn show_result(ah: &slint::Weak<AppWindow>, message: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_config_history_busy(false);
+            app.set_current_message(message.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    app.on_select_config_history_target(move |index| {
+        let Some(app) = ah.upgrade() else { return };
+        let versions = target_at(&app, index).map(|t| versions_to_ui(&t)).unwrap_or_default();
+        app.set_config_history_versions(slint::ModelRc::from(Rc::new(VecModel::from(versions))));
+        app.set_config_history_selected("".into());
+        app.set_config_history_diff(slint::ModelRc::default());
+    });
+
+    let ah = app_handle.clone();
+    app.on_show_config_history_version(move |id| {
+        let Some(app) = ah.upgrade() else { return };
+        let Some(target) = target_at(&app, app.get_config_history_target_index()) else { return };
+        if app.get_config_history_selected() == id {
+            app.set_config_history_selected("".into());
+            app.set_config_history_diff(slint::ModelRc::default());
+            return;
+        }
+        show_version(&app, &target, &id);
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_restore_config_history_version(move |id| {
+        let Some(app) = ah.upgrade() else { return };
+        let Some(target) = target_at(&app, app.get_config_history_target_index()) else { return };
+        info!("Operation: Restore version {} of {}", id, target.label());
+        app.set_config_history_busy(true);
+        tokio::spawn(restore(ah.clone(), as_ptr.clone(), target, id.to_string()));
+    });
+}
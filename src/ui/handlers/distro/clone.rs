@@ -71,12 +71,34 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                             .to_string_lossy()
                             .to_string();
 
-                        app.set_clone_source_name(name_str.into());
+                        // Suggest a hostname derived from the new instance name, within the
+                        // 63 character label limit
+                        let hostname: String = target_name
+                            .chars()
+                            .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+                            .collect::<String>()
+                            .trim_start_matches('-')
+                            .chars()
+                            .take(63)
+                            .collect::<String>()
+                            .trim_end_matches('-')
+                            .to_string();
+
+                        app.set_clone_source_name(name_str.clone().into());
                         app.set_clone_target_name(target_name.into());
-                        app.set_clone_target_path(target_path.into());
+                        app.set_clone_hostname(hostname.into());
+                        app.set_clone_default_user("".into());
+                        app.set_clone_regen_machine_id(true);
+                        app.set_clone_regen_ssh_keys(true);
+                        app.set_clone_as_sandbox(false);
+                        app.set_clone_sandbox_expiry(0);
+                        app.set_clone_target_path(target_path.clone().into());
                         app.set_clone_base_path(distro_location.to_string().into());
                         app.set_clone_error("".into());
+                        app.set_clone_space_plan("".into());
                         app.set_show_clone_dialog(true);
+
+                        tokio::spawn(refresh_clone_space_plan(ah.clone(), as_ptr, name_str, target_path));
                     }
                 });
             });
@@ -85,6 +107,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
 
     {
         let ah_select = app_handle.clone();
+        let as_select = app_state.clone();
         app.on_select_clone_folder(move || {
             if let Some(path) = rfd::FileDialog::new()
                 .set_title(i18n::t("dialog.select_clone_dir"))
@@ -93,8 +116,11 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 if let Some(app) = ah_select.upgrade() {
                     let target_name = app.get_clone_target_name().to_string();
                     let final_path = path.join(target_name).to_string_lossy().to_string();
-                    app.set_clone_target_path(final_path.into());
+                    app.set_clone_target_path(final_path.clone().into());
                     app.set_clone_base_path(path.to_string_lossy().to_string().into());
+
+                    let source_name = app.get_clone_source_name().to_string();
+                    tokio::spawn(refresh_clone_space_plan(ah_select.clone(), as_select.clone(), source_name, final_path));
                 }
             }
         });
@@ -124,6 +150,9 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     return;
                 }
 
+                // Disk space preflight for the temp and target volumes
+                let plan = refresh_clone_space_plan(ah_weak.clone(), as_ptr_outer.clone(), source_name.to_string(), target_path.to_string()).await;
+
                 if let Some(app) = ah_weak.upgrade() {
                     let app: AppWindow = app;
                     if app.get_is_cloning() || app.get_is_exporting() || app.get_is_moving() {
@@ -155,7 +184,36 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         }
                     }
 
-                    // 4. Validation: Directory emptiness
+                    // 4. Validation: Identity reset options
+                    let hostname = app.get_clone_hostname().trim().to_string();
+                    if !hostname.is_empty() && !crate::wsl::ops::identity::is_valid_hostname(&hostname) {
+                        app.set_clone_error(i18n::t("dialog.clone_hostname_invalid").into());
+                        return;
+                    }
+                    let default_user = app.get_clone_default_user().trim().to_string();
+                    if !default_user.is_empty() && !crate::wsl::ops::identity::is_valid_username(&default_user) {
+                        app.set_clone_error(i18n::t("dialog.clone_user_invalid").into());
+                        return;
+                    }
+                    let identity = crate::wsl::ops::identity::IdentityReset {
+                        hostname: if hostname.is_empty() { None } else { Some(hostname) },
+                        regenerate_machine_id: app.get_clone_regen_machine_id(),
+                        regenerate_ssh_keys: app.get_clone_regen_ssh_keys(),
+                        default_user: if default_user.is_empty() { None } else { Some(default_user) },
+                    };
+                    let sandbox_expiry = if app.get_clone_as_sandbox() { Some(app.get_clone_sandbox_expiry()) } else { None };
+
+                    // 5. Validation: Enough disk space on every involved volume
+                    if let Some(err) = plan.shortage() {
+                        error!("Clone blocked by disk space preflight: {}", err);
+                        app.set_clone_error(err.into());
+                        return;
+                    }
+
+                    // A sandbox may only take the folder with it when it is created here
+                    let sandbox_dir = sandbox_expiry.and(crate::wsl::ops::sandbox::new_install_dir(target_path.as_str()));
+
+                    // 6. Validation: Directory emptiness
                     let p = std::path::Path::new(target_path.as_str());
                     if p.exists() {
                         if p.is_dir() {
@@ -205,7 +263,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                             return;
                         }
 
-                        super::clone_logic::perform_clone(ah_clone, as_ptr, source_name_inner, target_name_inner, target_path_inner).await;
+                        super::clone_logic::perform_clone(ah_clone, as_ptr, source_name_inner, target_name_inner, target_path_inner, identity, sandbox_expiry, sandbox_dir).await;
                     });
                 }
             });
@@ -232,3 +290,26 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         // Placeholder
     });
 }
+
+// Compute the disk space plan for cloning into target_path and show it in the dialog
+async fn refresh_clone_space_plan(
+    ah: slint::Weak<AppWindow>,
+    as_ptr: Arc<Mutex<AppState>>,
+    source_name: String,
+    target_path: String,
+) -> crate::wsl::ops::preflight::SpacePlan {
+    let executor = {
+        let state = as_ptr.lock().await;
+        state.wsl_dashboard.executor().clone()
+    };
+    let (temp_dir, _) = super::resolve_temp_path(as_ptr.clone(), &source_name, "clone_tmp", "vhdx").await;
+    let plan = crate::wsl::ops::preflight::plan_clone(&executor, &source_name, &temp_dir.to_string_lossy(), &target_path).await;
+
+    let summary = plan.summary();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_clone_space_plan(summary.into());
+        }
+    });
+    plan
+}
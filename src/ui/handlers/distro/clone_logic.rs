@@ -6,7 +6,6 @@ use tokio::sync::Mutex;
 use tracing::{info};
 use crate::{AppWindow, AppState, i18n};
 use crate::ui::data::refresh_distros_ui;
-use std::io::{Read, Write};
 
 pub async fn perform_clone(
     ah_clone: slint::Weak<AppWindow>,
@@ -14,6 +13,9 @@ pub async fn perform_clone(
     source_name: String,
     target_name: String,
     target_path: String,
+    identity: crate::wsl::ops::identity::IdentityReset,
+    sandbox_expiry: Option<i32>,
+    sandbox_dir: Option<String>,
 ) {
     let _guard = crate::ui::data::BusyGuard::new();
     {
@@ -75,35 +77,35 @@ pub async fn perform_clone(
             let ah_clone = ah_clone.clone();
             let source_name = source_name.clone();
             move || {
-                let mut source_file = std::fs::File::open(&source_path).map_err(|e| format!("Open source failed: {}", e))?;
-                let mut target_file = std::fs::File::create(&target_path).map_err(|e| format!("Create target failed: {}", e))?;
-                let total_size = source_file.metadata().map_err(|e| e.to_string())?.len();
-                let mut copied = 0u64;
-                let mut buffer = vec![0u8; 8 * 1024 * 1024]; // 8MB buffer on heap
-                let mut last_update = 0u64;
-
-                while copied < total_size {
-                    let bytes_read = source_file.read(&mut buffer).map_err(|e| e.to_string())?;
-                    if bytes_read == 0 { break; }
-                    target_file.write_all(&buffer[..bytes_read]).map_err(|e| e.to_string())?;
-                    copied += bytes_read as u64;
-
-                    // Update UI every ~64MB
-                    if copied - last_update > 64 * 1024 * 1024 || copied == total_size {
-                        last_update = copied;
+                // Nothing resumes an interrupted clone, so a failed copy is discarded
+                let options = crate::wsl::ops::vhdx_copy::CopyOptions { resume: false, ..Default::default() };
+                crate::wsl::ops::vhdx_copy::copy_vhdx(
+                    std::path::Path::new(&source_path),
+                    std::path::Path::new(&target_path),
+                    &options,
+                    |progress| {
                         let ah_inner = ah_clone.clone();
                         let source_name_inner = source_name.clone();
-                        let mb = copied / 1024 / 1024;
+                        let key = match progress.phase {
+                            crate::wsl::ops::vhdx_copy::CopyPhase::Copying => "operation.cloning_step1_wsl2",
+                            crate::wsl::ops::vhdx_copy::CopyPhase::Verifying => "operation.verifying_copy",
+                        };
+                        let text = progress.display();
                         let _ = slint::invoke_from_event_loop(move || {
                             if let Some(app) = ah_inner.upgrade() {
-                                let msg = i18n::tr("operation.cloning_step1_wsl2", &[source_name_inner, format!("{} MB", mb)]);
+                                let msg = i18n::tr(key, &[source_name_inner, text]);
                                 app.set_task_status_text(msg.into());
                                 app.set_task_status_visible(true);
                             }
                         });
-                    }
-                }
-                Ok(total_size)
+                    },
+                ).map(|outcome| {
+                    info!(
+                        "WSL2 Clone: copied {} bytes ({} written, resumed from {}), sha256: {}",
+                        outcome.total, outcome.written, outcome.resumed_from, outcome.sha256.unwrap_or_default()
+                    );
+                    outcome.total
+                })
             }
         }).await.map_err(|e| e.to_string()).and_then(|r| r);
 
@@ -116,7 +118,6 @@ pub async fn perform_clone(
                     app.set_current_message(i18n::tr("dialog.clone_failed_export", &[e]).into());
                     app.set_show_message_dialog(true);
                 }
-                let _ = std::fs::remove_file(&temp_vhdx_file);
                 return;
             }
         };
@@ -144,7 +145,7 @@ pub async fn perform_clone(
         ]).await;
 
         // Cleanup temp file
-        let _ = std::fs::remove_file(&temp_vhdx_file);
+        crate::wsl::ops::vhdx_copy::discard_partial(std::path::Path::new(&temp_vhdx_file));
 
         if import_result.success {
             // Monitor VHDX growth/stability after "success" return
@@ -197,13 +198,23 @@ pub async fn perform_clone(
                 crate::wsl::ops::sparse::apply_sparse_vhdx(&executor, &target_name, true, false).await;
             }
 
+            // Post-import identity reset
+            let identity_warning = apply_identity(&ah_clone, &executor, &target_name, &identity).await;
+            if let Some(expiry) = sandbox_expiry {
+                mark_sandbox(&as_ptr, &target_name, expiry, sandbox_dir.as_deref()).await;
+            }
+
             // 1. Set Success Message
             let ah_inner = ah_clone.clone();
             let source = source_name.clone();
             let target = target_name.clone();
             let _ = slint::invoke_from_event_loop(move || {
                 if let Some(app) = ah_inner.upgrade() {
-                    app.set_current_message(i18n::tr("dialog.clone_success", &[source, target]).into());
+                    let mut msg = i18n::tr("dialog.clone_success", &[source, target]);
+                    if let Some(warning) = identity_warning {
+                        msg = format!("{}\n{}", msg, warning);
+                    }
+                    app.set_current_message(msg.into());
                 }
             });
 
@@ -335,13 +346,23 @@ pub async fn perform_clone(
                 }
             }
 
+            // Post-import identity reset
+            let identity_warning = apply_identity(&ah_clone, &executor, &target_name, &identity).await;
+            if let Some(expiry) = sandbox_expiry {
+                mark_sandbox(&as_ptr, &target_name, expiry, sandbox_dir.as_deref()).await;
+            }
+
             // Success Path
             let ah_inner = ah_clone.clone();
             let source = source_name.clone();
             let target = target_name.clone();
             let _ = slint::invoke_from_event_loop(move || {
                 if let Some(app) = ah_inner.upgrade() {
-                    app.set_current_message(i18n::tr("dialog.clone_success", &[source, target]).into());
+                    let mut msg = i18n::tr("dialog.clone_success", &[source, target]);
+                    if let Some(warning) = identity_warning {
+                        msg = format!("{}\n{}", msg, warning);
+                    }
+                    app.set_current_message(msg.into());
                 }
             });
 
@@ -380,3 +401,40 @@ pub async fn perform_clone(
         });
     }
 }
+
+// Reset hostname, machine-id, SSH host keys and default user of the new clone.
+// Returns a warning message when any step failed.
+async fn apply_identity(
+    ah: &slint::Weak<AppWindow>,
+    executor: &crate::wsl::executor::WslCommandExecutor,
+    target_name: &str,
+    identity: &crate::wsl::ops::identity::IdentityReset,
+) -> Option<String> {
+    if identity.is_empty() {
+        return None;
+    }
+    let ah_inner = ah.clone();
+    let target = target_name.to_string();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_inner.upgrade() {
+            app.set_task_status_text(i18n::tr("operation.cloning_identity", &[target]).into());
+        }
+    });
+
+    let result = crate::wsl::ops::identity::apply_identity_reset(executor, target_name, identity).await;
+    if result.success {
+        None
+    } else {
+        Some(i18n::tr("dialog.clone_identity_failed", &[result.error.unwrap_or_default()]))
+    }
+}
+
+async fn mark_sandbox(as_ptr: &Arc<Mutex<AppState>>, target_name: &str, expiry: i32, created_dir: Option<&str>) {
+    let config_manager = {
+        let state = as_ptr.lock().await;
+        state.config_manager.clone()
+    };
+    if let Err(e) = crate::wsl::ops::sandbox::mark_sandbox(&config_manager, target_name, expiry, created_dir) {
+        tracing::warn!("Failed to mark '{}' as sandbox: {}", target_name, e);
+    }
+}
@@ -18,6 +18,17 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         let name = distro_name.to_string();
         
         tokio::spawn(async move {
+            if crate::config::ConfigManager::is_protected(&name) {
+                let msg = i18n::tr("protect.blocked", &[name.clone()]);
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah.upgrade() {
+                        app.set_current_message(msg.into());
+                        app.set_show_message_dialog(true);
+                    }
+                });
+                return;
+            }
+
             let (executor, debug_cleanup) = {
                 let state = as_ptr.lock().await;
                 (state.wsl_dashboard.executor().clone(), state.debug_config.distro.cleanup_script.clone())
@@ -53,39 +64,17 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         Path::new(&info.install_location).to_path_buf()
                     };
 
-                    if base_path.as_os_str().len() >= 3 {
-                        let path_str = base_path.to_string_lossy();
-                        let clean_path = if path_str.starts_with(r"\\?\") {
-                            &path_str[4..]
-                        } else {
-                            &path_str
-                        };
-
-                        if clean_path.len() >= 3 {
-                            let drive = &clean_path[..3];
-                            let free_bytes = crate::utils::system::get_disk_free_space(drive);
-                            free_space = format!("{:.2} GB", free_bytes as f64 / (1024.0 * 1024.0 * 1024.0));
-                            
-                            let vhdx_bytes = if vhdx_size.contains("GB") {
-                                (vhdx_size.split_whitespace().next().unwrap_or("0").parse::<f64>().unwrap_or(0.0) * 1024.0 * 1024.0 * 1024.0) as u64
-                            } else if vhdx_size.contains("MB") {
-                                (vhdx_size.split_whitespace().next().unwrap_or("0").parse::<f64>().unwrap_or(0.0) * 1024.0 * 1024.0) as u64
-                            } else {
-                                0
-                            };
-                            sufficient = free_bytes > (vhdx_bytes + 2 * 1024 * 1024 * 1024);
-                        }
+                    let vhdx_bytes = crate::wsl::ops::preflight::parse_size_string(&vhdx_size);
+                    let plan = crate::wsl::ops::preflight::plan_compress(&base_path.to_string_lossy(), vhdx_bytes);
+                    if let Some(volume) = plan.volumes.first() {
+                        free_space = crate::wsl::ops::preflight::format_bytes(volume.free);
                     }
+                    sufficient = !plan.volumes.is_empty() && plan.fits();
                     
-                    let mut b_path = if base_path.is_dir() {
-                        base_path.join(format!("{}.tar", name)).to_string_lossy().to_string()
-                    } else {
-                        format!("{}.tar", base_path.display())
-                    };
-                    if b_path.starts_with(r"\\?\") {
-                        b_path = b_path[4..].to_string();
-                    }
-                    backup_path = b_path;
+                    let b_path = compress::backup_path_for(&name, &info.vhdx_path, &info.install_location)
+                        .to_string_lossy()
+                        .to_string();
+                    backup_path = b_path.trim_start_matches(r"\\?\").to_string();
 
                     // Use local debug cleanup script if configured; skip network API call
                     if debug_cleanup.is_empty() {
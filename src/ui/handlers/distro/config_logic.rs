@@ -3,12 +3,22 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use std::rc::Rc;
 use std::sync::atomic::{AtomicBool, Ordering};
+use once_cell::sync::Lazy;
+use slint::VecModel;
 use tracing::{info, error, debug};
-use crate::{AppState, AppWindow};
-use crate::wsl::ops::config::{get_wsl_conf, check_wsl_version_support, validate_wsl_conf, save_wsl_conf, serialize_wsl_conf, WslVersionMeta, WslConf};
+use crate::{AppState, AppWindow, ConfigDiffLine};
+use crate::utils::text_diff::{diff_with_context, DiffKind, DiffLine};
+use crate::ui::handlers::wsl_global::diagnostics_to_ui;
+use crate::wsl::ops::lint::lint_wsl_conf;
+use crate::wsl::ops::config::{read_wsl_conf, parse_wsl_conf, check_wsl_version_support, validate_wsl_conf, save_wsl_conf, update_wsl_conf, WslVersionMeta, WslConf};
 
 static IS_LOADING_CONFIG: AtomicBool = AtomicBool::new(false);
+// wsl.conf as it was when the dialog opened, the preview shows the changes against it
+static LOADED_CONTENT: Lazy<std::sync::Mutex<String>> = Lazy::new(|| std::sync::Mutex::new(String::new()));
+// Unchanged lines shown around each change in the preview
+const PREVIEW_CONTEXT_LINES: usize = 2;
 
 pub async fn handle_configs_clicked(
     ah: slint::Weak<AppWindow>,
@@ -53,7 +63,23 @@ pub async fn handle_configs_clicked(
     let version_meta = check_wsl_version_support(&executor).await;
 
     // 2. Read wsl.conf
-    let conf = get_wsl_conf(&executor, &name).await;
+    let content = match read_wsl_conf(&executor, &name).await {
+        Ok(content) => content,
+        Err(e) => {
+            error!("Failed to read wsl.conf for '{}': {}", name, e);
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(app) = ah.upgrade() {
+                    app.set_task_status_visible(false);
+                    app.set_current_message(crate::i18n::tr("wsl_conf.load_failed", &[e]).into());
+                    app.set_show_message_dialog(true);
+                }
+            });
+            return;
+        }
+    };
+    let conf = parse_wsl_conf(&content);
+    let diagnostics = lint_wsl_conf(&content, &version_meta);
+    *LOADED_CONTENT.lock().unwrap() = content;
 
     // 3. Update UI
     let _ = slint::invoke_from_event_loop(move || {
@@ -74,7 +100,8 @@ pub async fn handle_configs_clicked(
             // Clear errors
             app.set_wsl_config_user_error("".into());
             app.set_wsl_config_command_error("".into());
-            app.set_wsl_config_preview_content("".into());
+            app.set_wsl_config_preview_lines(slint::ModelRc::default());
+            app.set_wsl_config_diagnostics(slint::ModelRc::from(Rc::new(VecModel::from(diagnostics_to_ui("", &diagnostics)))));
 
             // Map [automount]
             app.set_wsl_config_automount_enabled(conf.automount.enabled.unwrap_or(true));
@@ -155,12 +182,28 @@ fn collect_conf_from_ui(app: &AppWindow) -> (WslConf, WslVersionMeta) {
     (conf, meta)
 }
 
+// Also used by the preset preview
+pub fn diff_to_ui(lines: Vec<DiffLine>) -> Vec<ConfigDiffLine> {
+    lines.into_iter()
+        .map(|l| ConfigDiffLine {
+            kind: l.kind.as_str().into(),
+            line: if l.kind == DiffKind::Skipped { "".into() } else { l.line.to_string().into() },
+            text: l.text.into(),
+        })
+        .collect()
+}
+
 pub async fn handle_request_preview(ah: slint::Weak<AppWindow>) {
     let _ = slint::invoke_from_event_loop(move || {
         if let Some(app) = ah.upgrade() {
             let (conf, meta) = collect_conf_from_ui(&app);
-            let preview = serialize_wsl_conf(&conf, &meta);
-            app.set_wsl_config_preview_content(preview.into());
+            let original = LOADED_CONTENT.lock().unwrap().clone();
+            let updated = update_wsl_conf(&original, &conf, &meta);
+            let lines = diff_to_ui(diff_with_context(&original, &updated, PREVIEW_CONTEXT_LINES));
+            app.set_wsl_config_preview_lines(slint::ModelRc::from(Rc::new(VecModel::from(lines))));
+            // Lint what would be written, so line numbers match the preview
+            let diagnostics = diagnostics_to_ui("", &lint_wsl_conf(&updated, &meta));
+            app.set_wsl_config_diagnostics(slint::ModelRc::from(Rc::new(VecModel::from(diagnostics))));
         }
     });
 }
@@ -0,0 +1,149 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::VecModel;
+use tracing::{info, error};
+use crate::{AppWindow, AppState, DownloadJobItem, i18n};
+use crate::wsl::ops::preflight::format_bytes;
+use super::mirror_install::DownloadError;
+use super::mirror_install::manager::{self, JobSnapshot, JobStatus};
+use super::sanitize_instance_name;
+
+fn show_message(ah: &slint::Weak<AppWindow>, msg: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_current_message(msg.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+fn display_name(name: &str, version: &str) -> String {
+    if version.is_empty() {
+        name.to_string()
+    } else {
+        format!("{} {}", name, version)
+    }
+}
+
+fn detail_text(snapshot: &JobSnapshot) -> String {
+    let job = &snapshot.job;
+    // The live percentage is fresher than the last checkpoint
+    let downloaded = match snapshot.percent {
+        Some(percent) if job.total_size > 0 => (job.total_size as f64 * percent as f64 / 100.0) as u64,
+        _ => job.downloaded(),
+    };
+    let progress = if job.total_size > 0 {
+        format!("{} / {}", format_bytes(downloaded), format_bytes(job.total_size))
+    } else {
+        format_bytes(downloaded)
+    };
+    match job.status {
+        JobStatus::Running => i18n::tr("downloads.status_running", &[
+            progress,
+            snapshot.speed.clone().unwrap_or_else(|| "0.0 MB/s".to_string()),
+        ]),
+        JobStatus::Queued => i18n::tr("downloads.status_queued", &[progress]),
+        JobStatus::Paused => i18n::tr("downloads.status_paused", &[progress]),
+        JobStatus::Failed => i18n::tr("downloads.status_failed", &[progress, job.error.clone()]),
+        JobStatus::Completed => i18n::tr("downloads.status_completed", &[format_bytes(job.total_size)]),
+    }
+}
+
+// Reload the download queue shown in settings
+pub async fn refresh_download_list(ah: slint::Weak<AppWindow>) {
+    let jobs = tokio::task::spawn_blocking(manager::list).await.unwrap_or_default();
+    let rows: Vec<(String, String, String, &'static str)> = jobs.iter()
+        .map(|s| (s.job.id.clone(), display_name(&s.job.name, &s.job.version), detail_text(s), s.job.status.as_str()))
+        .collect();
+
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            let items: Vec<DownloadJobItem> = rows.into_iter()
+                .map(|(id, name, detail, status)| DownloadJobItem {
+                    id: id.into(),
+                    name: name.into(),
+                    detail: detail.into(),
+                    status: status.into(),
+                })
+                .collect();
+            app.set_download_jobs(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, _app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    manager::set_listener(move || {
+        tokio::spawn(refresh_download_list(ah.clone()));
+    });
+
+    let ah = app_handle.clone();
+    app.on_refresh_downloads(move || {
+        tokio::spawn(refresh_download_list(ah.clone()));
+    });
+
+    app.on_pause_download(move |id| {
+        info!("Operation: Pause download - {}", id);
+        manager::pause(&id);
+    });
+
+    // Resumed from settings the download runs on its own; once complete it can be
+    // installed from here or is picked up by the next install of that distro.
+    let ah = app_handle.clone();
+    app.on_resume_download(move |id| {
+        info!("Operation: Resume download - {}", id);
+        let id = id.to_string();
+        if !manager::can_resume(&id) {
+            return;
+        }
+        let ah = ah.clone();
+        tokio::spawn(async move {
+            match manager::run(&id, |_| {}).await {
+                Ok(_) | Err(DownloadError::Paused) | Err(DownloadError::Cancelled) => {}
+                Err(e) => {
+                    error!("Resumed download {} failed: {}", id, e);
+                    let name = manager::get(&id).map(|j| display_name(&j.name, &j.version)).unwrap_or(id);
+                    show_message(&ah, i18n::tr("downloads.failed", &[name, e.to_string()]));
+                }
+            }
+        });
+    });
+
+    // A finished download goes through the archive install form, like a cached image
+    let ah = app_handle.clone();
+    app.on_install_download(move |id| {
+        info!("Operation: Install from download - {}", id);
+        let job = manager::get(&id).filter(|j| j.status == JobStatus::Completed && j.path().exists());
+        let Some(job) = job else {
+            show_message(&ah, i18n::t("downloads.not_found"));
+            tokio::spawn(refresh_download_list(ah.clone()));
+            return;
+        };
+        if let Some(app) = ah.upgrade() {
+            app.set_selected_source_idx(0);
+            app.invoke_source_selected(0);
+            app.set_install_file_path(job.path().to_string_lossy().to_string().into());
+            app.set_new_instance_name(sanitize_instance_name(&display_name(&job.name, &job.version)).into());
+            // Switching tabs recomputes the install path from the new name
+            app.invoke_select_tab(1);
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_remove_download(move |id| {
+        info!("Operation: Remove download - {}", id);
+        let ah = ah.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            let result = tokio::task::spawn_blocking(move || manager::remove(&id)).await;
+            if let Ok(Err(e)) = result {
+                show_message(&ah, i18n::tr("downloads.remove_failed", &[e]));
+            }
+        });
+    });
+}
@@ -57,6 +57,8 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         }
                         app.set_export_distro_name(name_str.into());
                         app.set_export_compress(true);
+                        app.set_export_as_template(false);
+                        app.set_export_template_description("".into());
                         let default_path = app.get_distro_location();
                         app.set_export_target_path(default_path);
                         app.set_export_error("".into());
@@ -121,6 +123,8 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
 
                     app.set_export_error("".into());
                     let use_compress_inner = app.get_export_compress();
+                    let as_template = app.get_export_as_template();
+                    let template_description = app.get_export_template_description().trim().to_string();
                     app.set_show_export_dialog(false);
                     
                     app.set_is_exporting(true);
@@ -200,6 +204,24 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
 
                         stop_signal.store(true, std::sync::atomic::Ordering::Relaxed);
 
+                        // Register the fresh export in the template library
+                        let mut template_note = String::new();
+                        if result.success && as_template {
+                            let template = crate::config::DistroTemplate::with_default_provisioning(
+                                &distro_source_inner,
+                                &template_description,
+                                &export_file_str,
+                            );
+                            let res = {
+                                let state = as_ptr.lock().await;
+                                state.config_manager.save_template(template)
+                            };
+                            template_note = match res {
+                                Ok(_) => i18n::tr("dialog.template_saved", &[distro_source_inner.clone()]),
+                                Err(e) => i18n::tr("dialog.template_save_failed", &[e.to_string()]),
+                            };
+                        }
+
                         let ah_final = ah_clone.clone();
                         let distro_final = distro_source_inner.clone();
                         let file_final = export_file_str.clone();
@@ -209,7 +231,11 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                                 app.set_is_exporting(false);
                                 
                                 if result.success {
-                                    app.set_current_message(i18n::tr("dialog.export_success", &[distro_final, file_final]).into());
+                                    let mut msg = i18n::tr("dialog.export_success", &[distro_final, file_final]);
+                                    if !template_note.is_empty() {
+                                        msg = format!("{}\n{}", msg, template_note);
+                                    }
+                                    app.set_current_message(msg.into());
                                 } else {
                                     let err = result.error.unwrap_or_else(|| i18n::t("dialog.error"));
                                     app.set_current_message(i18n::tr("dialog.export_failed", &[err]).into());
@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::VecModel;
+use tracing::{info, error};
+use crate::{AppWindow, AppState, CachedImageItem, i18n};
+use crate::wsl::ops::preflight::format_bytes;
+use crate::wsl::ops::image_cache;
+use super::sanitize_instance_name;
+
+fn format_used_time(ms: &str) -> String {
+    use chrono::TimeZone;
+    let ms = ms.parse::<i64>().unwrap_or(0);
+    chrono::Local.timestamp_millis_opt(ms).single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn show_message(ah: &slint::Weak<AppWindow>, msg: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_current_message(msg.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+fn display_name(image: &image_cache::CachedImage) -> String {
+    if image.version.is_empty() {
+        image.name.clone()
+    } else {
+        format!("{} {}", image.name, image.version)
+    }
+}
+
+// Reload the cached image list shown in settings
+pub async fn refresh_image_cache_list(ah: slint::Weak<AppWindow>, as_ptr: Arc<Mutex<AppState>>) {
+    let max_gb = {
+        let state = as_ptr.lock().await;
+        state.config_manager.get_settings().image_cache_max_gb
+    };
+    let images = tokio::task::spawn_blocking(image_cache::list).await.unwrap_or_default();
+
+    let total: u64 = images.iter().map(|i| i.size).sum();
+    let summary = i18n::tr("image_cache.summary", &[
+        images.len().to_string(),
+        format_bytes(total),
+        max_gb.to_string(),
+    ]);
+    let rows: Vec<(String, String, String)> = images.iter()
+        .map(|i| (i.sha256.clone(), display_name(i), format!("{} · {}", format_bytes(i.size), format_used_time(&i.last_used))))
+        .collect();
+
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            let items: Vec<CachedImageItem> = rows.into_iter()
+                .map(|(id, name, detail)| CachedImageItem {
+                    id: id.into(),
+                    name: name.into(),
+                    detail: detail.into(),
+                })
+                .collect();
+            app.set_cached_images(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+            app.set_image_cache_summary(summary.into());
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_refresh_image_cache(move || {
+        tokio::spawn(refresh_image_cache_list(ah.clone(), as_ptr.clone()));
+    });
+
+    // Re-create a distro from the cache: prefill the archive install form, no network needed
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_install_cached_image(move |id| {
+        info!("Operation: Install from cached image - {}", id);
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let id = id.to_string();
+        let _ = slint::spawn_local(async move {
+            let image = tokio::task::spawn_blocking(move || image_cache::find_by_id(&id)).await.ok().flatten();
+            let Some(image) = image else {
+                show_message(&ah, i18n::t("image_cache.not_found"));
+                refresh_image_cache_list(ah, as_ptr).await;
+                return;
+            };
+
+            if let Some(app) = ah.upgrade() {
+                app.set_selected_source_idx(0);
+                app.invoke_source_selected(0);
+                app.set_install_file_path(image.path().to_string_lossy().to_string().into());
+                app.set_new_instance_name(sanitize_instance_name(&display_name(&image)).into());
+                // Switching tabs recomputes the install path from the new name
+                app.invoke_select_tab(1);
+            }
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_template_from_cached_image(move |id| {
+        info!("Operation: Create template from cached image - {}", id);
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            let image = tokio::task::spawn_blocking(move || image_cache::find_by_id(&id)).await.ok().flatten();
+            let Some(image) = image else {
+                show_message(&ah, i18n::t("image_cache.not_found"));
+                refresh_image_cache_list(ah, as_ptr).await;
+                return;
+            };
+
+            let name = sanitize_instance_name(&display_name(&image));
+            let template = crate::config::DistroTemplate::with_default_provisioning(
+                &name,
+                &image.source,
+                &image.path().to_string_lossy(),
+            );
+            let result = {
+                let state = as_ptr.lock().await;
+                state.config_manager.save_template(template)
+            };
+            let msg = match result {
+                Ok(_) => i18n::tr("image_cache.template_saved", &[name]),
+                Err(e) => {
+                    error!("Failed to save template from cached image: {}", e);
+                    i18n::tr("dialog.template_save_failed", &[e.to_string()])
+                }
+            };
+            show_message(&ah, msg);
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_remove_cached_image(move |id| {
+        info!("Operation: Remove cached image - {}", id);
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let id = id.to_string();
+        tokio::spawn(async move {
+            let result = tokio::task::spawn_blocking(move || image_cache::remove(&id)).await;
+            if let Ok(Err(e)) = result {
+                show_message(&ah, i18n::tr("image_cache.remove_failed", &[e]));
+            }
+            refresh_image_cache_list(ah, as_ptr).await;
+        });
+    });
+}
@@ -1,11 +1,12 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::rc::Rc;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use slint::{ComponentHandle, Model};
+use slint::{ComponentHandle, Model, VecModel};
 use tracing::{debug, info, warn};
-use crate::{AppWindow, AppState, i18n};
+use crate::{AppWindow, AppState, TemplateParamItem, TemplateStepItem, i18n};
 use crate::ui::data::refresh_installable_distros;
 use crate::utils::system::copy_to_clipboard;
 use super::sanitize_instance_name;
@@ -64,7 +65,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             .set_title(i18n::t("dialog.select_install_file"));
         
         dialog = match source_idx {
-            0 => dialog.add_filter(i18n::t("dialog.archive"), &["tar", "tar.gz", "tar.xz", "wsl"]),
+            0 => dialog.add_filter(i18n::t("dialog.archive"), &["tar", "tar.gz", "tar.xz", "wsl", "appx", "appxbundle", "msix", "msixbundle"]),
             1 => dialog.add_filter(i18n::t("dialog.vhdx"), &["vhdx"]),
             _ => dialog,
         };
@@ -75,9 +76,15 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 
                 if let Some(name_os) = path.file_name() {
                     let mut full_stem = name_os.to_string_lossy().to_string();
+                    let is_package = path.extension()
+                        .is_some_and(|ext| crate::wsl::ops::appx::PACKAGE_EXTENSIONS.iter().any(|p| ext.eq_ignore_ascii_case(p)));
 
                     // Optimize: Remove specific suffixes first to get clean name
-                    if full_stem.ends_with(".tar.gz") {
+                    if is_package {
+                        if let Some(name) = crate::wsl::ops::appx::suggested_name(&path) {
+                            full_stem = name;
+                        }
+                    } else if full_stem.ends_with(".tar.gz") {
                         full_stem.truncate(full_stem.len() - 7);
                     } else if full_stem.ends_with(".tar.xz") {
                         full_stem.truncate(full_stem.len() - 7);
@@ -128,11 +135,86 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         .to_string_lossy()
                         .to_string();
                     app.set_new_instance_path(new_path.into());
+
+                    // A .wsl package may name itself, read that in the background
+                    if crate::wsl::ops::wsl_package::is_wsl_package(&path) {
+                        let ah = ah.clone();
+                        let package = path.clone();
+                        tokio::spawn(async move {
+                            let pkg = package.clone();
+                            let name = tokio::task::spawn_blocking(move || crate::wsl::ops::wsl_package::default_name(&pkg))
+                                .await
+                                .ok()
+                                .flatten();
+                            let Some(name) = name else { return; };
+                            let _ = slint::invoke_from_event_loop(move || {
+                                let Some(app) = ah.upgrade() else { return; };
+                                // Skip if another file was picked meanwhile
+                                if app.get_install_file_path().as_str() != package.display().to_string() {
+                                    return;
+                                }
+                                let sanitized = sanitize_instance_name(&name);
+                                let distro_location = app.get_distro_location().to_string();
+                                let new_path = std::path::Path::new(&distro_location)
+                                    .join(&sanitized)
+                                    .to_string_lossy()
+                                    .to_string();
+                                app.set_new_instance_name(sanitized.into());
+                                app.set_new_instance_path(new_path.into());
+                            });
+                        });
+                    }
                 }
             }
         }
     });
 
+    // Suggest an instance name from the image reference as it is typed
+    let ah = app_handle.clone();
+    app.on_oci_reference_edited(move |reference| {
+        if let Some(app) = ah.upgrade() {
+            let Ok(image) = crate::wsl::ops::oci::ImageReference::parse(&reference) else { return; };
+            let sanitized = sanitize_instance_name(&image.suggested_name());
+            let distro_location = app.get_distro_location().to_string();
+            let new_path = std::path::Path::new(&distro_location)
+                .join(&sanitized)
+                .to_string_lossy()
+                .to_string();
+            app.set_new_instance_name(sanitized.into());
+            app.set_new_instance_path(new_path.into());
+        }
+    });
+
+    // Disk space plan for the install confirmation of a mirror distro
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_install_confirm_opened(move || {
+        let Some(app) = ah.upgrade() else { return };
+        app.set_install_space_plan("".into());
+        if app.get_selected_source_idx() != 3 {
+            return;
+        }
+        app.set_install_space_plan(i18n::t("dialog.space_plan_checking").into());
+        let internal_id = app.get_selected_install_distro().to_string();
+        let final_name = app.get_new_instance_name().to_string();
+        let install_path = app.get_new_instance_path().to_string();
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        tokio::spawn(async move {
+            let config_manager = as_ptr.lock().await.config_manager.clone();
+            let plan = super::mirror_install::preview_space_plan(&config_manager, &internal_id, &final_name, &install_path).await;
+            let summary = plan.map(|p| match p.shortage() {
+                Some(err) => format!("{}\n{}", p.summary(), err),
+                None => p.summary(),
+            }).unwrap_or_default();
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(app) = ah.upgrade() {
+                    app.set_install_space_plan(summary.into());
+                }
+            });
+        });
+    });
+
     let ah = app_handle.clone();
     app.on_distro_selected(move |val| {
         if let Some(app) = ah.upgrade() {
@@ -258,6 +340,221 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                       }
                   }
              });
+        } else if idx == 4 {
+             let _ = slint::spawn_local(refresh_template_list(ah.clone(), as_ptr.clone(), None));
+        }
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_template_selected(move |name| {
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let name = name.to_string();
+        let _ = slint::spawn_local(async move {
+            let template = {
+                let state = as_ptr.lock().await;
+                state.config_manager.get_template(&name)
+            };
+            let Some(template) = template else { return; };
+            if let Some(app) = ah.upgrade() {
+                let params: Vec<TemplateParamItem> = template.parameters.iter()
+                    .map(|p| TemplateParamItem {
+                        key: p.key.clone().into(),
+                        label: p.label.clone().into(),
+                        value: p.default.clone().into(),
+                    })
+                    .collect();
+                app.set_template_params(slint::ModelRc::from(Rc::new(VecModel::from(params))));
+                let steps: Vec<TemplateStepItem> = template.steps.iter()
+                    .map(|s| TemplateStepItem {
+                        name: s.name.clone().into(),
+                        user: s.user.clone().into(),
+                        script: s.script.clone().into(),
+                    })
+                    .collect();
+                app.set_template_steps(slint::ModelRc::from(Rc::new(VecModel::from(steps))));
+                app.set_template_default_user(template.default_user.clone().into());
+                app.set_template_description(template.description.clone().into());
+                app.set_selected_install_distro(template.name.clone().into());
+
+                let sanitized = sanitize_instance_name(&template.name);
+                app.set_new_instance_name(sanitized.clone().into());
+                let distro_location = app.get_distro_location().to_string();
+                let new_path = std::path::Path::new(&distro_location)
+                    .join(&sanitized)
+                    .to_string_lossy()
+                    .to_string();
+                app.set_new_instance_path(new_path.into());
+            }
+        });
+    });
+
+    let ah = app_handle.clone();
+    app.on_template_param_edited(move |idx, value| {
+        if let Some(app) = ah.upgrade() {
+            let params = app.get_template_params();
+            if let Some(mut item) = params.row_data(idx as usize) {
+                item.value = value;
+                params.set_row_data(idx as usize, item);
+            }
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_template_step_edited(move |idx, step| {
+        if let Some(app) = ah.upgrade() {
+            let steps = app.get_template_steps();
+            if (idx as usize) < steps.row_count() {
+                steps.set_row_data(idx as usize, step);
+            }
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_add_template_step(move || {
+        if let Some(app) = ah.upgrade() {
+            let mut steps: Vec<TemplateStepItem> = app.get_template_steps().iter().collect();
+            steps.push(TemplateStepItem {
+                name: "".into(),
+                user: crate::config::models::default_step_user().into(),
+                script: "".into(),
+            });
+            app.set_template_steps(slint::ModelRc::from(Rc::new(VecModel::from(steps))));
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_remove_template_step(move |idx| {
+        if let Some(app) = ah.upgrade() {
+            let mut steps: Vec<TemplateStepItem> = app.get_template_steps().iter().collect();
+            if (idx as usize) < steps.len() {
+                steps.remove(idx as usize);
+                app.set_template_steps(slint::ModelRc::from(Rc::new(VecModel::from(steps))));
+            }
+        }
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_save_template_steps(move || {
+        let Some(app) = ah.upgrade() else { return; };
+        let name = app.get_selected_install_distro().to_string();
+        // Steps without a script have nothing to run
+        let steps: Vec<crate::config::models::ProvisionStep> = app.get_template_steps().iter()
+            .filter(|s| !s.script.trim().is_empty())
+            .map(|s| crate::config::models::ProvisionStep {
+                name: s.name.to_string(),
+                script: s.script.to_string(),
+                user: if s.user.trim().is_empty() {
+                    crate::config::models::default_step_user()
+                } else {
+                    s.user.trim().to_string()
+                },
+            })
+            .collect();
+        let default_user = app.get_template_default_user().trim().to_string();
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let _ = slint::spawn_local(async move {
+            let res = {
+                let state = as_ptr.lock().await;
+                match state.config_manager.get_template(&name) {
+                    Some(mut template) => {
+                        template.steps = steps;
+                        template.default_user = default_user;
+                        state.config_manager.save_template(template).map_err(|e| e.to_string())
+                    }
+                    None => Err(i18n::t("add.no_templates")),
+                }
+            };
+            let Some(app) = ah.upgrade() else { return; };
+            match res {
+                Ok(_) => {
+                    app.set_current_message(i18n::tr("dialog.template_steps_saved", &[name.clone()]).into());
+                    app.set_show_message_dialog(true);
+                    refresh_template_list(ah.clone(), as_ptr.clone(), Some(name)).await;
+                }
+                Err(e) => {
+                    warn!("Failed to save steps of template '{}': {}", name, e);
+                    app.set_current_message(format!("{}: {}", i18n::t("install.error"), e).into());
+                    app.set_show_message_dialog(true);
+                }
+            }
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_add_template_from_file(move || {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title(i18n::t("dialog.select_template_file"))
+            .add_filter(i18n::t("dialog.archive"), &["tar", "tar.gz", "tar.xz", "wsl"])
+            .add_filter(i18n::t("dialog.vhdx"), &["vhdx"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let file_name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+        let stem = [".tar.gz", ".tar.xz", ".tar", ".wsl", ".vhdx"].iter()
+            .find_map(|ext| file_name.strip_suffix(ext))
+            .unwrap_or(&file_name)
+            .to_string();
+        let name = sanitize_instance_name(&stem);
+        let template = crate::config::DistroTemplate::with_default_provisioning(&name, "", &path.display().to_string());
+
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let _ = slint::spawn_local(async move {
+            let res = {
+                let state = as_ptr.lock().await;
+                state.config_manager.save_template(template)
+            };
+            if let Err(e) = res {
+                warn!("Failed to register template '{}': {}", name, e);
+                if let Some(app) = ah.upgrade() {
+                    app.set_install_status(format!("{}: {}", i18n::t("install.error"), e).into());
+                }
+                return;
+            }
+            refresh_template_list(ah, as_ptr, Some(name)).await;
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_remove_template(move |name| {
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let name = name.to_string();
+        let _ = slint::spawn_local(async move {
+            {
+                let state = as_ptr.lock().await;
+                if let Err(e) = state.config_manager.remove_template(&name) {
+                    warn!("Failed to remove template '{}': {}", name, e);
+                }
+            }
+            refresh_template_list(ah, as_ptr, None).await;
+        });
+    });
+
+    let ah = app_handle.clone();
+    app.on_select_user_data(move || {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title(i18n::t("dialog.select_user_data"))
+            .add_filter(i18n::t("dialog.user_data"), &["yaml", "yml", "cfg", "txt"])
+            .pick_file()
+        else {
+            return;
+        };
+        let Some(app) = ah.upgrade() else { return };
+        match load_user_data(&path.display().to_string()) {
+            Ok(_) => app.set_user_data_path(path.display().to_string().into()),
+            Err(e) => {
+                app.set_current_message(i18n::tr("install.user_data_invalid", &[e]).into());
+                app.set_show_message_dialog(true);
+            }
         }
     });
 
@@ -277,14 +574,55 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         let as_ptr = as_ptr.clone();
         
         let _ = slint::spawn_local(async move {
-            let (manager, internal_id) = if let Some(app) = ah_weak.upgrade() {
+            let (manager, internal_id, template_values, sandbox_expiry, oci_credentials, user_data) = if let Some(app) = ah_weak.upgrade() {
                 if app.get_is_installing() {
                     warn!("[UI Event] Installation already in progress, ignoring click.");
                     return;
                 }
 
+                // Parameter values entered for template provisioning
+                let template_values: std::collections::HashMap<String, String> = if source_idx == 4 {
+                    app.get_template_params().iter()
+                        .map(|p| (p.key.to_string(), p.value.to_string()))
+                        .collect()
+                } else {
+                    Default::default()
+                };
+
+                let sandbox_expiry = if source_idx == 4 && app.get_template_as_sandbox() {
+                    Some(app.get_template_sandbox_expiry())
+                } else {
+                    None
+                };
+
+                // Registry login entered for an OCI image, `docker login` is used otherwise
+                let oci_username = app.get_oci_username().trim().to_string();
+                let oci_credentials = if source_idx == 5 && !oci_username.is_empty() {
+                    Some(crate::wsl::ops::oci::Credentials {
+                        username: oci_username,
+                        password: app.get_oci_password().to_string(),
+                    })
+                } else {
+                    None
+                };
+
+                // First-boot user-data is checked before anything is installed
+                let user_data_path = app.get_user_data_path().to_string();
+                let user_data = if user_data_path.is_empty() {
+                    None
+                } else {
+                    match load_user_data(&user_data_path) {
+                        Ok(data) => Some(data),
+                        Err(e) => {
+                            app.set_install_success(false);
+                            app.set_install_status(i18n::tr("install.user_data_invalid", &[e]).into());
+                            return;
+                        }
+                    }
+                };
+
                 let state = as_ptr.lock().await;
-                (state.wsl_dashboard.clone(), app.get_selected_install_distro().to_string())
+                (state.wsl_dashboard.clone(), app.get_selected_install_distro().to_string(), template_values, sandbox_expiry, oci_credentials, user_data)
             } else {
                 return;
             };
@@ -302,7 +640,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             if let Some(app) = ah_weak.upgrade() {
                 let ah_logic = app.as_weak();
                 let _ = tokio::spawn(async move {
-                    super::install_logic::perform_install(ah_logic, as_ptr, source_idx, name, friendly_name, internal_id, install_path, file_path).await;
+                    super::install_logic::perform_install(ah_logic, as_ptr, source_idx, name, friendly_name, internal_id, install_path, file_path, template_values, sandbox_expiry, oci_credentials, user_data).await;
                 });
             }
         });
@@ -312,3 +650,37 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         let _ = copy_to_clipboard(text.as_str());
     });
 }
+
+fn load_user_data(path: &str) -> Result<crate::wsl::ops::cloud_init::UserData, String> {
+    let text = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    crate::wsl::ops::cloud_init::parse(&text)
+}
+
+// Reload the template library into the source combo box and select a template
+async fn refresh_template_list(ah: slint::Weak<AppWindow>, as_ptr: Arc<Mutex<AppState>>, select: Option<String>) {
+    let templates = {
+        let state = as_ptr.lock().await;
+        state.config_manager.get_templates()
+    };
+    let Some(app) = ah.upgrade() else { return; };
+
+    let names: Vec<slint::SharedString> = templates.iter().map(|t| t.name.clone().into()).collect();
+    app.set_template_names(slint::ModelRc::from(Rc::new(VecModel::from(names))));
+
+    let idx = select
+        .and_then(|s| templates.iter().position(|t| t.name == s))
+        .unwrap_or(0);
+    match templates.get(idx) {
+        Some(t) => {
+            app.set_selected_template_idx(idx as i32);
+            app.invoke_template_selected(t.name.clone().into());
+        }
+        None => {
+            app.set_selected_install_distro("".into());
+            app.set_template_description(i18n::t("add.no_templates").into());
+            app.set_template_params(slint::ModelRc::from(Rc::new(VecModel::<TemplateParamItem>::default())));
+            app.set_template_steps(slint::ModelRc::from(Rc::new(VecModel::<TemplateStepItem>::default())));
+            app.set_template_default_user("".into());
+        }
+    }
+}
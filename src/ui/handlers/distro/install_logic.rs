@@ -18,6 +18,10 @@ pub async fn perform_install(
     internal_id: String,
     install_path: String,
     file_path: String,
+    template_values: std::collections::HashMap<String, String>,
+    sandbox_expiry: Option<i32>,
+    oci_credentials: Option<crate::wsl::ops::oci::Credentials>,
+    user_data: Option<crate::wsl::ops::cloud_init::UserData>,
 ) {
     let _guard = crate::ui::data::BusyGuard::new();
     info!("perform_install started: source={}, name={}, friendly={}, internal_id={}, path={}", 
@@ -153,6 +157,8 @@ pub async fn perform_install(
 
     let mut success = false;
     let mut error_msg = String::new();
+    // Install folder created for a sandbox, removed together with it
+    let mut sandbox_dir: Option<String> = None;
 
     // 4. Source-specific installation logic
     match source_idx {
@@ -498,6 +504,30 @@ pub async fn perform_install(
                     let base = PathBuf::from(&distro_location);
                     target_path = base.join(&final_name).to_string_lossy().to_string();
                 }
+
+                // Disk space preflight for the target volume
+                let plan = crate::wsl::ops::preflight::plan_import(&file_path, &target_path, source_idx == 1);
+                terminal_buffer.push_str(&format!("{}\n", plan.summary()));
+                let ah_cb = ah.clone();
+                let tb_clone = terminal_buffer.clone();
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah_cb.upgrade() {
+                        let app_typed: AppWindow = app;
+                        app_typed.set_terminal_output(tb_clone.into());
+                    }
+                });
+                if let Some(err) = plan.shortage() {
+                    let ah_cb = ah.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_cb.upgrade() {
+                            let app_typed: AppWindow = app;
+                            app_typed.set_install_success(false);
+                            app_typed.set_install_status(format!("{}: {}", i18n::t("install.error"), err).into());
+                            app_typed.set_is_installing(false);
+                        }
+                    });
+                    return;
+                }
                 
                 let tp_clone = target_path.clone();
                 if let Err(e) = tokio::task::spawn_blocking(move || std::fs::create_dir_all(&tp_clone)).await.unwrap() {
@@ -514,6 +544,50 @@ pub async fn perform_install(
                     return;
                 }
 
+                // Store packages are unpacked first, `wsl --import` only takes the rootfs tarball
+                let mut import_file = file_path.clone();
+                let mut unpack_dir = None;
+                let package = PathBuf::from(&file_path);
+                if source_idx == 0 && tokio::task::spawn_blocking(move || crate::wsl::ops::appx::is_package(&package)).await.unwrap_or(false) {
+                    terminal_buffer.push_str(&format!("{}\n", i18n::t("install.unpacking_package")));
+                    let ah_cb = ah.clone();
+                    let tb_clone = terminal_buffer.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_cb.upgrade() {
+                            let app_typed: AppWindow = app;
+                            app_typed.set_install_status(i18n::t("install.unpacking_package").into());
+                            app_typed.set_terminal_output(tb_clone.into());
+                        }
+                    });
+
+                    let temp_location = config_manager.get_settings().temp_location.clone();
+                    let work_dir = PathBuf::from(&temp_location).join(format!("package_{}", uuid::Uuid::new_v4()));
+                    let (package, wd) = (PathBuf::from(&file_path), work_dir.clone());
+                    let extracted = tokio::task::spawn_blocking(move || crate::wsl::ops::appx::extract_rootfs(&package, &wd))
+                        .await
+                        .map_err(|e| e.to_string())
+                        .and_then(|r| r);
+                    match extracted {
+                        Ok(rootfs) => {
+                            import_file = rootfs.to_string_lossy().to_string();
+                            unpack_dir = Some(work_dir);
+                        }
+                        Err(err) => {
+                            let _ = tokio::fs::remove_dir_all(&work_dir).await;
+                            let ah_cb = ah.clone();
+                            let _ = slint::invoke_from_event_loop(move || {
+                                if let Some(app) = ah_cb.upgrade() {
+                                    let app_typed: AppWindow = app;
+                                    app_typed.set_install_success(false);
+                                    app_typed.set_install_status(format!("{}: {}", i18n::t("install.error"), err).into());
+                                    app_typed.set_is_installing(false);
+                                }
+                            });
+                            return;
+                        }
+                    }
+                }
+
                 let ah_cb = ah.clone();
                 let _ = slint::invoke_from_event_loop(move || {
                     if let Some(app) = ah_cb.upgrade() {
@@ -522,7 +596,18 @@ pub async fn perform_install(
                     }
                 });
 
-                let mut import_args = vec!["--import", &final_name, &target_path, &file_path];
+                // .wsl packages go through `wsl --install --from-file` where available, so
+                // WSL runs their OOBE and sets the default user on first launch
+                let is_package = source_idx == 0 && crate::wsl::ops::wsl_package::is_wsl_package(std::path::Path::new(&file_path));
+                let install_from_file = is_package && {
+                    let meta = crate::wsl::ops::config::check_wsl_version_support(&executor).await;
+                    crate::wsl::ops::wsl_package::supports_install_from_file(&meta.version_string)
+                };
+                let mut import_args = if install_from_file {
+                    vec!["--install", "--from-file", &import_file, "--name", &final_name, "--location", &target_path, "--no-launch"]
+                } else {
+                    vec!["--import", &final_name, &target_path, &import_file]
+                };
                 if source_idx == 1 {
                     import_args.push("--vhd");
                 }
@@ -602,6 +687,9 @@ pub async fn perform_install(
 
                 drop(tx);
                 terminal_buffer = ui_task.await.unwrap_or(terminal_buffer);
+                if let Some(dir) = unpack_dir {
+                    let _ = tokio::fs::remove_dir_all(&dir).await;
+                }
 
                 success = result.success;
                 if !success {
@@ -610,6 +698,9 @@ pub async fn perform_install(
                     }
                     error_msg = result.error.unwrap_or_else(|| i18n::t("install.import_failed"));
                 } else {
+                    if is_package && !install_from_file {
+                        apply_package_default_uid(&executor, &final_name, &file_path, &mut terminal_buffer).await;
+                    }
                     terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.step_3_3", &[final_name.clone()])));
                 }
                 
@@ -655,12 +746,98 @@ pub async fn perform_install(
                 }
             }
         },
+        4 => { // Template
+            if sandbox_expiry.is_some() {
+                // Same default as install_from_template
+                let target_path = if install_path.is_empty() {
+                    PathBuf::from(&config_manager.get_settings().distro_location).join(&final_name).to_string_lossy().to_string()
+                } else {
+                    install_path.clone()
+                };
+                sandbox_dir = crate::wsl::ops::sandbox::new_install_dir(&target_path);
+            }
+            match super::template_install::install_from_template(
+                ah.clone(),
+                Arc::new(executor.clone()),
+                Arc::new(config_manager.clone()),
+                internal_id.clone(),
+                final_name.clone(),
+                install_path.clone(),
+                template_values,
+            ).await {
+                Ok(final_buf) => {
+                    success = true;
+                    let ah_cb = ah.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_cb.upgrade() {
+                            let app_typed: AppWindow = app;
+                            app_typed.set_terminal_output(slint::SharedString::from(final_buf));
+                        }
+                    });
+                }
+                Err((e, final_buf)) => {
+                    success = false;
+                    error_msg = e;
+                    let ah_cb = ah.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_cb.upgrade() {
+                            let app_typed: AppWindow = app;
+                            app_typed.set_terminal_output(slint::SharedString::from(final_buf));
+                        }
+                    });
+                }
+            }
+        },
+        5 => { // OCI Image
+            match super::oci_install::install_from_oci(
+                ah.clone(),
+                Arc::new(executor.clone()),
+                Arc::new(config_manager.clone()),
+                file_path.clone(),
+                oci_credentials,
+                final_name.clone(),
+                install_path.clone(),
+            ).await {
+                Ok(final_buf) => {
+                    success = true;
+                    let ah_cb = ah.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_cb.upgrade() {
+                            let app_typed: AppWindow = app;
+                            app_typed.set_terminal_output(slint::SharedString::from(final_buf));
+                        }
+                    });
+                }
+                Err((e, final_buf)) => {
+                    success = false;
+                    error_msg = e;
+                    let ah_cb = ah.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_cb.upgrade() {
+                            let app_typed: AppWindow = app;
+                            app_typed.set_terminal_output(slint::SharedString::from(final_buf));
+                        }
+                    });
+                }
+            }
+        },
         _ => {
             error_msg = i18n::t("install.unknown_source");
         }
     }
 
+    let mut user_data_failures = Vec::new();
     if success {
+        if let Some(data) = user_data.as_ref().filter(|d| !d.is_empty()) {
+            user_data_failures = apply_user_data(&ah, &executor, &final_name, data).await;
+        }
+
+        if let Some(expiry) = sandbox_expiry {
+            if let Err(e) = crate::wsl::ops::sandbox::mark_sandbox(&config_manager, &final_name, expiry, sandbox_dir.as_deref()) {
+                error!("Failed to mark '{}' as sandbox: {}", final_name, e);
+            }
+        }
+
         // Handle Sparse VHD auto-enable if configured in .wslconfig
         if crate::utils::wsl_config::get_sparse_vhd() {
             let ah_ui = ah.clone();
@@ -696,7 +873,10 @@ pub async fn perform_install(
     let _ = slint::invoke_from_event_loop(move || {
         if let Some(app) = ah_final.upgrade() {
             let app_typed: AppWindow = app;
-            if success {
+            if success && !user_data_failures.is_empty() {
+                app_typed.set_install_success(false);
+                app_typed.set_install_status(i18n::tr("install.user_data_partial", &[final_name_clone, user_data_failures.join(", ")]).into());
+            } else if success {
                 app_typed.set_install_success(true);
                 app_typed.set_install_status(i18n::tr("install.created_success", &[final_name_clone]).into());
             } else {
@@ -711,3 +891,80 @@ pub async fn perform_install(
         refresh_distros_ui(ah.clone(), as_ptr.clone()).await;
     }
 }
+
+// `wsl --import` ignores /etc/wsl-distribution.conf. Its OOBE is interactive and cannot
+// run here, but the package's default user is applied when the image already has it.
+async fn apply_package_default_uid(
+    executor: &crate::wsl::executor::WslCommandExecutor,
+    distro_name: &str,
+    package: &str,
+    terminal_buffer: &mut String,
+) {
+    let path = PathBuf::from(package);
+    let uid = tokio::task::spawn_blocking(move || crate::wsl::ops::wsl_package::default_uid(&path)).await.ok().flatten();
+    let Some(uid) = uid else { return; };
+
+    let uid_str = uid.to_string();
+    let check = executor.execute_command(&["-d", distro_name, "-u", "root", "-e", "getent", "passwd", &uid_str]).await;
+    if !check.success {
+        info!("Package default UID {} does not exist in '{}' yet, keeping root", uid, distro_name);
+        terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.package_oobe_skipped", &[uid_str])));
+        return;
+    }
+    let name = distro_name.to_string();
+    match tokio::task::spawn_blocking(move || crate::utils::registry::set_distro_default_uid(&name, uid).map_err(|e| e.to_string())).await {
+        Ok(Ok(())) => info!("Set default UID of '{}' to {} from the package", distro_name, uid),
+        Ok(Err(e)) => error!("Failed to set default UID of '{}': {}", distro_name, e),
+        Err(e) => error!("Failed to set default UID of '{}': {}", distro_name, e),
+    }
+}
+
+fn append_terminal(ah: &slint::Weak<AppWindow>, text: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            let mut output = app.get_terminal_output().to_string();
+            output.push_str(&text);
+            app.set_terminal_output(output.into());
+        }
+    });
+}
+
+// Run the cloud-init user-data on the new distro, one terminal line per module.
+// Returns the names of the modules that failed.
+async fn apply_user_data(
+    ah: &slint::Weak<AppWindow>,
+    executor: &crate::wsl::executor::WslCommandExecutor,
+    distro_name: &str,
+    data: &crate::wsl::ops::cloud_init::UserData,
+) -> Vec<String> {
+    let ah_status = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_status.upgrade() {
+            app.set_install_status(i18n::t("install.user_data_applying").into());
+        }
+    });
+    let mut header = format!("\n{}\n", i18n::tr("install.user_data_start", &[distro_name.to_string()]));
+    if !data.ignored_keys.is_empty() {
+        header.push_str(&format!("{}\n", i18n::tr("install.user_data_ignored", &[data.ignored_keys.join(", ")])));
+    }
+    append_terminal(ah, header);
+
+    let results = crate::wsl::ops::cloud_init::apply(executor, distro_name, data, |result| {
+        let mut line = if result.success {
+            i18n::tr("install.user_data_module_ok", &[result.module.to_string(), result.message.clone()])
+        } else {
+            i18n::tr("install.user_data_module_failed", &[result.module.to_string(), result.message.clone()])
+        };
+        line.push('\n');
+        if !result.output.is_empty() {
+            line.push_str(&format!("{}\n", result.output));
+        }
+        append_terminal(ah, line);
+    }).await;
+
+    results.into_iter()
+        .filter(|r| !r.success)
+        .map(|r| r.module.to_string())
+        .collect()
+}
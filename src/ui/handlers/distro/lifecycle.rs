@@ -3,7 +3,7 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::info;
+use tracing::{info, error};
 use crate::{AppWindow, AppState, i18n};
 
 pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
@@ -16,9 +16,9 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             let ah = ah_outer.clone();
             let as_ptr = as_outer.clone();
             tokio::spawn(async move {
-                let manager = {
+                let (manager, config_manager) = {
                     let app_state = as_ptr.lock().await;
-                    app_state.wsl_dashboard.clone()
+                    (app_state.wsl_dashboard.clone(), app_state.config_manager.clone())
                 };
 
                 // Sentinel Check: Distro busy?
@@ -43,7 +43,9 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     }
                 });
 
-                manager.start_distro(&name).await;
+                if manager.start_distro(&name).await.success {
+                    crate::wsl::ops::sandbox::mark_sandbox_started(&config_manager, &name);
+                }
                 let ah_res = ah.clone();
                 let _ = slint::invoke_from_event_loop(move || {
                     if let Some(app) = ah_res.upgrade() {
@@ -187,6 +189,18 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     });
                     return;
                 }
+
+                // Sentinel Check: Protected?
+                if crate::config::ConfigManager::is_protected(&name) {
+                    let msg = i18n::tr("protect.blocked", &[name.to_string()]);
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah.upgrade() {
+                            app.set_current_message(msg.into());
+                            app.set_show_message_dialog(true);
+                        }
+                    });
+                    return;
+                }
                 
                 let ah_init = ah.clone();
                 let _ = slint::invoke_from_event_loop(move || {
@@ -196,7 +210,38 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     }
                 });
 
-                dashboard.delete_distro(&config_manager, &name).await;
+                // Keep a restorable copy first when the recycle bin is enabled
+                let mut recycled = None;
+                if config_manager.get_settings().recycle_bin {
+                    let ah_status = ah.clone();
+                    let _ = slint::invoke_from_event_loop(move || {
+                        if let Some(app) = ah_status.upgrade() {
+                            app.set_task_status_text(i18n::t("operation.recycling").into());
+                        }
+                    });
+                    match crate::wsl::ops::recycle::move_to_bin(&dashboard, &config_manager, &name).await {
+                        Ok(entry) => recycled = Some(entry),
+                        Err(e) => {
+                            error!("Failed to move '{}' into the recycle bin: {}", name, e);
+                            let msg = i18n::tr("recycle.move_failed", &[name.to_string(), e]);
+                            let _ = slint::invoke_from_event_loop(move || {
+                                if let Some(app) = ah.upgrade() {
+                                    app.set_task_status_visible(false);
+                                    app.set_current_message(msg.into());
+                                    app.set_show_message_dialog(true);
+                                }
+                            });
+                            return;
+                        }
+                    }
+                }
+
+                let result = dashboard.delete_distro(&config_manager, &name).await;
+                if let Some(entry) = recycled.as_ref() {
+                    if !result.success {
+                        crate::wsl::ops::recycle::undo_move(entry);
+                    }
+                }
                 
                 let ah_final = ah.clone();
                 let _ = slint::invoke_from_event_loop(move || {
@@ -208,8 +253,17 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         });
     }
 
-    // Delete confirmation log
+    // Delete confirmation, refused up front for protected distros
+    let ah_confirm = app_handle.clone();
     app.on_delete_clicked(move |name| {
         info!("Operation: Open delete confirmation - {}", name);
+        let Some(app) = ah_confirm.upgrade() else { return; };
+        if crate::config::ConfigManager::is_protected(&name) {
+            app.set_current_message(i18n::tr("protect.blocked", &[name.to_string()]).into());
+            app.set_show_message_dialog(true);
+            return;
+        }
+        app.set_distro_to_delete(name);
+        app.set_show_delete_confirmation(true);
     });
 }
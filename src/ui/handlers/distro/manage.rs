@@ -90,7 +90,13 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                             proxy_exports = Some(exports);
                         }
                         
-                        let _ = executor.open_distro_terminal(&name, &working_dir, proxy_exports).await;
+                        let config_manager = {
+                            let app_state = as_ptr.lock().await;
+                            app_state.config_manager.clone()
+                        };
+                        if executor.open_distro_terminal(&name, &working_dir, proxy_exports).await.success {
+                            crate::wsl::ops::sandbox::mark_sandbox_started(&config_manager, &name);
+                        }
                     }
                 }
                 refresh_distros_ui(ah, as_ptr).await;
@@ -171,12 +177,14 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         app.set_show_vscode_startup(true);
                     }
 
-                    let working_dir = {
+                    let (working_dir, config_manager) = {
                         let state = as_ptr.lock().await;
-                        state.config_manager.get_instance_config(&name).vscode_dir
+                        (state.config_manager.get_instance_config(&name).vscode_dir, state.config_manager.clone())
                     };
                     
-                    let _ = executor.open_distro_vscode(&name, &working_dir).await;
+                    if executor.open_distro_vscode(&name, &working_dir).await.success {
+                        crate::wsl::ops::sandbox::mark_sandbox_started(&config_manager, &name);
+                    }
                     refresh_distros_ui(ah, as_ptr).await;
 
                     slint::Timer::single_shot(std::time::Duration::from_secs(6), move || {
@@ -375,6 +383,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     app.set_settings_vscode_dir(instance_config.vscode_dir.into());
                     app.set_settings_startup_script(instance_config.startup_script.into());
                     app.set_settings_terminal_proxy(instance_config.terminal_proxy);
+                    app.set_settings_protected(instance_config.protected);
                     let is_task_exists = crate::network::scheduler::check_task_exists();
                     app.set_settings_autostart(instance_config.auto_startup && is_task_exists);
                     app.set_settings_is_task_exists(is_task_exists);
@@ -422,7 +431,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
     {
         let ah_outer = app_handle.clone();
         let as_outer = app_state.clone();
-        app.on_confirm_distro_settings(move |name, terminal_dir, vscode_dir, is_default, autostart, startup_script, terminal_proxy| {
+        app.on_confirm_distro_settings(move |name, terminal_dir, vscode_dir, is_default, autostart, startup_script, terminal_proxy, protected| {
             let ah = ah_outer.clone();
             let as_ptr = as_outer.clone();
             let name = name.to_string();
@@ -431,7 +440,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             let startup_script = startup_script.to_string();
 
             let _ = slint::spawn_local(async move {
-                super::settings_logic::perform_save_settings(ah, as_ptr, name, terminal_dir, vscode_dir, is_default, autostart, startup_script, terminal_proxy).await;
+                super::settings_logic::perform_save_settings(ah, as_ptr, name, terminal_dir, vscode_dir, is_default, autostart, startup_script, terminal_proxy, protected).await;
             });
         });
     }
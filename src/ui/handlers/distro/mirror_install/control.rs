@@ -0,0 +1,79 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::time::Instant;
+
+// Pause switch of one download, shared by its connections
+#[derive(Default)]
+pub struct TransferControl {
+    paused: AtomicBool,
+    notify: Notify,
+}
+
+impl TransferControl {
+    pub fn pause(&self) {
+        self.paused.store(true, Ordering::Relaxed);
+        self.notify.notify_waiters();
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    // Resolves once `pause` has been called
+    pub async fn paused(&self) {
+        loop {
+            let notified = self.notify.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+            if self.is_paused() {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+// Token bucket shared by every connection of every download. The bucket holds at
+// most one second worth of bytes; a connection taking more than is available goes
+// into debt and sleeps it off, which keeps the long-run rate at the limit.
+pub struct RateLimiter {
+    bytes_per_sec: AtomicU64,
+    bucket: std::sync::Mutex<(Instant, f64)>,
+}
+
+impl RateLimiter {
+    pub fn new() -> Self {
+        Self {
+            bytes_per_sec: AtomicU64::new(0),
+            bucket: std::sync::Mutex::new((Instant::now(), 0.0)),
+        }
+    }
+
+    // 0 disables the limit
+    pub fn set_limit(&self, bytes_per_sec: u64) {
+        self.bytes_per_sec.store(bytes_per_sec, Ordering::Relaxed);
+    }
+
+    pub async fn acquire(&self, bytes: usize) {
+        let rate = self.bytes_per_sec.load(Ordering::Relaxed);
+        if rate == 0 {
+            return;
+        }
+        let rate = rate as f64;
+        let wait = {
+            let mut bucket = self.bucket.lock().unwrap();
+            let (last, tokens) = *bucket;
+            let now = Instant::now();
+            let tokens = (tokens + now.duration_since(last).as_secs_f64() * rate).min(rate) - bytes as f64;
+            *bucket = (now, tokens);
+            if tokens < 0.0 { -tokens / rate } else { 0.0 }
+        };
+        if wait > 0.0 {
+            tokio::time::sleep(Duration::from_secs_f64(wait)).await;
+        }
+    }
+}
@@ -1,19 +1,23 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::io::SeekFrom;
 use std::path::{Path, PathBuf};
 use std::time::Duration;
 use std::sync::atomic::{AtomicU64, Ordering};
 use tokio::time::Instant;
 use futures_util::StreamExt;
-use reqwest::header::{RANGE, CONTENT_LENGTH, CONTENT_TYPE, ACCEPT_RANGES};
+use reqwest::header::{RANGE, CONTENT_LENGTH, CONTENT_TYPE, ACCEPT_RANGES, ETAG};
 use tokio::fs::{File, OpenOptions};
-use tokio::io::AsyncWriteExt;
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tokio::sync::Semaphore;
 use std::sync::Arc;
 use tracing::{info, warn, debug};
 
 use crate::api::models::MirrorSource;
-use super::types::{DownloadError, DownloadProgress};
+use super::control::{RateLimiter, TransferControl};
+use super::history;
+use super::types::{DownloadError, DownloadProgress, Segment};
 
 fn build_app_headers() -> reqwest::header::HeaderMap {
     let mut headers = reqwest::header::HeaderMap::new();
@@ -50,7 +54,7 @@ fn build_browser_headers() -> reqwest::header::HeaderMap {
 }
 
 fn build_client(headers: reqwest::header::HeaderMap) -> Result<reqwest::Client, reqwest::Error> {
-    reqwest::Client::builder()
+    crate::network::http::client_builder()
         .connect_timeout(Duration::from_secs(15))
         .default_headers(headers)
         .no_gzip()
@@ -59,12 +63,12 @@ fn build_client(headers: reqwest::header::HeaderMap) -> Result<reqwest::Client,
         .build()
 }
 
-// Probe file size and Range support
-// Returns (total_size, supports_range)
+// Probe file size, Range support and ETag
+// Returns (total_size, supports_range, etag)
 async fn probe_file_size_and_range(
     client: &reqwest::Client,
     url: &str,
-) -> Result<(u64, bool), DownloadError> {
+) -> Result<(u64, bool, Option<String>), DownloadError> {
     let resp = client
         .request(reqwest::Method::HEAD, url)
         .send()
@@ -84,121 +88,345 @@ async fn probe_file_size_and_range(
         .map(|v| v.to_str().unwrap_or("").contains("bytes"))
         .unwrap_or(false);
 
-    Ok((total_size, supports_range))
+    let etag = resp
+        .headers()
+        .get(ETAG)
+        .and_then(|v| v.to_str().ok())
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty());
+
+    Ok((total_size, supports_range, etag))
+}
+
+// Probe the download size of a mirror source, used by the disk space preflight
+pub async fn probe_download_size(source: &MirrorSource) -> Option<u64> {
+    let client = build_client(build_app_headers()).ok()?;
+    match probe_file_size_and_range(&client, &source.url).await {
+        Ok((size, _, _)) if size > 0 => Some(size),
+        Ok(_) => None,
+        Err(e) => {
+            debug!("probe_download_size: {} failed: {}", source.mirror, e);
+            None
+        }
+    }
+}
+
+// Where a download stands: the ranges on disk, the file size they belong to, where
+// those bytes came from and the mirror that finished it
+#[derive(Debug, Clone, Default)]
+pub struct DownloadState {
+    pub total_size: u64,
+    pub segments: Vec<Segment>,
+    pub mirror: String,
+    // URL, published SHA-256 and ETag of the file the bytes on disk belong to
+    pub origin: String,
+    pub origin_sha256: String,
+    pub etag: String,
+}
+
+fn normalized_hash(hash: &str) -> String {
+    hash.trim().trim_start_matches("0x").to_lowercase()
+}
+
+impl DownloadState {
+    pub fn downloaded(&self) -> u64 {
+        self.segments.iter().map(|s| s.done).sum()
+    }
+
+    fn reset(&mut self) {
+        self.total_size = 0;
+        self.segments.clear();
+        self.origin.clear();
+        self.origin_sha256.clear();
+        self.etag.clear();
+    }
+
+    // Whether `source` serves the same file as the bytes on disk. Another URL only
+    // counts when both publish the same SHA-256 or report the same ETag, equal sizes
+    // say nothing. Jobs from older versions have no origin and are continued.
+    pub fn continues_with(&self, source: &MirrorSource, etag: Option<&str>) -> bool {
+        if self.origin.is_empty() {
+            return true;
+        }
+        let etag = etag.unwrap_or("");
+        if self.origin == source.url {
+            return self.etag.is_empty() || etag.is_empty() || self.etag == etag;
+        }
+        let source_sha256 = normalized_hash(source.sha256.as_deref().unwrap_or(""));
+        if !self.origin_sha256.is_empty() && !source_sha256.is_empty() {
+            return normalized_hash(&self.origin_sha256) == source_sha256;
+        }
+        !self.etag.is_empty() && self.etag == etag
+    }
+
+    fn set_origin(&mut self, source: &MirrorSource, etag: Option<&str>) {
+        self.origin = source.url.clone();
+        self.origin_sha256 = source.sha256.clone().unwrap_or_default();
+        self.etag = etag.unwrap_or("").to_string();
+    }
+}
+
+pub struct TransferOptions {
+    pub connections: usize,
+    pub control: Arc<TransferControl>,
+    pub limiter: Arc<RateLimiter>,
+}
+
+// Smallest range worth a connection of its own
+const MIN_SEGMENT_SIZE: u64 = 8 * 1024 * 1024;
+// A connection without data for this long is given up
+const STALL_TIMEOUT: Duration = Duration::from_secs(30);
+// How often the segment progress is handed out for persisting
+const CHECKPOINT_INTERVAL_MS: u128 = 2000;
+
+fn plan_segments(total_size: u64, connections: usize, supports_range: bool) -> Vec<Segment> {
+    if total_size == 0 {
+        return vec![Segment { start: 0, end: 0, done: 0 }];
+    }
+    let count = if supports_range {
+        (total_size / MIN_SEGMENT_SIZE).clamp(1, connections.max(1) as u64)
+    } else {
+        1
+    };
+    let per_segment = total_size.div_ceil(count);
+    (0..count)
+        .map(|i| Segment { start: i * per_segment, end: ((i + 1) * per_segment).min(total_size), done: 0 })
+        .filter(|s| s.start < s.end)
+        .collect()
+}
+
+// Fit the state to what this mirror serves. Bytes from an earlier attempt are kept
+// when they belong to the same file (see `continues_with`), the file size matches
+// and the mirror can continue them with Range requests.
+fn prepare_state(state: &mut DownloadState, probe: Option<(u64, bool, Option<String>)>, connections: usize, source: &MirrorSource) {
+    let mirror = &source.mirror;
+    let etag = probe.as_ref().and_then(|(_, _, etag)| etag.clone());
+    if state.downloaded() > 0 && !state.continues_with(source, etag.as_deref()) {
+        info!("Mirror {} can't be shown to serve the same file as {}, restarting the download", mirror, state.origin);
+        state.reset();
+    }
+    match probe {
+        Some((size, supports_range, _)) if size > 0 => {
+            if !state.segments.is_empty() && state.total_size != size {
+                info!("Mirror {} serves {} bytes instead of {}, restarting the download", mirror, size, state.total_size);
+                state.reset();
+            } else if !supports_range && state.downloaded() > 0 {
+                info!("Mirror {} does not support Range, restarting the download", mirror);
+                state.reset();
+            }
+            if state.segments.is_empty() {
+                state.total_size = size;
+                state.segments = plan_segments(size, connections, supports_range);
+            }
+        }
+        _ => {
+            if state.segments.is_empty() {
+                state.total_size = 0;
+                state.segments = plan_segments(0, connections, false);
+            }
+        }
+    }
+    // Only a known origin is kept, so a later mirror can be compared against it
+    if state.downloaded() == 0 || state.origin == source.url || state.origin.is_empty() {
+        state.set_origin(source, etag.as_deref());
+    }
+}
+
+// Preallocate to the full size, or cut an unknown-size download back to what is recorded
+async fn prepare_file(path: &Path, state: &DownloadState, mirror: &str) -> Result<(), DownloadError> {
+    let file = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(path)
+        .await
+        .map_err(|e| DownloadError::FileError { mirror: mirror.to_string(), error: e.to_string() })?;
+    let len = if state.total_size > 0 { state.total_size } else { state.downloaded() };
+    file.set_len(len)
+        .await
+        .map_err(|e| DownloadError::FileError { mirror: mirror.to_string(), error: e.to_string() })
 }
 
-// Chunked concurrent download
-// Splits the file into multiple chunks, downloads each to .chunk.N temp files, then merges
-async fn download_file_chunked<F>(
+// Server answered with an error page or a challenge instead of the file
+fn rejection_reason(response: &reqwest::Response, requested: u64) -> Option<String> {
+    let status = response.status();
+    if !status.is_success() {
+        return Some(format!("HTTP {}", status));
+    }
+    if let Some(ct) = response.headers().get(CONTENT_TYPE) {
+        let s = ct.to_str().unwrap_or("");
+        if s.contains("text/html") || s.contains("application/json") {
+            return Some(format!("Content-Type: {}", s));
+        }
+    }
+    let length = response.headers()
+        .get(CONTENT_LENGTH)
+        .and_then(|cl| cl.to_str().ok())
+        .and_then(|s| s.parse::<u64>().ok())
+        .unwrap_or(0);
+    if length > 0 && length < 1_000_000 && length < requested {
+        return Some(format!("Content-Length < 1MB: {} bytes", length));
+    }
+    None
+}
+
+// What every connection of one mirror attempt shares
+#[derive(Clone)]
+struct Connection {
+    client: reqwest::Client,
+    source: MirrorSource,
+    path: PathBuf,
+    control: Arc<TransferControl>,
+    // Tripped by the first failing connection so the others stop too
+    stop: Arc<TransferControl>,
+    limiter: Arc<RateLimiter>,
+}
+
+// Download the rest of one segment into its place in the file
+async fn fetch_segment(conn: Connection, segment: Segment, done: Arc<AtomicU64>) -> Result<(), DownloadError> {
+    let Connection { client, source, path, control, stop, limiter } = conn;
+    let mirror = source.mirror.clone();
+    let network_err = |e: String| DownloadError::NetworkError { mirror: mirror.clone(), error: e };
+    let file_err = |e: std::io::Error| DownloadError::FileError { mirror: mirror.clone(), error: e.to_string() };
+
+    let mut offset = segment.start + done.load(Ordering::Relaxed);
+    let request = if segment.end > 0 {
+        client.get(&source.url).header(RANGE, format!("bytes={}-{}", offset, segment.end - 1))
+    } else if offset > 0 {
+        client.get(&source.url).header(RANGE, format!("bytes={}-", offset))
+    } else {
+        client.get(&source.url)
+    };
+    let response = request.send().await.map_err(|e| network_err(e.to_string()))?;
+
+    let requested = if segment.end > 0 { segment.end - offset } else { u64::MAX };
+    if let Some(reason) = rejection_reason(&response, requested) {
+        return Err(DownloadError::HumanVerification { mirror: mirror.clone(), reason });
+    }
+    if offset > 0 && response.status() != reqwest::StatusCode::PARTIAL_CONTENT {
+        // Range ignored, the body starts at byte 0
+        if segment.start > 0 {
+            return Err(network_err("server ignored the Range request".to_string()));
+        }
+        done.store(0, Ordering::Relaxed);
+        offset = 0;
+    }
+
+    let mut file = OpenOptions::new().write(true).open(&path).await.map_err(file_err)?;
+    file.seek(SeekFrom::Start(offset)).await.map_err(file_err)?;
+
+    let mut remaining = if segment.end > 0 { segment.end - offset } else { u64::MAX };
+    let mut stream = response.bytes_stream();
+    while remaining > 0 {
+        let next = tokio::select! {
+            next = tokio::time::timeout(STALL_TIMEOUT, stream.next()) => next,
+            _ = control.paused() => {
+                file.flush().await.map_err(file_err)?;
+                return Err(DownloadError::Paused);
+            }
+            _ = stop.paused() => {
+                file.flush().await.map_err(file_err)?;
+                return Err(DownloadError::Paused);
+            }
+        };
+        let chunk = match next {
+            Ok(Some(chunk)) => chunk.map_err(|e| network_err(e.to_string()))?,
+            Ok(None) => break,
+            Err(_) => return Err(network_err(format!("no data for {}s", STALL_TIMEOUT.as_secs()))),
+        };
+        let take = (chunk.len() as u64).min(remaining) as usize;
+        limiter.acquire(take).await;
+        file.write_all(&chunk[..take]).await.map_err(file_err)?;
+        done.fetch_add(take as u64, Ordering::Relaxed);
+        remaining = remaining.saturating_sub(take as u64);
+    }
+    file.flush().await.map_err(file_err)?;
+
+    if segment.end > 0 && remaining > 0 {
+        return Err(network_err(format!(
+            "connection closed at byte {} of range {}-{}",
+            segment.end - remaining,
+            segment.start,
+            segment.end
+        )));
+    }
+    Ok(())
+}
+
+// Download the missing segments from one mirror, in parallel where the server allows
+async fn download_from_mirror<F, C>(
     source: &MirrorSource,
     temp_file_path: &Path,
-    custom_headers: &reqwest::header::HeaderMap,
-    _browser_headers: &reqwest::header::HeaderMap,
+    headers: &reqwest::header::HeaderMap,
+    state: &mut DownloadState,
+    options: &TransferOptions,
     progress_callback: &F,
-    total_size: u64,
+    checkpoint: &C,
 ) -> Result<(), DownloadError>
 where
     F: Fn(DownloadProgress) + Send + Sync,
+    C: Fn(&DownloadState) + Send + Sync,
 {
-    const CHUNK_SIZE: u64 = 8 * 1024 * 1024; // 8MB per chunk
-    const MAX_CONNECTIONS: usize = 4;         // max 4 concurrent connections
+    let client = build_client(headers.clone())
+        .map_err(|e| DownloadError::NetworkError { mirror: source.mirror.clone(), error: e.to_string() })?;
 
-    let chunk_count = ((total_size + CHUNK_SIZE - 1) / CHUNK_SIZE).max(1) as usize;
-    let connections = chunk_count.min(MAX_CONNECTIONS);
-    // Chunk size per connection
-    let per_conn_size = (total_size + connections as u64 - 1) / connections as u64;
+    let probe = match probe_file_size_and_range(&client, &source.url).await {
+        Ok(probe) => Some(probe),
+        Err(e) => {
+            debug!("Probe failed for {}: {}", source.mirror, e);
+            None
+        }
+    };
+    prepare_state(state, probe.clone(), options.connections, source);
+    prepare_file(temp_file_path, state, &source.mirror).await?;
+    checkpoint(state);
 
+    let already = state.downloaded();
+    if already > 0 && probe.is_some() {
+        progress_callback(DownloadProgress::Resuming { downloaded: already });
+    }
     info!(
-        "Chunked download: {} bytes, {} connections, ~{:.1}MB each",
-        total_size,
-        connections,
-        per_conn_size as f64 / 1024.0 / 1024.0
+        "Downloading from {}: {} bytes in {} segment(s), {} on disk, {} connection(s)",
+        source.mirror,
+        state.total_size,
+        state.segments.len(),
+        already,
+        options.connections
     );
 
-    let client = build_client(custom_headers.clone())
-        .map_err(|e| DownloadError::NetworkError { mirror: source.mirror.clone(), error: e.to_string() })?;
-
-    let total_downloaded = Arc::new(AtomicU64::new(0));
-    let start_time = Instant::now();
-
-    // Build download task for each connection
-    let mut handles = Vec::with_capacity(connections);
-    for i in 0..connections {
-        let range_start = i as u64 * per_conn_size;
-        let range_end = ((i as u64 + 1) * per_conn_size - 1).min(total_size - 1);
-        if range_start > range_end { continue; }
-
-        let chunk_path = temp_file_path.with_extension(format!(
-            "{}.chunk.{}",
-            temp_file_path.extension().unwrap_or_default().to_string_lossy(),
-            i
-        ));
-
-        // Check existing partial download (resume support)
-        let existing = if chunk_path.exists() {
-            tokio::fs::metadata(&chunk_path).await.map(|m| m.len()).unwrap_or(0)
-        } else {
-            0
-        };
-
-        let actual_start = range_start + existing;
-        total_downloaded.fetch_add(existing, Ordering::Relaxed);
+    let done: Vec<Arc<AtomicU64>> = state.segments.iter().map(|s| Arc::new(AtomicU64::new(s.done))).collect();
+    let conn = Connection {
+        client,
+        source: source.clone(),
+        path: temp_file_path.to_path_buf(),
+        control: options.control.clone(),
+        stop: Arc::new(TransferControl::default()),
+        limiter: options.limiter.clone(),
+    };
+    let slots = Arc::new(Semaphore::new(options.connections.max(1)));
 
-        if actual_start > range_end {
-            // This chunk is already fully downloaded
-            handles.push(tokio::spawn(async move { Ok::<(), DownloadError>(()) }));
+    let mut handles = Vec::new();
+    for (segment, done) in state.segments.iter().zip(&done) {
+        if segment.is_complete() {
             continue;
         }
-
-        let url = source.url.clone();
-        let mirror = source.mirror.clone();
-        let client = client.clone();
-        let path = chunk_path.clone();
-        let td = total_downloaded.clone();
-
+        let task = fetch_segment(conn.clone(), segment.clone(), done.clone());
+        let slots = slots.clone();
+        let stop = conn.stop.clone();
         handles.push(tokio::spawn(async move {
-            let req = client
-                .get(&url)
-                .header(RANGE, format!("bytes={}-{}", actual_start, range_end));
-
-            let response = req.send().await.map_err(|e| DownloadError::NetworkError {
-                mirror: mirror.clone(),
-                error: e.to_string(),
-            })?;
-
-            let status = response.status();
-            if !status.is_success() && status != reqwest::StatusCode::PARTIAL_CONTENT {
-                return Err(DownloadError::NetworkError {
-                    mirror: mirror.clone(),
-                    error: format!("HTTP {}", status),
-                });
+            let _permit = slots.acquire_owned().await;
+            let result = task.await;
+            if result.is_err() {
+                stop.pause();
             }
-
-            let mut file = OpenOptions::new()
-                .create(true)
-                .append(true)
-                .open(&path)
-                .await
-                .map_err(|e| DownloadError::FileError { mirror: mirror.clone(), error: e.to_string() })?;
-
-            let mut stream = response.bytes_stream();
-            while let Some(chunk) = stream.next().await {
-                let chunk = chunk.map_err(|e| DownloadError::NetworkError {
-                    mirror: mirror.clone(),
-                    error: e.to_string(),
-                })?;
-                file.write_all(&chunk).await.map_err(|e| DownloadError::FileError {
-                    mirror: mirror.clone(),
-                    error: e.to_string(),
-                })?;
-                td.fetch_add(chunk.len() as u64, Ordering::Relaxed);
-            }
-            Ok(())
+            result
         }));
     }
 
     // Wait for all tasks in background, periodically report progress
     let handles_fut = tokio::spawn(futures_util::future::join_all(handles));
+    let start_time = Instant::now();
+    let mut last_checkpoint = 0u128;
     // Ordered sampling queue: (timestamp_ms, cumulative_bytes), max 30 entries (~3s window)
     const SPEED_WINDOW_SIZE: usize = 30;
     let mut speed_window: std::collections::VecDeque<(u128, u64)> = std::collections::VecDeque::with_capacity(SPEED_WINDOW_SIZE);
@@ -206,9 +434,9 @@ where
     loop {
         tokio::time::sleep(Duration::from_millis(100)).await;
 
-        let downloaded = total_downloaded.load(Ordering::Relaxed);
-        let percent = if total_size > 0 {
-            (downloaded as f32 / total_size as f32) * 100.0
+        let downloaded: u64 = done.iter().map(|d| d.load(Ordering::Relaxed)).sum();
+        let percent = if state.total_size > 0 {
+            (downloaded as f32 / state.total_size as f32) * 100.0
         } else {
             0.0
         };
@@ -221,256 +449,78 @@ where
         }
 
         // Calculate average speed using head and tail of window
-        let speed = if speed_window.len() >= 2 {
-            let (oldest_ts, oldest_bytes) = speed_window.front().unwrap();
-            let (newest_ts, newest_bytes) = speed_window.back().unwrap();
-            let dt_ms = newest_ts - oldest_ts;
-            if dt_ms > 0 {
+        let speed = match (speed_window.front(), speed_window.back()) {
+            (Some((oldest_ts, oldest_bytes)), Some((newest_ts, newest_bytes))) if newest_ts > oldest_ts => {
                 let delta = newest_bytes.saturating_sub(*oldest_bytes);
-                format!("{:.1} MB/s", (delta as f64 / 1024.0 / 1024.0) / (dt_ms as f64 / 1000.0))
-            } else {
-                "0.0 MB/s".to_string()
+                format!("{:.1} MB/s", (delta as f64 / 1024.0 / 1024.0) / ((newest_ts - oldest_ts) as f64 / 1000.0))
             }
-        } else {
-            "0.0 MB/s".to_string()
+            _ => "0.0 MB/s".to_string(),
         };
         progress_callback(DownloadProgress::Downloading { percent, speed: &speed });
 
+        if ts_ms - last_checkpoint >= CHECKPOINT_INTERVAL_MS {
+            last_checkpoint = ts_ms;
+            for (segment, d) in state.segments.iter_mut().zip(&done) {
+                segment.done = d.load(Ordering::Relaxed);
+            }
+            checkpoint(state);
+        }
+
         if handles_fut.is_finished() {
             break;
         }
     }
 
-    // Collect results
     let results = handles_fut.await.map_err(|e| DownloadError::NetworkError {
         mirror: source.mirror.clone(),
         error: format!("Join error: {}", e),
     })?;
+    for (segment, d) in state.segments.iter_mut().zip(&done) {
+        segment.done = d.load(Ordering::Relaxed);
+    }
 
-    // Final progress
-    let downloaded = total_downloaded.load(Ordering::Relaxed);
-    let elapsed = start_time.elapsed().as_secs_f64();
-    let speed = if elapsed > 0.0 {
-        format!("{:.1} MB/s", (downloaded as f64 / 1024.0 / 1024.0) / elapsed)
-    } else {
-        "0.0 MB/s".to_string()
-    };
-    progress_callback(DownloadProgress::Downloading { percent: 100.0, speed: &speed });
-
-    // Check for failed tasks
-    for (i, result) in results.into_iter().enumerate() {
+    // A real failure wins over the connections it stopped
+    let mut paused = false;
+    let mut failure = None;
+    for result in results {
         match result {
             Ok(Ok(())) => {}
-            Ok(Err(e)) => {
-                // Clean up all temp chunk files
-                for j in 0..connections {
-                    let p = temp_file_path.with_extension(format!(
-                        "{}.chunk.{}",
-                        temp_file_path.extension().unwrap_or_default().to_string_lossy(),
-                        j
-                    ));
-                    let _ = tokio::fs::remove_file(&p).await;
-                }
-                return Err(e);
-            }
+            Ok(Err(DownloadError::Paused)) => paused = true,
+            Ok(Err(e)) => { failure.get_or_insert(e); }
             Err(e) => {
-                for j in 0..connections {
-                    let p = temp_file_path.with_extension(format!(
-                        "{}.chunk.{}",
-                        temp_file_path.extension().unwrap_or_default().to_string_lossy(),
-                        j
-                    ));
-                    let _ = tokio::fs::remove_file(&p).await;
-                }
-                return Err(DownloadError::NetworkError {
+                failure.get_or_insert(DownloadError::NetworkError {
                     mirror: source.mirror.clone(),
-                    error: format!("Task {} panicked: {}", i, e),
+                    error: format!("Task panicked: {}", e),
                 });
             }
         }
     }
-
-    // Merge all chunks into the target file
-    let mut output = OpenOptions::new()
-        .create(true)
-        .truncate(true)
-        .write(true)
-        .open(temp_file_path)
-        .await
-        .map_err(|e| DownloadError::FileError { mirror: source.mirror.clone(), error: e.to_string() })?;
-
-    for i in 0..connections {
-        let chunk_path = temp_file_path.with_extension(format!(
-            "{}.chunk.{}",
-            temp_file_path.extension().unwrap_or_default().to_string_lossy(),
-            i
-        ));
-        let data = tokio::fs::read(&chunk_path).await
-            .map_err(|e| DownloadError::FileError { mirror: source.mirror.clone(), error: e.to_string() })?;
-        output.write_all(&data).await
-            .map_err(|e| DownloadError::FileError { mirror: source.mirror.clone(), error: e.to_string() })?;
-        let _ = tokio::fs::remove_file(&chunk_path).await;
+    if let Some(e) = failure {
+        checkpoint(state);
+        return Err(e);
     }
-
-    info!("Chunked download completed: {} bytes in {:.1}s", total_size, elapsed);
-    Ok(())
-}
-
-async fn download_file<F>(
-    source: &MirrorSource,
-    temp_file_path: &Path,
-    custom_headers: &reqwest::header::HeaderMap,
-    browser_headers: &reqwest::header::HeaderMap,
-    progress_callback: &F,
-) -> Result<(), DownloadError>
-where
-    F: Fn(DownloadProgress) + Send + Sync,
-{
-    let client = build_client(custom_headers.clone())
-        .map_err(|e| DownloadError::NetworkError { mirror: source.mirror.clone(), error: e.to_string() })?;
-
-    // Try chunked concurrent download: probe file size and Range support
-    const MIN_CHUNKED_SIZE: u64 = 10 * 1024 * 1024; // Enable chunked for files > 10MB
-    match probe_file_size_and_range(&client, &source.url).await {
-        Ok((total_size, true)) if total_size >= MIN_CHUNKED_SIZE => {
-            info!("Mirror {} supports Range, size {}MB, using chunked download", source.mirror, total_size / 1024 / 1024);
-            match download_file_chunked(source, temp_file_path, custom_headers, browser_headers, progress_callback, total_size).await {
-                Ok(()) => return Ok(()),
-                Err(e) => {
-                    warn!("Chunked download failed for {}: {}, falling back to single-stream", source.mirror, e);
-                    // Clean up any remaining chunk files
-                    for j in 0..4 {
-                        let p = temp_file_path.with_extension(format!(
-                            "{}.chunk.{}",
-                            temp_file_path.extension().unwrap_or_default().to_string_lossy(),
-                            j
-                        ));
-                        let _ = tokio::fs::remove_file(&p).await;
-                    }
-                }
-            }
-        }
-        Ok((total_size, supports_range)) => {
-            debug!("Mirror {}: size={}MB, range={}, using single-stream", source.mirror, total_size / 1024 / 1024, supports_range);
-        }
-        Err(e) => {
-            debug!("Probe failed for {}: {}, using single-stream", source.mirror, e);
-        }
+    if paused {
+        checkpoint(state);
+        return Err(DownloadError::Paused);
     }
 
-    // Single-stream download (fallback logic)
-    let mut start_byte = 0;
-    if temp_file_path.exists() {
-        if let Ok(metadata) = tokio::fs::metadata(temp_file_path).await {
-            start_byte = metadata.len();
+    // The stream end marks the size when the server did not announce one
+    if state.total_size == 0 {
+        state.total_size = state.downloaded();
+        if let Some(segment) = state.segments.first_mut() {
+            segment.end = segment.done;
         }
     }
+    checkpoint(state);
 
-    let req = if start_byte > 0 {
-        client.get(&source.url).header(RANGE, format!("bytes={}-", start_byte))
+    let elapsed = start_time.elapsed().as_secs_f64();
+    let speed = if elapsed > 0.0 {
+        format!("{:.1} MB/s", ((state.downloaded() - already) as f64 / 1024.0 / 1024.0) / elapsed)
     } else {
-        client.get(&source.url)
+        "0.0 MB/s".to_string()
     };
-
-    let response = req.send().await.map_err(|e| DownloadError::NetworkError { mirror: source.mirror.clone(), error: e.to_string() })?;
-
-    let status = response.status();
-    let is_server_rejection = (!status.is_success() && status != reqwest::StatusCode::PARTIAL_CONTENT)
-        || response.headers().get(CONTENT_TYPE).map_or(false, |ct| {
-            let s = ct.to_str().unwrap_or("");
-            s.contains("text/html") || s.contains("application/json")
-        })
-        || response.headers().get(CONTENT_LENGTH).map_or(false, |cl| {
-            cl.to_str().ok().and_then(|s| s.parse::<u64>().ok()).map_or(false, |len| len > 0 && len < 1_000_000)
-        });
-
-    if is_server_rejection {
-        let reason = if !status.is_success() && status != reqwest::StatusCode::PARTIAL_CONTENT {
-            format!("HTTP {}", status)
-        } else if let Some(ct) = response.headers().get(CONTENT_TYPE) {
-            format!("Content-Type: {}", ct.to_str().unwrap_or(""))
-        } else {
-            format!("Content-Length < 1MB")
-        };
-
-        let ua_is_app = custom_headers.get(reqwest::header::USER_AGENT)
-            .and_then(|v| v.to_str().ok())
-            .map_or(false, |s| s.starts_with("WslDashboard/"));
-
-        if ua_is_app {
-            warn!("Mirror {} rejected app UA ({}), retrying with browser UA", source.mirror, reason);
-            return Box::pin(download_file(source, temp_file_path, browser_headers, browser_headers, progress_callback)).await;
-        } else {
-            warn!("Mirror {} rejected browser UA ({}): {}", source.mirror, reason, status);
-            return Err(DownloadError::NetworkError { mirror: source.mirror.clone(), error: format!("HTTP {} ({})", status, reason) });
-        }
-    }
-
-    let total_size = response
-        .headers()
-        .get(CONTENT_LENGTH)
-        .and_then(|ct_len| ct_len.to_str().ok())
-        .and_then(|ct_len| ct_len.parse::<u64>().ok())
-        .unwrap_or(0) + start_byte;
-
-    if total_size > 0 && total_size < 1_000_000 {
-        let ua_is_app = custom_headers.get(reqwest::header::USER_AGENT)
-            .and_then(|v| v.to_str().ok())
-            .map_or(false, |s| s.starts_with("WslDashboard/"));
-
-        if ua_is_app {
-            warn!("Mirror {} Content-Length suspicious with app UA, retrying with browser UA", source.mirror);
-            return Box::pin(download_file(source, temp_file_path, browser_headers, browser_headers, progress_callback)).await;
-        }
-        return Err(DownloadError::HumanVerification {
-            mirror: source.mirror.clone(),
-            reason: format!("Content-Length < 1MB: {} bytes", total_size),
-        });
-    }
-
-    let mut file = OpenOptions::new()
-        .create(true)
-        .append(true)
-        .open(temp_file_path)
-        .await
-        .map_err(|e| DownloadError::FileError { mirror: source.mirror.clone(), error: e.to_string() })?;
-
-    let mut downloaded = start_byte;
-    let mut stream = response.bytes_stream();
-    let start_time = Instant::now();
-    let mut last_percent = -1.0;
-
-    while let Some(chunk) = stream.next().await {
-        let chunk = chunk.map_err(|e| DownloadError::NetworkError { mirror: source.mirror.clone(), error: e.to_string() })?;
-        file.write_all(&chunk).await.map_err(|e| DownloadError::FileError { mirror: source.mirror.clone(), error: e.to_string() })?;
-        downloaded += chunk.len() as u64;
-
-        if total_size > 0 {
-            let percent = (downloaded as f32 / total_size as f32) * 100.0;
-            if percent - last_percent >= 0.1 {
-                last_percent = percent;
-                let elapsed = start_time.elapsed().as_secs_f64();
-                let speed = if elapsed > 0.0 {
-                    format!("{:.1} MB/s", ((downloaded - start_byte) as f64 / 1024.0 / 1024.0) / elapsed)
-                } else {
-                    "0.0 MB/s".to_string()
-                };
-                progress_callback(DownloadProgress::Downloading { percent, speed: &speed });
-            }
-        }
-    }
-
-    // Final progress report
-    if total_size > 0 {
-        let elapsed = start_time.elapsed().as_secs_f64();
-        let speed = if elapsed > 0.0 {
-            format!("{:.1} MB/s", ((downloaded - start_byte) as f64 / 1024.0 / 1024.0) / elapsed)
-        } else {
-            "0.0 MB/s".to_string()
-        };
-        progress_callback(DownloadProgress::Downloading { percent: 100.0, speed: &speed });
-    }
-
+    progress_callback(DownloadProgress::Downloading { percent: 100.0, speed: &speed });
+    info!("Download from {} completed: {} bytes in {:.1}s", source.mirror, state.total_size, elapsed);
     Ok(())
 }
 
@@ -498,6 +548,15 @@ async fn validate_downloaded_file(path: &Path, expected_format: &str) -> Result<
                 return Err("Invalid XZ file header".to_string());
             }
         }
+        "appx" => {
+            let mut header = [0u8; 2];
+            if file.read_exact(&mut header).await.is_err() {
+                return Err("Failed to read package header".to_string());
+            }
+            if &header != &[0x50, 0x4B] {
+                return Err("Invalid package header".to_string());
+            }
+        }
         "tar.gz" => {
             let mut header = [0u8; 2];
             if file.read_exact(&mut header).await.is_err() {
@@ -513,13 +572,42 @@ async fn validate_downloaded_file(path: &Path, expected_format: &str) -> Result<
     Ok(())
 }
 
-pub async fn download_with_fallback<F>(
+// Compare against the published SHA-256; manifests write it as "0x" + hex
+async fn verify_checksum(path: &Path, expected: &str) -> Result<(), String> {
+    let expected = expected.trim().trim_start_matches("0x").to_lowercase();
+    let file = path.to_path_buf();
+    let actual = tokio::task::spawn_blocking(move || crate::utils::hash::sha256_file(&file, |_| {}))
+        .await
+        .map_err(|e| e.to_string())??;
+    if actual.to_lowercase() != expected {
+        return Err(format!("SHA-256 mismatch: expected {}, got {}", expected, actual));
+    }
+    Ok(())
+}
+
+// Bytes/sec of a finished attempt, 0 when too little was fetched to tell
+fn measured_throughput(bytes: u64, start: Instant) -> u64 {
+    const MIN_SAMPLE: u64 = 1024 * 1024;
+    let secs = start.elapsed().as_secs_f64();
+    if bytes < MIN_SAMPLE || secs <= 0.0 {
+        return 0;
+    }
+    (bytes as f64 / secs) as u64
+}
+
+// Try the mirrors in order. Whatever an attempt got onto disk stays in `state`, so
+// the next mirror, or a later resume, only fetches the missing ranges.
+pub async fn download_with_fallback<F, C>(
     sources: &[MirrorSource],
     temp_file_path: &Path,
+    state: &mut DownloadState,
+    options: &TransferOptions,
     progress_callback: F,
+    checkpoint: C,
 ) -> Result<PathBuf, DownloadError>
 where
     F: Fn(DownloadProgress) + Send + Sync,
+    C: Fn(&DownloadState) + Send + Sync,
 {
     let app_headers = build_app_headers();
     let browser_headers = build_browser_headers();
@@ -527,26 +615,56 @@ where
 
     for source in sources {
         let mirror_name = &source.mirror;
+        if options.control.is_paused() {
+            return Err(DownloadError::Paused);
+        }
 
         progress_callback(DownloadProgress::TryingMirror {
             mirror: mirror_name,
             url: &source.url,
         });
 
-        match download_file(source, temp_file_path, &app_headers, &browser_headers, &progress_callback).await {
+        let before = state.downloaded();
+        let attempt_start = Instant::now();
+        let mut result = download_from_mirror(source, temp_file_path, &app_headers, state, options, &progress_callback, &checkpoint).await;
+        // Some mirrors turn the app UA away, give them a browser UA once
+        if let Err(e) = &result {
+            if !matches!(e, DownloadError::Paused | DownloadError::FileError { .. }) && state.downloaded() == before {
+                warn!("Mirror {} rejected app UA ({}), retrying with browser UA", mirror_name, e);
+                result = download_from_mirror(source, temp_file_path, &browser_headers, state, options, &progress_callback, &checkpoint).await;
+            }
+        }
+
+        match result {
             Ok(_) => {
-                if let Err(e) = validate_downloaded_file(temp_file_path, &source.format).await {
+                let validation = match validate_downloaded_file(temp_file_path, &source.format).await {
+                    Ok(_) => match source.sha256.as_deref().filter(|h| !h.is_empty()) {
+                        Some(expected) => verify_checksum(temp_file_path, expected).await,
+                        None => Ok(()),
+                    },
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = validation {
                     warn!("Mirror {} file validation failed: {}", mirror_name, e);
                     progress_callback(DownloadProgress::MirrorFileInvalid {
                         mirror: mirror_name,
                     });
                     errors.push(format!("{}: File error: {}", mirror_name, e));
+                    history::record_failure(&source.url, "InvalidFile");
+                    state.reset();
+                    checkpoint(state);
                     let _ = tokio::fs::remove_file(temp_file_path).await;
                     continue;
                 }
                 info!("Download from mirror {} succeeded.", mirror_name);
+                history::record_success(&source.url, measured_throughput(state.downloaded() - before, attempt_start));
+                state.mirror = mirror_name.clone();
                 return Ok(temp_file_path.to_path_buf());
             }
+            Err(DownloadError::Paused) => {
+                info!("Download paused at {} bytes", state.downloaded());
+                return Err(DownloadError::Paused);
+            }
             Err(e) => {
                 let err_msg = e.to_string();
                 warn!("Mirror {} failed: {}", mirror_name, err_msg);
@@ -555,6 +673,10 @@ where
                     error: &err_msg,
                 });
                 errors.push(format!("{}: {}", mirror_name, err_msg));
+                // A local disk problem says nothing about the mirror
+                if !matches!(e, DownloadError::FileError { .. } | DownloadError::Cancelled) {
+                    history::record_failure(&source.url, e.kind());
+                }
                 continue;
             }
         }
@@ -0,0 +1,275 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Mirror performance history
+//
+// Every download outcome is kept per mirror domain in ~/.wsldashboard/mirrors.toml,
+// probe results separately so they don't count as download attempts. Combined with
+// the live probe it gives each mirror a score. Mirrors that keep failing are skipped
+// before probing, pinned mirrors are always tried first and banned mirrors are only
+// used when nothing else is left.
+//
+// Results are collected in memory and written by `flush`, once per download.
+
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+const INDEX_FILE_NAME: &str = "mirrors.toml";
+// Throughput samples kept per mirror
+const MAX_SAMPLES: usize = 10;
+// Past this many attempts the counters are halved, so old results fade out
+const MAX_ATTEMPTS: u32 = 20;
+// A mirror is skipped up front once it has failed most of at least this many attempts...
+const SKIP_MIN_ATTEMPTS: u32 = 3;
+const SKIP_SUCCESS_RATE: f64 = 0.25;
+// ...until its last failure is this old, then it gets another chance
+const SKIP_COOLDOWN_SECS: i64 = 3 * 24 * 3600;
+// Human verification pages tend to stay up for a while
+const VERIFICATION_PENALTY_SECS: i64 = 24 * 3600;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MirrorPreference {
+    #[default]
+    Auto,
+    Pinned,
+    Banned,
+}
+
+impl MirrorPreference {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Auto => "auto",
+            Self::Pinned => "pinned",
+            Self::Banned => "banned",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MirrorRecord {
+    pub domain: String,
+    #[serde(default)]
+    pub attempts: u32,
+    #[serde(default)]
+    pub successes: u32,
+    // Recent throughput samples in bytes/sec, newest last
+    #[serde(default)]
+    pub throughput: Vec<u64>,
+    // Kind of the last failure, e.g. `HumanVerification`
+    #[serde(rename = "last-failure", default)]
+    pub last_failure: String,
+    #[serde(rename = "last-failure-time", default)]
+    pub last_failure_time: i64,
+    #[serde(rename = "last-success-time", default)]
+    pub last_success_time: i64,
+    // Throughput of the short selection probes in bytes/sec, newest last
+    #[serde(rename = "probe-throughput", default)]
+    pub probe_throughput: Vec<u64>,
+    #[serde(rename = "last-probe-time", default)]
+    pub last_probe_time: i64,
+    #[serde(default)]
+    pub preference: MirrorPreference,
+}
+
+fn push_sample(samples: &mut Vec<u64>, value: u64) {
+    samples.push(value);
+    let excess = samples.len().saturating_sub(MAX_SAMPLES);
+    samples.drain(..excess);
+}
+
+fn median(samples: &[u64]) -> Option<u64> {
+    if samples.is_empty() {
+        return None;
+    }
+    let mut samples = samples.to_vec();
+    samples.sort_unstable();
+    Some(samples[samples.len() / 2])
+}
+
+impl MirrorRecord {
+    fn new(domain: &str) -> Self {
+        Self { domain: domain.to_string(), ..Default::default() }
+    }
+
+    pub fn success_rate(&self) -> Option<f64> {
+        (self.attempts > 0).then(|| self.successes as f64 / self.attempts as f64)
+    }
+
+    // Median of the download samples
+    pub fn median_throughput(&self) -> Option<u64> {
+        median(&self.throughput)
+    }
+
+    // Downloads are the better measure, probes stand in until there are any
+    pub fn past_throughput(&self) -> Option<u64> {
+        self.median_throughput().or_else(|| median(&self.probe_throughput))
+    }
+
+    // Low-scoring mirrors are left out before probing
+    pub fn is_skipped(&self, now: i64) -> bool {
+        self.preference != MirrorPreference::Pinned
+            && self.attempts >= SKIP_MIN_ATTEMPTS
+            && self.success_rate().unwrap_or(1.0) < SKIP_SUCCESS_RATE
+            && now - self.last_failure_time < SKIP_COOLDOWN_SECS
+    }
+
+    // Success rate with one imaginary success and failure, so one result doesn't decide
+    fn reliability(&self) -> f64 {
+        (self.successes as f64 + 1.0) / (self.attempts as f64 + 2.0)
+    }
+
+    fn count_attempt(&mut self) {
+        if self.attempts >= MAX_ATTEMPTS {
+            self.attempts /= 2;
+            self.successes /= 2;
+        }
+        self.attempts += 1;
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct MirrorIndex {
+    #[serde(default)]
+    mirrors: Vec<MirrorRecord>,
+}
+
+static HISTORY: Lazy<Mutex<Vec<MirrorRecord>>> = Lazy::new(|| {
+    let index: MirrorIndex = std::fs::read_to_string(index_path())
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default();
+    Mutex::new(index.mirrors)
+});
+
+// Set when the records changed since the last flush
+static DIRTY: AtomicBool = AtomicBool::new(false);
+
+fn index_path() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".wsldashboard").join(INDEX_FILE_NAME)
+}
+
+async fn save(index: &MirrorIndex) -> Result<(), String> {
+    let content = toml::to_string_pretty(index).map_err(|e| e.to_string())?;
+    let path = index_path();
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir).await.map_err(|e| e.to_string())?;
+    }
+    tokio::fs::write(path, content).await.map_err(|e| e.to_string())
+}
+
+// Write the records if anything changed. The lock is only held to take a copy.
+pub async fn flush() {
+    if !DIRTY.swap(false, Ordering::AcqRel) {
+        return;
+    }
+    let index = MirrorIndex { mirrors: HISTORY.lock().unwrap().clone() };
+    if let Err(e) = save(&index).await {
+        DIRTY.store(true, Ordering::Release);
+        warn!("Mirror history: failed to save {}: {}", INDEX_FILE_NAME, e);
+    }
+}
+
+fn update(domain: &str, f: impl FnOnce(&mut MirrorRecord)) {
+    if domain.is_empty() {
+        return;
+    }
+    let mut history = HISTORY.lock().unwrap();
+    match history.iter_mut().find(|r| r.domain == domain) {
+        Some(record) => f(record),
+        None => {
+            let mut record = MirrorRecord::new(domain);
+            f(&mut record);
+            history.push(record);
+        }
+    }
+    DIRTY.store(true, Ordering::Release);
+}
+
+// History is kept per host, mirrors often serve many distros from one domain
+pub fn domain_of(url: &str) -> String {
+    match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed.host_str().unwrap_or("").to_string(),
+        Err(_) => url.to_string(),
+    }
+}
+
+// `throughput` in bytes/sec, 0 when too little was transferred to measure it
+pub fn record_success(url: &str, throughput: u64) {
+    let now = chrono::Utc::now().timestamp();
+    update(&domain_of(url), |record| {
+        record.count_attempt();
+        record.successes += 1;
+        record.last_success_time = now;
+        if throughput > 0 {
+            push_sample(&mut record.throughput, throughput);
+        }
+    });
+}
+
+// Outcome of a selection probe, 0 when it failed. Not a download attempt, so the
+// success rate is left alone.
+pub fn record_probe(url: &str, throughput: u64) {
+    let now = chrono::Utc::now().timestamp();
+    update(&domain_of(url), |record| {
+        record.last_probe_time = now;
+        if throughput > 0 {
+            push_sample(&mut record.probe_throughput, throughput);
+        }
+    });
+}
+
+pub fn record_failure(url: &str, reason: &str) {
+    let now = chrono::Utc::now().timestamp();
+    update(&domain_of(url), |record| {
+        record.count_attempt();
+        record.last_failure = reason.to_string();
+        record.last_failure_time = now;
+    });
+}
+
+// Records of the given domains, unknown ones come back empty
+pub fn lookup(domains: &[String]) -> Vec<MirrorRecord> {
+    let history = HISTORY.lock().unwrap();
+    domains.iter()
+        .map(|d| history.iter().find(|r| &r.domain == d).cloned().unwrap_or_else(|| MirrorRecord::new(d)))
+        .collect()
+}
+
+// Score from the live probe (bytes/sec, 0 if it failed) and the history
+pub fn score(record: &MirrorRecord, live_throughput: u64, now: i64) -> f64 {
+    let live = live_throughput as f64;
+    let expected = match record.past_throughput() {
+        Some(past) if live_throughput > 0 => 0.7 * live + 0.3 * past as f64,
+        // Down right now, the past counts for less
+        Some(past) => 0.5 * past as f64,
+        None => live,
+    };
+    let mut score = expected * record.reliability();
+    if record.last_failure == "HumanVerification" && now - record.last_failure_time < VERIFICATION_PENALTY_SECS {
+        score *= 0.25;
+    }
+    score
+}
+
+pub fn list() -> Vec<MirrorRecord> {
+    let mut records = HISTORY.lock().unwrap().clone();
+    records.sort_by(|a, b| a.domain.cmp(&b.domain));
+    records
+}
+
+pub fn set_preference(domain: &str, preference: MirrorPreference) {
+    update(domain, |record| record.preference = preference);
+}
+
+// Drop the statistics of a mirror, its preference is kept
+pub fn reset(domain: &str) {
+    update(domain, |record| {
+        *record = MirrorRecord { preference: record.preference, ..MirrorRecord::new(&record.domain) };
+    });
+}
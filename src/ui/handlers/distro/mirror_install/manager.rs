@@ -0,0 +1,462 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Persistent download queue
+//
+// Mirror downloads run as jobs recorded in ~/.wsldashboard/downloads.toml together
+// with the byte ranges already on disk, so a paused, failed or interrupted download
+// continues where it stopped, across restarts too. Jobs run one at a time in the
+// order they were started; connection count and bandwidth cap apply to all of them.
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Semaphore;
+use tokio::time::Instant;
+use tracing::{info, warn};
+
+use crate::api::models::MirrorSource;
+use super::control::{RateLimiter, TransferControl};
+use super::download::{download_with_fallback, DownloadState, TransferOptions};
+use super::types::{DownloadError, DownloadProgress, Segment};
+
+const INDEX_FILE_NAME: &str = "downloads.toml";
+// Minimum gap between progress notifications to the listener
+const NOTIFY_INTERVAL_MS: u128 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Paused,
+    Failed,
+    Completed,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Queued => "queued",
+            Self::Running => "running",
+            Self::Paused => "paused",
+            Self::Failed => "failed",
+            Self::Completed => "completed",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DownloadJob {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    // Mirrors in the order they are tried
+    pub sources: Vec<MirrorSource>,
+    // Target file, preallocated to the full size while downloading
+    pub file: String,
+    #[serde(rename = "total-size", default)]
+    pub total_size: u64,
+    #[serde(default)]
+    pub segments: Vec<Segment>,
+    pub status: JobStatus,
+    // Mirror that finished the download
+    #[serde(default)]
+    pub mirror: String,
+    // URL, published SHA-256 and ETag of the file the bytes on disk belong to
+    #[serde(default)]
+    pub origin: String,
+    #[serde(rename = "origin-sha256", default)]
+    pub origin_sha256: String,
+    #[serde(default)]
+    pub etag: String,
+    #[serde(default)]
+    pub error: String,
+    #[serde(rename = "added-time")]
+    pub added_time: String,
+}
+
+impl DownloadJob {
+    pub fn path(&self) -> PathBuf {
+        PathBuf::from(&self.file)
+    }
+
+    pub fn downloaded(&self) -> u64 {
+        self.segments.iter().map(|s| s.done).sum()
+    }
+
+    // The source of the mirror that finished the download
+    pub fn source(&self) -> Option<&MirrorSource> {
+        self.sources.iter().find(|s| s.mirror == self.mirror)
+    }
+
+    // Archive format as published by the mirror that finished the download
+    pub fn format(&self) -> String {
+        self.source().map(|s| s.format.clone()).unwrap_or_default()
+    }
+
+    fn state(&self) -> DownloadState {
+        DownloadState {
+            total_size: self.total_size,
+            segments: self.segments.clone(),
+            mirror: self.mirror.clone(),
+            origin: self.origin.clone(),
+            origin_sha256: self.origin_sha256.clone(),
+            etag: self.etag.clone(),
+        }
+    }
+
+    fn apply(&mut self, state: &DownloadState) {
+        self.total_size = state.total_size;
+        self.segments = state.segments.clone();
+        self.mirror = state.mirror.clone();
+        self.origin = state.origin.clone();
+        self.origin_sha256 = state.origin_sha256.clone();
+        self.etag = state.etag.clone();
+    }
+}
+
+// A job with its live progress, for display
+#[derive(Debug, Clone)]
+pub struct JobSnapshot {
+    pub job: DownloadJob,
+    pub percent: Option<f32>,
+    pub speed: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct JobIndex {
+    #[serde(default)]
+    jobs: Vec<DownloadJob>,
+}
+
+// Live side of a job that has a runner
+struct Active {
+    control: Arc<TransferControl>,
+    percent: Option<f32>,
+    speed: Option<String>,
+    // Removed while running, the runner deletes the file when it stops
+    removed: bool,
+}
+
+struct Queue {
+    jobs: Vec<DownloadJob>,
+    active: HashMap<String, Active>,
+    // Serializes runners of the same job
+    locks: HashMap<String, Arc<tokio::sync::Mutex<()>>>,
+}
+
+impl Queue {
+    fn load() -> Self {
+        let mut index: JobIndex = std::fs::read_to_string(index_path())
+            .ok()
+            .and_then(|content| toml::from_str(&content).ok())
+            .unwrap_or_default();
+        // Nothing runs right after start, unfinished jobs wait to be resumed
+        for job in &mut index.jobs {
+            if matches!(job.status, JobStatus::Queued | JobStatus::Running) {
+                job.status = JobStatus::Paused;
+            }
+        }
+        Self { jobs: index.jobs, active: HashMap::new(), locks: HashMap::new() }
+    }
+
+    fn save(&self) {
+        let index = JobIndex { jobs: self.jobs.clone() };
+        let result = toml::to_string_pretty(&index)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                let path = index_path();
+                if let Some(dir) = path.parent() {
+                    std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+                }
+                std::fs::write(path, content).map_err(|e| e.to_string())
+            });
+        if let Err(e) = result {
+            warn!("Download queue: failed to save {}: {}", INDEX_FILE_NAME, e);
+        }
+    }
+
+    fn job_mut(&mut self, id: &str) -> Option<&mut DownloadJob> {
+        self.jobs.iter_mut().find(|j| j.id == id)
+    }
+}
+
+static QUEUE: Lazy<Mutex<Queue>> = Lazy::new(|| Mutex::new(Queue::load()));
+// One download at a time, waiting runners are served in order
+static SLOT: Lazy<Semaphore> = Lazy::new(|| Semaphore::new(1));
+static LIMITER: Lazy<Arc<RateLimiter>> = Lazy::new(|| Arc::new(RateLimiter::new()));
+static CONNECTIONS: AtomicUsize = AtomicUsize::new(4);
+type Listener = Arc<dyn Fn() + Send + Sync>;
+static LISTENER: Lazy<Mutex<Option<Listener>>> = Lazy::new(|| Mutex::new(None));
+
+fn index_path() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".wsldashboard").join(INDEX_FILE_NAME)
+}
+
+fn notify() {
+    let listener = LISTENER.lock().unwrap().clone();
+    if let Some(listener) = listener {
+        listener();
+    }
+}
+
+// Apply the download settings, running downloads pick up the bandwidth cap at once
+pub fn configure(config: &crate::config::DownloadConfig) {
+    CONNECTIONS.store(config.connections.clamp(1, 16) as usize, Ordering::Relaxed);
+    LIMITER.set_limit(config.bandwidth_limit_mb as u64 * 1024 * 1024);
+}
+
+// Called whenever a job changes, progress included (throttled)
+pub fn set_listener(listener: impl Fn() + Send + Sync + 'static) {
+    *LISTENER.lock().unwrap() = Some(Arc::new(listener));
+}
+
+// All jobs, oldest first
+pub fn list() -> Vec<JobSnapshot> {
+    let queue = QUEUE.lock().unwrap();
+    queue.jobs.iter()
+        .map(|job| {
+            let active = queue.active.get(&job.id);
+            JobSnapshot {
+                job: job.clone(),
+                percent: active.and_then(|a| a.percent),
+                speed: active.and_then(|a| a.speed.clone()),
+            }
+        })
+        .collect()
+}
+
+pub fn get(id: &str) -> Option<DownloadJob> {
+    QUEUE.lock().unwrap().jobs.iter().find(|j| j.id == id).cloned()
+}
+
+// Job for this distro version. An unfinished or completed one is reused, so the
+// download continues from the bytes already on disk. A finished file none of the
+// new sources is known to serve is downloaded again.
+pub fn enqueue(name: &str, version: &str, sources: Vec<MirrorSource>, temp_location: &str) -> String {
+    let mut queue = QUEUE.lock().unwrap();
+    if let Some(job) = queue.jobs.iter_mut().find(|j| j.name == name && j.version == version) {
+        let state = job.state();
+        if job.status == JobStatus::Completed && !sources.iter().any(|s| state.continues_with(s, None)) {
+            info!("Download of '{} {}' came from {}, which is no longer listed, downloading again", name, version, job.origin);
+            job.status = JobStatus::Queued;
+            job.apply(&DownloadState::default());
+        }
+        job.sources = sources;
+        let id = job.id.clone();
+        queue.save();
+        return id;
+    }
+
+    let id = uuid::Uuid::new_v4().to_string();
+    let file = PathBuf::from(temp_location).join(format!("mirror_download_{}.tmp", id));
+    queue.jobs.push(DownloadJob {
+        id: id.clone(),
+        name: name.to_string(),
+        version: version.to_string(),
+        sources,
+        file: file.to_string_lossy().to_string(),
+        total_size: 0,
+        segments: Vec::new(),
+        status: JobStatus::Queued,
+        mirror: String::new(),
+        origin: String::new(),
+        origin_sha256: String::new(),
+        etag: String::new(),
+        error: String::new(),
+        added_time: chrono::Utc::now().timestamp_millis().to_string(),
+    });
+    queue.save();
+    drop(queue);
+    notify();
+    id
+}
+
+fn save_progress(id: &str, state: &DownloadState) {
+    let mut queue = QUEUE.lock().unwrap();
+    if let Some(job) = queue.job_mut(id) {
+        job.apply(state);
+        queue.save();
+    }
+}
+
+fn set_status(id: &str, status: JobStatus) {
+    let mut queue = QUEUE.lock().unwrap();
+    if let Some(job) = queue.job_mut(id) {
+        job.status = status;
+        queue.save();
+    }
+}
+
+// Download a job, waiting for its turn in the queue. Returns the finished job; a
+// completed job whose file is still there returns right away.
+pub async fn run<F>(id: &str, progress_callback: F) -> Result<DownloadJob, DownloadError>
+where
+    F: Fn(DownloadProgress) + Send + Sync,
+{
+    let lock = QUEUE.lock().unwrap().locks.entry(id.to_string()).or_default().clone();
+    let _guard = lock.lock().await;
+
+    let control = Arc::new(TransferControl::default());
+    let job = {
+        let mut queue = QUEUE.lock().unwrap();
+        let Some(job) = queue.job_mut(id) else {
+            return Err(DownloadError::Cancelled);
+        };
+        if job.status == JobStatus::Completed && job.path().exists() {
+            return Ok(job.clone());
+        }
+        if !job.path().exists() {
+            // The partial file is gone (temp folder cleaned), start over
+            job.total_size = 0;
+            job.segments.clear();
+        }
+        job.status = JobStatus::Queued;
+        job.error.clear();
+        let job = job.clone();
+        queue.active.insert(id.to_string(), Active { control: control.clone(), percent: None, speed: None, removed: false });
+        queue.save();
+        job
+    };
+    notify();
+
+    let permit = match SLOT.try_acquire() {
+        Ok(permit) => Some(permit),
+        Err(_) => {
+            progress_callback(DownloadProgress::Queued);
+            tokio::select! {
+                permit = SLOT.acquire() => permit.ok(),
+                _ = control.paused() => None,
+            }
+        }
+    };
+
+    let mut state = job.state();
+    let result = if permit.is_some() && !control.is_paused() {
+        set_status(id, JobStatus::Running);
+        notify();
+        info!("Download queue: starting '{} {}' ({} bytes on disk)", job.name, job.version, job.downloaded());
+
+        let options = TransferOptions {
+            connections: CONNECTIONS.load(Ordering::Relaxed),
+            control: control.clone(),
+            limiter: LIMITER.clone(),
+        };
+        let last_notify = Mutex::new(Instant::now());
+        download_with_fallback(&job.sources, &job.path(), &mut state, &options, |progress| {
+            if let DownloadProgress::Downloading { percent, speed } = &progress {
+                if let Some(active) = QUEUE.lock().unwrap().active.get_mut(id) {
+                    active.percent = Some(*percent);
+                    active.speed = Some(speed.to_string());
+                }
+                let mut last = last_notify.lock().unwrap();
+                if last.elapsed().as_millis() >= NOTIFY_INTERVAL_MS {
+                    *last = Instant::now();
+                    drop(last);
+                    notify();
+                }
+            }
+            progress_callback(progress);
+        }, |state| save_progress(id, state)).await
+    } else {
+        Err(DownloadError::Paused)
+    };
+    drop(permit);
+    super::history::flush().await;
+
+    let status = match &result {
+        Ok(_) => JobStatus::Completed,
+        Err(DownloadError::Paused) => JobStatus::Paused,
+        Err(_) => JobStatus::Failed,
+    };
+    let (finished, removed) = {
+        let mut queue = QUEUE.lock().unwrap();
+        let removed = queue.active.remove(id).is_some_and(|a| a.removed);
+        let finished = match queue.job_mut(id) {
+            Some(job) if !removed => {
+                job.apply(&state);
+                job.status = status;
+                job.error = result.as_ref().err().map(|e| e.to_string()).unwrap_or_default();
+                let job = job.clone();
+                queue.save();
+                Some(job)
+            }
+            _ => None,
+        };
+        (finished, removed)
+    };
+    if removed {
+        let _ = tokio::fs::remove_file(job.path()).await;
+    }
+    notify();
+
+    match (result, finished) {
+        (Ok(_), Some(job)) => {
+            info!("Download queue: '{} {}' completed", job.name, job.version);
+            Ok(job)
+        }
+        (Err(e), Some(_)) => Err(e),
+        (_, None) => Err(DownloadError::Cancelled),
+    }
+}
+
+// Stop a queued or running job, keeping what is on disk
+pub fn pause(id: &str) {
+    let queue = QUEUE.lock().unwrap();
+    if let Some(active) = queue.active.get(id) {
+        info!("Download queue: pausing {}", id);
+        active.control.pause();
+    }
+}
+
+// Whether the job can be started again (paused or failed, and not running)
+pub fn can_resume(id: &str) -> bool {
+    let queue = QUEUE.lock().unwrap();
+    !queue.active.contains_key(id)
+        && queue.jobs.iter().any(|j| j.id == id && matches!(j.status, JobStatus::Paused | JobStatus::Failed))
+}
+
+// Drop a job and its file
+pub fn remove(id: &str) -> Result<(), String> {
+    let mut queue = QUEUE.lock().unwrap();
+    let Some(pos) = queue.jobs.iter().position(|j| j.id == id) else {
+        return Ok(());
+    };
+    let job = queue.jobs.remove(pos);
+    queue.save();
+    let running = match queue.active.get_mut(id) {
+        Some(active) => {
+            active.removed = true;
+            active.control.pause();
+            true
+        }
+        None => false,
+    };
+    drop(queue);
+    notify();
+
+    if !running {
+        if let Err(e) = std::fs::remove_file(job.path()) {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                return Err(e.to_string());
+            }
+        }
+    }
+    info!("Download queue: removed '{} {}'", job.name, job.version);
+    Ok(())
+}
+
+// Forget a completed job once its file has been taken over by an install or the cache
+pub fn finish(id: &str) {
+    let mut queue = QUEUE.lock().unwrap();
+    queue.jobs.retain(|j| j.id != id);
+    queue.locks.remove(id);
+    queue.save();
+    drop(queue);
+    notify();
+}
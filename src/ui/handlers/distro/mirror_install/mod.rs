@@ -3,17 +3,80 @@
 
 mod types;
 mod probe;
+mod control;
 mod download;
+pub mod manager;
+pub mod history;
 
-pub use types::{DownloadProgress, DownloadProgressOwned};
+pub use types::{DownloadError, DownloadProgress, DownloadProgressOwned};
 pub use probe::select_fastest_mirrors;
-pub use download::download_with_fallback;
+pub use download::probe_download_size;
 
 use types::replace_last_line;
+use crate::wsl::ops::{appx, image_cache};
+use crate::wsl::ops::preflight::{plan_import, plan_mirror_install, SpacePlan};
 use std::sync::Arc;
 use tracing::{info, error, debug};
 use crate::{AppWindow, i18n};
 
+fn find_distro(internal_id: &str) -> Option<crate::api::models::DistroInfo> {
+    let cache = crate::ui::data::MIRROR_LIST_CACHE.lock().unwrap();
+    cache.iter().find(|d| format!("{} {}", d.name, d.version) == internal_id).cloned()
+}
+
+fn target_path_for(config_manager: &crate::config::ConfigManager, final_name: &str, install_path: &str) -> String {
+    if install_path.is_empty() {
+        let base = std::path::PathBuf::from(&config_manager.get_settings().distro_location);
+        base.join(final_name).to_string_lossy().to_string()
+    } else {
+        install_path.to_string()
+    }
+}
+
+// Disk space plan shown in the install confirmation. A cached image only needs the import,
+// otherwise the size is probed on the first source, the install re-checks on the mirror it picks.
+pub async fn preview_space_plan(
+    config_manager: &crate::config::ConfigManager,
+    internal_id: &str,
+    final_name: &str,
+    install_path: &str,
+) -> Option<SpacePlan> {
+    let distro_info = find_distro(internal_id)?;
+    let settings = config_manager.get_settings();
+    let target_path = target_path_for(config_manager, final_name, install_path);
+    if settings.image_cache {
+        let sources = distro_info.sources.clone();
+        if let Some(image) = tokio::task::spawn_blocking(move || image_cache::find(&sources)).await.ok().flatten() {
+            return Some(plan_import(&image.path().to_string_lossy(), &target_path, false));
+        }
+    }
+    let download_size = match distro_info.sources.first() {
+        Some(source) => probe_download_size(source).await,
+        None => None,
+    };
+    Some(plan_mirror_install(download_size, &settings.temp_location, &target_path))
+}
+
+// Show the plan in the terminal view, `Err` when it does not fit
+fn check_space_plan(ah: &slint::Weak<AppWindow>, plan: &SpacePlan, terminal_buffer: &mut String) -> Result<(), String> {
+    if !terminal_buffer.ends_with('\n') { terminal_buffer.push('\n'); }
+    terminal_buffer.push_str(&format!("{}\n", plan.summary()));
+    let ah_cb = ah.clone();
+    let tb = terminal_buffer.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_cb.upgrade() {
+            app.set_terminal_output(tb.into());
+        }
+    });
+    match plan.shortage() {
+        Some(err) => {
+            error!("install_from_mirror: blocked by disk space preflight: {}", err);
+            Err(err)
+        }
+        None => Ok(()),
+    }
+}
+
 pub async fn install_from_mirror(
     ah: slint::Weak<AppWindow>,
     executor: Arc<crate::wsl::executor::WslCommandExecutor>,
@@ -24,10 +87,7 @@ pub async fn install_from_mirror(
 ) -> Result<String, (String, String)> {
     let mut terminal_buffer = String::new();
 
-    let distro_info = {
-        let cache = crate::ui::data::MIRROR_LIST_CACHE.lock().unwrap();
-        cache.iter().find(|d| format!("{} {}", d.name, d.version) == internal_id).cloned()
-    };
+    let distro_info = find_distro(&internal_id);
 
     debug!("install_from_mirror: looking up internal_id='{}' in MIRROR_LIST_CACHE (entries: {})",
         internal_id,
@@ -45,6 +105,55 @@ pub async fn install_from_mirror(
         }
     };
 
+    let (cache_enabled, cache_max_gb) = {
+        let settings = config_manager.get_settings();
+        (settings.image_cache, settings.image_cache_max_gb)
+    };
+
+    let temp_location = config_manager.get_settings().temp_location.clone();
+
+    let target_path = target_path_for(&config_manager, &final_name, &install_path);
+
+    // Reuse a cached image with the published hash, or from the same URL and catalog.
+    // It is hashed again first, no network needed.
+    if cache_enabled {
+        let sources = distro_info.sources.clone();
+        let cached = tokio::task::spawn_blocking(move || image_cache::find(&sources)).await.ok().flatten();
+        let cached = match cached {
+            Some(image) => {
+                terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.cache_verifying", &[image.file.clone()])));
+                let ah_cb = ah.clone();
+                let tb = terminal_buffer.clone();
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah_cb.upgrade() {
+                        app.set_terminal_output(tb.into());
+                    }
+                });
+                let verified = tokio::task::spawn_blocking(move || image_cache::verify(image)).await.ok().flatten();
+                if verified.is_none() {
+                    replace_last_line(&mut terminal_buffer, &i18n::t("install.cache_invalid"));
+                }
+                verified
+            }
+            None => None,
+        };
+        if let Some(image) = cached {
+            info!("install_from_mirror: using cached image '{}' for '{}'", image.file, internal_id);
+            replace_last_line(&mut terminal_buffer, &i18n::tr("install.cache_hit", &[
+                format!("{} {}", image.name, image.version),
+                crate::wsl::ops::preflight::format_bytes(image.size),
+            ]));
+            // The import still needs room in the target
+            let plan = plan_import(&image.path().to_string_lossy(), &target_path, false);
+            if let Err(err) = check_space_plan(&ah, &plan, &mut terminal_buffer) {
+                return Err((err, terminal_buffer));
+            }
+            let sha256 = image.sha256.clone();
+            let _ = tokio::task::spawn_blocking(move || image_cache::touch(&sha256)).await;
+            return import_image(&ah, &executor, &final_name, &target_path, &temp_location, &image.path(), terminal_buffer).await;
+        }
+    }
+
     let ah_cb = ah.clone();
     let _ = slint::invoke_from_event_loop(move || {
         if let Some(app) = ah_cb.upgrade() {
@@ -69,6 +178,14 @@ pub async fn install_from_mirror(
     let fastest_mirror_name = &fastest_mirrors[0].mirror;
     replace_last_line(&mut terminal_buffer, &i18n::tr("install.mirror_selected", &[fastest_mirror_name.clone()]));
 
+    // Disk space preflight: download to temp, then extraction into the target.
+    // An unknown size still checks the safety margin and that the volumes resolve.
+    let download_size = probe_download_size(&fastest_mirrors[0]).await;
+    let plan = plan_mirror_install(download_size, &temp_location, &target_path);
+    if let Err(err) = check_space_plan(&ah, &plan, &mut terminal_buffer) {
+        return Err((err, terminal_buffer));
+    }
+
     let ah_cb = ah.clone();
     let _ = slint::invoke_from_event_loop(move || {
         if let Some(app) = ah_cb.upgrade() {
@@ -76,10 +193,10 @@ pub async fn install_from_mirror(
         }
     });
 
-    let temp_location = config_manager.get_settings().temp_location.clone();
     let temp_dir = std::path::PathBuf::from(temp_location.clone());
     let _ = tokio::task::spawn_blocking(move || std::fs::create_dir_all(&temp_dir)).await;
-    let temp_file = std::path::PathBuf::from(temp_location).join(format!("mirror_download_{}.tmp", uuid::Uuid::new_v4()));
+    // An earlier, paused or interrupted download of this version is continued
+    let job_id = manager::enqueue(&distro_info.name, &distro_info.version, fastest_mirrors.clone(), &temp_location);
 
     let (tx, mut rx) = tokio::sync::mpsc::channel(100);
     let ah_ui = ah.clone();
@@ -90,6 +207,16 @@ pub async fn install_from_mirror(
         let mut current_mirror = String::new();
         while let Some(msg) = rx.recv().await {
             match msg {
+                DownloadProgressOwned::Queued => {
+                    buffer.push_str(&format!("{}\n", i18n::t("install.download_queued")));
+                }
+                DownloadProgressOwned::Resuming { downloaded } => {
+                    let text = i18n::tr("install.download_resuming", &[crate::wsl::ops::preflight::format_bytes(downloaded)]);
+                    // Progress updates replace the last line, keep this one above it
+                    let trying = i18n::tr("install.trying_mirror", &[current_mirror.clone()]);
+                    replace_last_line(&mut buffer, &text);
+                    buffer.push_str(&format!("{}\n", trying));
+                }
                 DownloadProgressOwned::TryingMirror { mirror, .. } => {
                     current_mirror = mirror.clone();
                     let text = i18n::tr("install.trying_mirror", &[mirror]);
@@ -136,9 +263,11 @@ pub async fn install_from_mirror(
         buffer
     });
 
-    let download_res = download_with_fallback(&fastest_mirrors, &temp_file, |progress| {
+    let download_res = manager::run(&job_id, |progress| {
         // Send and drop Owned strings
         let owned_progress = match progress {
+            DownloadProgress::Queued => DownloadProgressOwned::Queued,
+            DownloadProgress::Resuming { downloaded } => DownloadProgressOwned::Resuming { downloaded },
             DownloadProgress::TryingMirror { mirror, url } => DownloadProgressOwned::TryingMirror { mirror: mirror.to_string(), url: url.to_string() },
             DownloadProgress::Downloading { percent, speed } => DownloadProgressOwned::Downloading { percent, speed: speed.to_string() },
             DownloadProgress::MirrorFailed { mirror, error } => DownloadProgressOwned::MirrorFailed { mirror: mirror.to_string(), error: error.to_string() },
@@ -150,102 +279,170 @@ pub async fn install_from_mirror(
     drop(tx);
     terminal_buffer = ui_task.await.unwrap_or(terminal_buffer);
 
-    match download_res {
-        Ok(downloaded_file) => {
-            if !terminal_buffer.ends_with('\n') { terminal_buffer.push('\n'); }
-            terminal_buffer.push_str(&format!("{}\n", i18n::t("install.download_complete")));
-            
-            let ah_cb = ah.clone();
-            let tb = terminal_buffer.clone();
-            let _ = slint::invoke_from_event_loop(move || {
-                if let Some(app) = ah_cb.upgrade() {
-                    app.set_install_status(i18n::t("install.importing").into());
-                    app.set_terminal_output(tb.into());
+    let job = match download_res {
+        Ok(job) => job,
+        Err(DownloadError::Paused) => return Err((i18n::t("install.download_paused"), terminal_buffer)),
+        Err(e) => return Err((e.to_string(), terminal_buffer)),
+    };
+    // The install takes the file over from here
+    manager::finish(&job.id);
+    let downloaded_file = job.path();
+
+    if !terminal_buffer.ends_with('\n') { terminal_buffer.push('\n'); }
+    terminal_buffer.push_str(&format!("{}\n", i18n::t("install.download_complete")));
+
+    if !cache_enabled {
+        let result = import_image(&ah, &executor, &final_name, &target_path, &temp_location, &downloaded_file, terminal_buffer).await;
+        let _ = tokio::fs::remove_file(&downloaded_file).await;
+        return result;
+    }
+
+    // Keep the download in the image cache and import from there
+    let source = job.source().cloned().unwrap_or_default();
+    let templates: Vec<String> = config_manager.get_templates().into_iter().map(|t| t.file).collect();
+    let (name, version) = (distro_info.name.clone(), distro_info.version.clone());
+    let file = downloaded_file.clone();
+    let stored = tokio::task::spawn_blocking(move || image_cache::store(&name, &version, &source, &file))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+
+    match stored {
+        Ok(image) => {
+            let result = import_image(&ah, &executor, &final_name, &target_path, &temp_location, &image.path(), terminal_buffer).await;
+            // Prune only once the import is done, and never evict the image just installed
+            let _ = tokio::task::spawn_blocking(move || {
+                let evicted = image_cache::prune(cache_max_gb as u64 * 1024 * 1024 * 1024, &templates, &image.sha256);
+                if !evicted.is_empty() {
+                    info!("install_from_mirror: evicted {} cached image(s): {}", evicted.len(), evicted.join(", "));
                 }
-            });
+            }).await;
+            result
+        }
+        Err(e) => {
+            error!("install_from_mirror: caching the image failed: {}", e);
+            let result = import_image(&ah, &executor, &final_name, &target_path, &temp_location, &downloaded_file, terminal_buffer).await;
+            let _ = tokio::fs::remove_file(&downloaded_file).await;
+            result
+        }
+    }
+}
+
+// Import a downloaded or cached image, streaming WSL output into the terminal view
+async fn import_image(
+    ah: &slint::Weak<AppWindow>,
+    executor: &crate::wsl::executor::WslCommandExecutor,
+    final_name: &str,
+    target_path: &str,
+    temp_location: &str,
+    image_file: &std::path::Path,
+    mut terminal_buffer: String,
+) -> Result<String, (String, String)> {
+    let ah_cb = ah.clone();
+    let tb = terminal_buffer.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_cb.upgrade() {
+            app.set_install_status(i18n::t("install.importing").into());
+            app.set_terminal_output(tb.into());
+        }
+    });
 
-            let mut target_path = install_path.clone();
-            if target_path.is_empty() {
-                let distro_location = config_manager.get_settings().distro_location.clone();
-                let base = std::path::PathBuf::from(&distro_location);
-                target_path = base.join(&final_name).to_string_lossy().to_string();
+    let tp_clone = target_path.to_string();
+    if let Err(e) = tokio::task::spawn_blocking(move || std::fs::create_dir_all(&tp_clone)).await.unwrap() {
+        let err = format!("Failed to create directory: {}", e);
+        return Err((err, terminal_buffer));
+    }
+
+    // Store packages are unpacked first, `wsl --import` only takes the rootfs tarball
+    let mut image_file = image_file.to_path_buf();
+    let mut unpack_dir = None;
+    let package = image_file.clone();
+    if tokio::task::spawn_blocking(move || appx::is_package(&package)).await.unwrap_or(false) {
+        terminal_buffer.push_str(&format!("{}\n", i18n::t("install.unpacking_package")));
+        let work_dir = std::path::Path::new(temp_location).join(format!("package_{}", uuid::Uuid::new_v4()));
+        let (package, wd) = (image_file.clone(), work_dir.clone());
+        let extracted = tokio::task::spawn_blocking(move || appx::extract_rootfs(&package, &wd))
+            .await
+            .map_err(|e| e.to_string())
+            .and_then(|r| r);
+        match extracted {
+            Ok(rootfs) => {
+                image_file = rootfs;
+                unpack_dir = Some(work_dir);
             }
-            
-            let tp_clone = target_path.clone();
-            if let Err(e) = tokio::task::spawn_blocking(move || std::fs::create_dir_all(&tp_clone)).await.unwrap() {
-                let err = format!("Failed to create directory: {}", e);
-                return Err((err, terminal_buffer));
+            Err(e) => {
+                let _ = tokio::fs::remove_dir_all(&work_dir).await;
+                return Err((e, terminal_buffer));
             }
+        }
+    }
 
-            let downloaded_file_str = downloaded_file.to_string_lossy().to_string();
-            let import_args = vec!["--import", &final_name, &target_path, &downloaded_file_str];
-            let display_file = if downloaded_file_str.len() > 30 {
-                format!("{}...", &downloaded_file_str[..30])
-            } else {
-                downloaded_file_str.clone()
-            };
-            let cmd_display = format!("wsl --import {} {} {}", final_name, target_path, display_file);
-            terminal_buffer.push_str(&i18n::tr("install.mirror_step_import", &[cmd_display]));
-            terminal_buffer.push('\n');
-            
-            let (tx_out, mut rx_out) = tokio::sync::mpsc::channel::<String>(100);
-            let ah_ui_2 = ah.clone();
-            let initial_tb_2 = terminal_buffer.clone();
-            let ui_task_2 = tokio::spawn(async move {
-                let mut buffer = initial_tb_2;
-                while let Some(msg) = rx_out.recv().await {
-                    // Filter out sparse VHD warnings from WSL command stdout/stderr
-                    // Note: Do NOT filter success messages here - use exit code (result.success) instead
-                    let filtered: String = msg
-                        .lines()
-                        .filter(|line| {
-                            let l = line.trim().to_lowercase();
-                            !l.contains("sparse vhd support is currently disabled")
-                                && !l.contains("to force a distribution to use a sparse vhd")
-                                && !l.contains("wsl.exe --manage")
-                                && !l.contains("allow-unsafe")
-                        })
-                        .map(|line| format!("{}\n", line))
-                        .collect();
-
-                    if !filtered.trim().is_empty() {
-                        buffer.push_str(&filtered);
-                        let ah_cb = ah_ui_2.clone();
-                        let tb = buffer.clone();
-                        let _ = slint::invoke_from_event_loop(move || {
-                            if let Some(app) = ah_cb.upgrade() {
-                                app.set_terminal_output(tb.into());
-                            }
-                        });
-                    }
-                }
-                buffer
-            });
-
-            let tx_callback = tx_out.clone();
-            let result = executor.execute_command_streaming(&import_args, move |text| {
-                let _ = tx_callback.try_send(text);
-            }).await;
+    let image_file_str = image_file.to_string_lossy().to_string();
+    let import_args = vec!["--import", final_name, target_path, &image_file_str];
+    let display_file = if image_file_str.len() > 30 {
+        format!("{}...", &image_file_str[..30])
+    } else {
+        image_file_str.clone()
+    };
+    let cmd_display = format!("wsl --import {} {} {}", final_name, target_path, display_file);
+    terminal_buffer.push_str(&i18n::tr("install.mirror_step_import", &[cmd_display]));
+    terminal_buffer.push('\n');
 
-            drop(tx_out);
-            terminal_buffer = ui_task_2.await.unwrap_or(terminal_buffer);
-            let _ = tokio::fs::remove_file(&downloaded_file).await;
+    let (tx_out, mut rx_out) = tokio::sync::mpsc::channel::<String>(100);
+    let ah_ui_2 = ah.clone();
+    let initial_tb_2 = terminal_buffer.clone();
+    let ui_task_2 = tokio::spawn(async move {
+        let mut buffer = initial_tb_2;
+        while let Some(msg) = rx_out.recv().await {
+            // Filter out sparse VHD warnings from WSL command stdout/stderr
+            // Note: Do NOT filter success messages here - use exit code (result.success) instead
+            let filtered: String = msg
+                .lines()
+                .filter(|line| {
+                    let l = line.trim().to_lowercase();
+                    !l.contains("sparse vhd support is currently disabled")
+                        && !l.contains("to force a distribution to use a sparse vhd")
+                        && !l.contains("wsl.exe --manage")
+                        && !l.contains("allow-unsafe")
+                })
+                .map(|line| format!("{}\n", line))
+                .collect();
 
-            if result.success {
-                info!("mirror_install: wsl --import succeeded for '{}'", final_name);
-                if !terminal_buffer.ends_with('\n') { terminal_buffer.push('\n'); }
-                terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.mirror_step_done", &[final_name.clone()])));
-                Ok(terminal_buffer)
-            } else {
-                error!("mirror_install: wsl --import failed for '{}': {:?}", final_name, result.error);
-                if !result.output.trim().is_empty() {
-                    terminal_buffer.push_str(&format!("\n[WSL Output]\n{}\n", result.output));
-                }
-                Err((result.error.unwrap_or_else(|| i18n::t("install.import_failed")), terminal_buffer))
+            if !filtered.trim().is_empty() {
+                buffer.push_str(&filtered);
+                let ah_cb = ah_ui_2.clone();
+                let tb = buffer.clone();
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah_cb.upgrade() {
+                        app.set_terminal_output(tb.into());
+                    }
+                });
             }
         }
-        Err(e) => {
-            Err((e.to_string(), terminal_buffer))
+        buffer
+    });
+
+    let tx_callback = tx_out.clone();
+    let result = executor.execute_command_streaming(&import_args, move |text| {
+        let _ = tx_callback.try_send(text);
+    }).await;
+
+    drop(tx_out);
+    terminal_buffer = ui_task_2.await.unwrap_or(terminal_buffer);
+    if let Some(dir) = unpack_dir {
+        let _ = tokio::fs::remove_dir_all(&dir).await;
+    }
+
+    if result.success {
+        info!("mirror_install: wsl --import succeeded for '{}'", final_name);
+        if !terminal_buffer.ends_with('\n') { terminal_buffer.push('\n'); }
+        terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.mirror_step_done", &[final_name.to_string()])));
+        Ok(terminal_buffer)
+    } else {
+        error!("mirror_install: wsl --import failed for '{}': {:?}", final_name, result.error);
+        if !result.output.trim().is_empty() {
+            terminal_buffer.push_str(&format!("\n[WSL Output]\n{}\n", result.output));
         }
+        Err((result.error.unwrap_or_else(|| i18n::t("install.import_failed")), terminal_buffer))
     }
 }
@@ -8,6 +8,7 @@ use reqwest::header::RANGE;
 use tracing::{info, warn, debug};
 
 use crate::api::models::MirrorSource;
+use super::history::{self, MirrorPreference, MirrorRecord};
 
 // Probe TCP connection latency (ms) as fallback when throughput probing fails
 async fn probe_tcp_latency(domain: &str) -> u64 {
@@ -37,7 +38,7 @@ async fn probe_mirror_throughput(source: &MirrorSource) -> u64 {
     const PROBE_SIZE: u64 = 3 * 1024 * 1024; // 3MB
     const TIMEOUT: Duration = Duration::from_secs(6);
 
-    let client = match reqwest::Client::builder()
+    let client = match crate::network::http::client_builder()
         .connect_timeout(Duration::from_secs(5))
         .no_gzip()
         .no_brotli()
@@ -96,49 +97,75 @@ async fn probe_mirror_throughput(source: &MirrorSource) -> u64 {
     }
 }
 
-// Extract domain from URL
-fn extract_domain(url: &str) -> String {
-    if let Ok(parsed) = reqwest::Url::parse(url) {
-        parsed.host_str().unwrap_or("").to_string()
-    } else {
-        url.to_string()
+// Order mirrors by score: the live throughput probe weighed against the history of
+// each mirror. Banned mirrors and those that keep failing are left out, pinned ones go first.
+// When every mirror is banned they are all kept and ranked like any other.
+pub async fn select_fastest_mirrors(sources: &[MirrorSource]) -> Vec<MirrorSource> {
+    let now = chrono::Utc::now().timestamp();
+    let domains: Vec<String> = sources.iter().map(|s| history::domain_of(&s.url)).collect();
+    let records = history::lookup(&domains);
+
+    // Layer 0: history, before any network traffic
+    let (mut candidates, banned): (Vec<(MirrorSource, MirrorRecord)>, Vec<_>) = sources.iter().cloned()
+        .zip(records)
+        .partition(|(_, r)| r.preference != MirrorPreference::Banned);
+    for (s, r) in &banned {
+        info!("Mirror {} ({}) is banned, skipping", s.mirror, r.domain);
     }
-}
+    if candidates.is_empty() && !banned.is_empty() {
+        warn!("All {} mirrors are banned, falling back to the least bad one", banned.len());
+        candidates = banned;
+    }
+    let (kept, skipped): (Vec<_>, Vec<_>) = candidates.drain(..).partition(|(_, r)| !r.is_skipped(now));
+    for (s, r) in &skipped {
+        info!("Mirror {} skipped: {}/{} recent attempts succeeded, last failure: {}",
+            s.mirror, r.successes, r.attempts, r.last_failure);
+    }
+    // Better a poor mirror than none
+    let candidates = if kept.is_empty() { skipped } else { kept };
 
-pub async fn select_fastest_mirrors(sources: &[MirrorSource]) -> Vec<MirrorSource> {
-    // Layer 1: concurrent throughput probing for all mirrors
-    let probes: Vec<_> = sources
-        .iter()
-        .map(|s| async {
-            let throughput = probe_mirror_throughput(s).await;
-            (s.clone(), throughput)
+    // Layer 1: concurrent throughput probing for the remaining mirrors
+    let probes: Vec<_> = candidates
+        .into_iter()
+        .map(|(s, r)| async move {
+            let throughput = probe_mirror_throughput(&s).await;
+            history::record_probe(&s.url, throughput);
+            (s, r, throughput)
         })
         .collect();
 
-    let mut results = futures_util::future::join_all(probes).await;
+    let results = futures_util::future::join_all(probes).await;
 
-    // Check if any throughput probe succeeded
-    let has_throughput = results.iter().any(|(_, t)| *t > 0);
+    // Scores need a live or a past throughput of at least one mirror
+    let has_throughput = results.iter().any(|(_, r, t)| *t > 0 || r.past_throughput().is_some());
+    let pinned_first = |r: &MirrorRecord| r.preference != MirrorPreference::Pinned;
 
     if has_throughput {
-        // Sort by throughput descending, failed ones go to the end
-        results.sort_by(|a, b| b.1.cmp(&a.1));
-        let sorted: Vec<MirrorSource> = results.into_iter().map(|(s, _)| s).collect();
+        let mut scored: Vec<_> = results.into_iter()
+            .map(|(s, r, t)| {
+                let score = history::score(&r, t, now);
+                debug!("Mirror {} score {:.0} (live {} B/s, past {:?} B/s, success rate {:?})",
+                    s.mirror, score, t, r.past_throughput(), r.success_rate());
+                (s, r, score)
+            })
+            .collect();
+        // Pinned first, then by score descending
+        scored.sort_by(|a, b| pinned_first(&a.1).cmp(&pinned_first(&b.1)).then(b.2.total_cmp(&a.2)));
+        let sorted: Vec<MirrorSource> = scored.into_iter().map(|(s, _, _)| s).collect();
         if let Some(best) = sorted.first() {
-            info!("Fastest mirror selected by throughput: {} ({})", best.mirror, best.url);
+            info!("Best mirror selected by score: {} ({})", best.mirror, best.url);
         }
         sorted
     } else {
         // Layer 2 (fallback): all throughput probes failed, degrade to TCP latency probing
         warn!("All throughput probes failed, falling back to TCP latency probing");
         let mut with_latency: Vec<_> = Vec::new();
-        for s in sources {
-            let domain = extract_domain(&s.url);
-            let latency = probe_tcp_latency(&domain).await;
-            with_latency.push((s.clone(), latency));
+        for (s, r, _) in results {
+            let latency = probe_tcp_latency(&history::domain_of(&s.url)).await;
+            with_latency.push((s, r, latency));
         }
-        with_latency.sort_by_key(|(_, latency)| *latency);
-        let sorted: Vec<MirrorSource> = with_latency.into_iter().map(|(s, _)| s).collect();
+        with_latency.sort_by_key(|(_, r, latency)| (pinned_first(r), *latency));
+        let sorted: Vec<MirrorSource> = with_latency.into_iter().map(|(s, _, _)| s).collect();
         if let Some(best) = sorted.first() {
             info!("Fastest mirror selected by TCP latency (fallback): {} ({})", best.mirror, best.url);
         }
@@ -1,12 +1,18 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
+use serde::{Deserialize, Serialize};
+
 #[derive(Debug, Clone)]
 pub enum DownloadError {
     HumanVerification { mirror: String, reason: String },
     NetworkError { mirror: String, error: String },
     FileError { mirror: String, error: String },
     AllMirrorsFailed { #[allow(dead_code)] errors: Vec<String> },
+    // Stopped on request, the bytes on disk are kept for a later resume
+    Paused,
+    // The job was removed from the queue
+    Cancelled,
 }
 
 impl std::fmt::Display for DownloadError {
@@ -18,13 +24,47 @@ impl std::fmt::Display for DownloadError {
             Self::AllMirrorsFailed { .. } => {
                 write!(f, "All mirrors failed")
             }
+            Self::Paused => write!(f, "Download paused"),
+            Self::Cancelled => write!(f, "Download cancelled"),
         }
     }
 }
 
 impl std::error::Error for DownloadError {}
 
+impl DownloadError {
+    // Short name recorded in the mirror history
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::HumanVerification { .. } => "HumanVerification",
+            Self::NetworkError { .. } => "NetworkError",
+            Self::FileError { .. } => "FileError",
+            Self::AllMirrorsFailed { .. } => "AllMirrorsFailed",
+            Self::Paused => "Paused",
+            Self::Cancelled => "Cancelled",
+        }
+    }
+}
+
+// Byte range [start, end) of the target file and how much of it is on disk.
+// `end` is 0 when the server did not report a size.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Segment {
+    pub start: u64,
+    pub end: u64,
+    #[serde(default)]
+    pub done: u64,
+}
+
+impl Segment {
+    pub fn is_complete(&self) -> bool {
+        self.end > 0 && self.start + self.done >= self.end
+    }
+}
+
 pub enum DownloadProgress<'a> {
+    Queued,
+    Resuming { downloaded: u64 },
     TryingMirror { mirror: &'a str, url: &'a str },
     Downloading { percent: f32, speed: &'a str },
     MirrorFailed { mirror: &'a str, error: &'a str },
@@ -32,6 +72,8 @@ pub enum DownloadProgress<'a> {
 }
 
 pub enum DownloadProgressOwned {
+    Queued,
+    Resuming { downloaded: u64 },
     #[allow(dead_code)]
     TryingMirror { mirror: String, url: String },
     Downloading { percent: f32, speed: String },
@@ -0,0 +1,103 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::VecModel;
+use tracing::info;
+use crate::{AppWindow, AppState, MirrorStatItem, i18n};
+use crate::wsl::ops::preflight::format_bytes;
+use super::mirror_install::history::{self, MirrorPreference, MirrorRecord};
+
+fn format_time(secs: i64) -> String {
+    use chrono::TimeZone;
+    chrono::Local.timestamp_opt(secs, 0).single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn detail_text(record: &MirrorRecord, now: i64) -> String {
+    if record.attempts == 0 {
+        return i18n::t("mirrors.no_attempts");
+    }
+    let mut parts = vec![i18n::tr("mirrors.success_rate", &[record.successes.to_string(), record.attempts.to_string()])];
+    if let Some(median) = record.median_throughput() {
+        parts.push(i18n::tr("mirrors.median_speed", &[format_bytes(median)]));
+    }
+    if !record.last_failure.is_empty() {
+        parts.push(i18n::tr("mirrors.last_failure", &[record.last_failure.clone(), format_time(record.last_failure_time)]));
+    }
+    if record.is_skipped(now) {
+        parts.push(i18n::t("mirrors.skipped"));
+    }
+    parts.join(" · ")
+}
+
+// Reload the mirror list shown in settings: every mirror with history, plus the
+// domains of the loaded mirror list so they can be banned before first use
+pub async fn refresh_mirror_list(ah: slint::Weak<AppWindow>) {
+    let rows = tokio::task::spawn_blocking(|| {
+        let mut domains: Vec<String> = history::list().into_iter().map(|r| r.domain).collect();
+        if let Ok(cache) = crate::ui::data::MIRROR_LIST_CACHE.lock() {
+            domains.extend(cache.iter().flat_map(|d| d.sources.iter()).map(|s| history::domain_of(&s.url)));
+        }
+        domains.retain(|d| !d.is_empty());
+        domains.sort();
+        domains.dedup();
+
+        let now = chrono::Utc::now().timestamp();
+        history::lookup(&domains).into_iter()
+            .map(|r| (r.domain.clone(), detail_text(&r, now), r.preference.as_str()))
+            .collect::<Vec<_>>()
+    }).await.unwrap_or_default();
+
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            let items: Vec<MirrorStatItem> = rows.into_iter()
+                .map(|(domain, detail, preference)| MirrorStatItem {
+                    domain: domain.into(),
+                    detail: detail.into(),
+                    preference: preference.into(),
+                })
+                .collect();
+            app.set_mirror_stats(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, _app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    app.on_refresh_mirror_stats(move || {
+        tokio::spawn(refresh_mirror_list(ah.clone()));
+    });
+
+    let ah = app_handle.clone();
+    app.on_set_mirror_preference(move |domain, preference| {
+        info!("Operation: Set mirror preference - {} -> {}", domain, preference);
+        let preference = match preference.as_str() {
+            "pinned" => MirrorPreference::Pinned,
+            "banned" => MirrorPreference::Banned,
+            _ => MirrorPreference::Auto,
+        };
+        let ah = ah.clone();
+        let domain = domain.to_string();
+        tokio::spawn(async move {
+            history::set_preference(&domain, preference);
+            history::flush().await;
+            refresh_mirror_list(ah).await;
+        });
+    });
+
+    let ah = app_handle.clone();
+    app.on_reset_mirror_stats(move |domain| {
+        info!("Operation: Reset mirror history - {}", domain);
+        let ah = ah.clone();
+        let domain = domain.to_string();
+        tokio::spawn(async move {
+            history::reset(&domain);
+            history::flush().await;
+            refresh_mirror_list(ah).await;
+        });
+    });
+}
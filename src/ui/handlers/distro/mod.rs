@@ -19,8 +19,15 @@ pub mod move_distro;
 pub mod move_logic;
 pub mod settings_logic;
 pub mod config_logic;
+pub mod mounts;
 pub mod compress;
 pub mod mirror_install;
+pub mod template_install;
+pub mod oci_install;
+pub mod recycle;
+pub mod image_cache;
+pub mod downloads;
+pub mod mirrors;
 
 pub fn sanitize_instance_name(name: &str) -> String {
     let mut sanitized = String::new();
@@ -62,11 +69,16 @@ pub fn generate_random_suffix(name: &str) -> String {
 pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
     lifecycle::setup(app, app_handle.clone(), app_state.clone());
     manage::setup(app, app_handle.clone(), app_state.clone());
+    mounts::setup(app, app_handle.clone(), app_state.clone());
     export::setup(app, app_handle.clone(), app_state.clone());
     clone::setup(app, app_handle.clone(), app_state.clone());
     install::setup(app, app_handle.clone(), app_state.clone());
     move_distro::setup(app, app_handle.clone(), app_state.clone());
     compress::setup(app, app_handle.clone(), app_state.clone());
+    recycle::setup(app, app_handle.clone(), app_state.clone());
+    image_cache::setup(app, app_handle.clone(), app_state.clone());
+    downloads::setup(app, app_handle.clone(), app_state.clone());
+    mirrors::setup(app, app_handle.clone(), app_state.clone());
 }
 
 pub fn spawn_file_size_monitor(
@@ -131,21 +143,7 @@ pub async fn resolve_temp_path(
                     info.actual_used // Fallback to actual_used (WSL1)
                 };
 
-                if !size_str.is_empty() && !size_str.contains("Unknown") {
-                    let parts: Vec<&str> = size_str.split_whitespace().collect();
-                    if parts.len() >= 2 {
-                        if let Ok(val) = parts[0].parse::<f64>() {
-                            let unit = parts[1].to_uppercase();
-                            vhdx_bytes = if unit.contains("GB") {
-                                (val * 1024.0 * 1024.0 * 1024.0) as u64
-                            } else if unit.contains("MB") {
-                                (val * 1024.0 * 1024.0) as u64
-                            } else {
-                                (val * 1024.0) as u64
-                            };
-                        }
-                    }
-                }
+                vhdx_bytes = crate::wsl::ops::preflight::parse_size_string(&size_str);
             }
         }
     }
@@ -0,0 +1,249 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// The mounts dialog: /etc/fstab entries and active mounts of one distro,
+// opened from the [automount] section of the wsl.conf dialog
+
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::VecModel;
+use tracing::{info, error};
+use crate::{AppWindow, AppState, FstabEntryItem, MountForm, MountItem, i18n};
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::ops::fstab::{
+    add_line, check_fstab, mount_entry, parse_fstab, read_fstab, read_mounts, remove_line, write_fstab,
+    DrvfsOptions, MountKind, NewMount,
+};
+
+enum Action {
+    Check(NewMount),
+    Add(NewMount),
+    Remove(usize, String),
+    Mount(String),
+}
+
+fn to_new_mount(form: &MountForm) -> NewMount {
+    NewMount {
+        kind: MountKind::from_index(form.kind),
+        source: form.source.to_string(),
+        target: form.target.to_string(),
+        drvfs: DrvfsOptions {
+            metadata: form.metadata,
+            uid: form.uid.to_string(),
+            gid: form.gid.to_string(),
+            umask: form.umask.to_string(),
+        },
+        extra_options: form.options.to_string(),
+    }
+}
+
+fn set_status(ah: &slint::Weak<AppWindow>, message: String, is_error: bool) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_mounts_busy(false);
+            app.set_mounts_status(message.into());
+            app.set_mounts_status_error(is_error);
+        }
+    });
+}
+
+async fn load(ah: slint::Weak<AppWindow>, executor: &WslCommandExecutor, distro: &str) -> Result<(), String> {
+    let fstab = read_fstab(executor, distro).await?;
+    let active = read_mounts(executor, distro).await?;
+    let entries: Vec<FstabEntryItem> = parse_fstab(&fstab).into_iter()
+        .map(|e| FstabEntryItem {
+            line: e.line as i32,
+            mounted: active.iter().any(|m| m.target == e.target.trim_end_matches('/')),
+            source: e.source.into(),
+            target: e.target.into(),
+            fstype: e.fstype.into(),
+            options: e.options.into(),
+        })
+        .collect();
+    let mounts: Vec<MountItem> = active.into_iter()
+        .map(|m| MountItem {
+            source: m.source.into(),
+            target: m.target.into(),
+            fstype: m.fstype.into(),
+            options: m.options.into(),
+        })
+        .collect();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_mounts_fstab(slint::ModelRc::from(Rc::new(VecModel::from(entries))));
+            app.set_mounts_active(slint::ModelRc::from(Rc::new(VecModel::from(mounts))));
+        }
+    });
+    Ok(())
+}
+
+async fn refresh(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, distro: String) {
+    let executor = app_state.lock().await.wsl_dashboard.executor().clone();
+    match load(ah.clone(), &executor, &distro).await {
+        Ok(()) => set_status(&ah, String::new(), false),
+        Err(e) => {
+            error!("Failed to read the mounts of '{}': {}", distro, e);
+            set_status(&ah, i18n::tr("mounts.load_failed", &[e]), true);
+        }
+    }
+}
+
+// The file with `mount` added, and the mount point the dry run has to find
+async fn with_new_mount(executor: &WslCommandExecutor, distro: &str, mount: &NewMount) -> Result<(String, String), String> {
+    let content = read_fstab(executor, distro).await?;
+    let line = mount.to_line(&parse_fstab(&content))?;
+    Ok((add_line(&content, &line), mount.target()))
+}
+
+async fn run(executor: &WslCommandExecutor, distro: &str, action: Action) -> Result<String, String> {
+    match action {
+        Action::Check(mount) => {
+            let (content, target) = with_new_mount(executor, distro, &mount).await?;
+            let missing = check_fstab(executor, distro, &content, &[target.clone()]).await?;
+            if missing.is_empty() {
+                Ok(i18n::t("mounts.check_passed"))
+            } else {
+                Ok(i18n::tr("mounts.check_target_missing", &[target]))
+            }
+        }
+        Action::Add(mount) => {
+            let (content, target) = with_new_mount(executor, distro, &mount).await?;
+            let missing = check_fstab(executor, distro, &content, &[target.clone()]).await?;
+            write_fstab(executor, distro, &content, &missing).await?;
+            Ok(i18n::tr("mounts.added", &[target]))
+        }
+        Action::Remove(line, target) => {
+            let content = remove_line(&read_fstab(executor, distro).await?, line, &target)?;
+            write_fstab(executor, distro, &content, &[]).await?;
+            Ok(i18n::tr("mounts.removed", &[target]))
+        }
+        Action::Mount(target) => {
+            mount_entry(executor, distro, &target).await?;
+            Ok(i18n::tr("mounts.mounted", &[target]))
+        }
+    }
+}
+
+async fn apply(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, distro: String, action: Action) {
+    let changes_file = matches!(action, Action::Add(_) | Action::Remove(..));
+    let wsl_dashboard = app_state.lock().await.wsl_dashboard.clone();
+    wsl_dashboard.increment_manual_operation();
+    let dashboard_c = wsl_dashboard.clone();
+    let _op_guard = scopeguard::guard((), move |_| {
+        dashboard_c.decrement_manual_operation();
+    });
+    let executor = wsl_dashboard.executor().clone();
+
+    let result = run(&executor, &distro, action).await;
+    if let Err(e) = load(ah.clone(), &executor, &distro).await {
+        error!("Failed to read the mounts of '{}': {}", distro, e);
+    }
+    match result {
+        Ok(message) => {
+            if changes_file {
+                let _ = slint::invoke_from_event_loop({
+                    let ah = ah.clone();
+                    move || {
+                        if let Some(app) = ah.upgrade() {
+                            let mut form = app.get_mounts_form();
+                            form.source = "".into();
+                            form.target = "".into();
+                            app.set_mounts_form(form);
+                        }
+                    }
+                });
+            }
+            set_status(&ah, message, false);
+        }
+        Err(e) => {
+            error!("Mount operation in '{}' failed: {}", distro, e);
+            set_status(&ah, e, true);
+        }
+    }
+}
+
+fn start(ah: &slint::Weak<AppWindow>, app_state: &Arc<Mutex<AppState>>, action: impl FnOnce(&AppWindow) -> Action) {
+    let Some(app) = ah.upgrade() else { return };
+    if app.get_mounts_busy() {
+        return;
+    }
+    let distro = app.get_mounts_distro_name().to_string();
+    let action = action(&app);
+    app.set_mounts_busy(true);
+    tokio::spawn(apply(ah.clone(), app_state.clone(), distro, action));
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_open_mounts(move || {
+        let Some(app) = ah.upgrade() else { return };
+        let distro = app.get_wsl_config_distro_name().to_string();
+        info!("Operation: Open the mounts of '{}'", distro);
+        app.set_mounts_distro_name(distro.clone().into());
+        // What the wsl.conf dialog shows, saved or not
+        app.set_mounts_fs_tab_enabled(app.get_wsl_config_automount_mount_fs_tab());
+        app.set_mounts_form(MountForm { metadata: true, ..MountForm::default() });
+        app.set_mounts_fstab(slint::ModelRc::default());
+        app.set_mounts_active(slint::ModelRc::default());
+        app.set_mounts_status("".into());
+        app.set_mounts_busy(true);
+        app.set_show_mounts(true);
+        tokio::spawn(refresh(ah.clone(), as_ptr.clone(), distro));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_refresh_mounts(move || {
+        let Some(app) = ah.upgrade() else { return };
+        app.set_mounts_busy(true);
+        tokio::spawn(refresh(ah.clone(), as_ptr.clone(), app.get_mounts_distro_name().to_string()));
+    });
+
+    let ah = app_handle.clone();
+    app.on_browse_mount_source(move || {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(i18n::t("mounts.select_folder"))
+            .pick_folder()
+        {
+            if let Some(app) = ah.upgrade() {
+                let mut form = app.get_mounts_form();
+                form.source = path.display().to_string().into();
+                app.set_mounts_form(form);
+            }
+        }
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_check_mount(move || {
+        start(&ah, &as_ptr, |app| Action::Check(to_new_mount(&app.get_mounts_form())));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_add_mount(move || {
+        start(&ah, &as_ptr, |app| {
+            let mount = to_new_mount(&app.get_mounts_form());
+            info!("Operation: Add {} to /etc/fstab of '{}'", mount.target(), app.get_mounts_distro_name());
+            Action::Add(mount)
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_remove_fstab_entry(move |line, target| {
+        start(&ah, &as_ptr, |app| {
+            info!("Operation: Remove {} from /etc/fstab of '{}'", target, app.get_mounts_distro_name());
+            Action::Remove(line.max(0) as usize, target.to_string())
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_mount_fstab_entry(move |target| {
+        start(&ah, &as_ptr, |_| Action::Mount(target.to_string()));
+    });
+}
@@ -3,7 +3,7 @@
 
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tracing::info;
+use tracing::{info, warn};
 use slint::{ComponentHandle, Model};
 use crate::{AppWindow, AppState, i18n};
 
@@ -47,6 +47,18 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 return;
             }
 
+            // Sentinel Check: Protected?
+            if crate::config::ConfigManager::is_protected(&name_str) {
+                let msg = i18n::tr("protect.blocked", &[name_str.clone()]);
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah.upgrade() {
+                        app.set_current_message(msg.into());
+                        app.set_show_message_dialog(true);
+                    }
+                });
+                return;
+            }
+
             let _ = slint::invoke_from_event_loop(move || {
                 if let Some(app) = ah.upgrade() {
                     if app.get_is_installing() || app.get_is_exporting() || app.get_is_cloning() || app.get_is_moving() {
@@ -61,10 +73,13 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         .to_string();
                     app.set_move_source_name(name_str.clone().into());
                     app.set_move_target_name(name_str.clone().into());
-                    app.set_move_target_path(target_path.into());
+                    app.set_move_target_path(target_path.clone().into());
                     app.set_move_original_path("".into());
                     app.set_move_error("".into());
+                    app.set_move_space_plan("".into());
                     app.set_show_move_dialog(true);
+
+                    tokio::spawn(refresh_move_space_plan(ah.clone(), as_ptr, name_str, target_path));
                 }
             });
         });
@@ -108,7 +123,18 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 let target_name = app.get_move_target_name().to_string();
                 let target_path = app.get_move_target_path().to_string();
 
-                info!("Operation: Move confirmed - Starting WSL2 Move for {}", source_name);
+                let mut version = "2".to_string();
+                let distros = app.get_distros();
+                for i in 0..distros.row_count() {
+                    if let Some(d) = distros.row_data(i) {
+                        if d.name == source_name {
+                            version = d.version.to_string();
+                            break;
+                        }
+                    }
+                }
+
+                info!("Operation: Move confirmed - Starting WSL{} Move for {}", version, source_name);
                 
                 // Synchronously set moving status
                 app.set_is_moving(true);
@@ -119,20 +145,25 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     source_name, 
                     target_name, 
                     target_path, 
-                    "2".to_string()
+                    version
                 );
             }
         });
     });
 
     let ah_folder = app_handle.clone();
+    let as_folder = app_state.clone();
     app.on_select_move_folder(move || {
         if let Some(path) = rfd::FileDialog::new()
             .set_title(i18n::t("dialog.select_move_dir"))
             .pick_folder()
         {
             if let Some(app) = ah_folder.upgrade() {
-                app.set_move_target_path(path.to_string_lossy().to_string().into());
+                let target_path = path.to_string_lossy().to_string();
+                app.set_move_target_path(target_path.clone().into());
+
+                let source_name = app.get_move_source_name().to_string();
+                tokio::spawn(refresh_move_space_plan(ah_folder.clone(), as_folder.clone(), source_name, target_path));
             }
         }
     });
@@ -174,11 +205,20 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 return;
             }
 
+            // Disk space preflight for the temp and target volumes
+            let plan = refresh_move_space_plan(ah_weak.clone(), as_ptr.clone(), source_name.clone(), target_path.clone()).await;
+
             if let Some(app) = ah_weak.upgrade() {
                 if app.get_is_installing() || app.get_is_exporting() || app.get_is_cloning() || app.get_is_moving() {
                     return;
                 }
 
+                if let Some(err) = plan.shortage() {
+                    warn!("Move blocked by disk space preflight: {}", err);
+                    app.set_move_error(err.into());
+                    return;
+                }
+
                 // 1. Sync Validations
                 let p = std::path::Path::new(target_path.as_str());
                 if p.exists() {
@@ -236,16 +276,10 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     app.set_show_move_confirm(true);
                     app.set_show_move_dialog(false); 
                 } else {
+                    // WSL1 is moved by export/unregister/import, confirm by name as well
+                    app.set_move_confirm_message(i18n::tr("dialog.move_wsl1_confirm", &[source_name.clone()]).into());
+                    app.set_show_move_confirm(true);
                     app.set_show_move_dialog(false);
-                    app.set_is_moving(true);
-                    run_move_process(
-                        app.as_weak(),
-                        as_ptr.clone(),
-                        source_name,
-                        target_name,
-                        target_path,
-                        version
-                    );
                 }
             }
         });
@@ -262,3 +296,26 @@ fn run_move_process(
 ) {
     super::move_logic::run_move_process(ah_move, as_ptr, source_name, target_name, target_path, version);
 }
+
+// Compute the disk space plan for moving into target_path and show it in the dialog
+async fn refresh_move_space_plan(
+    ah: slint::Weak<AppWindow>,
+    as_ptr: Arc<Mutex<AppState>>,
+    source_name: String,
+    target_path: String,
+) -> crate::wsl::ops::preflight::SpacePlan {
+    let executor = {
+        let state = as_ptr.lock().await;
+        state.wsl_dashboard.executor().clone()
+    };
+    let (temp_dir, _) = super::resolve_temp_path(as_ptr.clone(), &source_name, "wsl_move", "tar").await;
+    let plan = crate::wsl::ops::preflight::plan_move(&executor, &source_name, &temp_dir.to_string_lossy(), &target_path).await;
+
+    let summary = plan.summary();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_move_space_plan(summary.into());
+        }
+    });
+    plan
+}
@@ -6,6 +6,7 @@ use tokio::sync::Mutex;
 use tracing::{info, warn};
 use crate::{AppWindow, AppState, i18n};
 use crate::ui::data::refresh_distros_ui;
+use crate::wsl::ops::move_journal::{self, MoveJournal, MoveStep, Recovered};
 
 pub fn run_move_process(
     ah_move: slint::Weak<AppWindow>, 
@@ -60,12 +61,28 @@ pub fn run_move_process(
         
         let old_install_location = dashboard.executor().get_distro_install_location(&source_name).await.data;
 
+        // Every step from here on is journaled so it can be rolled back, even after a crash
+        let mut journal = match MoveJournal::begin(&source_name, &target_name, &target_path, if version == "2" { 2 } else { 1 }) {
+            Ok(journal) => journal,
+            Err(e) => {
+                if let Some(app) = ah_move.upgrade() {
+                    app.set_task_status_visible(false);
+                    app.set_is_moving(false);
+                    app.set_current_message(i18n::tr("dialog.move_failed", &[e]).into());
+                    app.set_show_message_dialog(true);
+                }
+                dashboard.set_manual_operation(false);
+                return;
+            }
+        };
+
         let result = if version == "2" {
             if let Some(app) = ah_move.upgrade() {
                 let msg = i18n::tr("operation.moving_wsl2_msg", &[source_name.clone(), size_str]);
                 app.set_task_status_text(msg.into());
             }
 
+            journal.set_step(MoveStep::Moving);
             let mut move_res = crate::wsl::models::WslCommandResult::error("".into(), "".into());
             for attempt in 1..=5 {
                 if attempt > 1 {
@@ -85,9 +102,68 @@ pub fn run_move_process(
                     continue;
                 }
             }
+
+            // wsl --manage --move did not relocate the distro, fall back to a verified VHDX copy
+            if !move_res.success {
+                let still_at_source = match (&old_install_location, dashboard.executor().get_distro_install_location(&source_name).await.data) {
+                    (Some(old), Some(current)) => old.eq_ignore_ascii_case(&current),
+                    _ => false,
+                };
+                if still_at_source {
+                    warn!("WSL 2 Move: --manage --move failed ({:?}), falling back to VHDX copy", move_res.error);
+                    move_res = move_wsl2_by_copy(ah_move.clone(), &dashboard, &mut journal, &source_name, &target_path).await;
+                }
+            }
             move_res
         } else {
-            move_wsl1(ah_move.clone(), as_ptr.clone(), &source_name, &target_name, &target_path).await
+            move_wsl1(ah_move.clone(), as_ptr.clone(), &mut journal, &source_name, &target_name, &target_path).await
+        };
+
+        // Only a verified target allows the source to be cleaned up, anything else is rolled back
+        let result = if result.success {
+            match journal.verify_target(&dashboard).await {
+                Ok(_) => result,
+                Err(e) => {
+                    warn!("Move: target verification failed: {}", e);
+                    crate::wsl::models::WslCommandResult::error(String::new(), e)
+                }
+            }
+        } else {
+            result
+        };
+
+        let result = if result.success {
+            journal.set_step(MoveStep::Verified);
+            journal.cleanup_source();
+            journal.finish();
+            result
+        } else {
+            let err = result.error.clone().unwrap_or_else(|| i18n::t("dialog.error"));
+            match journal.rollback(&dashboard).await {
+                Ok(_) => {
+                    journal.finish();
+                    crate::wsl::models::WslCommandResult {
+                        success: false,
+                        output: "ROLLED_BACK".into(),
+                        error: Some(err),
+                        data: None,
+                    }
+                }
+                Err(e) => {
+                    // Keep the journal, the next launch retries the rollback
+                    warn!("Move: rollback of '{}' failed: {}", source_name, e);
+                    if version != "2" && std::path::Path::new(&journal.backup_file).exists() {
+                        crate::wsl::models::WslCommandResult {
+                            success: false,
+                            output: "BACKUP_SAVED".into(),
+                            error: Some(journal.backup_file.clone()),
+                            data: None,
+                        }
+                    } else {
+                        crate::wsl::models::WslCommandResult::error(String::new(), format!("{}\n{}", err, e))
+                    }
+                }
+            }
         };
 
         if let Some(app) = ah_move.upgrade() {
@@ -118,6 +194,8 @@ pub fn run_move_process(
                     } else {
                             app.set_current_message_url(err.into());
                     }
+                } else if result.output == "ROLLED_BACK" {
+                    app.set_current_message(i18n::tr("dialog.move_rolled_back", &[err]).into());
                 } else {
                     app.set_current_message(i18n::tr("dialog.move_failed", &[err]).into());
                 }
@@ -134,6 +212,7 @@ pub fn run_move_process(
 async fn move_wsl1(
     ah: slint::Weak<AppWindow>, 
     as_ptr: Arc<Mutex<AppState>>, 
+    journal: &mut MoveJournal,
     source_name: &str, 
     target_name: &str, 
     target_path: &str
@@ -142,6 +221,7 @@ async fn move_wsl1(
     
     let (temp_dir, temp_file_str) = super::resolve_temp_path(as_ptr.clone(), source_name, "wsl_move", "tar").await;
     let _ = std::fs::create_dir_all(&temp_dir);
+    journal.set_backup(&temp_file_str);
 
     info!("WSL1 Move: Exporting '{}' to '{}'...", source_name, temp_file_str);
     let stop_signal = Arc::new(std::sync::atomic::AtomicBool::new(false));
@@ -172,18 +252,17 @@ async fn move_wsl1(
     stop_signal.store(true, std::sync::atomic::Ordering::Relaxed);
 
     if !export_result.success {
-        let _ = std::fs::remove_file(&temp_file_str);
         return export_result;
     }
 
     if let Ok(metadata) = std::fs::metadata(&temp_file_str) {
         if metadata.len() == 0 {
-            let _ = std::fs::remove_file(&temp_file_str);
             return WslCommandResult::error("".into(), "Exported file is empty".into());
         }
     } else {
         return WslCommandResult::error("".into(), "Failed to verify exported file".into());
     }
+    journal.set_step(MoveStep::Exported);
 
     info!("WSL1 Move: Unregistering '{}'...", source_name);
     // Yield before unregister operation
@@ -197,9 +276,9 @@ async fn move_wsl1(
     };
 
     if !unregister_result.success {
-        let _ = std::fs::remove_file(&temp_file_str);
         return unregister_result;
     }
+    journal.set_step(MoveStep::Unregistered);
 
     info!("WSL1 Move: Importing to '{}' at '{}'...", target_name, target_path);
     if let Some(app) = ah.upgrade() {
@@ -216,36 +295,107 @@ async fn move_wsl1(
         dashboard.import_distro(target_name, target_path, &temp_file_str).await
     };
 
-    if !import_result.success {
-        return WslCommandResult {
-            success: false,
-            output: "BACKUP_SAVED".into(),
-            error: Some(temp_file_str),
-            data: None,
-        };
+    if import_result.success {
+        journal.set_step(MoveStep::Imported);
     }
+    import_result
+}
 
-    // Yield before verification
-    tokio::task::yield_now().await;
-    let verify_result = {
-        let executor = {
-            let state = as_ptr.lock().await;
-            state.wsl_dashboard.executor().clone()
-        };
-        executor.execute_command(&["-l", "-v"]).await
-    };
+// Relocate a stopped WSL2 distro by copying its VHDX with the verified copy engine
+// and pointing the registry BasePath at the new directory. The old disk is removed
+// by the caller once the target is verified.
+async fn move_wsl2_by_copy(
+    ah: slint::Weak<AppWindow>,
+    dashboard: &crate::wsl::dashboard::WslDashboard,
+    journal: &mut MoveJournal,
+    source_name: &str,
+    target_path: &str,
+) -> crate::wsl::models::WslCommandResult<String> {
+    use crate::wsl::models::WslCommandResult;
+    use crate::wsl::ops::vhdx_copy;
 
-    if verify_result.success && verify_result.output.contains(target_name) {
-        info!("WSL1 Move: Success, cleaning up temp file");
-        let _ = std::fs::remove_file(&temp_file_str);
-        WslCommandResult::success("Move successful".into(), None)
-    } else {
-        warn!("WSL1 Move: Import appeared successful but distro not found in list");
-        WslCommandResult {
-            success: false,
-            output: "BACKUP_SAVED".into(),
-            error: Some(temp_file_str),
-            data: None,
+    let info_res = crate::wsl::ops::info::get_distro_information(dashboard.executor(), source_name).await;
+    let vhdx_path = info_res.data.map(|info| info.vhdx_path).unwrap_or_default();
+    if vhdx_path.is_empty() {
+        return WslCommandResult::error(String::new(), "VHDX path of the distro could not be resolved".into());
+    }
+
+    let source_vhdx = std::path::PathBuf::from(vhdx_path.trim_start_matches(r"\\?\"));
+    let file_name = source_vhdx.file_name().map(|n| n.to_os_string()).unwrap_or_else(|| "ext4.vhdx".into());
+    let target_vhdx = std::path::Path::new(target_path).join(file_name);
+    journal.set_vhdx(&source_vhdx, &target_vhdx);
+
+    let copy_result = tokio::task::spawn_blocking({
+        let source_vhdx = source_vhdx.clone();
+        let target_vhdx = target_vhdx.clone();
+        let source_name = source_name.to_string();
+        move || {
+            vhdx_copy::copy_vhdx(&source_vhdx, &target_vhdx, &vhdx_copy::CopyOptions::default(), |progress| {
+                let ah_inner = ah.clone();
+                let source_name_inner = source_name.clone();
+                let key = match progress.phase {
+                    vhdx_copy::CopyPhase::Copying => "operation.moving_wsl2_msg",
+                    vhdx_copy::CopyPhase::Verifying => "operation.verifying_copy",
+                };
+                let text = progress.display();
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah_inner.upgrade() {
+                        app.set_task_status_text(i18n::tr(key, &[source_name_inner, text]).into());
+                    }
+                });
+            })
         }
+    }).await.map_err(|e| e.to_string()).and_then(|r| r);
+
+    if let Err(e) = copy_result {
+        return WslCommandResult::error(String::new(), e);
     }
+
+    if let Err(e) = crate::utils::registry::set_distro_base_path(source_name, target_path) {
+        return WslCommandResult::error(String::new(), format!("Failed to update registry: {}", e));
+    }
+    journal.set_step(MoveStep::Registered);
+
+    info!("WSL 2 Move: '{}' relocated to '{}' via VHDX copy", source_name, target_path);
+    WslCommandResult::success("Move successful".into(), None)
+}
+
+// Resolve moves interrupted by a crash or power loss, run once on startup
+pub async fn recover_interrupted_moves(ah: slint::Weak<AppWindow>, as_ptr: Arc<Mutex<AppState>>) {
+    let pending = move_journal::list_pending();
+    if pending.is_empty() {
+        return;
+    }
+
+    let dashboard = {
+        let state = as_ptr.lock().await;
+        state.wsl_dashboard.clone()
+    };
+    let _guard = crate::ui::data::BusyGuard::new();
+    dashboard.set_manual_operation(true);
+
+    let mut messages = Vec::new();
+    for journal in pending {
+        let name = journal.distro_name.clone();
+        warn!("Move: found unfinished move of '{}' (step {:?}), recovering", name, journal.step);
+        let msg = match move_journal::recover(&dashboard, journal).await {
+            Ok(Recovered::RolledBack) => i18n::tr("dialog.move_recovered", &[name]),
+            Ok(Recovered::Completed) => i18n::tr("dialog.move_success_recovered", &[name]),
+            Err(e) => {
+                warn!("Move: recovery of '{}' failed: {}", name, e);
+                i18n::tr("dialog.move_recover_failed", &[name, e])
+            }
+        };
+        messages.push(msg);
+    }
+    dashboard.set_manual_operation(false);
+
+    let ah_msg = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_msg.upgrade() {
+            app.set_current_message(messages.join("\n\n").into());
+            app.set_show_message_dialog(true);
+        }
+    });
+    refresh_distros_ui(ah, as_ptr).await;
 }
@@ -0,0 +1,190 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{info, warn, error};
+use crate::{AppWindow, i18n};
+use crate::wsl::ops::oci::{self, Credentials, ImageReference, RegistryClient};
+use crate::wsl::ops::preflight::format_bytes;
+
+fn push_terminal(ah: &slint::Weak<AppWindow>, buffer: &str) {
+    let ah_cb = ah.clone();
+    let tb = buffer.to_string();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_cb.upgrade() {
+            app.set_terminal_output(tb.into());
+        }
+    });
+}
+
+fn set_status(ah: &slint::Weak<AppWindow>, status: String) {
+    let ah_cb = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_cb.upgrade() {
+            app.set_install_status(status.into());
+        }
+    });
+}
+
+// Pull an image from its registry, flatten the layers and import the result
+pub async fn install_from_oci(
+    ah: slint::Weak<AppWindow>,
+    executor: Arc<crate::wsl::executor::WslCommandExecutor>,
+    config_manager: Arc<crate::config::ConfigManager>,
+    image_ref: String,
+    credentials: Option<Credentials>,
+    final_name: String,
+    install_path: String,
+) -> Result<String, (String, String)> {
+    let mut terminal_buffer = String::new();
+
+    let image = match ImageReference::parse(&image_ref) {
+        Ok(image) => image,
+        Err(e) => return Err((i18n::tr("install.oci_invalid_reference", &[e]), terminal_buffer)),
+    };
+
+    let mut target_path = install_path.clone();
+    if target_path.is_empty() {
+        let distro_location = config_manager.get_settings().distro_location.clone();
+        let base = PathBuf::from(&distro_location);
+        target_path = base.join(&final_name).to_string_lossy().to_string();
+    }
+    let temp_location = config_manager.get_settings().temp_location.clone();
+    let work_dir = Path::new(&temp_location).join(format!("oci_{}", uuid::Uuid::new_v4()));
+
+    let result = pull_and_import(&ah, &executor, &config_manager, image, credentials, &final_name, &target_path, &temp_location, &work_dir, &mut terminal_buffer).await;
+    if let Err(e) = tokio::fs::remove_dir_all(&work_dir).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("install_from_oci: failed to clean up '{}': {}", work_dir.display(), e);
+        }
+    }
+
+    match result {
+        Ok(_) => Ok(terminal_buffer),
+        Err(e) => {
+            error!("install_from_oci: {}", e);
+            Err((e, terminal_buffer))
+        }
+    }
+}
+
+async fn pull_and_import(
+    ah: &slint::Weak<AppWindow>,
+    executor: &crate::wsl::executor::WslCommandExecutor,
+    config_manager: &crate::config::ConfigManager,
+    image: ImageReference,
+    credentials: Option<Credentials>,
+    final_name: &str,
+    target_path: &str,
+    temp_location: &str,
+    work_dir: &Path,
+    terminal_buffer: &mut String,
+) -> Result<(), String> {
+    // Credentials entered in the form win over `docker login`
+    let credentials = match credentials {
+        Some(c) => Some(c),
+        None => {
+            let image = image.clone();
+            tokio::task::spawn_blocking(move || oci::docker_config_credentials(&image)).await.ok().flatten()
+        }
+    };
+    let mut client = RegistryClient::new(image.clone(), credentials)?;
+
+    // 1. Resolve the manifest for this platform
+    set_status(ah, i18n::t("install.oci_resolving"));
+    terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.oci_resolving_image", &[image.to_string()])));
+    push_terminal(ah, terminal_buffer);
+    let manifest = client.fetch_manifest().await?;
+    let total_size = manifest.total_size();
+    terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.oci_resolved", &[
+        manifest.layers.len().to_string(),
+        format_bytes(total_size),
+    ])));
+
+    // Disk space preflight for temp and the target volume
+    let plan = crate::wsl::ops::preflight::plan_oci_install(total_size, temp_location, target_path);
+    terminal_buffer.push_str(&format!("{}\n", plan.summary()));
+    push_terminal(ah, terminal_buffer);
+    if let Some(err) = plan.shortage() {
+        return Err(err);
+    }
+
+    let config = client.fetch_config(&manifest.config).await?;
+
+    // 2. Download the layers
+    tokio::fs::create_dir_all(work_dir).await.map_err(|e| format!("Failed to create directory: {}", e))?;
+    set_status(ah, i18n::t("install.status_downloading"));
+    let count = manifest.layers.len();
+    let mut layer_files = Vec::with_capacity(count);
+    for (i, layer) in manifest.layers.iter().enumerate() {
+        let dest = work_dir.join(format!("layer_{:03}", i));
+        let label = format!("{}/{}", i + 1, count);
+        let layer_size = format_bytes(layer.size);
+        let ah_progress = ah.clone();
+        let mut last_update = std::time::Instant::now();
+        client.download_blob(layer, &dest, |downloaded| {
+            if last_update.elapsed().as_millis() >= 300 {
+                last_update = std::time::Instant::now();
+                set_status(&ah_progress, i18n::tr("install.oci_layer_progress", &[
+                    label.clone(),
+                    format_bytes(downloaded),
+                    layer_size.clone(),
+                ]));
+            }
+        }).await?;
+        terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.oci_layer_done", &[label, layer_size])));
+        push_terminal(ah, terminal_buffer);
+        layer_files.push(dest);
+    }
+
+    // 3. Apply the layers, whiteouts included, into one rootfs tarball
+    set_status(ah, i18n::t("install.oci_flattening"));
+    terminal_buffer.push_str(&format!("{}\n", i18n::t("install.oci_flattening")));
+    push_terminal(ah, terminal_buffer);
+    let rootfs = work_dir.join("rootfs.tar");
+    let rootfs_out = rootfs.clone();
+    tokio::task::spawn_blocking(move || oci::flatten(&layer_files, &rootfs_out))
+        .await
+        .map_err(|e| e.to_string())??;
+
+    // 4. Import
+    set_status(ah, i18n::t("install.importing"));
+    let tp_clone = target_path.to_string();
+    tokio::task::spawn_blocking(move || std::fs::create_dir_all(&tp_clone))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.step_2_3", &[final_name.to_string()])));
+    push_terminal(ah, terminal_buffer);
+
+    info!("install_from_oci: importing '{}' from '{}'", final_name, image);
+    let import_res = crate::wsl::ops::transfer::import_distro(executor, final_name, target_path, &rootfs.to_string_lossy()).await;
+    if !import_res.success {
+        if !import_res.output.trim().is_empty() {
+            terminal_buffer.push_str(&format!("\n[WSL Output]\n{}\n", import_res.output));
+        }
+        return Err(import_res.error.unwrap_or_else(|| i18n::t("install.import_failed")));
+    }
+
+    // 5. Carry the container config over. A failure here leaves a working distro,
+    // so it is reported without failing the install.
+    set_status(ah, i18n::t("install.oci_applying_config"));
+    if let Err(e) = oci::apply_image_config(executor, final_name, &config).await {
+        warn!("install_from_oci: image config only partially applied to '{}': {}", final_name, e);
+        terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.oci_config_partial", &[e])));
+    }
+    if let Some(dir) = oci::working_dir(&config) {
+        let mut instance_config = config_manager.get_instance_config(final_name);
+        instance_config.terminal_dir = dir.clone();
+        instance_config.vscode_dir = dir;
+        if let Err(e) = config_manager.update_instance_config(final_name, instance_config) {
+            warn!("install_from_oci: failed to save WORKDIR for '{}': {}", final_name, e);
+        }
+    }
+
+    // Restart so the wsl.conf default user takes effect
+    let _ = executor.execute_command(&["--terminate", final_name]).await;
+    terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.step_3_3", &[final_name.to_string()])));
+    Ok(())
+}
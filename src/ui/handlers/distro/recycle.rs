@@ -0,0 +1,164 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::VecModel;
+use tracing::{info, error};
+use crate::{AppWindow, AppState, RecycleItem, i18n};
+use crate::wsl::ops::preflight::format_bytes;
+use crate::wsl::ops::recycle;
+
+fn format_deleted_time(ms: &str) -> String {
+    use chrono::TimeZone;
+    let ms = ms.parse::<i64>().unwrap_or(0);
+    chrono::Local.timestamp_millis_opt(ms).single()
+        .map(|dt| dt.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn show_message(ah: &slint::Weak<AppWindow>, msg: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_task_status_visible(false);
+            app.set_current_message(msg.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+// Reload the "Recently deleted" list from disk
+pub async fn refresh_recycle_list(ah: slint::Weak<AppWindow>, as_ptr: Arc<Mutex<AppState>>) {
+    let (entries, days, max_gb) = {
+        let state = as_ptr.lock().await;
+        let settings = state.config_manager.get_settings();
+        (recycle::list_entries(&state.config_manager), settings.recycle_days, settings.recycle_max_gb)
+    };
+
+    let total: u64 = entries.iter().map(|e| e.size).sum();
+    let mut summary = i18n::tr("recycle.summary", &[
+        entries.len().to_string(),
+        format_bytes(total),
+        days.to_string(),
+        max_gb.to_string(),
+    ]);
+    if max_gb > 0 && total > max_gb as u64 * 1024 * 1024 * 1024 {
+        summary = format!("{} {}", summary, i18n::t("recycle.over_cap"));
+    }
+    let rows: Vec<(String, String, String, String)> = entries.iter()
+        .map(|e| (e.id(), e.name.clone(), format_deleted_time(&e.deleted_time), format_bytes(e.size)))
+        .collect();
+
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            let items: Vec<RecycleItem> = rows.into_iter()
+                .map(|(id, name, deleted, size)| RecycleItem {
+                    id: id.into(),
+                    name: name.into(),
+                    deleted: deleted.into(),
+                    size: size.into(),
+                })
+                .collect();
+            app.set_recycle_items(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+            app.set_recycle_summary(summary.into());
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_open_recycle_bin(move || {
+        info!("Operation: Open recycle bin");
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        if let Some(app) = ah.upgrade() {
+            app.set_show_recycle_bin(true);
+        }
+        tokio::spawn(refresh_recycle_list(ah, as_ptr));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_restore_recycled(move |id| {
+        info!("Operation: Restore recycled distribution - {}", id);
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        tokio::spawn(async move {
+            let _guard = crate::ui::data::BusyGuard::new();
+            let (dashboard, config_manager) = {
+                let state = as_ptr.lock().await;
+                (state.wsl_dashboard.clone(), state.config_manager.clone())
+            };
+
+            // Sentinel Check: System heavy op?
+            if dashboard.heavy_op_lock().try_lock().is_err() {
+                show_message(&ah, i18n::t("toast.system_busy"));
+                return;
+            }
+
+            let Some(entry) = recycle::find_entry(&config_manager, &id) else {
+                refresh_recycle_list(ah, as_ptr).await;
+                return;
+            };
+
+            let ah_init = ah.clone();
+            let name = entry.name.clone();
+            let _ = slint::invoke_from_event_loop(move || {
+                if let Some(app) = ah_init.upgrade() {
+                    app.set_task_status_text(i18n::tr("operation.restoring", &[name]).into());
+                    app.set_task_status_visible(true);
+                }
+            });
+
+            let msg = match recycle::restore_entry(&dashboard, &config_manager, &entry).await {
+                Ok(_) => {
+                    // USB rules live in the main config, update them through the shared manager
+                    if !entry.usb_auto_attach.is_empty() {
+                        let mut state = as_ptr.lock().await;
+                        let mut usb = state.config_manager.get_usb_config().clone();
+                        for device in &entry.usb_auto_attach {
+                            if !usb.auto_attach_list.iter().any(|d| d.bus_id == device.bus_id) {
+                                usb.auto_attach_list.push(device.clone());
+                            }
+                        }
+                        if let Err(e) = state.config_manager.update_usb_config(usb) {
+                            error!("Failed to restore USB rules for '{}': {}", entry.name, e);
+                        }
+                    }
+                    i18n::tr("recycle.restored", &[entry.name.clone()])
+                }
+                Err(e) => {
+                    error!("Failed to restore '{}': {}", entry.name, e);
+                    i18n::tr("recycle.restore_failed", &[entry.name.clone(), e])
+                }
+            };
+
+            show_message(&ah, msg);
+            refresh_recycle_list(ah, as_ptr).await;
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_purge_recycled(move |id| {
+        info!("Operation: Purge recycled distribution - {}", id);
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        tokio::spawn(async move {
+            let entry = {
+                let state = as_ptr.lock().await;
+                recycle::find_entry(&state.config_manager, &id)
+            };
+            if let Some(entry) = entry {
+                let result = tokio::task::spawn_blocking(move || recycle::purge_entry(&entry)).await;
+                if let Ok(Err(e)) = result {
+                    show_message(&ah, i18n::tr("recycle.purge_failed", &[e]));
+                }
+            }
+            refresh_recycle_list(ah, as_ptr).await;
+        });
+    });
+}
@@ -17,6 +17,7 @@ pub async fn perform_save_settings(
     autostart: bool,
     startup_script: String,
     terminal_proxy: bool,
+    protected: bool,
 ) {
     info!("Operation: Save settings - {}", name);
 
@@ -88,18 +89,19 @@ pub async fn perform_save_settings(
         app.set_show_settings(false);
     }
 
-    // 2. Save to instances.toml
-    let config = crate::config::DistroInstanceConfig {
-        terminal_dir,
-        vscode_dir,
-        auto_startup: autostart,
-        startup_script: startup_script.clone(),
-        terminal_proxy,
-    };
-
+    // 2. Save to instances.toml (keeping the sandbox fields untouched)
     {
         let lock_timeout = std::time::Duration::from_millis(500);
         if let Ok(state) = tokio::time::timeout(lock_timeout, as_ptr.lock()).await {
+            let config = crate::config::DistroInstanceConfig {
+                terminal_dir,
+                vscode_dir,
+                auto_startup: autostart,
+                startup_script: startup_script.clone(),
+                terminal_proxy,
+                protected,
+                ..state.config_manager.get_instance_config(&name)
+            };
             if let Err(e) = state.config_manager.update_instance_config(&name, config) {
                 error!("Failed to save instance settings for '{}': {}", name, e);
             }
@@ -0,0 +1,171 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, error};
+use crate::{AppWindow, i18n};
+
+fn push_terminal(ah: &slint::Weak<AppWindow>, buffer: &str) {
+    let ah_cb = ah.clone();
+    let tb = buffer.to_string();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_cb.upgrade() {
+            app.set_terminal_output(tb.into());
+        }
+    });
+}
+
+fn set_status(ah: &slint::Weak<AppWindow>, status: String) {
+    let ah_cb = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah_cb.upgrade() {
+            app.set_install_status(status.into());
+        }
+    });
+}
+
+// Import a template and run its provisioning steps, streaming each step's output
+pub async fn install_from_template(
+    ah: slint::Weak<AppWindow>,
+    executor: Arc<crate::wsl::executor::WslCommandExecutor>,
+    config_manager: Arc<crate::config::ConfigManager>,
+    template_name: String,
+    final_name: String,
+    install_path: String,
+    input_values: HashMap<String, String>,
+) -> Result<String, (String, String)> {
+    let mut terminal_buffer = String::new();
+
+    let template = match config_manager.get_template(&template_name) {
+        Some(t) => t,
+        None => {
+            error!("install_from_template: template '{}' not found", template_name);
+            return Err((i18n::tr("install.template_not_found", &[template_name]), terminal_buffer));
+        }
+    };
+
+    if !std::path::Path::new(&template.file).exists() {
+        return Err((i18n::tr("install.template_file_missing", &[template.file.clone()]), terminal_buffer));
+    }
+
+    let values = match crate::wsl::ops::provision::resolve_values(&template.parameters, &input_values) {
+        Ok(v) => v,
+        Err(key) => {
+            let label = template.parameters.iter()
+                .find(|p| p.key == key)
+                .map(|p| p.label.clone())
+                .unwrap_or(key);
+            return Err((i18n::tr("install.template_param_invalid", &[label]), terminal_buffer));
+        }
+    };
+
+    let mut target_path = install_path.clone();
+    if target_path.is_empty() {
+        let distro_location = config_manager.get_settings().distro_location.clone();
+        let base = std::path::PathBuf::from(&distro_location);
+        target_path = base.join(&final_name).to_string_lossy().to_string();
+    }
+
+    // Disk space preflight for the target volume
+    let plan = crate::wsl::ops::preflight::plan_import(&template.file, &target_path, template.is_vhdx);
+    terminal_buffer.push_str(&format!("{}\n", plan.summary()));
+    push_terminal(&ah, &terminal_buffer);
+    if let Some(err) = plan.shortage() {
+        return Err((err, terminal_buffer));
+    }
+
+    let tp_clone = target_path.clone();
+    if let Err(e) = tokio::task::spawn_blocking(move || std::fs::create_dir_all(&tp_clone)).await.unwrap() {
+        return Err((format!("Failed to create directory: {}", e), terminal_buffer));
+    }
+
+    // 1. Import the template image
+    set_status(&ah, i18n::t("install.importing"));
+    let mut import_args = vec!["--import", &final_name, &target_path, &template.file];
+    if template.is_vhdx {
+        import_args.push("--vhd");
+    }
+    terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.template_importing", &[template.name.clone()])));
+    push_terminal(&ah, &terminal_buffer);
+
+    info!("install_from_template: importing '{}' from '{}'", final_name, template.file);
+    let import_res = executor.execute_command(&import_args).await;
+    if !import_res.success {
+        if !import_res.output.trim().is_empty() {
+            terminal_buffer.push_str(&format!("\n[WSL Output]\n{}\n", import_res.output));
+        }
+        return Err((import_res.error.unwrap_or_else(|| i18n::t("install.import_failed")), terminal_buffer));
+    }
+
+    // 2. Run the provisioning steps in order, stop at the first failure
+    let total = template.steps.len();
+    for (i, step) in template.steps.iter().enumerate() {
+        let step_label = i18n::tr("install.template_step", &[(i + 1).to_string(), total.to_string(), step.name.clone()]);
+        set_status(&ah, step_label.clone());
+        terminal_buffer.push_str(&format!("\n{}\n", step_label));
+        push_terminal(&ah, &terminal_buffer);
+
+        let (tx, mut rx) = tokio::sync::mpsc::channel::<String>(100);
+        let ah_ui = ah.clone();
+        let initial_tb = terminal_buffer.clone();
+        let ui_task = tokio::spawn(async move {
+            let mut buffer = initial_tb;
+            let mut last_update = std::time::Instant::now();
+            while let Some(text) = rx.recv().await {
+                buffer.push_str(&text);
+
+                if buffer.len() > 20_000 {
+                    let to_drain = buffer.len() - 10_000;
+                    if let Some(pos) = buffer[to_drain..].find('\n') {
+                        buffer.drain(..to_drain + pos + 1);
+                    } else {
+                        buffer.drain(..to_drain);
+                    }
+                }
+
+                // Throttle UI updates
+                if last_update.elapsed().as_millis() >= 200 {
+                    last_update = std::time::Instant::now();
+                    push_terminal(&ah_ui, &buffer);
+                }
+            }
+            buffer
+        });
+
+        let tx_callback = tx.clone();
+        let result = crate::wsl::ops::provision::run_step(&executor, &final_name, step, &values, move |text| {
+            let _ = tx_callback.try_send(text);
+        }).await;
+
+        drop(tx);
+        terminal_buffer = ui_task.await.unwrap_or(terminal_buffer);
+        if !terminal_buffer.ends_with('\n') {
+            terminal_buffer.push('\n');
+        }
+        push_terminal(&ah, &terminal_buffer);
+
+        if !result.success {
+            let err = result.error.unwrap_or_default();
+            terminal_buffer.push_str(&format!("{}\n", i18n::tr("install.template_step_failed", &[step.name.clone(), err.clone()])));
+            return Err((i18n::tr("install.template_step_failed", &[step.name.clone(), err]), terminal_buffer));
+        }
+    }
+
+    // The login user goes through the wsl.conf editor, not through a step script
+    let default_user = crate::wsl::ops::provision::render_script(&template.default_user, &values).trim().to_string();
+    if !default_user.is_empty() {
+        terminal_buffer.push_str(&format!("\n{}\n", i18n::tr("install.template_default_user", &[default_user.clone()])));
+        push_terminal(&ah, &terminal_buffer);
+        if let Err(e) = crate::wsl::ops::provision::set_default_user(&executor, &final_name, &default_user).await {
+            let message = i18n::tr("install.template_step_failed", &["wsl.conf".to_string(), e]);
+            terminal_buffer.push_str(&format!("{}\n", message));
+            return Err((message, terminal_buffer));
+        }
+    }
+
+    // Restart so wsl.conf changes made by the steps take effect
+    let _ = executor.execute_command(&["--terminate", &final_name]).await;
+    terminal_buffer.push_str(&format!("\n{}\n", i18n::tr("install.step_3_3", &[final_name.clone()])));
+    Ok(terminal_buffer)
+}
@@ -179,7 +179,7 @@ pub fn trigger_fetch(app_handle: slint::Weak<AppWindow>, _app_state: Arc<tokio::
 
 // Helper: thread-safe image download and decoding
 fn load_image_pixels(url: &str) -> Result<(Vec<u8>, u32, u32), String> {
-    let resp = ureq::get(url)
+    let resp = crate::network::http::get(url)
         .timeout(std::time::Duration::from_secs(10))
         .call()
         .map_err(|e| e.to_string())?;
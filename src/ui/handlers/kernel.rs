@@ -0,0 +1,289 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Custom kernels: registered images, switching the kernel in ~/.wslconfig and
+// telling whether the running VM still has to restart to boot it
+
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::VecModel;
+use tracing::{info, error};
+use crate::{AppWindow, AppState, KernelImageItem, i18n};
+use crate::config::KernelImage;
+use crate::utils::wsl_config::{get_kernel, kernel_matches, set_kernel, KernelKeys};
+use crate::wsl::ops::kernel::running_kernel;
+
+fn same_path(a: &str, b: &str) -> bool {
+    a.trim().eq_ignore_ascii_case(b.trim())
+}
+
+fn optional(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn keys_of(kernel: &KernelImage) -> KernelKeys {
+    (optional(&kernel.path), optional(&kernel.modules), optional(&kernel.command_line))
+}
+
+// Remember that the kernel keys changed while the VM booted with `boot_id` was up
+pub async fn mark_restart_pending(app_state: &Arc<Mutex<AppState>>) {
+    let executor = app_state.lock().await.wsl_dashboard.executor().clone();
+    let boot_id = running_kernel(&executor, false).await.map(|k| k.boot_id).unwrap_or_default();
+    if let Err(e) = app_state.lock().await.config_manager.set_kernel_restart_pending(true, &boot_id) {
+        error!("Failed to save the kernel restart state: {}", e);
+    }
+}
+
+pub async fn clear_restart_pending(app_state: &Arc<Mutex<AppState>>) {
+    if let Err(e) = app_state.lock().await.config_manager.set_kernel_restart_pending(false, "") {
+        error!("Failed to save the kernel restart state: {}", e);
+    }
+}
+
+// With `start_default` the default distro is started when nothing is running,
+// otherwise the running kernel stays unknown until a distro is started
+pub async fn refresh(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, start_default: bool) {
+    let (kernels, executor) = {
+        let state = app_state.lock().await;
+        (state.config_manager.get_kernels().clone(), state.wsl_dashboard.executor().clone())
+    };
+    let (kernel, _, _) = get_kernel();
+    let running = running_kernel(&executor, start_default).await;
+
+    // A VM that booted after the switch already runs the configured kernel
+    let mut restart_pending = kernels.restart_pending;
+    if let Some(ref running) = running {
+        if restart_pending && running.boot_id != kernels.boot_id {
+            info!("WSL restarted since the kernel was switched");
+            clear_restart_pending(&app_state).await;
+            restart_pending = false;
+        }
+    }
+
+    let using_stock = kernel.is_none();
+    let configured = match kernel {
+        None => i18n::t("kernel.stock"),
+        Some(ref path) => kernels.images.iter()
+            .find(|k| same_path(&k.path, path))
+            .map(|k| k.name.clone())
+            .unwrap_or_else(|| path.clone()),
+    };
+    let items: Vec<KernelImageItem> = kernels.images.iter()
+        .map(|k| KernelImageItem {
+            name: k.name.clone().into(),
+            path: k.path.clone().into(),
+            modules: k.modules.clone().into(),
+            command_line: k.command_line.clone().into(),
+            active: kernel.as_deref().is_some_and(|path| same_path(&k.path, path)),
+            missing: !Path::new(&k.path).is_file() || (!k.modules.is_empty() && !Path::new(&k.modules).is_file()),
+        })
+        .collect();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_kernel_images(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+            app.set_kernel_configured(configured.into());
+            app.set_kernel_using_stock(using_stock);
+            match running {
+                Some(k) => {
+                    app.set_kernel_running(k.release.into());
+                    app.set_kernel_running_distro(k.distro.into());
+                }
+                None => {
+                    app.set_kernel_running("".into());
+                    app.set_kernel_running_distro("".into());
+                }
+            }
+            app.set_kernel_restart_pending(restart_pending);
+            app.set_kernel_busy(false);
+        }
+    });
+}
+
+fn show_message(ah: &slint::Weak<AppWindow>, message: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_kernel_busy(false);
+            app.set_current_message(message.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+async fn register(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, kernel: KernelImage) {
+    for (key, path) in [("kernel", &kernel.path), ("kernelModules", &kernel.modules)] {
+        if !path.is_empty() && !Path::new(path).is_file() {
+            show_message(&ah, i18n::tr("wslconfig.file_not_found", &[key.to_string(), path.clone()]));
+            return;
+        }
+    }
+    let name = kernel.name.clone();
+    if let Err(e) = app_state.lock().await.config_manager.save_kernel(kernel) {
+        error!("Failed to register kernel '{}': {}", name, e);
+        show_message(&ah, i18n::tr("settings.saved_failed", &[e.to_string()]));
+        return;
+    }
+    let _ = slint::invoke_from_event_loop({
+        let ah = ah.clone();
+        move || {
+            if let Some(app) = ah.upgrade() {
+                app.set_kernel_new_path("".into());
+                app.set_kernel_new_modules("".into());
+                app.set_kernel_registering(false);
+            }
+        }
+    });
+    refresh(ah, app_state, false).await;
+}
+
+async fn remove(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, name: String) {
+    if let Err(e) = app_state.lock().await.config_manager.remove_kernel(&name) {
+        error!("Failed to remove kernel '{}': {}", name, e);
+        show_message(&ah, i18n::tr("settings.saved_failed", &[e.to_string()]));
+        return;
+    }
+    refresh(ah, app_state, false).await;
+}
+
+// Write the kernel keys and remember that WSL has to restart to pick them up.
+// The first kernelCommandLine a custom kernel replaces is saved so `revert` can put it back.
+async fn switch(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, keys: KernelKeys, revert: bool) {
+    if kernel_matches(&keys) {
+        if revert {
+            clear_saved_command_line(&app_state).await;
+        }
+        refresh(ah, app_state, false).await;
+        return;
+    }
+    let saved = app_state.lock().await.config_manager.get_kernels().saved_command_line.clone();
+    if !revert && keys.2.is_some() && saved.is_none() {
+        let (_, _, current) = get_kernel();
+        if let Err(e) = app_state.lock().await.config_manager.set_kernel_saved_command_line(Some(current.unwrap_or_default())) {
+            error!("Failed to save the kernel command line: {}", e);
+        }
+    }
+    if let Err(e) = set_kernel(&keys) {
+        error!("Failed to switch the kernel: {}", e);
+        show_message(&ah, i18n::tr("kernel.switch_failed", &[e]));
+        return;
+    }
+    if revert {
+        clear_saved_command_line(&app_state).await;
+    }
+    mark_restart_pending(&app_state).await;
+    refresh(ah.clone(), app_state, false).await;
+    show_message(&ah, i18n::t("kernel.switched"));
+}
+
+async fn clear_saved_command_line(app_state: &Arc<Mutex<AppState>>) {
+    if let Err(e) = app_state.lock().await.config_manager.set_kernel_saved_command_line(None) {
+        error!("Failed to save the kernel command line: {}", e);
+    }
+}
+
+async fn restart(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    info!("Operation: Restart WSL to boot the configured kernel");
+    if let Err(e) = crate::ui::handlers::wsl_global::shutdown_wsl(&app_state).await {
+        show_message(&ah, i18n::tr("wslconfig.restart_failed", &[e]));
+        return;
+    }
+    clear_restart_pending(&app_state).await;
+    refresh(ah, app_state, false).await;
+}
+
+fn pick_file(title: &str) -> Option<String> {
+    rfd::FileDialog::new()
+        .set_title(title)
+        .pick_file()
+        .map(|path| path.display().to_string())
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_detect_running_kernel(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_kernel_busy(true);
+        }
+        tokio::spawn(refresh(ah.clone(), as_ptr.clone(), true));
+    });
+
+    let ah = app_handle.clone();
+    app.on_browse_kernel_image(move || {
+        if let (Some(path), Some(app)) = (pick_file(&i18n::t("wslconfig.select_kernel")), ah.upgrade()) {
+            app.set_kernel_new_path(path.into());
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_browse_kernel_modules(move || {
+        if let (Some(path), Some(app)) = (pick_file(&i18n::t("wslconfig.select_kernel_modules")), ah.upgrade()) {
+            app.set_kernel_new_modules(path.into());
+        }
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_register_kernel(move |name, path, modules, command_line| {
+        let kernel = KernelImage {
+            name: name.trim().to_string(),
+            path: path.trim().to_string(),
+            modules: modules.trim().to_string(),
+            command_line: command_line.trim().to_string(),
+        };
+        if kernel.name.is_empty() || kernel.path.is_empty() {
+            show_message(&ah, i18n::t("kernel.name_path_required"));
+            return;
+        }
+        info!("Operation: Register kernel '{}' ({})", kernel.name, kernel.path);
+        tokio::spawn(register(ah.clone(), as_ptr.clone(), kernel));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_remove_kernel(move |name| {
+        info!("Operation: Remove kernel '{}'", name);
+        tokio::spawn(remove(ah.clone(), as_ptr.clone(), name.to_string()));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_use_kernel(move |name| {
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let name = name.to_string();
+        tokio::spawn(async move {
+            let kernel = as_ptr.lock().await.config_manager.get_kernels().images.iter()
+                .find(|k| k.name == name)
+                .cloned();
+            let Some(kernel) = kernel else { return };
+            info!("Operation: Boot WSL with kernel '{}' ({})", kernel.name, kernel.path);
+            switch(ah, as_ptr, keys_of(&kernel), false).await;
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_revert_kernel(move || {
+        info!("Operation: Revert to the built-in WSL kernel");
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        tokio::spawn(async move {
+            // Put back the command line the custom kernel replaced, leave it alone otherwise
+            let saved = as_ptr.lock().await.config_manager.get_kernels().saved_command_line.clone();
+            switch(ah, as_ptr, (None, None, saved), true).await;
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_restart_wsl_for_kernel(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_kernel_busy(true);
+        }
+        tokio::spawn(restart(ah.clone(), as_ptr.clone()));
+    });
+}
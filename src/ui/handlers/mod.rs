@@ -4,6 +4,10 @@
 pub mod window;
 pub mod distro;
 pub mod settings;
+pub mod wsl_global;
+pub mod wsl_presets;
+pub mod config_history;
+pub mod kernel;
 pub mod update;
 pub mod common;
 pub mod instance;
@@ -23,6 +27,10 @@ pub async fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_stat
     window::setup(app, app_handle.clone());
     distro::setup(app, app_handle.clone(), app_state.clone());
     settings::setup(app, app_handle.clone(), app_state.clone());
+    wsl_global::setup(app, app_handle.clone(), app_state.clone());
+    wsl_presets::setup(app, app_handle.clone(), app_state.clone());
+    config_history::setup(app, app_handle.clone(), app_state.clone());
+    kernel::setup(app, app_handle.clone(), app_state.clone());
     update::setup(app, app_handle.clone(), app_state.clone());
     instance::setup(app, app_handle.clone(), app_state.clone());
     usb::setup(app, app_handle.clone(), app_state.clone());
@@ -32,6 +32,8 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     app.set_network_proxy_username(config.username.into());
                     app.set_network_proxy_password(config.password.into());
                     app.set_network_proxy_no_proxy(no_proxy.into());
+                    app.set_network_proxy_use_system(!config.ignore_system_proxy);
+                    app.set_network_proxy_ca_bundle(config.ca_bundle.into());
                     app.set_network_proxy_default_host(network::models::default_host().into());
                     app.set_network_proxy_default_port(network::models::default_port().into());
                     app.set_network_proxy_default_no_proxy(network::models::default_no_proxy().into());
@@ -42,7 +44,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
 
     let ah = app_handle.clone();
     let as_ptr = app_state.clone();
-    app.on_save_proxy_settings(move |enabled, host, port, no_proxy, auth, user, pass| {
+    app.on_save_proxy_settings(move |enabled, host, port, no_proxy, auth, user, pass, use_system, ca_bundle| {
         let ah = ah.clone();
         let as_ptr = as_ptr.clone();
         let host = host.to_string();
@@ -50,6 +52,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         let no_proxy = no_proxy.to_string();
         let user = user.to_string();
         let pass = pass.to_string();
+        let ca_bundle = ca_bundle.trim().to_string();
 
         // Validation: Port range 1-65534 (allow empty)
         let port_valid = if port_str.is_empty() {
@@ -99,6 +102,27 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             return;
         }
 
+        // Validation: the CA bundle must hold at least one readable certificate
+        if !ca_bundle.is_empty() {
+            if let Err(e) = network::http::load_ca_bundle(&ca_bundle) {
+                let ah_err = ah.clone();
+                let ah_timer = ah.clone();
+                let _ = slint::invoke_from_event_loop(move || {
+                    if let Some(app) = ah_err.upgrade() {
+                        let err_msg = crate::i18n::tr("network.proxy_error_ca_bundle", &[e]);
+                        app.set_task_status_text(err_msg.into());
+                        app.set_task_status_visible(true);
+                    }
+                    slint::Timer::single_shot(std::time::Duration::from_secs(4), move || {
+                        if let Some(app) = ah_timer.upgrade() {
+                            app.set_task_status_visible(false);
+                        }
+                    });
+                });
+                return;
+            }
+        }
+
         tokio::spawn(async move {
             tracing::info!("Updating HTTP proxy settings: enabled={}, host={}, port={}", enabled, host, port);
             let state = as_ptr.lock().await;
@@ -110,6 +134,8 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             net_config.proxy.username = user.clone();
             net_config.proxy.password = pass.clone();
             net_config.proxy.no_proxy = no_proxy.clone();
+            net_config.proxy.ignore_system_proxy = !use_system;
+            net_config.proxy.ca_bundle = ca_bundle.clone();
             
             let _ = state.config_manager.update_network_config(net_config);
             
@@ -130,6 +156,19 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         });
     });
 
+    let ah = app_handle.clone();
+    app.on_select_ca_bundle(move || {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(crate::i18n::t("dialog.select_ca_bundle"))
+            .add_filter(crate::i18n::t("dialog.certificate"), &["pem", "crt", "cer"])
+            .pick_file()
+        {
+            if let Some(app) = ah.upgrade() {
+                app.set_network_proxy_ca_bundle(path.display().to_string().into());
+            }
+        }
+    });
+
     let ah = app_handle.clone();
     let as_ptr = app_state.clone();
     app.on_check_proxy_connection(move |url| {
@@ -150,39 +189,29 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         
         tracing::info!("Testing proxy connection to {} using proxy {}:{}", url, host, port);
         tokio::spawn(async move {
-            let proxy_config = network::models::HttpProxyConfig {
-                is_enabled: true,
-                host: host.clone(),
-                port: port.clone(),
-                auth_enabled: auth,
-                username: user.clone(),
-                password: pass.clone(),
-                no_proxy: no_proxy.clone(),
-            };
-            
-            {
+            // Tested on its own client, the saved settings only change once it works
+            let proxy_config = {
                 let state = as_ptr.lock().await;
-                let mut net_config = state.config_manager.get_network_config().clone();
-                net_config.proxy = proxy_config.clone();
-                let _ = state.config_manager.update_network_config(net_config);
-            }
-            
-            let mut proxy_str = format!("http://{}:{}", proxy_config.host, proxy_config.port);
-            
-            if proxy_config.auth_enabled {
-                let user_enc = urlencoding::encode(&proxy_config.username);
-                let pass_enc = urlencoding::encode(&proxy_config.password);
-                proxy_str = format!("http://{}:{}@{}:{}", user_enc, pass_enc, proxy_config.host, proxy_config.port);
-            }
+                let mut proxy_config = state.config_manager.get_network_config().proxy.clone();
+                proxy_config.is_enabled = true;
+                proxy_config.host = host.clone();
+                proxy_config.port = port.clone();
+                proxy_config.auth_enabled = auth;
+                proxy_config.username = user.clone();
+                proxy_config.password = pass.clone();
+                proxy_config.no_proxy = no_proxy.clone();
+                proxy_config
+            };
             
-            let proxy_obj = match ureq::Proxy::new(&proxy_str) {
-                Ok(p) => p,
+            // Always through the proxy, even if the test URL is on the bypass list
+            let request = match network::http::get_via(&proxy_config, &url) {
+                Ok(request) => request,
                 Err(e) => {
                     let ah_err = ah.clone();
                     let ah_timer = ah.clone();
                     let _ = slint::invoke_from_event_loop(move || {
                         if let Some(app) = ah_err.upgrade() {
-                            let err_msg = crate::i18n::tr("network.proxy_error_invalid", &[e.to_string()]);
+                            let err_msg = crate::i18n::tr("network.proxy_error_invalid", &[e]);
                             app.set_task_status_text(err_msg.into());
                             app.set_task_status_visible(true);
                         }
@@ -196,15 +225,21 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 }
             };
             
-            let agent = ureq::AgentBuilder::new()
-                .proxy(proxy_obj)
+            let res = request
                 .timeout(std::time::Duration::from_secs(8))
-                .build();
-                
-            let res = agent.get(&url).call();
+                .call();
             
+            let saved = res.is_ok();
             let result_msg = match res {
-                Ok(response) => crate::i18n::tr("network.proxy_test_success", &[response.status().to_string()]),
+                Ok(response) => {
+                    let state = as_ptr.lock().await;
+                    let mut net_config = state.config_manager.get_network_config().clone();
+                    net_config.proxy = proxy_config;
+                    if let Err(e) = state.config_manager.update_network_config(net_config) {
+                        tracing::warn!("Failed to save the tested proxy settings: {}", e);
+                    }
+                    crate::i18n::tr("network.proxy_test_success", &[response.status().to_string()])
+                }
                 Err(e) => crate::i18n::tr("network.proxy_test_failed", &[e.to_string()]),
             };
             
@@ -214,6 +249,9 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             let ah_timer = ah.clone();
             let _ = slint::invoke_from_event_loop(move || {
                 if let Some(app) = ah_status.upgrade() {
+                    if saved {
+                        app.set_network_proxy_is_enabled(true);
+                    }
                     app.set_task_status_text(result_msg.into());
                     app.set_task_status_visible(true);
                 }
@@ -5,6 +5,8 @@ use std::sync::Arc;
 use tokio::sync::Mutex;
 use crate::{AppState, AppWindow};
 use crate::network;
+use crate::network::models::PortProxyType;
+use crate::network::port_proxy::listen_key;
 use super::utils::{refresh_network_view_data, show_toast};
 
 pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
@@ -19,14 +21,16 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             app.set_network_add_enable_fw(false);
             app.set_network_add_distro_idx(0);
             app.set_network_add_ip_idx(0);
+            app.set_network_add_type_idx(0);
         }
     });
 
     let ah_add = app_handle.clone();
     let as_add = app_state.clone();
-    app.on_add_network_rule(move |distro, ip, lport, tport, fw| {
+    app.on_add_network_rule(move |distro, ip, lport, tport, fw, type_idx| {
         let ah = ah_add.clone();
         let as_ptr = as_add.clone();
+        let proxy_type = PortProxyType::from_index(type_idx);
         
         let lport_str = lport.to_string();
         let tport_str = tport.to_string();
@@ -51,15 +55,39 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             return;
         }
 
+        // The listen address has to belong to the table's listening family
+        let ip_valid = if proxy_type.listens_on_v6() {
+            ip.parse::<std::net::Ipv6Addr>().is_ok_and(|a| !a.is_multicast() && !a.is_unicast_link_local())
+        } else {
+            ip.parse::<std::net::Ipv4Addr>().is_ok_and(|a| !a.is_multicast())
+        };
+        if !ip_valid {
+            if let Some(app) = ah.upgrade() {
+                let err_msg = crate::i18n::tr("network.proxy_error_listen_address", &[ip.to_string(), proxy_type.as_str().to_string()]);
+                app.set_network_add_error(err_msg.into());
+            }
+            return;
+        }
+
         tokio::spawn(async move {
             let state = as_ptr.lock().await;
 
-            // 1. Check for duplicates
-            let current_config = state.config_manager.get_network_config();
-            let listen_port_num = lport.parse::<u16>().unwrap_or(0);
-            
-            let is_duplicate = current_config.port_proxies.iter().any(|r| {
-                r.listen_address == ip.as_str() && r.listen_port == listen_port_num
+            let mut net_config = state.config_manager.get_network_config();
+            let rule = network::models::PortProxyRule {
+                id: chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0).to_string(),
+                distro_name: distro.to_string(),
+                listen_address: ip.to_string(),
+                listen_port: lport.parse::<u16>().unwrap_or(0),
+                target_port: tport.parse().unwrap_or(0),
+                enable_firewall: fw,
+                proxy_type,
+            };
+
+            // 1. Check for duplicates, the same address and port in another table still collides
+            let (_, listen_addr, listen_port) = listen_key(&rule);
+            let is_duplicate = net_config.port_proxies.iter().any(|r| {
+                let (_, addr, port) = listen_key(r);
+                addr == listen_addr && port == listen_port
             });
 
             if is_duplicate {
@@ -72,15 +100,6 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 return;
             }
 
-            let mut net_config = current_config;
-            let rule = network::models::PortProxyRule {
-                id: chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0).to_string(),
-                distro_name: distro.to_string(),
-                listen_address: ip.to_string(),
-                listen_port: listen_port_num,
-                target_port: tport.parse().unwrap_or(0),
-                enable_firewall: fw,
-            };
             if rule.listen_port > 0 && rule.target_port > 0 {
                 let log_distro = rule.distro_name.clone();
                 let log_addr = rule.listen_address.clone();
@@ -107,10 +126,10 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     // Scenario B: If distro IS started, execute existing logic.
                     let is_running = state.wsl_dashboard.is_distro_running(&log_distro).await;
                     if is_running {
-                        match network::tracker::get_distro_ip(&log_distro) {
+                        match network::tracker::get_distro_address(&log_distro, proxy_type.connects_to_v6()) {
                             Ok(target_ip) => {
                                 let _ = network::port_proxy::add_port_proxy_and_firewall_elevated(
-                                    &log_addr, log_lport, &target_ip, log_tport, 
+                                    proxy_type, &log_addr, log_lport, &target_ip, log_tport, 
                                     log_fw, &log_distro
                                 );
                                 tracing::info!("Instant rule apply initiated (Distro Running) for {}:{}", log_addr, log_lport);
@@ -122,7 +141,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                     } else {
                         tracing::info!("Distro {} is not running (from state). Skipping instant portproxy apply. Rule saved.", log_distro);
                         if log_fw {
-                            let _ = network::port_proxy::add_firewall_rule_elevated(&log_addr, log_lport, &log_distro);
+                            let _ = network::port_proxy::add_firewall_rule_elevated(proxy_type, &log_addr, log_lport, &log_distro);
                             tracing::info!("Firewall rule applied for inactive distro {}.", log_distro);
                         }
                     }
@@ -157,6 +176,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 let listen_port = rule.listen_port;
                 let target_port = rule.target_port;
                 let enable_fw = rule.enable_firewall;
+                let proxy_type = rule.proxy_type;
                 let dashboard = state.wsl_dashboard.clone();
                 drop(state);
 
@@ -169,9 +189,9 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
 
                 // 2. Get current IP if running
                 tracing::info!("Manually applying rule for {}:{} -> target distro: {}", listen_addr, listen_port, distro_name);
-                match network::tracker::get_distro_ip(&distro_name) {
+                match network::tracker::get_distro_address(&distro_name, proxy_type.connects_to_v6()) {
                     Ok(ip) => {
-                        match network::port_proxy::add_port_proxy_elevated(&listen_addr, listen_port, &ip, target_port, enable_fw, &distro_name) {
+                        match network::port_proxy::add_port_proxy_elevated(proxy_type, &listen_addr, listen_port, &ip, target_port, enable_fw, &distro_name) {
                             Ok(_) => {
                                 tracing::info!("Applied port proxy: {}:{} -> {}:{} (Firewall: {})", listen_addr, listen_port, ip, target_port, enable_fw);
                                 let success_msg = crate::i18n::tr("network.rules_apply_success", &[listen_port.to_string(), ip.to_string()]);
@@ -189,7 +209,8 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                         }
                     },
                     Err(_e) => {
-                        let err_msg = crate::i18n::tr("network.rules_error_no_ip", &[distro_name.to_string()]);
+                        let key = if proxy_type.connects_to_v6() { "network.rules_error_no_ipv6" } else { "network.rules_error_no_ip" };
+                        let err_msg = crate::i18n::tr(key, &[distro_name.to_string()]);
                         show_toast(ah, err_msg);
                     }
                 }
@@ -208,7 +229,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             
             // Physical deletion from Windows with UAC
             if let Some(rule) = net_config.port_proxies.iter().find(|r| r.id == id.as_str()) {
-                let _ = network::port_proxy::delete_port_proxy_and_firewall_elevated(&rule.listen_address, rule.listen_port, &rule.distro_name);
+                let _ = network::port_proxy::delete_port_proxy_and_firewall_elevated(rule.proxy_type, &rule.listen_address, rule.listen_port, &rule.distro_name);
             }
 
             net_config.port_proxies.retain(|r| r.id != id.as_str());
@@ -235,10 +256,11 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 let listen_addr = rule.listen_address.clone();
                 let listen_port = rule.listen_port;
                 let distro_name = rule.distro_name.clone();
+                let proxy_type = rule.proxy_type;
                 drop(state);
 
                 tracing::info!("Canceling active rule for {}:{} (distro: {})", listen_addr, listen_port, distro_name);
-                match network::port_proxy::delete_port_proxy_elevated(&listen_addr, listen_port, &distro_name) {
+                match network::port_proxy::delete_port_proxy_elevated(proxy_type, &listen_addr, listen_port, &distro_name) {
                     Ok(_) => {
                         refresh_network_view_data(ah, as_ptr).await;
                     },
@@ -269,10 +291,10 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             
             let mut rules_to_apply = Vec::new();
             for rule in net_config.port_proxies {
-                if !active_ports.contains(&(rule.listen_address.clone(), rule.listen_port)) {
+                if !active_ports.contains(&listen_key(&rule)) {
                     // Check if distro is running before trying to get IP (Avoid starting the distro)
                     if dashboard.is_distro_running(&rule.distro_name).await {
-                        if let Ok(ip) = network::tracker::get_distro_ip(&rule.distro_name) {
+                        if let Ok(ip) = network::tracker::get_distro_address(&rule.distro_name, rule.proxy_type.connects_to_v6()) {
                             rules_to_apply.push((rule.clone(), ip));
                         }
                     } else {
@@ -317,7 +339,7 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
             
             let mut rules_to_cancel = Vec::new();
             for rule in net_config.port_proxies {
-                if active_ports.contains(&(rule.listen_address.clone(), rule.listen_port)) {
+                if active_ports.contains(&listen_key(&rule)) {
                     rules_to_cancel.push(rule.clone());
                 }
             }
@@ -28,11 +28,16 @@ pub async fn refresh_network_view_data(app: slint::Weak<AppWindow>, app_state: A
     }
     
     let mut local_ips = vec![SharedString::from("0.0.0.0")];
+    let mut local_ipv6s = vec![SharedString::from("::")];
     let mut other_ips = Vec::new();
     let mut wsl_internal_ips = Vec::new();
 
     for iface in interfaces {
         if !iface.is_loopback && !iface.is_virtual {
+            if iface.ip_address.contains(':') {
+                local_ipv6s.push(SharedString::from(iface.ip_address.clone()));
+                continue;
+            }
             if iface.ip_address.starts_with("172.") && iface.ip_address.ends_with(".1") {
                 continue;
             }
@@ -49,7 +54,7 @@ pub async fn refresh_network_view_data(app: slint::Weak<AppWindow>, app_state: A
     
     let mut rule_uis = Vec::new();
     for r in &net_config.port_proxies {
-        let is_active = active_ports.contains(&(r.listen_address.clone(), r.listen_port));
+        let is_active = active_ports.contains(&network::port_proxy::listen_key(r));
         rule_uis.push(PortProxyRuleUI {
             id: SharedString::from(&r.id),
             distro_name: SharedString::from(&r.distro_name),
@@ -58,6 +63,8 @@ pub async fn refresh_network_view_data(app: slint::Weak<AppWindow>, app_state: A
             target_port: SharedString::from(r.target_port.to_string()),
             enable_firewall: r.enable_firewall,
             is_active,
+            proxy_type: SharedString::from(r.proxy_type.as_str()),
+            listen_v6: r.proxy_type.listens_on_v6(),
         });
     }
 
@@ -69,6 +76,7 @@ pub async fn refresh_network_view_data(app: slint::Weak<AppWindow>, app_state: A
             
             let ip_model = ModelRc::new(VecModel::from(local_ips));
             app.set_network_local_ips(ip_model);
+            app.set_network_local_ipv6s(ModelRc::new(VecModel::from(local_ipv6s)));
             
             let rule_model = ModelRc::new(VecModel::from(rule_uis));
             app.set_network_rules(rule_model);
@@ -86,6 +94,8 @@ pub async fn refresh_network_view_data(app: slint::Weak<AppWindow>, app_state: A
             app.set_network_proxy_password(proxy_config.password.into());
             let no_proxy = if proxy_config.no_proxy.is_empty() { network::models::default_no_proxy() } else { proxy_config.no_proxy.clone() };
             app.set_network_proxy_no_proxy(no_proxy.into());
+            app.set_network_proxy_use_system(!proxy_config.ignore_system_proxy);
+            app.set_network_proxy_ca_bundle(proxy_config.ca_bundle.into());
         }
     });
 }
@@ -110,6 +110,17 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 let log_level = app.get_log_level() as u8;
                 let log_days = app.get_log_days() as u8;
                 let sparse_vhd = app.get_sparse_vhd();
+                let recycle_bin = app.get_recycle_bin();
+                let recycle_days = app.get_recycle_days().max(1) as u16;
+                let recycle_max_gb = app.get_recycle_max_gb().max(0) as u32;
+                let image_cache = app.get_image_cache();
+                let image_cache_max_gb = app.get_image_cache_max_gb().max(1) as u32;
+                let catalog_builtin = app.get_catalog_builtin();
+                let catalog_microsoft = app.get_catalog_microsoft();
+                let download_config = crate::config::DownloadConfig {
+                    connections: app.get_download_connections().clamp(1, 16) as u8,
+                    bandwidth_limit_mb: app.get_download_bandwidth_mb().max(0) as u32,
+                };
                 
                 // Write sparseVhd directly to ~/.wslconfig
                 if let Err(e) = crate::utils::wsl_config::set_sparse_vhd(sparse_vhd) {
@@ -135,12 +146,35 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
                 settings.auto_shutdown = auto_shutdown;
                 settings.log_level = log_level;
                 settings.log_days = log_days;
+                settings.recycle_bin = recycle_bin;
+                settings.recycle_days = recycle_days;
+                settings.recycle_max_gb = recycle_max_gb;
+                settings.image_cache = image_cache;
+                settings.image_cache_max_gb = image_cache_max_gb;
+
+                let mut catalog = state.config_manager.get_catalog_config().clone();
+                let catalog_changed = catalog.include_builtin != catalog_builtin || catalog.include_microsoft != catalog_microsoft;
+                if catalog_changed {
+                    catalog.include_builtin = catalog_builtin;
+                    catalog.include_microsoft = catalog_microsoft;
+                    if let Err(e) = state.config_manager.update_catalog_config(catalog) {
+                        error!("Failed to save catalog settings: {}", e);
+                    }
+                }
+
+                crate::ui::handlers::distro::mirror_install::manager::configure(&download_config);
+                if let Err(e) = state.config_manager.update_download_config(download_config) {
+                    error!("Failed to save download settings: {}", e);
+                }
 
                 match state.config_manager.update_settings(settings) {
                     Ok(_) => {
                         drop(state);
                         let _ = slint::invoke_from_event_loop(move || {
                             if let Some(app) = ah.upgrade() {
+                                if catalog_changed {
+                                    crate::ui::data::reset_mirror_list(&app);
+                                }
                                 app.set_current_message(i18n::t("settings.saved_success").into());
                                 app.set_show_message_dialog(true);
                             }
@@ -266,6 +300,94 @@ pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc
         }
     });
 
+    let ah = app_handle.clone();
+    app.on_select_catalog_folder(move || {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(i18n::t("catalog.select_folder"))
+            .pick_folder()
+        {
+            if let Some(app) = ah.upgrade() {
+                app.set_catalog_new_location(path.display().to_string().into());
+            }
+        }
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_add_catalog_source(move |name, location| {
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let name = name.trim().to_string();
+        let location = location.trim().to_string();
+        let _ = slint::spawn_local(async move {
+            let show = |msg: String| {
+                if let Some(app) = ah.upgrade() {
+                    app.set_current_message(msg.into());
+                    app.set_show_message_dialog(true);
+                }
+            };
+
+            let mut catalog = as_ptr.lock().await.config_manager.get_catalog_config().clone();
+            if catalog.sources.iter().any(|s| s.name.eq_ignore_ascii_case(&name)) {
+                show(i18n::tr("catalog.duplicate", &[name]));
+                return;
+            }
+            if !crate::api::catalog::is_remote(&location) && !std::path::Path::new(&location).exists() {
+                show(i18n::tr("catalog.invalid_location", &[location]));
+                return;
+            }
+
+            // Try the catalog right away so a typo shows up here rather than in the add view
+            let probe_location = location.clone();
+            let probe = tokio::task::spawn_blocking(move || crate::api::catalog::load(&probe_location))
+                .await
+                .unwrap_or_else(|e| Err(e.to_string()));
+
+            info!("Adding distro catalog '{}' ({})", name, location);
+            catalog.sources.push(crate::config::CatalogSource { name: name.clone(), location, enabled: true });
+            let saved = as_ptr.lock().await.config_manager.update_catalog_config(catalog.clone());
+
+            let msg = match (saved, probe) {
+                (Err(e), _) => {
+                    error!("Failed to save catalog '{}': {}", name, e);
+                    i18n::tr("settings.saved_failed", &[e.to_string()])
+                }
+                (Ok(_), Ok(response)) => i18n::tr("catalog.added", &[name, response.distros.len().to_string()]),
+                (Ok(_), Err(e)) => i18n::tr("catalog.added_unreachable", &[name, e]),
+            };
+            if let Some(app) = ah.upgrade() {
+                crate::ui::data::set_catalog_sources_to_ui(&app, &catalog);
+                crate::ui::data::reset_mirror_list(&app);
+                app.set_catalog_new_name("".into());
+                app.set_catalog_new_location("".into());
+            }
+            show(msg);
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_remove_catalog_source(move |name| {
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let name = name.to_string();
+        let _ = slint::spawn_local(async move {
+            let mut state = as_ptr.lock().await;
+            let mut catalog = state.config_manager.get_catalog_config().clone();
+            catalog.sources.retain(|s| s.name != name);
+            info!("Removing distro catalog '{}'", name);
+            if let Err(e) = state.config_manager.update_catalog_config(catalog.clone()) {
+                error!("Failed to remove catalog '{}': {}", name, e);
+                return;
+            }
+            drop(state);
+            if let Some(app) = ah.upgrade() {
+                crate::ui::data::set_catalog_sources_to_ui(&app, &catalog);
+                crate::ui::data::reset_mirror_list(&app);
+            }
+        });
+    });
+
     let ah = app_handle.clone();
     app.on_select_logs_folder(move || {
         if let Some(path) = rfd::FileDialog::new()
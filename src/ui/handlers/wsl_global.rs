@@ -0,0 +1,313 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// The WSL settings tab, an editor for ~/.wslconfig
+
+use std::rc::Rc;
+use std::str::FromStr;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::{info, error};
+use crate::{AppWindow, AppState, WslGlobalConfigForm, ConfigDiagnostic, i18n};
+use crate::utils::wsl_config::{WslGlobalConfig, WslConfigSupport, NETWORKING_MODES, MEMORY_RECLAIM_MODES};
+use crate::wsl::ops::lint::Diagnostic;
+
+fn text<T: ToString>(value: &Option<T>) -> slint::SharedString {
+    value.as_ref().map(|v| v.to_string()).unwrap_or_default().into()
+}
+
+fn choice_index(choices: &[&str], value: &Option<String>, default: &str) -> i32 {
+    let value = value.as_deref().unwrap_or(default);
+    choices.iter().position(|c| *c == value).unwrap_or(0) as i32
+}
+
+// Unset keys are shown with the value WSL uses when they are missing
+fn to_form(config: &WslGlobalConfig) -> WslGlobalConfigForm {
+    let w = &config.wsl2;
+    let e = &config.experimental;
+    WslGlobalConfigForm {
+        memory: text(&w.memory),
+        processors: text(&w.processors),
+        swap: text(&w.swap),
+        swap_file: text(&w.swap_file),
+        kernel: text(&w.kernel),
+        kernel_modules: text(&w.kernel_modules),
+        kernel_command_line: text(&w.kernel_command_line),
+        vm_idle_timeout: text(&w.vm_idle_timeout),
+        localhost_forwarding: w.localhost_forwarding.unwrap_or(true),
+        nested_virtualization: w.nested_virtualization.unwrap_or(true),
+        gui_applications: w.gui_applications.unwrap_or(true),
+        debug_console: w.debug_console.unwrap_or(false),
+        networking_mode: choice_index(&NETWORKING_MODES, &w.networking_mode, "nat"),
+        dns_tunneling: w.dns_tunneling.unwrap_or(true),
+        firewall: w.firewall.unwrap_or(true),
+        auto_proxy: w.auto_proxy.unwrap_or(true),
+        auto_memory_reclaim: choice_index(&MEMORY_RECLAIM_MODES, &e.auto_memory_reclaim, "dropcache"),
+        host_address_loopback: e.host_address_loopback.unwrap_or(false),
+        best_effort_dns_parsing: e.best_effort_dns_parsing.unwrap_or(false),
+        ignored_ports: text(&e.ignored_ports),
+        dns_tunneling_ip_address: text(&e.dns_tunneling_ip_address),
+        initial_auto_proxy_timeout: text(&e.initial_auto_proxy_timeout),
+    }
+}
+
+// A key is only written when it is already in the file or differs from the default,
+// so saving an untouched form doesn't fill the file with defaults
+fn explicit<T: PartialEq>(current: &Option<T>, value: T, default: T) -> Option<T> {
+    if current.is_some() || value != default { Some(value) } else { None }
+}
+
+fn optional_text(value: &str) -> Option<String> {
+    let value = value.trim();
+    (!value.is_empty()).then(|| value.to_string())
+}
+
+fn optional_number<T: FromStr>(key: &str, value: &str, errors: &mut Vec<String>) -> Option<T> {
+    let value = optional_text(value)?;
+    match value.parse() {
+        Ok(number) => Some(number),
+        Err(_) => {
+            errors.push(i18n::tr("wslconfig.invalid_number", &[key.to_string(), value]));
+            None
+        }
+    }
+}
+
+// A choice the list doesn't know is kept as long as the user didn't pick another one
+fn choice(choices: &[&str], index: i32, current: &Option<String>, default: &str) -> Option<String> {
+    if index == choice_index(choices, current, default) {
+        return current.clone();
+    }
+    let value = choices.get(index as usize).copied().unwrap_or(default);
+    explicit(current, value.to_string(), default.to_string())
+}
+
+fn from_form(form: &WslGlobalConfigForm, current: &WslGlobalConfig) -> (WslGlobalConfig, Vec<String>) {
+    let mut errors = Vec::new();
+    let mut config = current.clone();
+    let cw = &current.wsl2;
+    let ce = &current.experimental;
+
+    let w = &mut config.wsl2;
+    w.memory = optional_text(&form.memory);
+    w.processors = optional_number("processors", &form.processors, &mut errors);
+    w.swap = optional_text(&form.swap);
+    w.swap_file = optional_text(&form.swap_file);
+    w.kernel = optional_text(&form.kernel);
+    w.kernel_modules = optional_text(&form.kernel_modules);
+    w.kernel_command_line = optional_text(&form.kernel_command_line);
+    w.vm_idle_timeout = optional_number("vmIdleTimeout", &form.vm_idle_timeout, &mut errors);
+    w.localhost_forwarding = explicit(&cw.localhost_forwarding, form.localhost_forwarding, true);
+    w.nested_virtualization = explicit(&cw.nested_virtualization, form.nested_virtualization, true);
+    w.gui_applications = explicit(&cw.gui_applications, form.gui_applications, true);
+    w.debug_console = explicit(&cw.debug_console, form.debug_console, false);
+    w.networking_mode = choice(&NETWORKING_MODES, form.networking_mode, &cw.networking_mode, "nat");
+    w.dns_tunneling = explicit(&cw.dns_tunneling, form.dns_tunneling, true);
+    w.firewall = explicit(&cw.firewall, form.firewall, true);
+    w.auto_proxy = explicit(&cw.auto_proxy, form.auto_proxy, true);
+
+    let e = &mut config.experimental;
+    e.auto_memory_reclaim = choice(&MEMORY_RECLAIM_MODES, form.auto_memory_reclaim, &ce.auto_memory_reclaim, "dropcache");
+    e.host_address_loopback = explicit(&ce.host_address_loopback, form.host_address_loopback, false);
+    e.best_effort_dns_parsing = explicit(&ce.best_effort_dns_parsing, form.best_effort_dns_parsing, false);
+    e.ignored_ports = optional_text(&form.ignored_ports);
+    e.dns_tunneling_ip_address = optional_text(&form.dns_tunneling_ip_address);
+    e.initial_auto_proxy_timeout = optional_number("initialAutoProxyTimeout", &form.initial_auto_proxy_timeout, &mut errors);
+
+    (config, errors)
+}
+
+fn version_hint(support: &WslConfigSupport) -> String {
+    if support.detection_failed {
+        i18n::t("wslconfig.version_unknown")
+    } else if support.modern_supported {
+        i18n::tr("wslconfig.version_detected", &[support.version_string.clone()])
+    } else {
+        i18n::tr("wslconfig.version_legacy", &[support.version_string.clone()])
+    }
+}
+
+async fn detect_support(app_state: &Arc<Mutex<AppState>>) -> WslConfigSupport {
+    let executor = {
+        let state = app_state.lock().await;
+        state.wsl_dashboard.executor().clone()
+    };
+    let meta = crate::wsl::ops::config::check_wsl_version_support(&executor).await;
+    WslConfigSupport::from_meta(&meta)
+}
+
+async fn load(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    crate::ui::handlers::wsl_presets::refresh(ah.clone(), app_state.clone()).await;
+    crate::ui::handlers::config_history::refresh(ah.clone());
+    crate::ui::handlers::kernel::refresh(ah.clone(), app_state.clone(), false).await;
+    let support = detect_support(&app_state).await;
+    let config = WslGlobalConfig::load();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_global_form(to_form(&config));
+            app.set_wsl_global_modern_supported(!support.is_legacy());
+            app.set_wsl_global_version_hint(version_hint(&support).into());
+            app.set_wsl_global_errors("".into());
+        }
+    });
+}
+
+fn show_message(ah: &slint::Weak<AppWindow>, message: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_current_message(message.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+// `wsl --shutdown`, so the next start reads ~/.wslconfig again
+pub async fn shutdown_wsl(app_state: &Arc<Mutex<AppState>>) -> Result<(), String> {
+    let executor = {
+        let state = app_state.lock().await;
+        state.wsl_dashboard.executor().clone()
+    };
+    let result = executor.execute_command(&["--shutdown"]).await;
+    if !result.success {
+        let e = result.error.unwrap_or_default();
+        error!("Failed to shutdown WSL: {}", e);
+        return Err(e);
+    }
+    let state = app_state.lock().await;
+    state.wsl_dashboard.mark_all_distros_stopped().await;
+    Ok(())
+}
+
+async fn save(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, form: WslGlobalConfigForm, restart: bool) {
+    let support = detect_support(&app_state).await;
+    let current = WslGlobalConfig::load();
+    let (config, mut errors) = from_form(&form, &current);
+    errors.extend(config.validate(&support));
+    if !errors.is_empty() {
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(app) = ah.upgrade() {
+                app.set_wsl_global_errors(errors.join("\n").into());
+            }
+        });
+        return;
+    }
+
+    if let Err(e) = config.save(&support) {
+        error!("Failed to save ~/.wslconfig: {}", e);
+        show_message(&ah, i18n::tr("settings.saved_failed", &[e]));
+        return;
+    }
+    let kernel_changed = {
+        let (old, new) = (&current.wsl2, &WslGlobalConfig::load().wsl2);
+        (&old.kernel, &old.kernel_modules, &old.kernel_command_line) != (&new.kernel, &new.kernel_modules, &new.kernel_command_line)
+    };
+
+    if !restart {
+        if kernel_changed {
+            crate::ui::handlers::kernel::mark_restart_pending(&app_state).await;
+        }
+        load(ah.clone(), app_state.clone()).await;
+        show_message(&ah, i18n::t("wslconfig.saved"));
+        return;
+    }
+
+    info!("Operation: Restart WSL to apply ~/.wslconfig");
+    if let Err(e) = shutdown_wsl(&app_state).await {
+        load(ah.clone(), app_state.clone()).await;
+        show_message(&ah, i18n::tr("wslconfig.restart_failed", &[e]));
+        return;
+    }
+    crate::ui::handlers::kernel::clear_restart_pending(&app_state).await;
+    load(ah.clone(), app_state.clone()).await;
+    show_message(&ah, i18n::t("wslconfig.saved_restarted"));
+}
+
+// Also used by the wsl.conf dialog, which passes an empty file name
+pub fn diagnostics_to_ui(file: &str, diagnostics: &[Diagnostic]) -> Vec<ConfigDiagnostic> {
+    diagnostics.iter()
+        .map(|d| ConfigDiagnostic {
+            severity: d.severity.as_str().into(),
+            file: file.into(),
+            line: if d.line == 0 { "".into() } else { d.line.to_string().into() },
+            message: d.message.clone().into(),
+        })
+        .collect()
+}
+
+async fn lint_all(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let (dashboard, presets) = {
+        let state = app_state.lock().await;
+        (state.wsl_dashboard.clone(), crate::ui::handlers::wsl_presets::followed_presets(&state.config_manager))
+    };
+    let reports = crate::wsl::ops::lint::lint_all(dashboard.executor(), &presets).await;
+    dashboard.state_changed().notify_one();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            let results: Vec<ConfigDiagnostic> = reports.iter()
+                .flat_map(|r| diagnostics_to_ui(&r.file, &r.diagnostics))
+                .collect();
+            app.set_wsl_lint_results(slint::ModelRc::from(Rc::new(slint::VecModel::from(results))));
+            app.set_wsl_lint_running(false);
+            app.set_wsl_lint_done(true);
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_load_wsl_global_config(move || {
+        tokio::spawn(load(ah.clone(), as_ptr.clone()));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_save_wsl_global_config(move |restart| {
+        if let Some(app) = ah.upgrade() {
+            info!("Operation: Save ~/.wslconfig (restart: {})", restart);
+            tokio::spawn(save(ah.clone(), as_ptr.clone(), app.get_wsl_global_form(), restart));
+        }
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_lint_all_configs(move || {
+        if let Some(app) = ah.upgrade() {
+            if app.get_wsl_lint_running() {
+                return;
+            }
+            info!("Operation: Check all WSL configuration files");
+            app.set_wsl_lint_running(true);
+            tokio::spawn(lint_all(ah.clone(), as_ptr.clone()));
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_select_wsl_kernel(move || {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(i18n::t("wslconfig.select_kernel"))
+            .pick_file()
+        {
+            if let Some(app) = ah.upgrade() {
+                let mut form = app.get_wsl_global_form();
+                form.kernel = path.display().to_string().into();
+                app.set_wsl_global_form(form);
+            }
+        }
+    });
+
+    let ah = app_handle.clone();
+    app.on_select_wsl_kernel_modules(move || {
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title(i18n::t("wslconfig.select_kernel_modules"))
+            .pick_file()
+        {
+            if let Some(app) = ah.upgrade() {
+                let mut form = app.get_wsl_global_form();
+                form.kernel_modules = path.display().to_string().into();
+                app.set_wsl_global_form(form);
+            }
+        }
+    });
+}
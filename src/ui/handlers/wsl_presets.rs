@@ -0,0 +1,398 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Named wsl.conf presets: a partial wsl.conf applied to many distros at once.
+// Each distro remembers the preset it was set from, the lint check flags drift.
+
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use slint::{Model, VecModel};
+use tracing::{info, error};
+use crate::{AppWindow, AppState, ConfigDiffLine, WslConfPresetItem, WslConfPresetTarget, i18n};
+use crate::config::{ConfigManager, DistroInstanceConfig, WslConfPreset};
+use crate::ui::handlers::distro::config_logic::diff_to_ui;
+use crate::ui::handlers::wsl_global::diagnostics_to_ui;
+use crate::utils::ini_doc::IniDocument;
+use crate::utils::text_diff::diff_with_context;
+use crate::wsl::ops::config::{
+    check_wsl_version_support, format_wsl_conf, parse_wsl_conf, read_wsl_conf, save_wsl_conf,
+    update_wsl_conf, validate_wsl_conf, wsl_conf_entries, WslVersionMeta,
+};
+use crate::wsl::ops::lint::{lint_wsl_conf, preset_drift, record_drift, Diagnostic, Severity};
+use crate::wsl::models::WslStatus;
+
+const PREVIEW_CONTEXT_LINES: usize = 1;
+
+fn summary(preset: &WslConfPreset) -> String {
+    wsl_conf_entries(&preset.settings).iter()
+        .map(|(_, key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+// Distro name -> the preset it follows
+pub fn followed_presets(manager: &ConfigManager) -> HashMap<String, WslConfPreset> {
+    manager.get_wsl_conf_preset_followers().into_iter()
+        .filter_map(|(distro, name)| {
+            manager.get_wsl_conf_presets().iter().find(|p| p.name == name).map(|p| (distro, p.clone()))
+        })
+        .collect()
+}
+
+pub async fn refresh(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let (presets, followers) = {
+        let state = app_state.lock().await;
+        let manager = &state.config_manager;
+        (manager.get_wsl_conf_presets().to_vec(), manager.get_wsl_conf_preset_followers())
+    };
+    let items: Vec<WslConfPresetItem> = presets.iter()
+        .map(|p| {
+            let mut distros: Vec<&str> = followers.iter()
+                .filter(|(_, preset)| *preset == p.name)
+                .map(|(distro, _)| distro.as_str())
+                .collect();
+            distros.sort();
+            WslConfPresetItem {
+                name: p.name.clone().into(),
+                summary: summary(p).into(),
+                followers: distros.join(", ").into(),
+            }
+        })
+        .collect();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_conf_presets(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+        }
+    });
+}
+
+// Problems that keep the editor content from being saved. Keys the preset model
+// doesn't know would be dropped silently, so they are reported too.
+fn check_content(content: &str) -> Vec<Diagnostic> {
+    let mut diagnostics: Vec<Diagnostic> = lint_wsl_conf(content, &WslVersionMeta::default())
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error)
+        .collect();
+
+    let settings = parse_wsl_conf(content);
+    let known: HashSet<(&str, &str)> = wsl_conf_entries(&settings).into_iter()
+        .map(|(section, key, _)| (section, key))
+        .collect();
+    for entry in IniDocument::parse(content).entries() {
+        if !known.contains(&(entry.section.as_str(), entry.key.as_str())) {
+            diagnostics.push(Diagnostic {
+                severity: Severity::Error,
+                line: entry.line,
+                message: i18n::tr("preset.key_ignored", &[entry.section.clone(), entry.key.clone()]),
+            });
+        }
+    }
+    if diagnostics.is_empty() && known.is_empty() {
+        diagnostics.push(Diagnostic { severity: Severity::Error, line: 0, message: i18n::t("preset.no_settings") });
+    }
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+fn show_message(ah: &slint::Weak<AppWindow>, message: String) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_current_message(message.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+async fn save(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, name: String, content: String) {
+    let diagnostics = check_content(&content);
+    if !diagnostics.is_empty() {
+        let _ = slint::invoke_from_event_loop(move || {
+            if let Some(app) = ah.upgrade() {
+                let items = diagnostics_to_ui("", &diagnostics);
+                app.set_wsl_conf_preset_edit_diagnostics(slint::ModelRc::from(Rc::new(VecModel::from(items))));
+            }
+        });
+        return;
+    }
+
+    let preset = WslConfPreset { name: name.clone(), settings: parse_wsl_conf(&content) };
+    let saved = app_state.lock().await.config_manager.save_wsl_conf_preset(preset);
+    if let Err(e) = saved {
+        error!("Failed to save wsl.conf preset '{}': {}", name, e);
+        show_message(&ah, i18n::tr("settings.saved_failed", &[e.to_string()]));
+        return;
+    }
+    refresh(ah.clone(), app_state).await;
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_conf_preset_editing(false);
+        }
+    });
+}
+
+async fn remove(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, name: String) {
+    {
+        let mut state = app_state.lock().await;
+        if let Err(e) = state.config_manager.remove_wsl_conf_preset(&name) {
+            error!("Failed to remove wsl.conf preset '{}': {}", name, e);
+            drop(state);
+            show_message(&ah, i18n::tr("settings.saved_failed", &[e.to_string()]));
+            return;
+        }
+        // Distros that followed it no longer follow anything
+        for (distro, preset) in state.config_manager.get_wsl_conf_preset_followers() {
+            if preset == name {
+                let config = DistroInstanceConfig {
+                    wsl_conf_preset: String::new(),
+                    ..state.config_manager.get_instance_config(&distro)
+                };
+                if let Err(e) = state.config_manager.update_instance_config(&distro, config) {
+                    error!("Failed to clear the wsl.conf preset of '{}': {}", distro, e);
+                }
+            }
+        }
+    }
+    refresh(ah, app_state).await;
+}
+
+async fn open_apply(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, name: String) {
+    let (executor, followers) = {
+        let state = app_state.lock().await;
+        (state.wsl_dashboard.executor().clone(), state.config_manager.get_wsl_conf_preset_followers())
+    };
+    let mut distros: Vec<String> = executor.list_distros().await.data
+        .unwrap_or_default()
+        .into_iter()
+        .map(|d| d.name)
+        .collect();
+    distros.sort();
+
+    let targets: Vec<WslConfPresetTarget> = distros.into_iter()
+        .map(|distro| {
+            let preset = followers.iter().find(|(d, _)| *d == distro).map(|(_, p)| p.clone()).unwrap_or_default();
+            WslConfPresetTarget { selected: preset == name, name: distro.into(), preset: preset.into() }
+        })
+        .collect();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_conf_preset_apply_name(name.into());
+            app.set_wsl_conf_preset_apply_targets(slint::ModelRc::from(Rc::new(VecModel::from(targets))));
+            app.set_wsl_conf_preset_apply_preview(slint::ModelRc::default());
+            app.set_wsl_conf_preset_editing(false);
+            app.set_wsl_conf_preset_applying(true);
+        }
+    });
+}
+
+fn selected_targets(app: &AppWindow) -> Vec<String> {
+    app.get_wsl_conf_preset_apply_targets().iter()
+        .filter(|t| t.selected)
+        .map(|t| t.name.to_string())
+        .collect()
+}
+
+fn set_apply_busy(ah: &slint::Weak<AppWindow>, busy: bool) {
+    let ah = ah.clone();
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_conf_preset_apply_busy(busy);
+        }
+    });
+}
+
+async fn find_preset(app_state: &Arc<Mutex<AppState>>, name: &str) -> Option<WslConfPreset> {
+    let state = app_state.lock().await;
+    state.config_manager.get_wsl_conf_presets().iter().find(|p| p.name == name).cloned()
+}
+
+// What applying the preset would change in each running distro's wsl.conf.
+// Stopped distros are not read, that would start them.
+async fn preview(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, name: String, distros: Vec<String>) {
+    let Some(preset) = find_preset(&app_state, &name).await else { return };
+    let (dashboard, followed) = {
+        let state = app_state.lock().await;
+        (state.wsl_dashboard.clone(), followed_presets(&state.config_manager))
+    };
+    let executor = dashboard.executor().clone();
+    set_apply_busy(&ah, true);
+    let meta = check_wsl_version_support(&executor).await;
+    let running: HashSet<String> = executor.list_distros().await.data
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|d| d.status == WslStatus::Running)
+        .map(|d| d.name)
+        .collect();
+
+    let mut lines = Vec::new();
+    let mut drift_changed = false;
+    for distro in distros {
+        lines.push(ConfigDiffLine { kind: "file".into(), line: "".into(), text: distro.clone().into() });
+        if !running.contains(&distro) {
+            lines.push(ConfigDiffLine { kind: "note".into(), line: "".into(), text: i18n::t("preset.not_running").into() });
+            continue;
+        }
+        match read_wsl_conf(&executor, &distro).await {
+            Ok(original) => {
+                if let Some(own) = followed.get(&distro) {
+                    drift_changed |= record_drift(&distro, preset_drift(&original, own, &meta).is_some());
+                }
+                let updated = update_wsl_conf(&original, &preset.settings, &meta);
+                let diff = diff_with_context(&original, &updated, PREVIEW_CONTEXT_LINES);
+                if diff.is_empty() {
+                    lines.push(ConfigDiffLine { kind: "note".into(), line: "".into(), text: i18n::t("preset.no_changes").into() });
+                } else {
+                    lines.extend(diff_to_ui(diff));
+                }
+            }
+            Err(e) => {
+                error!("Preset preview: could not read wsl.conf of '{}': {}", distro, e);
+                lines.push(ConfigDiffLine { kind: "note".into(), line: "".into(), text: i18n::tr("preset.read_failed", &[e]).into() });
+            }
+        }
+    }
+
+    if drift_changed {
+        dashboard.state_changed().notify_one();
+    }
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_conf_preset_apply_preview(slint::ModelRc::from(Rc::new(VecModel::from(lines))));
+            app.set_wsl_conf_preset_apply_busy(false);
+        }
+    });
+}
+
+// Write the preset through the regular wsl.conf save path and record it per distro
+async fn apply(ah: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>, name: String, distros: Vec<String>) {
+    let Some(preset) = find_preset(&app_state, &name).await else { return };
+    let _guard = crate::ui::data::BusyGuard::new();
+    let wsl_dashboard = app_state.lock().await.wsl_dashboard.clone();
+    wsl_dashboard.increment_manual_operation();
+    let dashboard_c = wsl_dashboard.clone();
+    let _op_guard = scopeguard::guard((), move |_| {
+        dashboard_c.decrement_manual_operation();
+    });
+    let executor = wsl_dashboard.executor().clone();
+    set_apply_busy(&ah, true);
+    let meta = check_wsl_version_support(&executor).await;
+
+    let mut applied = 0;
+    let mut failures = Vec::new();
+    for distro in &distros {
+        info!("Operation: Apply wsl.conf preset '{}' to '{}'", name, distro);
+        let validation = validate_wsl_conf(&executor, distro, &preset.settings).await;
+        let result = if validation.success {
+            save_wsl_conf(&executor, distro, &preset.settings, &meta).await
+        } else {
+            Err(validation.user_error.or(validation.command_error).unwrap_or_default())
+        };
+        match result {
+            Ok(()) => {
+                applied += 1;
+                record_drift(distro, false);
+                let state = app_state.lock().await;
+                let config = DistroInstanceConfig {
+                    wsl_conf_preset: name.clone(),
+                    ..state.config_manager.get_instance_config(distro)
+                };
+                if let Err(e) = state.config_manager.update_instance_config(distro, config) {
+                    error!("Failed to record the wsl.conf preset of '{}': {}", distro, e);
+                }
+            }
+            Err(e) => {
+                error!("Failed to apply wsl.conf preset '{}' to '{}': {}", name, distro, e);
+                failures.push(format!("{}: {}", distro, e));
+            }
+        }
+    }
+
+    wsl_dashboard.state_changed().notify_one();
+    refresh(ah.clone(), app_state).await;
+    let mut message = i18n::tr("preset.applied", &[name, applied.to_string()]);
+    if !failures.is_empty() {
+        message = format!("{}\n\n{}\n{}", message, i18n::t("preset.apply_failed"), failures.join("\n"));
+    }
+    let _ = slint::invoke_from_event_loop(move || {
+        if let Some(app) = ah.upgrade() {
+            app.set_wsl_conf_preset_apply_busy(false);
+            app.set_wsl_conf_preset_applying(false);
+            app.set_current_message(message.into());
+            app.set_show_message_dialog(true);
+        }
+    });
+}
+
+pub fn setup(app: &AppWindow, app_handle: slint::Weak<AppWindow>, app_state: Arc<Mutex<AppState>>) {
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_edit_wsl_conf_preset(move |name| {
+        let Some(app) = ah.upgrade() else { return };
+        app.set_wsl_conf_preset_edit_diagnostics(slint::ModelRc::default());
+        app.set_wsl_conf_preset_editing(true);
+        if name.is_empty() {
+            return;
+        }
+        let ah = ah.clone();
+        let as_ptr = as_ptr.clone();
+        let name = name.to_string();
+        let _ = slint::spawn_local(async move {
+            if let (Some(preset), Some(app)) = (find_preset(&as_ptr, &name).await, ah.upgrade()) {
+                app.set_wsl_conf_preset_edit_name(preset.name.clone().into());
+                app.set_wsl_conf_preset_edit_content(format_wsl_conf(&preset.settings).into());
+            }
+        });
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_save_wsl_conf_preset(move |name, content| {
+        let name = name.trim().to_string();
+        if name.is_empty() {
+            return;
+        }
+        info!("Operation: Save wsl.conf preset '{}'", name);
+        tokio::spawn(save(ah.clone(), as_ptr.clone(), name, content.to_string()));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_remove_wsl_conf_preset(move |name| {
+        info!("Operation: Remove wsl.conf preset '{}'", name);
+        tokio::spawn(remove(ah.clone(), as_ptr.clone(), name.to_string()));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_open_wsl_conf_preset_apply(move |name| {
+        tokio::spawn(open_apply(ah.clone(), as_ptr.clone(), name.to_string()));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_preview_wsl_conf_preset_apply(move || {
+        let Some(app) = ah.upgrade() else { return };
+        let distros = selected_targets(&app);
+        if distros.is_empty() {
+            show_message(&ah, i18n::t("preset.none_selected"));
+            return;
+        }
+        let name = app.get_wsl_conf_preset_apply_name().to_string();
+        tokio::spawn(preview(ah.clone(), as_ptr.clone(), name, distros));
+    });
+
+    let ah = app_handle.clone();
+    let as_ptr = app_state.clone();
+    app.on_apply_wsl_conf_preset(move || {
+        let Some(app) = ah.upgrade() else { return };
+        let distros = selected_targets(&app);
+        if distros.is_empty() {
+            show_message(&ah, i18n::t("preset.none_selected"));
+            return;
+        }
+        let name = app.get_wsl_conf_preset_apply_name().to_string();
+        tokio::spawn(apply(ah.clone(), as_ptr.clone(), name, distros));
+    });
+}
@@ -0,0 +1,202 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Version history of ~/.wslconfig and every distro's /etc/wsl.conf
+//
+// Each file has a folder in ~/.wsldashboard/config-history with an `index.toml`
+// and one snapshot per version. Every write made by the dashboard is recorded,
+// together with the content it replaced when that was never seen before, so an
+// edit made outside the dashboard can be rolled back to as well.
+
+use std::path::PathBuf;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+const HISTORY_DIR_NAME: &str = "config-history";
+const INDEX_FILE_NAME: &str = "index.toml";
+// Older versions are dropped beyond this
+const MAX_VERSIONS: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigTarget {
+    WslConfig,
+    // /etc/wsl.conf of the named distro
+    WslConf(String),
+}
+
+// FNV-1a, stable across builds. WSL distro names are case-insensitive.
+fn name_hash(name: &str) -> u64 {
+    name.to_lowercase().bytes().fold(0xcbf29ce484222325, |hash, b| (hash ^ b as u64).wrapping_mul(0x100000001b3))
+}
+
+impl ConfigTarget {
+    fn dir_name(&self) -> String {
+        match self {
+            Self::WslConfig => "wslconfig".to_string(),
+            // The readable part can collide ("a b" and "a_b"), the hash of the full
+            // name keeps the folders apart
+            Self::WslConf(distro) => {
+                let safe: String = distro.chars()
+                    .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+                    .collect();
+                format!("wsl.conf-{}-{:08x}", safe, name_hash(distro) as u32)
+            }
+        }
+    }
+
+    fn dir(&self) -> PathBuf {
+        history_root().join(self.dir_name())
+    }
+
+    // What the user sees, e.g. `Ubuntu: /etc/wsl.conf`
+    pub fn label(&self) -> String {
+        match self {
+            Self::WslConfig => "~/.wslconfig".to_string(),
+            Self::WslConf(distro) => format!("{}: /etc/wsl.conf", distro),
+        }
+    }
+
+    // Back from `label`, so a selection in the UI names its target
+    pub fn from_label(label: &str) -> Option<Self> {
+        if label == "~/.wslconfig" {
+            return Some(Self::WslConfig);
+        }
+        label.strip_suffix(": /etc/wsl.conf")
+            .filter(|distro| !distro.is_empty())
+            .map(|distro| Self::WslConf(distro.to_string()))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum VersionOrigin {
+    // Found on disk, written by something other than the dashboard
+    External,
+    Dashboard,
+    Rollback,
+}
+
+impl VersionOrigin {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::External => "external",
+            Self::Dashboard => "dashboard",
+            Self::Rollback => "rollback",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigVersion {
+    // Snapshot file name without extension, unique within the target
+    pub id: String,
+    // Unix timestamp (ms)
+    pub time: String,
+    pub origin: VersionOrigin,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryIndex {
+    // Empty for ~/.wslconfig
+    #[serde(default)]
+    distro: String,
+    // Oldest first
+    #[serde(default)]
+    versions: Vec<ConfigVersion>,
+}
+
+fn history_root() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".wsldashboard").join(HISTORY_DIR_NAME)
+}
+
+fn load_index(target: &ConfigTarget) -> HistoryIndex {
+    std::fs::read_to_string(target.dir().join(INDEX_FILE_NAME))
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(target: &ConfigTarget, index: &HistoryIndex) -> Result<(), String> {
+    let content = toml::to_string_pretty(index).map_err(|e| e.to_string())?;
+    std::fs::write(target.dir().join(INDEX_FILE_NAME), content).map_err(|e| e.to_string())
+}
+
+fn snapshot_path(target: &ConfigTarget, id: &str) -> PathBuf {
+    target.dir().join(format!("{}.conf", id))
+}
+
+fn add_version(target: &ConfigTarget, index: &mut HistoryIndex, content: &str, origin: VersionOrigin) -> Result<(), String> {
+    // Two versions recorded in the same millisecond still need their own file
+    let mut time = chrono::Utc::now().timestamp_millis();
+    while index.versions.iter().any(|v| v.id == time.to_string()) {
+        time += 1;
+    }
+    let id = time.to_string();
+    std::fs::write(snapshot_path(target, &id), content).map_err(|e| e.to_string())?;
+    index.versions.push(ConfigVersion { id: id.clone(), time: id, origin });
+    Ok(())
+}
+
+fn record_inner(target: &ConfigTarget, before: &str, after: &str, origin: VersionOrigin) -> Result<(), String> {
+    std::fs::create_dir_all(target.dir()).map_err(|e| e.to_string())?;
+    let mut index = load_index(target);
+    if let ConfigTarget::WslConf(distro) = target {
+        index.distro = distro.clone();
+    }
+
+    // The replaced content is kept unless it is the latest version already
+    let latest = index.versions.last().and_then(|v| read(target, &v.id).ok());
+    if latest.as_deref() != Some(before) {
+        add_version(target, &mut index, before, VersionOrigin::External)?;
+    }
+    add_version(target, &mut index, after, origin)?;
+
+    while index.versions.len() > MAX_VERSIONS {
+        let old = index.versions.remove(0);
+        let _ = std::fs::remove_file(snapshot_path(target, &old.id));
+    }
+    save_index(target, &index)
+}
+
+// Called after a successful write. Failing to record never fails the write itself.
+pub fn record(target: &ConfigTarget, before: &str, after: &str, origin: VersionOrigin) {
+    match record_inner(target, before, after, origin) {
+        Ok(()) => info!("Recorded a new version of {}", target.label()),
+        Err(e) => warn!("Failed to record history of {}: {}", target.label(), e),
+    }
+}
+
+// Newest first
+pub fn list(target: &ConfigTarget) -> Vec<ConfigVersion> {
+    let mut versions = load_index(target).versions;
+    versions.reverse();
+    versions
+}
+
+pub fn read(target: &ConfigTarget, id: &str) -> Result<String, String> {
+    std::fs::read_to_string(snapshot_path(target, id)).map_err(|e| e.to_string())
+}
+
+// Every file with a history, ~/.wslconfig first
+pub fn targets() -> Vec<ConfigTarget> {
+    let mut distros: Vec<String> = std::fs::read_dir(history_root())
+        .map(|entries| {
+            entries.flatten()
+                .filter(|e| e.file_name().to_string_lossy().starts_with("wsl.conf-"))
+                .filter_map(|e| std::fs::read_to_string(e.path().join(INDEX_FILE_NAME)).ok())
+                .filter_map(|content| toml::from_str::<HistoryIndex>(&content).ok())
+                .map(|index| index.distro)
+                .filter(|distro| !distro.is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+    distros.sort_by_key(|d| d.to_lowercase());
+
+    let mut targets = Vec::new();
+    if !list(&ConfigTarget::WslConfig).is_empty() {
+        targets.push(ConfigTarget::WslConfig);
+    }
+    targets.extend(distros.into_iter().map(ConfigTarget::WslConf));
+    targets
+}
@@ -0,0 +1,80 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::io::Read;
+use std::path::Path;
+use windows::Win32::Security::Cryptography::{
+    BCryptCloseAlgorithmProvider, BCryptCreateHash, BCryptDestroyHash, BCryptFinishHash,
+    BCryptHashData, BCryptOpenAlgorithmProvider, BCRYPT_ALG_HANDLE, BCRYPT_HASH_HANDLE,
+    BCRYPT_OPEN_ALGORITHM_PROVIDER_FLAGS, BCRYPT_SHA256_ALGORITHM,
+};
+use windows::core::PCWSTR;
+
+// Incremental SHA-256 backed by Windows CNG
+pub struct Sha256 {
+    alg: BCRYPT_ALG_HANDLE,
+    hash: BCRYPT_HASH_HANDLE,
+}
+
+impl Sha256 {
+    pub fn new() -> Result<Self, String> {
+        let mut alg = BCRYPT_ALG_HANDLE::default();
+        let mut hash = BCRYPT_HASH_HANDLE::default();
+        unsafe {
+            BCryptOpenAlgorithmProvider(&mut alg, BCRYPT_SHA256_ALGORITHM, PCWSTR::null(), BCRYPT_OPEN_ALGORITHM_PROVIDER_FLAGS(0))
+                .ok()
+                .map_err(|e| format!("BCryptOpenAlgorithmProvider failed: {}", e))?;
+            if let Err(e) = BCryptCreateHash(alg, &mut hash, None, None, 0).ok() {
+                let _ = BCryptCloseAlgorithmProvider(alg, 0);
+                return Err(format!("BCryptCreateHash failed: {}", e));
+            }
+        }
+        Ok(Self { alg, hash })
+    }
+
+    pub fn update(&mut self, data: &[u8]) -> Result<(), String> {
+        unsafe {
+            BCryptHashData(self.hash, data, 0)
+                .ok()
+                .map_err(|e| format!("BCryptHashData failed: {}", e))
+        }
+    }
+
+    pub fn finalize_hex(self) -> Result<String, String> {
+        let mut digest = [0u8; 32];
+        unsafe {
+            BCryptFinishHash(self.hash, &mut digest, 0)
+                .ok()
+                .map_err(|e| format!("BCryptFinishHash failed: {}", e))?;
+        }
+        Ok(digest.iter().map(|b| format!("{:02x}", b)).collect())
+    }
+}
+
+impl Drop for Sha256 {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = BCryptDestroyHash(self.hash);
+            let _ = BCryptCloseAlgorithmProvider(self.alg, 0);
+        }
+    }
+}
+
+// Hash a whole file, reporting the number of bytes processed so far
+pub fn sha256_file<F>(path: &Path, mut on_progress: F) -> Result<String, String>
+where
+    F: FnMut(u64),
+{
+    let mut file = std::fs::File::open(path).map_err(|e| format!("Open {} failed: {}", path.display(), e))?;
+    let mut hasher = Sha256::new()?;
+    let mut buffer = vec![0u8; 8 * 1024 * 1024];
+    let mut processed = 0u64;
+    loop {
+        let n = file.read(&mut buffer).map_err(|e| e.to_string())?;
+        if n == 0 { break; }
+        hasher.update(&buffer[..n])?;
+        processed += n as u64;
+        on_progress(processed);
+    }
+    hasher.finalize_hex()
+}
@@ -0,0 +1,296 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Lossless INI document behind every edit of wsl.conf (`ops::config`) and
+// ~/.wslconfig (`utils::wsl_config`)
+//
+// The file is kept as its original lines. Reading looks keys up in place, and
+// editing only touches the line of the key being changed, so comments, ordering,
+// blank lines and keys we don't know about survive a save.
+
+use std::fmt;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct IniDocument {
+    lines: Vec<String>,
+    crlf: bool,
+    trailing_newline: bool,
+}
+
+// One `key = value` line, with its 1-based line number
+#[derive(Debug, Clone, PartialEq)]
+pub struct IniEntry {
+    // Empty for keys before the first section header
+    pub section: String,
+    pub key: String,
+    pub value: String,
+    pub line: usize,
+}
+
+enum Line<'a> {
+    Section(&'a str),
+    Entry(&'a str, &'a str),
+    // Blank line or comment
+    Other,
+    // Anything else, WSL skips it
+    Invalid,
+}
+
+fn classify(line: &str) -> Line<'_> {
+    let trimmed = line.trim();
+    if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with(';') {
+        return Line::Other;
+    }
+    if let Some((name, _)) = trimmed.strip_prefix('[').and_then(|rest| rest.split_once(']')) {
+        return Line::Section(name.trim());
+    }
+    match trimmed.split_once('=') {
+        Some((key, value)) => Line::Entry(key.trim(), value.trim()),
+        None => Line::Invalid,
+    }
+}
+
+// Values may be quoted, e.g. `options = "metadata,umask=22"`
+fn unquote(value: &str) -> &str {
+    if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        &value[1..value.len() - 1]
+    } else {
+        value
+    }
+}
+
+impl IniDocument {
+    pub fn parse(content: &str) -> Self {
+        let trailing_newline = content.is_empty() || content.ends_with('\n');
+        let body = content.strip_suffix('\n').unwrap_or(content);
+        let lines = if content.is_empty() {
+            Vec::new()
+        } else {
+            body.split('\n').map(|l| l.strip_suffix('\r').unwrap_or(l).to_string()).collect()
+        };
+        Self { lines, crlf: content.contains("\r\n"), trailing_newline }
+    }
+
+    pub fn entries(&self) -> Vec<IniEntry> {
+        let mut section = "";
+        let mut entries = Vec::new();
+        for (i, line) in self.lines.iter().enumerate() {
+            match classify(line) {
+                Line::Section(name) => section = name,
+                Line::Entry(key, value) => entries.push(IniEntry {
+                    section: section.to_string(),
+                    key: key.to_string(),
+                    value: unquote(value).to_string(),
+                    line: i + 1,
+                }),
+                _ => {}
+            }
+        }
+        entries
+    }
+
+    // Section headers with their line numbers, a repeated section is listed again
+    pub fn sections(&self) -> Vec<(String, usize)> {
+        self.lines.iter().enumerate()
+            .filter_map(|(i, line)| match classify(line) {
+                Line::Section(name) => Some((name.to_string(), i + 1)),
+                _ => None,
+            })
+            .collect()
+    }
+
+    // Lines that are neither a section, a key nor a comment
+    pub fn invalid_lines(&self) -> Vec<usize> {
+        self.lines.iter().enumerate()
+            .filter(|(_, line)| matches!(classify(line), Line::Invalid))
+            .map(|(i, _)| i + 1)
+            .collect()
+    }
+
+    // Index of the last line holding the key, like WSL the last occurrence wins
+    fn find(&self, section: &str, key: &str) -> Option<usize> {
+        let mut current = "";
+        let mut found = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            match classify(line) {
+                Line::Section(name) => current = name,
+                Line::Entry(k, _) if current == section && k == key => found = Some(i),
+                _ => {}
+            }
+        }
+        found
+    }
+
+    pub fn get(&self, section: &str, key: &str) -> Option<String> {
+        self.find(section, key).and_then(|i| match classify(&self.lines[i]) {
+            Line::Entry(_, value) => Some(unquote(value).to_string()),
+            _ => None,
+        })
+    }
+
+    // Change the value in place, keeping indentation, spacing around `=` and quotes.
+    // A new key goes after the last entry of its section, a new section at the end.
+    pub fn set(&mut self, section: &str, key: &str, value: &str) {
+        if let Some(i) = self.find(section, key) {
+            let line = &self.lines[i];
+            let eq = line.find('=').unwrap_or(line.len());
+            let after = &line[eq + 1..];
+            let spacing = &after[..after.len() - after.trim_start().len()];
+            let old = after.trim();
+            let quoted = unquote(old).len() != old.len() && unquote(value).len() == value.len();
+            let value = if quoted { format!("\"{}\"", value) } else { value.to_string() };
+            self.lines[i] = format!("{}={}{}", &line[..eq], spacing, value);
+            return;
+        }
+
+        let entry = format!("{} = {}", key, value);
+        match self.section_end(section) {
+            Some(end) => self.lines.insert(end, entry),
+            None => {
+                if self.lines.last().is_some_and(|l| !l.trim().is_empty()) {
+                    self.lines.push(String::new());
+                }
+                self.lines.push(format!("[{}]", section));
+                self.lines.push(entry);
+            }
+        }
+    }
+
+    // Remove every occurrence of the key. A section left without entries or
+    // comments is dropped with it.
+    pub fn remove(&mut self, section: &str, key: &str) -> bool {
+        let mut removed = false;
+        while let Some(i) = self.find(section, key) {
+            self.lines.remove(i);
+            removed = true;
+        }
+        if removed {
+            self.drop_empty_sections(section);
+        }
+        removed
+    }
+
+    // Where a key can be appended to the last block of the section: after its last
+    // entry, so comments and blank lines leading into the next section stay there
+    fn section_end(&self, section: &str) -> Option<usize> {
+        let mut current: Option<&str> = None;
+        let mut end = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            match classify(line) {
+                Line::Section(name) => {
+                    current = Some(name);
+                    if name == section {
+                        end = Some(i + 1);
+                    }
+                }
+                Line::Entry(..) if current == Some(section) => end = Some(i + 1),
+                _ => {}
+            }
+        }
+        end
+    }
+
+    fn drop_empty_sections(&mut self, section: &str) {
+        let mut dropped = false;
+        let mut i = 0;
+        while i < self.lines.len() {
+            if !matches!(classify(&self.lines[i]), Line::Section(name) if name == section) {
+                i += 1;
+                continue;
+            }
+            let block_end = (i + 1..self.lines.len())
+                .find(|&j| matches!(classify(&self.lines[j]), Line::Section(_)))
+                .unwrap_or(self.lines.len());
+            // The blank lines separating it from the previous section stay in place
+            if self.lines[i + 1..block_end].iter().all(|l| l.trim().is_empty()) {
+                self.lines.drain(i..block_end);
+                dropped = true;
+            } else {
+                i = block_end;
+            }
+        }
+        while dropped && self.lines.last().is_some_and(|l| l.trim().is_empty()) {
+            self.lines.pop();
+        }
+    }
+}
+
+impl fmt::Display for IniDocument {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let eol = if self.crlf { "\r\n" } else { "\n" };
+        f.write_str(&self.lines.join(eol))?;
+        if self.trailing_newline && !self.lines.is_empty() {
+            f.write_str(eol)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_keeps_quotes_and_spacing() {
+        let mut doc = IniDocument::parse("[automount]\noptions = \"metadata\"\nroot=/mnt/\n");
+        doc.set("automount", "options", "metadata,umask=22");
+        doc.set("automount", "root", "/win/");
+        assert_eq!(doc.to_string(), "[automount]\noptions = \"metadata,umask=22\"\nroot=/win/\n");
+        assert_eq!(doc.get("automount", "options").as_deref(), Some("metadata,umask=22"));
+    }
+
+    #[test]
+    fn set_does_not_quote_twice() {
+        let mut doc = IniDocument::parse("[user]\ndefault = \"old\"\n");
+        doc.set("user", "default", "\"new\"");
+        assert_eq!(doc.to_string(), "[user]\ndefault = \"new\"\n");
+    }
+
+    #[test]
+    fn comments_survive_edits() {
+        let text = "# managed by hand\n[boot]\n; keep systemd on\nsystemd = true\n\n# network below\n[network]\nhostname = box\n";
+        let mut doc = IniDocument::parse(text);
+        assert!(doc.entries().iter().all(|e| !e.key.starts_with('#') && !e.key.starts_with(';')));
+        doc.set("boot", "command", "echo hi");
+        doc.set("network", "hostname", "other");
+        assert_eq!(doc.to_string(), "# managed by hand\n[boot]\n; keep systemd on\nsystemd = true\ncommand = echo hi\n\n# network below\n[network]\nhostname = other\n");
+    }
+
+    #[test]
+    fn last_duplicate_wins() {
+        let mut doc = IniDocument::parse("[user]\ndefault = a\n[user]\ndefault = b\n");
+        assert_eq!(doc.get("user", "default").as_deref(), Some("b"));
+        doc.set("user", "default", "c");
+        assert_eq!(doc.to_string(), "[user]\ndefault = a\n[user]\ndefault = c\n");
+        assert_eq!(doc.sections().len(), 2);
+    }
+
+    #[test]
+    fn remove_drops_every_duplicate_and_the_empty_section() {
+        let mut doc = IniDocument::parse("[boot]\nsystemd = true\n\n[user]\ndefault = a\ndefault = b\n");
+        assert!(doc.remove("user", "default"));
+        assert_eq!(doc.to_string(), "[boot]\nsystemd = true\n");
+        assert!(!doc.remove("user", "default"));
+    }
+
+    #[test]
+    fn section_with_a_comment_is_kept() {
+        let mut doc = IniDocument::parse("[user]\n# who logs in\ndefault = a\n");
+        doc.remove("user", "default");
+        assert_eq!(doc.to_string(), "[user]\n# who logs in\n");
+    }
+
+    #[test]
+    fn crlf_and_missing_newline_round_trip() {
+        for text in ["[boot]\r\nsystemd = true\r\n", "[boot]\nsystemd = true"] {
+            assert_eq!(IniDocument::parse(text).to_string(), text);
+        }
+    }
+
+    #[test]
+    fn new_section_goes_at_the_end() {
+        let mut doc = IniDocument::parse("[boot]\nsystemd = true\n");
+        doc.set("user", "default", "me");
+        assert_eq!(doc.to_string(), "[boot]\nsystemd = true\n\n[user]\ndefault = me\n");
+    }
+}
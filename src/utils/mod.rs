@@ -6,4 +6,7 @@ pub mod logging;
 pub mod registry;
 pub mod system;
 pub mod wsl_config;
-pub mod theme;
\ No newline at end of file
+pub mod theme;
+pub mod hash;
+pub mod ini_doc;
+pub mod text_diff;
\ No newline at end of file
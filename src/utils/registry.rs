@@ -12,6 +12,8 @@ pub struct WslRegInfo {
     pub base_path: String,
     pub version: u32,
     pub package_family_name: String,
+    // Unset until a default user other than root was chosen
+    pub default_uid: Option<u32>,
 }
 
 pub fn get_wsl_distros_from_reg() -> Vec<WslRegInfo> {
@@ -62,6 +64,7 @@ fn get_distro_details_by_guid(parent_hkey: HKEY, guid: &str) -> Option<WslRegInf
         }
         let pfn = read_reg_string(sub_hkey, "PackageFamilyName").unwrap_or_default();
         let version = read_reg_dword(sub_hkey, "Version").unwrap_or(1);
+        let default_uid = read_reg_dword(sub_hkey, "DefaultUid");
         
         use windows::Win32::System::Registry::RegCloseKey;
         let _ = RegCloseKey(sub_hkey);
@@ -75,6 +78,7 @@ fn get_distro_details_by_guid(parent_hkey: HKEY, guid: &str) -> Option<WslRegInf
             base_path,
             version,
             package_family_name: pfn,
+            default_uid,
         })
     }
 }
@@ -136,6 +140,25 @@ pub fn get_system_locale() -> String {
     "en-US".to_string()
 }
 
+// Manual proxy from the Internet Options as (ProxyServer, ProxyOverride), None when it is off
+pub fn get_system_proxy() -> Option<(String, String)> {
+    let subkey = "Software\\Microsoft\\Windows\\CurrentVersion\\Internet Settings";
+    let mut hkey = HKEY::default();
+    unsafe {
+        if RegOpenKeyExW(HKEY_CURRENT_USER, PCWSTR(encode_wide(subkey).as_ptr()), 0, KEY_READ, &mut hkey).is_err() {
+            return None;
+        }
+        let enabled = read_reg_dword(hkey, "ProxyEnable").unwrap_or(0) == 1;
+        let server = read_reg_string(hkey, "ProxyServer");
+        let overrides = read_reg_string(hkey, "ProxyOverride").unwrap_or_default();
+        use windows::Win32::System::Registry::RegCloseKey;
+        let _ = RegCloseKey(hkey);
+        server
+            .filter(|s| enabled && !s.trim().is_empty())
+            .map(|s| (s, overrides))
+    }
+}
+
 pub fn get_system_timezone() -> String {
     use chrono::{Local, Offset};
     let now = Local::now();
@@ -172,6 +195,28 @@ pub fn write_reg_string(root: HKEY, subkey: &str, value_name: &str, value: &str)
     Ok(())
 }
 
+pub fn write_reg_dword(root: HKEY, subkey: &str, value_name: &str, value: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let subkey_wide = encode_wide(subkey);
+    let value_name_wide = encode_wide(value_name);
+
+    let mut hkey = HKEY::default();
+    unsafe {
+        use windows::Win32::System::Registry::{RegCreateKeyExW, RegSetValueExW, KEY_WRITE};
+        RegCreateKeyExW(root, PCWSTR(subkey_wide.as_ptr()), 0, None, REG_OPEN_CREATE_OPTIONS(0), KEY_WRITE, None, &mut hkey, None)
+            .ok()
+            .map_err(|e: windows::core::Error| e.to_string())?;
+
+        let res = RegSetValueExW(hkey, PCWSTR(value_name_wide.as_ptr()), 0, REG_DWORD, Some(&value.to_le_bytes()))
+            .ok()
+            .map_err(|e: windows::core::Error| e.to_string());
+
+        use windows::Win32::System::Registry::RegCloseKey;
+        let _ = RegCloseKey(hkey);
+        res?;
+    }
+    Ok(())
+}
+
 pub fn delete_reg_value(root: HKEY, subkey: &str, value_name: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
     let subkey_wide = encode_wide(subkey);
     let value_name_wide = encode_wide(value_name);
@@ -219,6 +264,61 @@ pub fn is_default_distro(name: &str) -> bool {
     false
 }
 
+// Find the Lxss subkey (GUID) of a distro by its name
+pub fn get_distro_guid(name: &str) -> Option<String> {
+    let subkey = "Software\\Microsoft\\Windows\\CurrentVersion\\Lxss";
+    let subkey_wide = encode_wide(subkey);
+    let mut hkey = HKEY::default();
+    let mut found = None;
+    unsafe {
+        if RegOpenKeyExW(HKEY_CURRENT_USER, PCWSTR(subkey_wide.as_ptr()), 0, KEY_READ, &mut hkey).is_err() {
+            return None;
+        }
+
+        let mut index = 0;
+        let mut name_buf = [0u16; 256];
+        loop {
+            let mut name_len = name_buf.len() as u32;
+            if RegEnumKeyExW(hkey, index, PWSTR(name_buf.as_mut_ptr()), &mut name_len, None, PWSTR::null(), None, None).is_err() {
+                break;
+            }
+            let guid = String::from_utf16_lossy(&name_buf[..name_len as usize]);
+            if let Some(info) = get_distro_details_by_guid(hkey, &guid) {
+                if info.name == name {
+                    found = Some(guid);
+                    break;
+                }
+            }
+            index += 1;
+        }
+
+        use windows::Win32::System::Registry::RegCloseKey;
+        let _ = RegCloseKey(hkey);
+    }
+    found
+}
+
+// Raw BasePath of a distro as stored in the registry, including any "\\?\" prefix
+pub fn get_distro_base_path(name: &str) -> Option<String> {
+    let guid = get_distro_guid(name)?;
+    let subkey = format!("Software\\Microsoft\\Windows\\CurrentVersion\\Lxss\\{}", guid);
+    read_reg_string_ext(HKEY_CURRENT_USER, &subkey, "BasePath")
+}
+
+// Point a distro's registration at a new install directory
+pub fn set_distro_base_path(name: &str, base_path: &str) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guid = get_distro_guid(name).ok_or_else(|| format!("Distro '{}' not found in registry", name))?;
+    let subkey = format!("Software\\Microsoft\\Windows\\CurrentVersion\\Lxss\\{}", guid);
+    write_reg_string(HKEY_CURRENT_USER, &subkey, "BasePath", base_path)
+}
+
+// Set the user a distro starts as, by uid
+pub fn set_distro_default_uid(name: &str, uid: u32) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let guid = get_distro_guid(name).ok_or_else(|| format!("Distro '{}' not found in registry", name))?;
+    let subkey = format!("Software\\Microsoft\\Windows\\CurrentVersion\\Lxss\\{}", guid);
+    write_reg_dword(HKEY_CURRENT_USER, &subkey, "DefaultUid", uid)
+}
+
 fn encode_wide(s: &str) -> Vec<u16> {
     s.encode_utf16().chain(std::iter::once(0)).collect()
 }
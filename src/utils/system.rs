@@ -28,6 +28,22 @@ pub fn get_c_drive_free_space() -> u64 {
     get_disk_free_space("C:\\")
 }
 
+// Physical memory of the host in bytes, 0 if it can't be queried
+pub fn get_total_memory() -> u64 {
+    use windows::Win32::System::SystemInformation::{GlobalMemoryStatusEx, MEMORYSTATUSEX};
+    let mut status = MEMORYSTATUSEX {
+        dwLength: std::mem::size_of::<MEMORYSTATUSEX>() as u32,
+        ..Default::default()
+    };
+    unsafe {
+        if GlobalMemoryStatusEx(&mut status).is_ok() {
+            status.ullTotalPhys
+        } else {
+            0
+        }
+    }
+}
+
 pub fn copy_to_clipboard(text: &str) -> Result<(), String> {
     use std::process::{Command, Stdio};
     use std::io::Write;
@@ -0,0 +1,158 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Line diff for config previews. The files are small, so a plain LCS table is enough.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiffKind {
+    Context,
+    Added,
+    Removed,
+    // Unchanged lines left out of the preview, `text` holds how many
+    Skipped,
+}
+
+impl DiffKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Context => "context",
+            Self::Added => "added",
+            Self::Removed => "removed",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffLine {
+    pub kind: DiffKind,
+    pub text: String,
+    // 1-based line number in the new text, or in the old one for removed lines
+    pub line: usize,
+}
+
+fn split(text: &str) -> Vec<&str> {
+    text.lines().collect()
+}
+
+// Every line of both texts, in order
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let a = split(old);
+    let b = split(new);
+
+    // lcs[i][j]: length of the common subsequence of a[i..] and b[j..]
+    let mut lcs = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for i in (0..a.len()).rev() {
+        for j in (0..b.len()).rev() {
+            lcs[i][j] = if a[i] == b[j] { lcs[i + 1][j + 1] + 1 } else { lcs[i + 1][j].max(lcs[i][j + 1]) };
+        }
+    }
+
+    let mut result = Vec::with_capacity(a.len().max(b.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < a.len() || j < b.len() {
+        if i < a.len() && j < b.len() && a[i] == b[j] {
+            result.push(DiffLine { kind: DiffKind::Context, text: b[j].to_string(), line: j + 1 });
+            i += 1;
+            j += 1;
+        } else if i < a.len() && (j == b.len() || lcs[i + 1][j] >= lcs[i][j + 1]) {
+            // Removals first, so a changed line reads as old then new
+            result.push(DiffLine { kind: DiffKind::Removed, text: a[i].to_string(), line: i + 1 });
+            i += 1;
+        } else {
+            result.push(DiffLine { kind: DiffKind::Added, text: b[j].to_string(), line: j + 1 });
+            j += 1;
+        }
+    }
+    result
+}
+
+// Only the changes with `context` unchanged lines around them, longer unchanged
+// runs collapse into one Skipped line. Empty when nothing changed.
+pub fn diff_with_context(old: &str, new: &str, context: usize) -> Vec<DiffLine> {
+    let lines = diff_lines(old, new);
+    let changed: Vec<usize> = lines.iter().enumerate()
+        .filter(|(_, l)| l.kind != DiffKind::Context)
+        .map(|(i, _)| i)
+        .collect();
+    if changed.is_empty() {
+        return Vec::new();
+    }
+
+    let near_change = |i: usize| changed.iter().any(|&c| c.abs_diff(i) <= context);
+    let mut result = Vec::new();
+    let mut skipped = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        if line.kind != DiffKind::Context || near_change(i) {
+            if skipped > 0 {
+                result.push(DiffLine { kind: DiffKind::Skipped, text: skipped.to_string(), line: 0 });
+                skipped = 0;
+            }
+            result.push(line);
+        } else {
+            skipped += 1;
+        }
+    }
+    if skipped > 0 {
+        result.push(DiffLine { kind: DiffKind::Skipped, text: skipped.to_string(), line: 0 });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(lines: &[DiffLine]) -> Vec<(DiffKind, &str)> {
+        lines.iter().map(|l| (l.kind, l.text.as_str())).collect()
+    }
+
+    #[test]
+    fn identical_texts_have_no_changes() {
+        let text = "[boot]\nsystemd=true\n";
+        assert!(diff_lines(text, text).iter().all(|l| l.kind == DiffKind::Context));
+        assert!(diff_with_context(text, text, 2).is_empty());
+    }
+
+    #[test]
+    fn changed_line_reads_as_removed_then_added() {
+        let lines = diff_lines("[boot]\nsystemd=false\n", "[boot]\nsystemd=true\n");
+        assert_eq!(kinds(&lines), vec![
+            (DiffKind::Context, "[boot]"),
+            (DiffKind::Removed, "systemd=false"),
+            (DiffKind::Added, "systemd=true"),
+        ]);
+        // Removed lines count in the old text, the rest in the new one
+        assert_eq!(lines[1].line, 2);
+        assert_eq!(lines[2].line, 2);
+    }
+
+    #[test]
+    fn from_and_to_empty() {
+        assert_eq!(kinds(&diff_lines("", "a\nb")), vec![(DiffKind::Added, "a"), (DiffKind::Added, "b")]);
+        assert_eq!(kinds(&diff_lines("a\nb", "")), vec![(DiffKind::Removed, "a"), (DiffKind::Removed, "b")]);
+    }
+
+    #[test]
+    fn long_unchanged_runs_collapse() {
+        let old: Vec<String> = (1..=10).map(|i| format!("line{}", i)).collect();
+        let mut new = old.clone();
+        new[4] = "changed".to_string();
+        let lines = diff_with_context(&old.join("\n"), &new.join("\n"), 1);
+        assert_eq!(kinds(&lines), vec![
+            (DiffKind::Skipped, "3"),
+            (DiffKind::Context, "line4"),
+            (DiffKind::Removed, "line5"),
+            (DiffKind::Added, "changed"),
+            (DiffKind::Context, "line6"),
+            (DiffKind::Skipped, "4"),
+        ]);
+    }
+
+    #[test]
+    fn nearby_changes_share_their_context() {
+        let lines = diff_with_context("a\nb\nc\nd\ne", "a\nB\nc\nD\ne", 1);
+        assert!(lines.iter().all(|l| l.kind != DiffKind::Skipped));
+        assert_eq!(lines.iter().filter(|l| l.kind == DiffKind::Added).count(), 2);
+    }
+}
@@ -1,86 +1,442 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use ini::Ini;
-use tracing::{debug, warn};
+// ~/.wslconfig, the settings shared by every WSL 2 distro
+//
+// Only the lines of the keys being changed are touched on save; comments, ordering
+// and keys or sections the user added by hand are kept. A change takes effect after
+// `wsl --shutdown`.
 
-// Get the WSL networking mode from ~/.wslconfig
-// Returns "nat" as default if file or setting is missing.
-pub fn get_wsl_networking_mode() -> String {
-    let home_dir = match dirs::home_dir() {
-        Some(path) => path,
-        None => {
-            warn!("Could not determine home directory, defaulting networkingMode to 'nat'");
-            return "nat".to_string();
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+use crate::i18n;
+use crate::utils::ini_doc::IniDocument;
+use crate::utils::config_history::{self, ConfigTarget, VersionOrigin};
+use crate::wsl::ops::config::WslVersionMeta;
+
+// ===== Data Models =====
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WslGlobalConfig {
+    pub wsl2: Wsl2Section,
+    pub experimental: ExperimentalSection,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Wsl2Section {
+    // Sizes keep the user's notation, e.g. `8GB`
+    pub memory: Option<String>,
+    pub processors: Option<u32>,
+    pub swap: Option<String>,
+    pub swap_file: Option<String>,
+    pub kernel: Option<String>,
+    // VHD with the modules that belong to a custom kernel
+    pub kernel_modules: Option<String>,
+    pub kernel_command_line: Option<String>,
+    pub localhost_forwarding: Option<bool>,
+    pub nested_virtualization: Option<bool>,
+    // Milliseconds
+    pub vm_idle_timeout: Option<u64>,
+    pub networking_mode: Option<String>,
+    pub dns_tunneling: Option<bool>,
+    pub firewall: Option<bool>,
+    pub auto_proxy: Option<bool>,
+    pub gui_applications: Option<bool>,
+    pub debug_console: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExperimentalSection {
+    pub auto_memory_reclaim: Option<String>,
+    pub sparse_vhd: Option<bool>,
+    pub host_address_loopback: Option<bool>,
+    pub best_effort_dns_parsing: Option<bool>,
+    // Comma separated ports, mirrored mode only
+    pub ignored_ports: Option<String>,
+    pub dns_tunneling_ip_address: Option<String>,
+    // Milliseconds
+    pub initial_auto_proxy_timeout: Option<u64>,
+}
+
+pub const NETWORKING_MODES: [&str; 4] = ["nat", "mirrored", "virtioproxy", "none"];
+pub const MEMORY_RECLAIM_MODES: [&str; 3] = ["disabled", "gradual", "dropcache"];
+
+// What the installed WSL understands, like `WslVersionMeta` for wsl.conf
+#[derive(Debug, Clone)]
+pub struct WslConfigSupport {
+    pub version_string: String,
+    // Mirrored networking, DNS tunneling, Hyper-V firewall, auto proxy, memory reclaim,
+    // sparse VHD and the other networking keys came with WSL 2.0.0
+    pub modern_supported: bool,
+    pub detection_failed: bool,
+}
+
+impl WslConfigSupport {
+    pub fn from_meta(meta: &WslVersionMeta) -> Self {
+        let major = meta.version_string.split('.').next().and_then(|p| p.parse::<u64>().ok()).unwrap_or(0);
+        Self {
+            version_string: meta.version_string.clone(),
+            modern_supported: !meta.detection_failed && major >= 2,
+            detection_failed: meta.detection_failed,
         }
-    };
-    
-    let wsl_config_path = home_dir.join(".wslconfig");
-    if !wsl_config_path.exists() {
-        debug!(".wslconfig not found at {:?}, defaulting networkingMode to 'nat'", wsl_config_path);
-        return "nat".to_string();
     }
-    
-    match Ini::load_from_file(&wsl_config_path) {
-        Ok(ini) => {
-            if let Some(section) = ini.section(Some("wsl2")) {
-                if let Some(mode) = section.get("networkingMode") {
-                    let mode_lower = mode.to_lowercase();
-                    debug!("Detected networkingMode from .wslconfig: {}", mode_lower);
-                    return mode_lower;
+
+    // Only a detected pre-2.0.0 WSL rejects the modern keys, a failed detection is unknown
+    pub fn is_legacy(&self) -> bool {
+        !self.modern_supported && !self.detection_failed
+    }
+}
+
+// One key of the file: (section, key, value, needs WSL 2.0.0)
+type Entry = (&'static str, &'static str, Option<String>, bool);
+
+fn bool_to_ini(val: bool) -> String {
+    if val { "true".to_string() } else { "false".to_string() }
+}
+
+// Windows paths are written with escaped backslashes, e.g. `kernel = C:\\wsl\\bzImage`
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\")
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("\\\\", "\\")
+}
+
+impl WslGlobalConfig {
+    pub fn load() -> Self {
+        match load_doc() {
+            Ok(doc) => Self::from_doc(&doc),
+            Err(e) => {
+                warn!("{}", e);
+                Self::default()
+            }
+        }
+    }
+
+    fn from_doc(doc: &IniDocument) -> Self {
+        let string = |section: &str, key: &str| {
+            doc.get(section, key)
+                .map(|v| unescape(v.trim()))
+                .filter(|v| !v.is_empty())
+        };
+        let boolean = |section: &str, key: &str| {
+            string(section, key).map(|v| v.eq_ignore_ascii_case("true") || v == "1" || v.eq_ignore_ascii_case("yes"))
+        };
+        let number = |section: &str, key: &str| string(section, key).and_then(|v| v.parse::<u64>().ok());
+
+        Self {
+            wsl2: Wsl2Section {
+                memory: string("wsl2", "memory"),
+                processors: string("wsl2", "processors").and_then(|v| v.parse().ok()),
+                swap: string("wsl2", "swap"),
+                swap_file: string("wsl2", "swapFile"),
+                kernel: string("wsl2", "kernel"),
+                kernel_modules: string("wsl2", "kernelModules"),
+                kernel_command_line: string("wsl2", "kernelCommandLine"),
+                localhost_forwarding: boolean("wsl2", "localhostForwarding"),
+                nested_virtualization: boolean("wsl2", "nestedVirtualization"),
+                vm_idle_timeout: number("wsl2", "vmIdleTimeout"),
+                networking_mode: string("wsl2", "networkingMode").map(|v| v.to_lowercase()),
+                dns_tunneling: boolean("wsl2", "dnsTunneling"),
+                firewall: boolean("wsl2", "firewall"),
+                auto_proxy: boolean("wsl2", "autoProxy"),
+                gui_applications: boolean("wsl2", "guiApplications"),
+                debug_console: boolean("wsl2", "debugConsole"),
+            },
+            experimental: ExperimentalSection {
+                auto_memory_reclaim: string("experimental", "autoMemoryReclaim").map(|v| v.to_lowercase()),
+                sparse_vhd: boolean("experimental", "sparseVhd"),
+                host_address_loopback: boolean("experimental", "hostAddressLoopback"),
+                best_effort_dns_parsing: boolean("experimental", "bestEffortDnsParsing"),
+                ignored_ports: string("experimental", "ignoredPorts"),
+                dns_tunneling_ip_address: string("experimental", "dnsTunnelingIpAddress"),
+                initial_auto_proxy_timeout: number("experimental", "initialAutoProxyTimeout"),
+            },
+        }
+    }
+
+    fn entries(&self) -> Vec<Entry> {
+        let w = &self.wsl2;
+        let e = &self.experimental;
+        vec![
+            ("wsl2", "memory", w.memory.clone(), false),
+            ("wsl2", "processors", w.processors.map(|v| v.to_string()), false),
+            ("wsl2", "swap", w.swap.clone(), false),
+            ("wsl2", "swapFile", w.swap_file.clone(), false),
+            ("wsl2", "kernel", w.kernel.clone(), false),
+            ("wsl2", "kernelModules", w.kernel_modules.clone(), true),
+            ("wsl2", "kernelCommandLine", w.kernel_command_line.clone(), false),
+            ("wsl2", "localhostForwarding", w.localhost_forwarding.map(bool_to_ini), false),
+            ("wsl2", "nestedVirtualization", w.nested_virtualization.map(bool_to_ini), false),
+            ("wsl2", "vmIdleTimeout", w.vm_idle_timeout.map(|v| v.to_string()), false),
+            ("wsl2", "networkingMode", w.networking_mode.clone(), false),
+            ("wsl2", "dnsTunneling", w.dns_tunneling.map(bool_to_ini), true),
+            ("wsl2", "firewall", w.firewall.map(bool_to_ini), true),
+            ("wsl2", "autoProxy", w.auto_proxy.map(bool_to_ini), true),
+            ("wsl2", "guiApplications", w.gui_applications.map(bool_to_ini), false),
+            ("wsl2", "debugConsole", w.debug_console.map(bool_to_ini), false),
+            ("experimental", "autoMemoryReclaim", e.auto_memory_reclaim.clone(), true),
+            ("experimental", "sparseVhd", e.sparse_vhd.map(bool_to_ini), true),
+            ("experimental", "hostAddressLoopback", e.host_address_loopback.map(bool_to_ini), true),
+            ("experimental", "bestEffortDnsParsing", e.best_effort_dns_parsing.map(bool_to_ini), true),
+            ("experimental", "ignoredPorts", e.ignored_ports.clone(), true),
+            ("experimental", "dnsTunnelingIpAddress", e.dns_tunneling_ip_address.clone(), true),
+            ("experimental", "initialAutoProxyTimeout", e.initial_auto_proxy_timeout.map(|v| v.to_string()), true),
+        ]
+    }
+
+    // Problems that would make WSL ignore a key or fail to start, as display messages
+    pub fn validate(&self, support: &WslConfigSupport) -> Vec<String> {
+        let mut errors = Vec::new();
+        let w = &self.wsl2;
+        let e = &self.experimental;
+
+        let total_memory = crate::utils::system::get_total_memory();
+        if let Some(ref memory) = w.memory {
+            match parse_size(memory) {
+                Some(bytes) if bytes > 0 && (total_memory == 0 || bytes <= total_memory) => {}
+                Some(bytes) if bytes > 0 => errors.push(i18n::tr("wslconfig.memory_too_large", &[
+                    memory.clone(),
+                    crate::wsl::ops::preflight::format_bytes(total_memory),
+                ])),
+                _ => errors.push(i18n::tr("wslconfig.invalid_size", &["memory".to_string(), memory.clone()])),
+            }
+        }
+        if let Some(ref swap) = w.swap {
+            if parse_size(swap).is_none() {
+                errors.push(i18n::tr("wslconfig.invalid_size", &["swap".to_string(), swap.clone()]));
+            }
+        }
+        if let Some(processors) = w.processors {
+            let available = std::thread::available_parallelism().map(|n| n.get() as u32).unwrap_or(u32::MAX);
+            if processors == 0 || processors > available {
+                errors.push(i18n::tr("wslconfig.invalid_processors", &[processors.to_string(), available.to_string()]));
+            }
+        }
+        if let Some(ref kernel) = w.kernel {
+            if !Path::new(kernel).is_file() {
+                errors.push(i18n::tr("wslconfig.file_not_found", &["kernel".to_string(), kernel.clone()]));
+            }
+        }
+        if let Some(ref modules) = w.kernel_modules {
+            if !Path::new(modules).is_file() {
+                errors.push(i18n::tr("wslconfig.file_not_found", &["kernelModules".to_string(), modules.clone()]));
+            } else if w.kernel.is_none() {
+                errors.push(i18n::t("wslconfig.modules_without_kernel"));
+            }
+        }
+        if let Some(ref swap_file) = w.swap_file {
+            if !Path::new(swap_file).is_absolute() {
+                errors.push(i18n::tr("wslconfig.path_not_absolute", &["swapFile".to_string(), swap_file.clone()]));
+            }
+        }
+        if let Some(ref mode) = w.networking_mode {
+            if !NETWORKING_MODES.contains(&mode.as_str()) {
+                errors.push(i18n::tr("wslconfig.invalid_choice", &["networkingMode".to_string(), mode.clone()]));
+            } else if mode != "nat" && support.is_legacy() {
+                errors.push(i18n::tr("wslconfig.requires_modern", &[format!("networkingMode={}", mode)]));
+            }
+        }
+        if support.is_legacy() {
+            // Keys already in the file are left to the user, only new values are refused
+            let current = Self::load();
+            for ((_, key, value, modern), (_, _, old, _)) in self.entries().into_iter().zip(current.entries()) {
+                if let Some(v) = value.filter(|v| modern && old.as_ref() != Some(v)) {
+                    errors.push(i18n::tr("wslconfig.requires_modern", &[format!("{}={}", key, v)]));
+                }
+            }
+        }
+        if let Some(ref reclaim) = e.auto_memory_reclaim {
+            if !MEMORY_RECLAIM_MODES.contains(&reclaim.as_str()) {
+                errors.push(i18n::tr("wslconfig.invalid_choice", &["autoMemoryReclaim".to_string(), reclaim.clone()]));
+            }
+        }
+        if let Some(ref ports) = e.ignored_ports {
+            let valid = ports.split(',').all(|p| p.trim().parse::<u16>().is_ok_and(|p| p > 0));
+            if !valid {
+                errors.push(i18n::tr("wslconfig.invalid_ports", &[ports.clone()]));
+            }
+        }
+        if let Some(ref address) = e.dns_tunneling_ip_address {
+            if address.parse::<Ipv4Addr>().is_err() {
+                errors.push(i18n::tr("wslconfig.invalid_ip", &[address.clone()]));
+            }
+        }
+        errors
+    }
+
+    // Write the keys that differ from the file, leaving everything else as it is.
+    // Modern keys are refused by `validate` on a known legacy WSL.
+    pub fn save(&self, support: &WslConfigSupport) -> Result<(), String> {
+        let path = wsl_config_path().ok_or("Could not determine home directory")?;
+        let mut doc = load_doc()?;
+        let current = Self::from_doc(&doc);
+
+        let mut changed = 0;
+        for ((section, key, value, modern), (_, _, old, _)) in self.entries().into_iter().zip(current.entries()) {
+            if value == old {
+                continue;
+            }
+            match value {
+                Some(v) => {
+                    if modern && support.detection_failed {
+                        warn!(".wslconfig: writing [{}] {} although the WSL version is unknown", section, key);
+                    }
+                    doc.set(section, key, &escape(&v));
+                }
+                None => {
+                    doc.remove(section, key);
                 }
             }
-            debug!("networkingMode not found in [wsl2] section of .wslconfig, defaulting to 'nat'");
-            "nat".to_string()
+            changed += 1;
         }
-        Err(e) => {
-            warn!("Failed to parse .wslconfig at {:?}: {}, defaulting networkingMode to 'nat'", wsl_config_path, e);
-            "nat".to_string()
+
+        if changed == 0 {
+            debug!(".wslconfig unchanged");
+            return Ok(());
         }
+        write_doc(&doc, VersionOrigin::Dashboard)?;
+        info!("Updated {} key(s) in {:?}", changed, path);
+        Ok(())
     }
 }
 
-// Check if sparseVhd is enabled in ~/.wslconfig
-pub fn get_sparse_vhd() -> bool {
-    let home_dir = match dirs::home_dir() {
-        Some(path) => path,
-        None => return false,
+// Size as WSL reads it: a number with an optional B/KB/MB/GB/TB unit
+pub fn parse_size(value: &str) -> Option<u64> {
+    let value = value.trim().to_uppercase();
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+    let multiplier: u64 = match unit.trim() {
+        "" | "B" => 1,
+        "K" | "KB" => 1 << 10,
+        "M" | "MB" => 1 << 20,
+        "G" | "GB" => 1 << 30,
+        "T" | "TB" => 1 << 40,
+        _ => return None,
     };
-    
-    let wsl_config_path = home_dir.join(".wslconfig");
-    if !wsl_config_path.exists() {
-        return false;
+    Some((number * multiplier as f64) as u64)
+}
+
+fn wsl_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".wslconfig"))
+}
+
+// An empty document when there is no file yet. A file that can't be read is an
+// error, so it is never overwritten with only the keys we know.
+fn load_doc() -> Result<IniDocument, String> {
+    Ok(IniDocument::parse(&read_wsl_config()?.unwrap_or_default()))
+}
+
+// Every write of ~/.wslconfig goes through here, so each version lands in the config history
+fn write_doc(doc: &IniDocument, origin: VersionOrigin) -> Result<(), String> {
+    let path = wsl_config_path().ok_or("Could not determine home directory")?;
+    let before = read_wsl_config()?.unwrap_or_default();
+    let after = doc.to_string();
+    std::fs::write(&path, &after).map_err(|e| format!("Failed to write .wslconfig: {}", e))?;
+    if after != before {
+        config_history::record(&ConfigTarget::WslConfig, &before, &after, origin);
     }
-    
-    match Ini::load_from_file(&wsl_config_path) {
-        Ok(ini) => {
-            if let Some(section) = ini.section(Some("experimental")) {
-                if let Some(val) = section.get("sparseVhd") {
-                    return val.to_lowercase() == "true";
-                }
+    Ok(())
+}
+
+// The kernel keys of [wsl2] as (kernel, kernelModules, kernelCommandLine)
+pub type KernelKeys = (Option<String>, Option<String>, Option<String>);
+
+pub fn get_kernel() -> KernelKeys {
+    let w = WslGlobalConfig::load().wsl2;
+    (w.kernel, w.kernel_modules, w.kernel_command_line)
+}
+
+// Whether .wslconfig already holds `keys`, a None command line matches anything
+pub fn kernel_matches(keys: &KernelKeys) -> bool {
+    let (kernel, modules, command_line) = get_kernel();
+    let command_line_matches = match &keys.2 {
+        None => true,
+        Some(v) if v.is_empty() => command_line.is_none(),
+        Some(v) => command_line.as_ref() == Some(v),
+    };
+    (&kernel, &modules) == (&keys.0, &keys.1) && command_line_matches
+}
+
+// Switch the kernel WSL boots, kernel and kernelModules are removed for the stock kernel.
+// kernelCommandLine is left alone when None and removed when empty, so arguments the user
+// set by hand survive switching to a kernel that brings none.
+// Image files are checked first so WSL is never left pointing at a missing file.
+pub fn set_kernel(keys: &KernelKeys) -> Result<(), String> {
+    let (kernel, modules, command_line) = keys;
+    for (key, path) in [("kernel", kernel), ("kernelModules", modules)] {
+        if let Some(path) = path {
+            if !Path::new(path).is_file() {
+                return Err(i18n::tr("wslconfig.file_not_found", &[key.to_string(), path.clone()]));
             }
-            false
         }
-        Err(_) => false
     }
+    if kernel_matches(keys) {
+        debug!(".wslconfig kernel unchanged");
+        return Ok(());
+    }
+
+    let mut doc = load_doc()?;
+    for (key, value) in [("kernel", kernel), ("kernelModules", modules)] {
+        match value {
+            Some(v) => doc.set("wsl2", key, &escape(v)),
+            None => {
+                doc.remove("wsl2", key);
+            }
+        }
+    }
+    match command_line {
+        Some(v) if v.is_empty() => {
+            doc.remove("wsl2", "kernelCommandLine");
+        }
+        Some(v) => doc.set("wsl2", "kernelCommandLine", &escape(v)),
+        None => {}
+    }
+    write_doc(&doc, VersionOrigin::Dashboard)?;
+    info!("Kernel in .wslconfig set to {}", kernel.as_deref().unwrap_or("the built-in kernel"));
+    Ok(())
+}
+
+// Put back an earlier version of ~/.wslconfig as it was, byte for byte
+pub fn restore_wsl_config(content: &str) -> Result<(), String> {
+    let path = wsl_config_path().ok_or("Could not determine home directory")?;
+    let before = read_wsl_config()?.unwrap_or_default();
+    std::fs::write(&path, content).map_err(|e| format!("Failed to write .wslconfig: {}", e))?;
+    config_history::record(&ConfigTarget::WslConfig, &before, content, VersionOrigin::Rollback);
+    info!("Restored {:?} from history", path);
+    Ok(())
+}
+
+// Raw ~/.wslconfig, None when there is no file
+pub fn read_wsl_config() -> Result<Option<String>, String> {
+    let path = wsl_config_path().ok_or("Could not determine home directory")?;
+    if !path.exists() {
+        return Ok(None);
+    }
+    std::fs::read_to_string(&path)
+        .map(Some)
+        .map_err(|e| format!("Failed to read .wslconfig at {:?}: {}", path, e))
+}
+
+// Get the WSL networking mode from ~/.wslconfig
+// Returns "nat" as default if file or setting is missing.
+pub fn get_wsl_networking_mode() -> String {
+    let mode = WslGlobalConfig::load().wsl2.networking_mode.unwrap_or_else(|| "nat".to_string());
+    debug!("networkingMode from .wslconfig: {}", mode);
+    mode
+}
+
+// Check if sparseVhd is enabled in ~/.wslconfig
+pub fn get_sparse_vhd() -> bool {
+    WslGlobalConfig::load().experimental.sparse_vhd.unwrap_or(false)
 }
 
 // Set sparseVhd in ~/.wslconfig
 pub fn set_sparse_vhd(enable: bool) -> Result<(), String> {
-    let home_dir = dirs::home_dir().ok_or("Could not determine home directory")?;
-    let wsl_config_path = home_dir.join(".wslconfig");
-    
-    let mut ini = if wsl_config_path.exists() {
-        Ini::load_from_file(&wsl_config_path).unwrap_or_else(|_| Ini::new())
-    } else {
-        Ini::new()
-    };
-    
-    if enable {
-        ini.with_section(Some("experimental")).set("sparseVhd", "true");
-    } else {
-        ini.with_section(Some("experimental")).set("sparseVhd", "false");
-    }
-    
-    ini.write_to_file(&wsl_config_path).map_err(|e| format!("Failed to write .wslconfig: {}", e))
+    let mut doc = load_doc()?;
+    doc.set("experimental", "sparseVhd", &bool_to_ini(enable));
+    write_doc(&doc, VersionOrigin::Dashboard)
 }
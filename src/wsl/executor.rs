@@ -10,6 +10,24 @@ use crate::wsl::models::WslCommandResult;
 
 use crate::wsl::decoder::{decode_output, WslOutputDecoder};
 
+// Distro targeted by an unregister, move or version conversion command, if it is protected
+fn protected_target(args: &[String]) -> Option<&str> {
+    let lower: Vec<String> = args.iter().map(|a| a.to_lowercase()).collect();
+    for (i, arg) in lower.iter().enumerate() {
+        let guarded = match arg.as_str() {
+            "--unregister" | "--set-version" => true,
+            "--manage" => lower.iter().any(|a| a == "--move"),
+            _ => false,
+        };
+        if guarded {
+            return args.get(i + 1)
+                .map(|s| s.as_str())
+                .filter(|name| crate::config::ConfigManager::is_protected(name));
+        }
+    }
+    None
+}
+
 // WSL command executor, responsible for executing various WSL commands
 #[derive(Clone)]
 pub struct WslCommandExecutor {
@@ -45,6 +63,12 @@ impl WslCommandExecutor {
         // Convert args to owned string vector for use in closure
         let args_owned: Vec<String> = args.iter().map(|&s| s.to_string()).collect();
         let command_str = format!("wsl {}", args_owned.join(" "));
+
+        // Last line of defence for protected distros, whatever code path issued the command
+        if let Some(name) = protected_target(&args_owned) {
+            warn!("Refusing '{}': distribution '{}' is protected", command_str, name);
+            return WslCommandResult::error(String::new(), crate::i18n::tr("protect.blocked", &[name.to_string()]));
+        }
         
         // Identify if the command is a write operation (state changing)
         let write_ops = [
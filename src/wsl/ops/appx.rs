@@ -0,0 +1,138 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Store packages (.appx / .msix and their bundles) carry the distro rootfs as
+// install.tar.gz. Unpacking relies on the tar.exe shipped with Windows, which reads
+// zip archives, so packages install offline without the Store.
+
+use std::io::Read;
+use std::os::windows::process::CommandExt;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::info;
+
+const CREATE_NO_WINDOW: u32 = 0x08000000;
+const ROOTFS_NAMES: [&str; 3] = ["install.tar.gz", "install.tar.xz", "install.tar"];
+pub const PACKAGE_EXTENSIONS: [&str; 4] = ["appx", "appxbundle", "msix", "msixbundle"];
+
+// Zip-based package rather than a tarball
+pub fn is_package(path: &Path) -> bool {
+    let mut header = [0u8; 2];
+    std::fs::File::open(path)
+        .and_then(|mut f| f.read_exact(&mut header))
+        .map(|_| header == [0x50, 0x4B])
+        .unwrap_or(false)
+}
+
+fn unzip(archive: &Path, dest: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(dest).map_err(|e| e.to_string())?;
+    let output = Command::new("tar")
+        .arg("-xf")
+        .arg(archive)
+        .arg("-C")
+        .arg(dest)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| format!("Failed to execute tar: {}", e))?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(format!("Failed to unpack '{}': {}", archive.display(), String::from_utf8_lossy(&output.stderr).trim()))
+    }
+}
+
+fn find_rootfs(dir: &Path) -> Option<PathBuf> {
+    ROOTFS_NAMES.iter().map(|n| dir.join(n)).find(|p| p.is_file())
+}
+
+// Architecture name as used in Appx manifests
+fn machine_arch() -> &'static str {
+    if std::env::consts::ARCH == "aarch64" { "arm64" } else { "x64" }
+}
+
+// Value of `name="..."` inside a single XML element
+fn attribute<'a>(element: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!(" {}=\"", name);
+    let start = element.find(&needle)? + needle.len();
+    let end = element[start..].find('"')?;
+    Some(&element[start..start + end])
+}
+
+// Refuse a package built for another architecture, `wsl --import` would accept
+// the rootfs but the distro could not start
+fn check_package_arch(dir: &Path) -> Result<(), String> {
+    let Ok(manifest) = std::fs::read_to_string(dir.join("AppxManifest.xml")) else { return Ok(()); };
+    let arch = manifest.find("<Identity")
+        .map(|i| &manifest[i..])
+        .and_then(|rest| rest.find('>').map(|end| &rest[..end]))
+        .and_then(|identity| attribute(identity, "ProcessorArchitecture"))
+        .unwrap_or("neutral");
+    if arch.eq_ignore_ascii_case("neutral") || arch.eq_ignore_ascii_case(machine_arch()) {
+        Ok(())
+    } else {
+        Err(format!("The package is built for {}, this machine needs {}", arch, machine_arch()))
+    }
+}
+
+// Bundles list their packages with type and architecture in AppxBundleManifest.xml
+fn pick_from_bundle_manifest(dir: &Path) -> Option<PathBuf> {
+    let manifest = std::fs::read_to_string(dir.join("AppxMetadata").join("AppxBundleManifest.xml")).ok()?;
+    manifest.split("<Package ").skip(1).find_map(|rest| {
+        let element = format!(" {}", &rest[..rest.find('>')?]);
+        let is_app = attribute(&element, "Type").is_none_or(|t| t.eq_ignore_ascii_case("application"));
+        let arch_ok = attribute(&element, "Architecture").is_some_and(|a| a.eq_ignore_ascii_case(machine_arch()));
+        if !is_app || !arch_ok {
+            return None;
+        }
+        attribute(&element, "FileName").map(|f| dir.join(f)).filter(|p| p.is_file())
+    })
+}
+
+// Pick the architecture package of a bundle, skipping language and scale resource packages
+fn pick_arch_package(dir: &Path) -> Option<PathBuf> {
+    if let Some(package) = pick_from_bundle_manifest(dir) {
+        return Some(package);
+    }
+    let arch_tag = format!("_{}", machine_arch());
+    let packages: Vec<PathBuf> = std::fs::read_dir(dir).ok()?
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| p.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("appx") || ext.eq_ignore_ascii_case("msix")))
+        .collect();
+    packages.iter()
+        .find(|p| p.file_name().is_some_and(|n| n.to_string_lossy().to_lowercase().contains(&arch_tag)))
+        .or_else(|| packages.iter().find(|p| {
+            let name = p.file_name().map(|n| n.to_string_lossy().to_lowercase()).unwrap_or_default();
+            !name.contains("_language-") && !name.contains("_scale-")
+        }))
+        .cloned()
+}
+
+// Unpack the rootfs tarball of an appx or appxbundle into `work_dir` and return its path
+pub fn extract_rootfs(package: &Path, work_dir: &Path) -> Result<PathBuf, String> {
+    unzip(package, work_dir)?;
+    if let Some(rootfs) = find_rootfs(work_dir) {
+        check_package_arch(work_dir)?;
+        return Ok(rootfs);
+    }
+
+    let inner = pick_arch_package(work_dir)
+        .ok_or_else(|| format!("No installable package for this architecture in '{}'", package.display()))?;
+    info!("Unpacking '{}' from bundle '{}'", inner.display(), package.display());
+    let inner_dir = work_dir.join("package");
+    unzip(&inner, &inner_dir)?;
+    check_package_arch(&inner_dir)?;
+    find_rootfs(&inner_dir).ok_or_else(|| format!("No install.tar.gz found in '{}'", package.display()))
+}
+
+// Instance name suggested by a package file name:
+// "CanonicalGroupLimited.Ubuntu24.04LTS_2404.0.5.0_x64.appx" -> "Ubuntu24.04LTS"
+pub fn suggested_name(path: &Path) -> Option<String> {
+    let stem = path.file_stem()?.to_string_lossy().to_string();
+    let identity = stem.split('_').next().unwrap_or(&stem);
+    let name = match identity.split_once('.') {
+        Some((_publisher, name)) if !name.is_empty() => name,
+        _ => identity,
+    };
+    Some(name.to_string())
+}
@@ -0,0 +1,791 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// cloud-init user-data for new distros
+//
+// A `#cloud-config` document is parsed and validated here, then applied once after
+// install by running each supported module as root through `wsl -e sh -c`, in
+// cloud-init's order: write_files, users, locale, timezone, packages, deferred
+// write_files, runcmd. Keys outside that subset are reported back as ignored.
+
+use std::io::Read;
+use base64::Engine;
+use serde::{Deserialize, Deserializer};
+use tracing::{info, warn};
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::models::WslCommandResult;
+use super::identity::is_valid_username;
+
+// Top level keys of the cloud-config subset we apply, everything else is reported as ignored
+const SUPPORTED_KEYS: &[&str] = &[
+    "users", "packages", "package_update", "package_upgrade",
+    "write_files", "runcmd", "locale", "timezone",
+];
+
+// Same file name as cloud-init, so sudo rules stay in one place if cloud-init runs later
+const SUDOERS_FILE: &str = "/etc/sudoers.d/90-cloud-init-users";
+
+// Base64 characters per command, Windows caps a command line at 32767 characters
+const WRITE_CHUNK: usize = 16 * 1024;
+
+// Subset of a cloud-init `#cloud-config` document, applied once after install
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct UserData {
+    pub users: Vec<UserEntry>,
+    pub packages: Vec<String>,
+    pub package_update: bool,
+    pub package_upgrade: bool,
+    pub write_files: Vec<WriteFile>,
+    pub runcmd: Vec<RunCmd>,
+    pub locale: Option<String>,
+    pub timezone: Option<String>,
+    #[serde(skip)]
+    pub ignored_keys: Vec<String>,
+}
+
+// `- default` refers to the image's cloud user, which WSL images don't have
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum UserEntry {
+    Name(String),
+    User(User),
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct User {
+    pub name: String,
+    pub gecos: Option<String>,
+    pub shell: Option<String>,
+    pub groups: Option<StringList>,
+    pub sudo: Option<Sudo>,
+    // Hashed password as accepted by `chpasswd -e`
+    pub passwd: Option<String>,
+    pub plain_text_passwd: Option<String>,
+    // Locked unless set to false, as in cloud-init
+    #[serde(default = "lock_passwd_default")]
+    pub lock_passwd: bool,
+    pub ssh_authorized_keys: Vec<String>,
+}
+
+fn lock_passwd_default() -> bool {
+    true
+}
+
+// cloud-init accepts both "a, b" and a YAML list
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum StringList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl StringList {
+    pub fn items(&self) -> Vec<String> {
+        match self {
+            StringList::One(s) => s.split(',').map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect(),
+            StringList::Many(v) => v.iter().map(|p| p.trim().to_string()).filter(|p| !p.is_empty()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum Sudo {
+    Enabled(bool),
+    Rule(String),
+    Rules(Vec<String>),
+}
+
+impl Sudo {
+    fn rules(&self) -> Vec<String> {
+        match self {
+            Sudo::Enabled(true) => vec!["ALL=(ALL) NOPASSWD:ALL".to_string()],
+            Sudo::Enabled(false) => Vec::new(),
+            Sudo::Rule(r) => vec![r.clone()],
+            Sudo::Rules(r) => r.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct WriteFile {
+    pub path: String,
+    pub content: String,
+    pub encoding: Option<String>,
+    pub owner: Option<String>,
+    // `'0644'` and an unquoted `0644` stay as written, a YAML number such as `0o644`
+    // is the mode value itself and is turned back into octal digits
+    #[serde(deserialize_with = "permissions_string")]
+    pub permissions: Option<String>,
+    pub append: bool,
+    // Written after users and packages, e.g. for files owned by a new user
+    pub defer: bool,
+}
+
+// A shell line, or an argument list that is quoted as-is
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum RunCmd {
+    Shell(String),
+    Args(Vec<String>),
+}
+
+fn permissions_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let value = Option::<serde_yaml_ng::Value>::deserialize(deserializer)?;
+    Ok(match value {
+        Some(serde_yaml_ng::Value::String(s)) => Some(s),
+        Some(serde_yaml_ng::Value::Number(n)) => Some(match n.as_u64() {
+            Some(mode) => format!("{:o}", mode),
+            None => n.to_string(),
+        }),
+        _ => None,
+    })
+}
+
+// Outcome of one cloud-init module
+#[derive(Debug, Clone)]
+pub struct ModuleResult {
+    pub module: &'static str,
+    pub success: bool,
+    pub message: String,
+    // Tail of the command output worth showing to the user
+    pub output: String,
+}
+
+impl ModuleResult {
+    fn ok(module: &'static str, message: String) -> Self {
+        Self { module, success: true, message, output: String::new() }
+    }
+
+    fn failed(module: &'static str, message: String, output: String) -> Self {
+        Self { module, success: false, message, output }
+    }
+}
+
+impl UserData {
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+            && self.packages.is_empty()
+            && !self.package_update
+            && !self.package_upgrade
+            && self.write_files.is_empty()
+            && self.runcmd.is_empty()
+            && self.locale.is_none()
+            && self.timezone.is_none()
+    }
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+fn is_single_line(value: &str) -> bool {
+    !value.contains('\n') && !value.contains('\r') && !value.contains('\0')
+}
+
+fn is_valid_package(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('-')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || "._+-:=~*".contains(c))
+}
+
+fn is_valid_locale(locale: &str) -> bool {
+    !locale.is_empty() && locale.chars().all(|c| c.is_ascii_alphanumeric() || "._@-".contains(c))
+}
+
+fn is_valid_timezone(tz: &str) -> bool {
+    !tz.is_empty()
+        && !tz.starts_with('/')
+        && !tz.split('/').any(|p| p.is_empty() || p == "." || p == "..")
+        && tz.chars().all(|c| c.is_ascii_alphanumeric() || "_/+-".contains(c))
+}
+
+fn is_valid_owner(owner: &str) -> bool {
+    let mut parts = owner.splitn(2, ':');
+    let user_ok = parts.next().is_some_and(is_valid_username);
+    user_ok && parts.next().is_none_or(|g| g.is_empty() || is_valid_username(g))
+}
+
+fn is_valid_permissions(mode: &str) -> bool {
+    let digits = mode.strip_prefix("0o").unwrap_or(mode);
+    !digits.is_empty() && digits.len() <= 4 && digits.chars().all(|c| ('0'..='7').contains(&c))
+}
+
+fn user_name(entry: &UserEntry) -> &str {
+    match entry {
+        UserEntry::Name(n) => n,
+        UserEntry::User(u) => &u.name,
+    }
+}
+
+// The login user: the first listed user this run created. A `default` entry listed
+// before it keeps the image's login user, and users that already existed never take over.
+fn login_user(users: &[UserEntry], created: &[String]) -> Option<String> {
+    for entry in users {
+        let name = user_name(entry);
+        if name == "default" {
+            return None;
+        }
+        if created.iter().any(|c| c == name) {
+            return Some(name.to_string());
+        }
+    }
+    None
+}
+
+// Parse and validate a user-data document. Only `#cloud-config` YAML is supported,
+// shell script user-data and MIME multipart are rejected.
+pub fn parse(text: &str) -> Result<UserData, String> {
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with("#!") {
+        return Err("shell script user-data is not supported, use #cloud-config".into());
+    }
+    if trimmed.starts_with("Content-Type:") || trimmed.starts_with("MIME-Version:") {
+        return Err("multipart user-data is not supported, use #cloud-config".into());
+    }
+
+    let value: serde_yaml_ng::Value = serde_yaml_ng::from_str(trimmed).map_err(|e| e.to_string())?;
+    let mapping = match &value {
+        serde_yaml_ng::Value::Mapping(m) => m,
+        serde_yaml_ng::Value::Null => return Ok(UserData::default()),
+        _ => return Err("user-data must be a YAML mapping".into()),
+    };
+    let ignored_keys: Vec<String> = mapping.keys()
+        .filter_map(|k| k.as_str())
+        .filter(|k| !SUPPORTED_KEYS.contains(k))
+        .map(|k| k.to_string())
+        .collect();
+
+    let mut data: UserData = serde_yaml_ng::from_value(value).map_err(|e| e.to_string())?;
+    data.ignored_keys = ignored_keys;
+    validate(&data)?;
+    Ok(data)
+}
+
+fn validate(data: &UserData) -> Result<(), String> {
+    for entry in &data.users {
+        let name = user_name(entry);
+        if name == "default" {
+            continue;
+        }
+        if !is_valid_username(name) {
+            return Err(format!("users: invalid user name '{}'", name));
+        }
+        let UserEntry::User(user) = entry else { continue };
+        if let Some(shell) = &user.shell {
+            if !shell.starts_with('/') || !is_single_line(shell) {
+                return Err(format!("users: invalid shell '{}' for '{}'", shell, name));
+            }
+        }
+        if let Some(gecos) = &user.gecos {
+            if !is_single_line(gecos) || gecos.contains(':') {
+                return Err(format!("users: invalid gecos for '{}'", name));
+            }
+        }
+        for group in user.groups.as_ref().map(|g| g.items()).unwrap_or_default() {
+            if !is_valid_username(&group) {
+                return Err(format!("users: invalid group '{}' for '{}'", group, name));
+            }
+        }
+        for rule in user.sudo.as_ref().map(|s| s.rules()).unwrap_or_default() {
+            if !is_single_line(&rule) {
+                return Err(format!("users: invalid sudo rule for '{}'", name));
+            }
+        }
+        let passwords = [&user.passwd, &user.plain_text_passwd];
+        if passwords.iter().any(|p| p.as_deref().is_some_and(|p| !is_single_line(p))) {
+            return Err(format!("users: invalid password for '{}'", name));
+        }
+        if user.ssh_authorized_keys.iter().any(|k| !is_single_line(k)) {
+            return Err(format!("users: SSH keys for '{}' must be one per line", name));
+        }
+    }
+
+    if let Some(pkg) = data.packages.iter().find(|p| !is_valid_package(p)) {
+        return Err(format!("packages: invalid package name '{}'", pkg));
+    }
+
+    for file in &data.write_files {
+        if !file.path.starts_with('/') || !is_single_line(&file.path) {
+            return Err(format!("write_files: path must be absolute: '{}'", file.path));
+        }
+        if let Some(owner) = &file.owner {
+            if !is_valid_owner(owner) {
+                return Err(format!("write_files: invalid owner '{}' for {}", owner, file.path));
+            }
+        }
+        if let Some(mode) = &file.permissions {
+            if !is_valid_permissions(mode) {
+                return Err(format!("write_files: invalid permissions '{}' for {}", mode, file.path));
+            }
+        }
+        decode_content(file).map_err(|e| format!("write_files: {}: {}", file.path, e))?;
+    }
+
+    if let Some(locale) = &data.locale {
+        if !is_valid_locale(locale) {
+            return Err(format!("locale: invalid locale '{}'", locale));
+        }
+    }
+    if let Some(tz) = &data.timezone {
+        if !is_valid_timezone(tz) {
+            return Err(format!("timezone: invalid time zone '{}'", tz));
+        }
+    }
+    Ok(())
+}
+
+fn decode_content(file: &WriteFile) -> Result<Vec<u8>, String> {
+    let encoding = file.encoding.as_deref().unwrap_or("text").to_ascii_lowercase();
+    let b64 = || base64::engine::general_purpose::STANDARD
+        .decode(file.content.split_whitespace().collect::<String>())
+        .map_err(|e| format!("invalid base64: {}", e));
+    let gunzip = |data: Vec<u8>| {
+        let mut out = Vec::new();
+        flate2::read::GzDecoder::new(&data[..]).read_to_end(&mut out)
+            .map(|_| out)
+            .map_err(|e| format!("invalid gzip data: {}", e))
+    };
+    match encoding.as_str() {
+        "text" | "text/plain" => Ok(file.content.clone().into_bytes()),
+        "b64" | "base64" => b64(),
+        "gz+b64" | "gz+base64" | "gzip+b64" | "gzip+base64" => gunzip(b64()?),
+        // A YAML string can't carry raw gzip bytes
+        other => Err(format!("unsupported encoding '{}'", other)),
+    }
+}
+
+// Last few lines of a failed command, enough to tell what went wrong
+fn failure_output(res: &WslCommandResult<String>) -> String {
+    let lines: Vec<&str> = res.output.lines().filter(|l| !l.trim().is_empty()).collect();
+    lines[lines.len().saturating_sub(10)..].join("\n")
+}
+
+fn failure_message(res: &WslCommandResult<String>) -> String {
+    res.error.as_deref().map(str::trim).filter(|e| !e.is_empty()).unwrap_or("command failed").to_string()
+}
+
+async fn run_as_root(executor: &WslCommandExecutor, distro_name: &str, script: &str) -> WslCommandResult<String> {
+    executor.execute_command(&["-d", distro_name, "-u", "root", "-e", "sh", "-c", script]).await
+}
+
+fn user_script(name: &str, user: Option<&User>) -> String {
+    let q = shell_quote(name);
+    let mut script = String::from("set -e\n");
+
+    let shell = user.and_then(|u| u.shell.as_deref());
+    let gecos = user.and_then(|u| u.gecos.as_deref());
+    let useradd_opts = format!(
+        "{}{}",
+        shell.map(|s| format!(" -s {}", shell_quote(s))).unwrap_or_default(),
+        gecos.map(|g| format!(" -c {}", shell_quote(g))).unwrap_or_default(),
+    );
+    let adduser_opts = format!(
+        "{}{}",
+        shell.map(|s| format!(" -s {}", shell_quote(s))).unwrap_or_default(),
+        gecos.map(|g| format!(" -g {}", shell_quote(g))).unwrap_or_default(),
+    );
+    script.push_str(&format!(
+        "if ! id -u {q} >/dev/null 2>&1; then \
+         if command -v useradd >/dev/null 2>&1; then useradd -m{useradd_opts} {q}; \
+         else adduser -D{adduser_opts} {q}; fi; fi\n"
+    ));
+
+    let Some(user) = user else { return script };
+
+    for group in user.groups.as_ref().map(|g| g.items()).unwrap_or_default() {
+        let g = shell_quote(&group);
+        script.push_str(&format!(
+            "getent group {g} >/dev/null 2>&1 || groupadd {g} 2>/dev/null || addgroup {g}\n\
+             usermod -aG {g} {q} 2>/dev/null || addgroup {q} {g}\n"
+        ));
+    }
+
+    if let Some(hash) = &user.passwd {
+        script.push_str(&format!("printf '%s\\n' {} | chpasswd -e\n", shell_quote(&format!("{}:{}", name, hash))));
+    } else if let Some(password) = &user.plain_text_passwd {
+        script.push_str(&format!("printf '%s\\n' {} | chpasswd\n", shell_quote(&format!("{}:{}", name, password))));
+    }
+    if user.lock_passwd {
+        script.push_str(&format!("passwd -l {q}\n"));
+    }
+
+    let rules = user.sudo.as_ref().map(|s| s.rules()).unwrap_or_default();
+    if !rules.is_empty() {
+        let lines: Vec<String> = rules.iter().map(|r| shell_quote(&format!("{} {}", name, r))).collect();
+        script.push_str(&format!(
+            "mkdir -p /etc/sudoers.d\nprintf '%s\\n' {} >> {SUDOERS_FILE}\nchmod 440 {SUDOERS_FILE}\n",
+            lines.join(" ")
+        ));
+    }
+
+    if !user.ssh_authorized_keys.is_empty() {
+        let keys: Vec<String> = user.ssh_authorized_keys.iter().map(|k| shell_quote(k.trim())).collect();
+        script.push_str(&format!(
+            "home=$(getent passwd {q} | cut -d: -f6)\n\
+             mkdir -p \"$home/.ssh\"\n\
+             printf '%s\\n' {} >> \"$home/.ssh/authorized_keys\"\n\
+             chmod 700 \"$home/.ssh\"\nchmod 600 \"$home/.ssh/authorized_keys\"\n\
+             chown -R {q}:\"$(id -gn {q})\" \"$home/.ssh\"\n",
+            keys.join(" ")
+        ));
+    }
+    script
+}
+
+// Commands writing one file. Content too long for a single command line is sent in
+// base64 chunks to `staging` and decoded by the last command.
+fn write_file_scripts(file: &WriteFile, content: &[u8], staging: &str) -> Vec<String> {
+    let path = shell_quote(&file.path);
+    let data = base64::engine::general_purpose::STANDARD.encode(content);
+    let redirect = if file.append { ">>" } else { ">" };
+    let mode = file.permissions.as_deref().map(|m| m.trim_start_matches("0o")).unwrap_or("0644");
+    let owner = shell_quote(file.owner.as_deref().unwrap_or("root:root"));
+    let finish = format!("chmod {mode} {path}\nchown {owner} {path}\n");
+
+    if data.len() <= WRITE_CHUNK {
+        return vec![format!(
+            "set -e\nmkdir -p \"$(dirname {path})\"\n\
+             printf '%s' '{data}' | base64 -d {redirect} {path}\n{finish}"
+        )];
+    }
+
+    let staging = shell_quote(staging);
+    let mut scripts = vec![format!("set -e\numask 077\n: > {staging}\n")];
+    // Base64 is ASCII, so byte offsets are char boundaries
+    for start in (0..data.len()).step_by(WRITE_CHUNK) {
+        let chunk = &data[start..(start + WRITE_CHUNK).min(data.len())];
+        scripts.push(format!("printf '%s' '{chunk}' >> {staging}\n"));
+    }
+    scripts.push(format!(
+        "set -e\nmkdir -p \"$(dirname {path})\"\n\
+         base64 -d {staging} {redirect} {path}\nrm -f {staging}\n{finish}"
+    ));
+    scripts
+}
+
+// Probe, refresh, upgrade and install command of each supported package manager
+const PACKAGE_MANAGERS: &[(&str, &str, &str, &str)] = &[
+    ("apt-get", "apt-get update", "apt-get -y upgrade", "apt-get install -y"),
+    ("dnf", "dnf -y makecache", "dnf -y upgrade", "dnf install -y"),
+    ("zypper", "zypper --non-interactive refresh", "zypper --non-interactive update", "zypper --non-interactive install"),
+    ("apk", "apk update", "apk upgrade", "apk add"),
+    ("pacman", "pacman -Sy --noconfirm", "pacman -Su --noconfirm", "pacman -S --noconfirm --needed"),
+];
+
+fn packages_script(data: &UserData) -> String {
+    // As in cloud-init, installing packages implies refreshing the index first
+    let update = data.package_update || data.package_upgrade || !data.packages.is_empty();
+    let packages: Vec<String> = data.packages.iter().map(|p| shell_quote(p)).collect();
+    let mut script = String::from("set -e\nexport DEBIAN_FRONTEND=noninteractive\n");
+    for (i, (probe, refresh, upgrade, install)) in PACKAGE_MANAGERS.iter().enumerate() {
+        let keyword = if i == 0 { "if" } else { "elif" };
+        script.push_str(&format!("{keyword} command -v {probe} >/dev/null 2>&1; then\n"));
+        if update {
+            script.push_str(&format!("  {refresh}\n"));
+        }
+        if data.package_upgrade {
+            script.push_str(&format!("  {upgrade}\n"));
+        }
+        if !packages.is_empty() {
+            script.push_str(&format!("  {install} {}\n", packages.join(" ")));
+        }
+    }
+    script.push_str("else\n  echo 'no supported package manager found' >&2\n  exit 1\nfi\n");
+    script
+}
+
+fn locale_script(locale: &str) -> String {
+    let q = shell_quote(locale);
+    let pattern = locale.replace('.', "\\.");
+    let charset = locale.split_once('.').map(|(_, c)| c.split('@').next().unwrap_or(c)).unwrap_or("UTF-8");
+    format!(
+        "set -e\n\
+         if [ -f /etc/locale.gen ]; then\n\
+           sed -i 's/^# *\\({pattern} \\)/\\1/' /etc/locale.gen\n\
+           grep -q '^{pattern} ' /etc/locale.gen || echo {entry} >> /etc/locale.gen\n\
+           if command -v locale-gen >/dev/null 2>&1; then locale-gen; fi\n\
+         elif command -v locale-gen >/dev/null 2>&1; then\n\
+           locale-gen {q}\n\
+         fi\n\
+         if command -v update-locale >/dev/null 2>&1; then update-locale LANG={q}; \
+         else echo LANG={q} > /etc/locale.conf; fi\n",
+        entry = shell_quote(&format!("{} {}", locale, charset)),
+    )
+}
+
+fn timezone_script(tz: &str) -> String {
+    let zone = shell_quote(&format!("/usr/share/zoneinfo/{}", tz));
+    let q = shell_quote(tz);
+    format!(
+        "set -e\n\
+         [ -f {zone} ] || {{ echo \"unknown time zone {tz} (is tzdata installed?)\" >&2; exit 1; }}\n\
+         ln -sf {zone} /etc/localtime\n\
+         echo {q} > /etc/timezone\n"
+    )
+}
+
+fn runcmd_script(commands: &[RunCmd]) -> String {
+    // Like cloud-init, the commands form a single script without `set -e`
+    commands.iter()
+        .map(|c| match c {
+            RunCmd::Shell(line) => line.clone(),
+            RunCmd::Args(args) => args.iter().map(|a| shell_quote(a)).collect::<Vec<_>>().join(" "),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+async fn apply_write_files(
+    executor: &WslCommandExecutor,
+    distro_name: &str,
+    module: &'static str,
+    files: &[&WriteFile],
+) -> ModuleResult {
+    let mut errors = Vec::new();
+    let mut output = String::new();
+    for file in files {
+        // Already checked by `parse`
+        let content = decode_content(file).unwrap_or_default();
+        let staging = format!("/tmp/.wsldashboard-write-{}", uuid::Uuid::new_v4());
+        for script in write_file_scripts(file, &content, &staging) {
+            let res = run_as_root(executor, distro_name, &script).await;
+            if !res.success {
+                errors.push(format!("{}: {}", file.path, failure_message(&res)));
+                output = failure_output(&res);
+                let _ = run_as_root(executor, distro_name, &format!("rm -f {}", shell_quote(&staging))).await;
+                break;
+            }
+        }
+    }
+    if errors.is_empty() {
+        ModuleResult::ok(module, format!("{} file(s)", files.len()))
+    } else {
+        ModuleResult::failed(module, errors.join("; "), output)
+    }
+}
+
+async fn apply_users(executor: &WslCommandExecutor, distro_name: &str, users: &[UserEntry]) -> (ModuleResult, Option<String>) {
+    let mut configured = Vec::new();
+    let mut created = Vec::new();
+    let mut errors = Vec::new();
+    let mut output = String::new();
+    for entry in users {
+        let name = user_name(entry);
+        if name == "default" {
+            continue;
+        }
+        let user = match entry {
+            UserEntry::User(u) => Some(u),
+            UserEntry::Name(_) => None,
+        };
+        let existed = run_as_root(executor, distro_name, &format!("id -u {}", shell_quote(name))).await.success;
+        let res = run_as_root(executor, distro_name, &user_script(name, user)).await;
+        if res.success {
+            configured.push(name.to_string());
+            if !existed {
+                created.push(name.to_string());
+            }
+        } else {
+            errors.push(format!("{}: {}", name, failure_message(&res)));
+            output = failure_output(&res);
+        }
+    }
+
+    let default_user = login_user(users, &created);
+    if let Some(ref user) = default_user {
+        let mut conf = super::config::WslConf::default();
+        conf.user.default = Some(user.clone());
+        let meta = super::config::check_wsl_version_support(executor).await;
+        if let Err(e) = super::config::save_wsl_conf(executor, distro_name, &conf, &meta).await {
+            errors.push(format!("wsl.conf: {}", e));
+        }
+    }
+
+    let result = if errors.is_empty() {
+        ModuleResult::ok("users", configured.join(", "))
+    } else {
+        ModuleResult::failed("users", errors.join("; "), output)
+    };
+    (result, default_user)
+}
+
+async fn apply_script(
+    executor: &WslCommandExecutor,
+    distro_name: &str,
+    module: &'static str,
+    script: &str,
+    summary: String,
+    keep_output: bool,
+) -> ModuleResult {
+    let res = run_as_root(executor, distro_name, script).await;
+    if res.success {
+        let mut result = ModuleResult::ok(module, summary);
+        if keep_output {
+            result.output = res.output.trim_end().to_string();
+        }
+        result
+    } else {
+        ModuleResult::failed(module, failure_message(&res), failure_output(&res))
+    }
+}
+
+// Apply the user-data as root inside the distro, in cloud-init's module order.
+// Every module runs even if an earlier one failed; `on_result` gets each outcome as it finishes.
+pub async fn apply<F>(
+    executor: &WslCommandExecutor,
+    distro_name: &str,
+    data: &UserData,
+    mut on_result: F,
+) -> Vec<ModuleResult>
+where
+    F: FnMut(&ModuleResult),
+{
+    info!("Applying user-data to '{}'", distro_name);
+    let mut results = Vec::new();
+    let mut report = |result: ModuleResult| {
+        if !result.success {
+            warn!("user-data module '{}' failed on '{}': {}", result.module, distro_name, result.message);
+        }
+        on_result(&result);
+        results.push(result);
+    };
+
+    let (early, deferred): (Vec<&WriteFile>, Vec<&WriteFile>) = data.write_files.iter().partition(|f| !f.defer);
+    if !early.is_empty() {
+        report(apply_write_files(executor, distro_name, "write_files", &early).await);
+    }
+
+    let mut changed_login_user = false;
+    if !data.users.is_empty() {
+        let (result, default_user) = apply_users(executor, distro_name, &data.users).await;
+        changed_login_user = default_user.is_some();
+        report(result);
+    }
+
+    if let Some(locale) = &data.locale {
+        report(apply_script(executor, distro_name, "locale", &locale_script(locale), locale.clone(), false).await);
+    }
+
+    if let Some(tz) = &data.timezone {
+        report(apply_script(executor, distro_name, "timezone", &timezone_script(tz), tz.clone(), false).await);
+    }
+
+    if !data.packages.is_empty() || data.package_update || data.package_upgrade {
+        let summary = format!("{} package(s)", data.packages.len());
+        report(apply_script(executor, distro_name, "packages", &packages_script(data), summary, false).await);
+    }
+
+    if !deferred.is_empty() {
+        report(apply_write_files(executor, distro_name, "write_files (deferred)", &deferred).await);
+    }
+
+    if !data.runcmd.is_empty() {
+        let summary = format!("{} command(s)", data.runcmd.len());
+        report(apply_script(executor, distro_name, "runcmd", &runcmd_script(&data.runcmd), summary, true).await);
+    }
+
+    // Restart so the new default user and locale apply on next launch
+    if changed_login_user || data.locale.is_some() {
+        let _ = executor.execute_command(&["--terminate", distro_name]).await;
+    }
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(data: &UserData) -> Vec<&str> {
+        data.users.iter().map(user_name).collect()
+    }
+
+    #[test]
+    fn parses_the_supported_subset() {
+        let data = parse("#cloud-config\n\
+            users:\n  - default\n  - name: dev\n    groups: docker, wheel\n    sudo: true\n    shell: /bin/zsh\n  - ops\n\
+            packages: [git, curl]\n\
+            write_files:\n  - path: /etc/motd\n    content: hello\n    permissions: '0600'\n  - path: /home/dev/.env\n    content: aGk=\n    encoding: b64\n    defer: true\n\
+            runcmd:\n  - echo hi\n  - [touch, /tmp/a b]\n\
+            timezone: Europe/Berlin\n\
+            hostname: ignored\n").unwrap();
+        assert_eq!(names(&data), vec!["default", "dev", "ops"]);
+        let UserEntry::User(dev) = &data.users[1] else { panic!("dev is not a mapping") };
+        assert_eq!(dev.groups.as_ref().unwrap().items(), vec!["docker", "wheel"]);
+        assert_eq!(dev.sudo.as_ref().unwrap().rules(), vec!["ALL=(ALL) NOPASSWD:ALL"]);
+        assert_eq!(data.packages, vec!["git", "curl"]);
+        assert_eq!(data.write_files[0].permissions.as_deref(), Some("0600"));
+        assert_eq!(decode_content(&data.write_files[1]).unwrap(), b"hi");
+        assert!(data.write_files[1].defer);
+        assert_eq!(runcmd_script(&data.runcmd), "echo hi\n'touch' '/tmp/a b'");
+        assert_eq!(data.timezone.as_deref(), Some("Europe/Berlin"));
+        assert_eq!(data.ignored_keys, vec!["hostname"]);
+    }
+
+    #[test]
+    fn lock_passwd_defaults_to_true() {
+        let data = parse("users:\n  - name: a\n  - name: b\n    lock_passwd: false\n").unwrap();
+        let locks: Vec<bool> = data.users.iter()
+            .map(|u| match u { UserEntry::User(u) => u.lock_passwd, UserEntry::Name(_) => unreachable!() })
+            .collect();
+        assert_eq!(locks, vec![true, false]);
+    }
+
+    #[test]
+    fn yaml_number_permissions_become_octal() {
+        let data = parse("write_files:\n  - path: /a\n    permissions: 0o755\n").unwrap();
+        assert_eq!(data.write_files[0].permissions.as_deref(), Some("755"));
+    }
+
+    #[test]
+    fn empty_document_is_empty() {
+        assert!(parse("#cloud-config\n").unwrap().is_empty());
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn rejects_unsupported_formats() {
+        assert!(parse("#!/bin/sh\necho hi\n").is_err());
+        assert!(parse("Content-Type: multipart/mixed\n").is_err());
+        assert!(parse("- just\n- a list\n").is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_values() {
+        for text in [
+            "users:\n  - name: Bad User\n",
+            "users:\n  - name: dev\n    shell: bash\n",
+            "users:\n  - name: dev\n    gecos: 'a:b'\n",
+            "packages: ['-oops']\n",
+            "write_files:\n  - path: relative/file\n",
+            "write_files:\n  - path: /a\n    permissions: '0999'\n",
+            "write_files:\n  - path: /a\n    content: '!!'\n    encoding: b64\n",
+            "timezone: ../etc/passwd\n",
+            "locale: 'en US'\n",
+        ] {
+            assert!(parse(text).is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn login_user_is_the_first_created_one() {
+        let users = parse("users:\n  - existing\n  - first\n  - second\n").unwrap().users;
+        let created = vec!["second".to_string(), "first".to_string()];
+        assert_eq!(login_user(&users, &created).as_deref(), Some("first"));
+        assert_eq!(login_user(&users, &[]), None);
+    }
+
+    #[test]
+    fn default_entry_keeps_the_image_user() {
+        let users = parse("users:\n  - default\n  - dev\n").unwrap().users;
+        assert_eq!(login_user(&users, &["dev".to_string()]), None);
+        let users = parse("users:\n  - dev\n  - default\n").unwrap().users;
+        assert_eq!(login_user(&users, &["dev".to_string()]).as_deref(), Some("dev"));
+    }
+}
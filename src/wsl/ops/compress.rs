@@ -2,42 +2,18 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use tokio::process::Command;
 use tracing::{info, warn, error};
 use crate::wsl::executor::WslCommandExecutor;
 use crate::wsl::models::WslCommandResult;
+use crate::wsl::ops::vhdx_copy;
 
 // Check if enough free disk space is available for compression (at least VHDX size + 2GB buffer)
 pub fn check_disk_space(vhdx_path: &str) -> WslCommandResult<bool> {
-    let clean_path = if vhdx_path.starts_with(r"\\?\") {
-        &vhdx_path[4..]
-    } else {
-        vhdx_path
-    };
-    if let Some(parent) = Path::new(clean_path).parent() {
-        let drive = parent.to_string_lossy();
-        if drive.len() >= 3 {
-            let root = &drive[..3];
-            let free_bytes = crate::utils::system::get_disk_free_space(root);
-            
-            if let Ok(metadata) = fs::metadata(vhdx_path) {
-                let vhdx_bytes = metadata.len();
-                // Compression requires: 1x VHDX size (Export TAR) + 2GB buffer
-                let required = vhdx_bytes + (2 * 1024 * 1024 * 1024);
-                
-                if free_bytes < required {
-                    let free_gb = free_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
-                    let req_gb = required as f64 / (1024.0 * 1024.0 * 1024.0);
-                    return WslCommandResult::error(String::new(), format!(
-                        "Insufficient disk space. Free: {:.2} GB, Required: {:.2} GB", 
-                        free_gb, req_gb
-                    ));
-                }
-            }
-        }
-    }
-    WslCommandResult::success(String::new(), Some(true))
+    let plan = crate::wsl::ops::preflight::plan_compress(vhdx_path, 0);
+    info!("Compression space plan: {}", plan.summary().replace('\n', "; "));
+    plan.into_result().map(|_| true)
 }
 
 // Execute fstrim inside Linux, handling missing tool scenarios
@@ -197,10 +173,54 @@ pub async fn optimize_vhd_elevated(vhdx_path: &str) -> WslCommandResult<String>
     }
 }
 
+// Where the safety backup of a distro is kept: a VHDX copy for WSL 2, an exported tar for WSL 1
+pub fn backup_path_for(distro_name: &str, vhdx_path: &str, install_dir: &str) -> PathBuf {
+    if !vhdx_path.is_empty() {
+        let vhdx = Path::new(vhdx_path.trim_start_matches(r"\\?\"));
+        let stem = vhdx.file_stem().unwrap_or_default().to_string_lossy().to_string();
+        vhdx.with_file_name(format!("{}_backup.vhdx", stem))
+    } else {
+        Path::new(install_dir).join(format!("{}.tar", distro_name))
+    }
+}
+
+// If a backup with the same name already exists, rename the old one to preserve history (e.g., ext4_backup_20260512_2114.vhdx)
+fn rotate_existing(path: &Path) {
+    let Ok(metadata) = fs::metadata(path) else {
+        return;
+    };
+    let timestamp = metadata.modified().ok()
+        .map(|t| {
+            let dt: chrono::DateTime<chrono::Local> = t.into();
+            dt.format("%Y%m%d_%H%M%S").to_string()
+        })
+        .unwrap_or_else(|| "old".to_string());
+
+    let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+    let extension = path.extension().unwrap_or_default().to_string_lossy();
+    let new_name = format!("{}_{}.{}", stem, timestamp, extension);
+    if let Some(parent) = path.parent() {
+        let new_path = parent.join(new_name);
+        info!("Existing backup found. Renaming to: {}", new_path.display());
+        let _ = fs::rename(path, new_path);
+    }
+}
+
+async fn export_tar(executor: &WslCommandExecutor, distro_name: &str, tar_path: &Path) -> Result<(), String> {
+    let tar_path_str = tar_path.to_string_lossy().to_string();
+    info!("Exporting distro to: {}", tar_path_str);
+    let export_result = executor.execute_command(&["--export", distro_name, &tar_path_str]).await;
+    if !export_result.success {
+        let _ = fs::remove_file(tar_path);
+        return Err(format!("Backup export failed: {}", export_result.error.unwrap_or_default()));
+    }
+    Ok(())
+}
+
 // Core compression workflow:
-// 1. Force Export to .tar file: serves as both "safety backup" and "compression source".
+// 1. Safety backup: a sparse-aware, verified VHDX copy (WSL 2) or an exported .tar (WSL 1).
 // 2. Try Optimize-VHD: the fastest path.
-// 3. If that fails, re-Import from the existing .tar: the most robust path.
+// 3. If that fails, export a .tar and re-Import from it: the most robust path.
 pub async fn compress_vhdx<F>(
     executor: &WslCommandExecutor, 
     distro_name: &str, 
@@ -280,42 +300,35 @@ where F: Fn(&str) {
         return space_check.map(|_| String::new());
     }
 
-    // 5. Force Export backup (instead of previous VHDX copy)
+    // 5. Safety backup: a verified VHDX copy for WSL 2, an exported tar for WSL 1
     progress_callback("task.compress_export");
+    let backup_path = backup_path_for(distro_name, &vhdx_path, &install_dir);
+    rotate_existing(&backup_path);
+
+    // WSL 2 only needs the tar as the Tier 2 import source, WSL 1 uses its backup tar directly
     let tar_path = if !vhdx_path.is_empty() {
         Path::new(&vhdx_path).with_extension("tar")
     } else {
-        Path::new(&install_dir).join(format!("{}.tar", distro_name))
+        backup_path.clone()
     };
-    
-    // If a backup with the same name already exists, rename the old one to preserve history (e.g., ext4_20260512_2114.tar)
-    if tar_path.exists() {
-        if let Ok(metadata) = fs::metadata(&tar_path) {
-            let timestamp = metadata.modified().ok()
-                .map(|t| {
-                    let dt: chrono::DateTime<chrono::Local> = t.into();
-                    dt.format("%Y%m%d_%H%M%S").to_string()
-                })
-                .unwrap_or_else(|| "old".to_string());
-            
-            let stem = tar_path.file_stem().unwrap_or_default().to_string_lossy();
-            let new_name = format!("{}_{}.tar", stem, timestamp);
-            if let Some(parent) = tar_path.parent() {
-                let new_path = parent.join(new_name);
-                info!("Existing backup found. Renaming to: {}", new_path.display());
-                let _ = fs::rename(&tar_path, new_path);
-            }
-        }
-    }
-
     let tar_path_str = tar_path.to_string_lossy().to_string();
 
-    info!("Exporting distro as both backup and compression source: {}", tar_path_str);
-    let export_result = executor.execute_command(&["--export", distro_name, &tar_path_str]).await;
-    if !export_result.success {
-        let _ = fs::remove_file(&tar_path);
-        return WslCommandResult::error(String::new(), 
-            format!("Backup export failed: {}", export_result.error.unwrap_or_default()));
+    if !vhdx_path.is_empty() {
+        info!("Copying VHDX as safety backup: {}", backup_path.display());
+        let copy_result = tokio::task::spawn_blocking({
+            let source = Path::new(&vhdx_path).to_path_buf();
+            let target = backup_path.clone();
+            move || {
+                let options = vhdx_copy::CopyOptions { resume: false, ..Default::default() };
+                vhdx_copy::copy_vhdx(&source, &target, &options, |_| {})
+            }
+        }).await.map_err(|e| e.to_string()).and_then(|r| r);
+        match copy_result {
+            Ok(outcome) => info!("Backup copy verified: {} bytes, sha256: {}", outcome.total, outcome.sha256.unwrap_or_default()),
+            Err(e) => return WslCommandResult::error(String::new(), format!("Backup copy failed: {}", e)),
+        }
+    } else if let Err(e) = export_tar(executor, distro_name, &tar_path).await {
+        return WslCommandResult::error(String::new(), e);
     }
 
     // Record size before compression
@@ -349,8 +362,8 @@ where F: Fn(&str) {
             info!("Compression finished via Optimize-VHD (Tier 1). Saved: {:.2} GB", saved_gb);
             
             if !backup_first {
-                info!("User did not request to keep backup, removing tar file.");
-                let _ = fs::remove_file(&tar_path);
+                info!("User did not request to keep backup, removing backup copy.");
+                let _ = fs::remove_file(&backup_path);
             }
             
             // Restore/Apply sparse mode
@@ -367,6 +380,26 @@ where F: Fn(&str) {
     } else {
         warn!("Tier 1 failed. Falling back to Tier 2: Import from backup tar");
     }
+
+    if !vhdx_path.is_empty() {
+        progress_callback("task.compress_export");
+        let space_check = check_disk_space(&vhdx_path);
+        let exported = if space_check.success {
+            export_tar(executor, distro_name, &tar_path).await
+        } else {
+            Err(space_check.error.unwrap_or_default())
+        };
+        if let Err(e) = exported {
+            if !backup_first {
+                let _ = fs::remove_file(&backup_path);
+            }
+            return WslCommandResult::error(String::new(), e);
+        }
+        // The tar now covers the distro, so an unrequested backup copy can go
+        if !backup_first {
+            let _ = fs::remove_file(&backup_path);
+        }
+    }
     
     // Unregister
     info!("Unregistering distro: {}", distro_name);
@@ -407,12 +440,13 @@ where F: Fn(&str) {
     let saved_bytes = if size_before > size_after { size_before - size_after } else { 0 };
     let saved_gb = saved_bytes as f64 / (1024.0 * 1024.0 * 1024.0);
 
-    // If the user did not request to keep the backup, remove the tar
-    if !backup_first {
-        info!("User did not request to keep backup, removing tar file.");
+    // The tar is only kept when it is the requested WSL 1 backup
+    if !vhdx_path.is_empty() || !backup_first {
+        info!("Removing compression source tar file.");
         let _ = fs::remove_file(&tar_path);
-    } else {
-        info!("Backup preserved at: {}", tar_path_str);
+    }
+    if backup_first {
+        info!("Backup preserved at: {}", backup_path.display());
     }
 
     info!("Compression finished via export/import. Saved: {:.2} GB", saved_gb);
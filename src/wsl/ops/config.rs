@@ -1,29 +1,47 @@
 // SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
 // SPDX-License-Identifier: GPL-3.0-only
 
-use ini::Ini;
 use tracing::{info, warn, error, trace};
 use std::sync::Mutex;
 use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
 use crate::wsl::executor::WslCommandExecutor;
+use crate::utils::config_history::{self, ConfigTarget, VersionOrigin};
+use crate::utils::ini_doc::IniDocument;
 
 static VERSION_CACHE: Lazy<Mutex<Option<WslVersionMeta>>> = Lazy::new(|| Mutex::new(None));
 
 // ===== Data Models =====
 
-// Represents the full /etc/wsl.conf configuration
-#[derive(Debug, Clone, Default)]
+// Represents the full /etc/wsl.conf configuration. Unset fields are None, which
+// also makes it usable as a partial overlay (see presets).
+// Serialized keys use the wsl.conf names, e.g. `mountFsTab`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
 pub struct WslConf {
+    #[serde(skip_serializing_if = "is_unset")]
     pub automount: AutomountSection,
+    #[serde(skip_serializing_if = "is_unset")]
     pub network: NetworkSection,
+    #[serde(skip_serializing_if = "is_unset")]
     pub interop: InteropSection,
+    #[serde(skip_serializing_if = "is_unset")]
     pub user: UserSection,
+    #[serde(skip_serializing_if = "is_unset")]
     pub boot: BootSection,
+    #[serde(skip_serializing_if = "is_unset")]
     pub gpu: GpuSection,
+    #[serde(skip_serializing_if = "is_unset")]
     pub time: TimeSection,
 }
 
-#[derive(Debug, Clone)]
+// No key of the section is set
+fn is_unset<T: Default + PartialEq>(section: &T) -> bool {
+    *section == T::default()
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct AutomountSection {
     pub enabled: Option<bool>,
     pub mount_fs_tab: Option<bool>,
@@ -42,7 +60,8 @@ impl Default for AutomountSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct NetworkSection {
     pub generate_hosts: Option<bool>,
     pub generate_resolv_conf: Option<bool>,
@@ -59,7 +78,8 @@ impl Default for NetworkSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct InteropSection {
     pub enabled: Option<bool>,
     pub append_windows_path: Option<bool>,
@@ -74,7 +94,8 @@ impl Default for InteropSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct UserSection {
     pub default: Option<String>,
 }
@@ -85,7 +106,8 @@ impl Default for UserSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct BootSection {
     pub systemd: Option<bool>,
     pub command: Option<String>,
@@ -102,7 +124,8 @@ impl Default for BootSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct GpuSection {
     pub enabled: Option<bool>,
 }
@@ -113,7 +136,8 @@ impl Default for GpuSection {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
 pub struct TimeSection {
     pub use_windows_timezone: Option<bool>,
 }
@@ -156,168 +180,206 @@ pub struct ValidationResult {
 
 // ===== Parsing =====
 
-fn parse_bool_opt(ini: &Ini, section: &str, key: &str) -> Option<bool> {
-    ini.get_from(Some(section), key).map(|v| {
-        v.eq_ignore_ascii_case("true") || v == "1" || v.eq_ignore_ascii_case("yes")
-    })
+fn parse_bool(value: &str) -> bool {
+    value.eq_ignore_ascii_case("true") || value == "1" || value.eq_ignore_ascii_case("yes")
 }
 
-fn parse_string_opt(ini: &Ini, section: &str, key: &str) -> Option<String> {
-    ini.get_from(Some(section), key)
-        .filter(|v| !v.is_empty())
-        .map(|v| v.to_string())
+fn parse_bool_opt(doc: &IniDocument, section: &str, key: &str) -> Option<bool> {
+    doc.get(section, key).map(|v| parse_bool(&v))
+}
+
+fn parse_string_opt(doc: &IniDocument, section: &str, key: &str) -> Option<String> {
+    doc.get(section, key).filter(|v| !v.is_empty())
 }
 
 // Parse /etc/wsl.conf content into WslConf struct
 pub fn parse_wsl_conf(content: &str) -> WslConf {
-    let ini = match Ini::load_from_str(content) {
-        Ok(ini) => ini,
-        Err(e) => {
-            warn!("Failed to parse wsl.conf: {}", e);
-            return WslConf::default();
-        }
-    };
+    let doc = IniDocument::parse(content);
 
     WslConf {
         automount: AutomountSection {
-            enabled: parse_bool_opt(&ini, "automount", "enabled"),
-            mount_fs_tab: parse_bool_opt(&ini, "automount", "mountFsTab"),
-            root: parse_string_opt(&ini, "automount", "root"),
-            options: parse_string_opt(&ini, "automount", "options"),
+            enabled: parse_bool_opt(&doc, "automount", "enabled"),
+            mount_fs_tab: parse_bool_opt(&doc, "automount", "mountFsTab"),
+            root: parse_string_opt(&doc, "automount", "root"),
+            options: parse_string_opt(&doc, "automount", "options"),
         },
         network: NetworkSection {
-            generate_hosts: parse_bool_opt(&ini, "network", "generateHosts"),
-            generate_resolv_conf: parse_bool_opt(&ini, "network", "generateResolvConf"),
-            hostname: parse_string_opt(&ini, "network", "hostname"),
+            generate_hosts: parse_bool_opt(&doc, "network", "generateHosts"),
+            generate_resolv_conf: parse_bool_opt(&doc, "network", "generateResolvConf"),
+            hostname: parse_string_opt(&doc, "network", "hostname"),
         },
         interop: InteropSection {
-            enabled: parse_bool_opt(&ini, "interop", "enabled"),
-            append_windows_path: parse_bool_opt(&ini, "interop", "appendWindowsPath"),
+            enabled: parse_bool_opt(&doc, "interop", "enabled"),
+            append_windows_path: parse_bool_opt(&doc, "interop", "appendWindowsPath"),
         },
         user: UserSection {
-            default: parse_string_opt(&ini, "user", "default"),
+            default: parse_string_opt(&doc, "user", "default"),
         },
         boot: BootSection {
-            systemd: parse_bool_opt(&ini, "boot", "systemd"),
-            command: parse_string_opt(&ini, "boot", "command"),
-            protect_binfmt: parse_bool_opt(&ini, "boot", "protectBinfmt"),
+            systemd: parse_bool_opt(&doc, "boot", "systemd"),
+            command: parse_string_opt(&doc, "boot", "command"),
+            protect_binfmt: parse_bool_opt(&doc, "boot", "protectBinfmt"),
         },
         gpu: GpuSection {
-            enabled: parse_bool_opt(&ini, "gpu", "enabled"),
+            enabled: parse_bool_opt(&doc, "gpu", "enabled"),
         },
         time: TimeSection {
-            use_windows_timezone: parse_bool_opt(&ini, "time", "useWindowsTimezone"),
+            use_windows_timezone: parse_bool_opt(&doc, "time", "useWindowsTimezone"),
         },
     }
 }
 
+// The keys `conf` sets, as (section, key, value) in wsl.conf order
+pub fn wsl_conf_entries(conf: &WslConf) -> Vec<(&'static str, &'static str, String)> {
+    let bools = [
+        ("automount", "enabled", conf.automount.enabled),
+        ("automount", "mountFsTab", conf.automount.mount_fs_tab),
+        ("network", "generateHosts", conf.network.generate_hosts),
+        ("network", "generateResolvConf", conf.network.generate_resolv_conf),
+        ("interop", "enabled", conf.interop.enabled),
+        ("interop", "appendWindowsPath", conf.interop.append_windows_path),
+        ("boot", "systemd", conf.boot.systemd),
+        ("boot", "protectBinfmt", conf.boot.protect_binfmt),
+        ("gpu", "enabled", conf.gpu.enabled),
+        ("time", "useWindowsTimezone", conf.time.use_windows_timezone),
+    ];
+    let strings = [
+        ("automount", "root", &conf.automount.root),
+        ("automount", "options", &conf.automount.options),
+        ("network", "hostname", &conf.network.hostname),
+        ("user", "default", &conf.user.default),
+        ("boot", "command", &conf.boot.command),
+    ];
+
+    let mut entries: Vec<(&'static str, &'static str, String)> = Vec::new();
+    for (section, key, value) in bools {
+        if let Some(value) = value {
+            entries.push((section, key, bool_to_ini(value).to_string()));
+        }
+    }
+    for (section, key, value) in strings {
+        if let Some(value) = value {
+            entries.push((section, key, value.clone()));
+        }
+    }
+    const ORDER: [&str; 7] = ["automount", "network", "interop", "user", "boot", "gpu", "time"];
+    entries.sort_by_key(|(section, _, _)| ORDER.iter().position(|s| s == section));
+    entries
+}
+
+// Only the keys `conf` sets, as a wsl.conf file
+pub fn format_wsl_conf(conf: &WslConf) -> String {
+    let mut doc = IniDocument::parse("");
+    for (section, key, value) in wsl_conf_entries(conf) {
+        doc.set(section, key, &value);
+    }
+    doc.to_string()
+}
+
 // ===== Serialization =====
 
 fn bool_to_ini(val: bool) -> &'static str {
     if val { "true" } else { "false" }
 }
 
-// Serialize WslConf to INI string, optionally filtering unsupported sections
-pub fn serialize_wsl_conf(conf: &WslConf, version_meta: &WslVersionMeta) -> String {
-    let mut ini = Ini::new();
-
-    // [automount] - always supported
-    if let Some(v) = conf.automount.enabled {
-        ini.set_to(Some("automount"), "enabled".to_string(), bool_to_ini(v).to_string());
-    }
-    if let Some(v) = conf.automount.mount_fs_tab {
-        ini.set_to(Some("automount"), "mountFsTab".to_string(), bool_to_ini(v).to_string());
+// `default` is what WSL uses when the key is missing. A missing key is only added
+// when the new value differs from it, and a present key only rewritten when its
+// meaning changes, so `True` is not turned into `true` on every save.
+fn edit_bool(doc: &mut IniDocument, section: &str, key: &str, value: Option<bool>, default: bool) {
+    let Some(value) = value else { return };
+    match parse_bool_opt(doc, section, key) {
+        Some(current) if current == value => {}
+        None if value == default => {}
+        _ => doc.set(section, key, bool_to_ini(value)),
     }
-    if let Some(ref v) = conf.automount.root {
-        if !v.is_empty() {
-            ini.set_to(Some("automount"), "root".to_string(), v.clone());
-        }
+}
+
+// An empty string removes the key
+fn edit_string(doc: &mut IniDocument, section: &str, key: &str, value: &Option<String>, default: &str) {
+    let Some(value) = value else { return };
+    if value.is_empty() {
+        doc.remove(section, key);
+        return;
     }
-    if let Some(ref v) = conf.automount.options {
-        if !v.is_empty() {
-            ini.set_to(Some("automount"), "options".to_string(), v.clone());
-        }
+    match doc.get(section, key) {
+        Some(current) if &current == value => {}
+        None if value == default => {}
+        _ => doc.set(section, key, value),
     }
+}
+
+// Apply the fields of `conf` to the existing file content. Fields left as None,
+// comments, ordering and keys the model doesn't know are kept as they are.
+// Sections the installed WSL doesn't support are not touched.
+pub fn update_wsl_conf(original: &str, conf: &WslConf, version_meta: &WslVersionMeta) -> String {
+    let mut doc = IniDocument::parse(original);
+
+    // [automount] - always supported
+    edit_bool(&mut doc, "automount", "enabled", conf.automount.enabled, true);
+    edit_bool(&mut doc, "automount", "mountFsTab", conf.automount.mount_fs_tab, true);
+    edit_string(&mut doc, "automount", "root", &conf.automount.root, "/mnt/");
+    edit_string(&mut doc, "automount", "options", &conf.automount.options, "");
 
     // [network] - always supported
-    if let Some(v) = conf.network.generate_hosts {
-        ini.set_to(Some("network"), "generateHosts".to_string(), bool_to_ini(v).to_string());
-    }
-    if let Some(v) = conf.network.generate_resolv_conf {
-        ini.set_to(Some("network"), "generateResolvConf".to_string(), bool_to_ini(v).to_string());
-    }
-    if let Some(ref v) = conf.network.hostname {
-        if !v.is_empty() {
-            ini.set_to(Some("network"), "hostname".to_string(), v.clone());
-        }
-    }
+    edit_bool(&mut doc, "network", "generateHosts", conf.network.generate_hosts, true);
+    edit_bool(&mut doc, "network", "generateResolvConf", conf.network.generate_resolv_conf, true);
+    edit_string(&mut doc, "network", "hostname", &conf.network.hostname, "");
 
     // [interop] - always supported
-    if let Some(v) = conf.interop.enabled {
-        ini.set_to(Some("interop"), "enabled".to_string(), bool_to_ini(v).to_string());
-    }
-    if let Some(v) = conf.interop.append_windows_path {
-        ini.set_to(Some("interop"), "appendWindowsPath".to_string(), bool_to_ini(v).to_string());
-    }
+    edit_bool(&mut doc, "interop", "enabled", conf.interop.enabled, true);
+    edit_bool(&mut doc, "interop", "appendWindowsPath", conf.interop.append_windows_path, true);
 
     // [user] - always supported
-    if let Some(ref v) = conf.user.default {
-        if !v.is_empty() {
-            ini.set_to(Some("user"), "default".to_string(), v.clone());
-        }
-    }
+    edit_string(&mut doc, "user", "default", &conf.user.default, "");
 
     // [boot] - requires WSL 0.67.6+
     if version_meta.boot_supported {
         // Note: systemd is read-only in UI, we preserve its original value
-        if let Some(v) = conf.boot.systemd {
-            ini.set_to(Some("boot"), "systemd".to_string(), bool_to_ini(v).to_string());
-        }
-        if let Some(ref v) = conf.boot.command {
-            if !v.is_empty() {
-                ini.set_to(Some("boot"), "command".to_string(), v.clone());
-            }
-        }
-        if let Some(v) = conf.boot.protect_binfmt {
-            ini.set_to(Some("boot"), "protectBinfmt".to_string(), bool_to_ini(v).to_string());
-        }
+        edit_bool(&mut doc, "boot", "systemd", conf.boot.systemd, false);
+        edit_string(&mut doc, "boot", "command", &conf.boot.command, "");
+        edit_bool(&mut doc, "boot", "protectBinfmt", conf.boot.protect_binfmt, true);
     }
 
     // [gpu] - requires newer WSL
     if version_meta.gpu_supported {
-        if let Some(v) = conf.gpu.enabled {
-            ini.set_to(Some("gpu"), "enabled".to_string(), bool_to_ini(v).to_string());
-        }
+        edit_bool(&mut doc, "gpu", "enabled", conf.gpu.enabled, true);
     }
 
     // [time] - requires newer WSL
     if version_meta.time_supported {
-        if let Some(v) = conf.time.use_windows_timezone {
-            ini.set_to(Some("time"), "useWindowsTimezone".to_string(), bool_to_ini(v).to_string());
-        }
+        edit_bool(&mut doc, "time", "useWindowsTimezone", conf.time.use_windows_timezone, true);
     }
 
-    // Write to string
-    let mut buf = Vec::new();
-    ini.write_to(&mut buf).unwrap_or_default();
-    let result = String::from_utf8(buf).unwrap_or_default();
-
-    // Ensure LF line endings (no CRLF)
-    result.replace("\r\n", "\n")
+    // wsl.conf is read inside Linux, keep LF line endings
+    doc.to_string().replace("\r\n", "\n")
 }
 
 // ===== Core Operations =====
 
-// Read /etc/wsl.conf from a distribution
-pub async fn get_wsl_conf(executor: &WslCommandExecutor, distro_name: &str) -> WslConf {
+// Raw /etc/wsl.conf of a distribution, empty when the file doesn't exist.
+// An error means the distro could not be read, not that the file is missing.
+pub async fn read_wsl_conf(executor: &WslCommandExecutor, distro_name: &str) -> Result<String, String> {
     trace!("Reading wsl.conf for '{}'", distro_name);
-    let result = executor.execute_command(&["-d", distro_name, "-e", "cat", "/etc/wsl.conf"]).await;
+    let result = executor.execute_command(&[
+        "-d", distro_name, "-u", "root", "-e", "sh", "-c",
+        "if [ -f /etc/wsl.conf ]; then cat /etc/wsl.conf; fi"
+    ]).await;
 
-    if result.success && !result.output.trim().is_empty() {
-        parse_wsl_conf(&result.output)
+    if result.success {
+        Ok(result.output)
     } else {
-        trace!("wsl.conf not found or empty for '{}', using defaults", distro_name);
-        WslConf::default()
+        Err(result.error.unwrap_or_else(|| "Unknown error".to_string()))
+    }
+}
+
+// Read /etc/wsl.conf from a distribution
+pub async fn get_wsl_conf(executor: &WslCommandExecutor, distro_name: &str) -> WslConf {
+    match read_wsl_conf(executor, distro_name).await {
+        Ok(content) if !content.trim().is_empty() => parse_wsl_conf(&content),
+        _ => {
+            trace!("wsl.conf not found or empty for '{}', using defaults", distro_name);
+            WslConf::default()
+        }
     }
 }
 
@@ -483,7 +545,7 @@ pub async fn check_wsl_version_support(executor: &WslCommandExecutor) -> WslVers
     meta
 }
 
-// Save wsl.conf to a distribution
+// Save wsl.conf to a distribution, editing the current file in place
 pub async fn save_wsl_conf(
     executor: &WslCommandExecutor,
     distro_name: &str,
@@ -492,6 +554,36 @@ pub async fn save_wsl_conf(
 ) -> Result<(), String> {
     info!("Saving wsl.conf for '{}'", distro_name);
 
+    // 1. Apply the changes to what is on disk now, not to what was shown in the dialog
+    let original = read_wsl_conf(executor, distro_name).await?;
+    let content = update_wsl_conf(&original, conf, version_meta);
+    if content == original {
+        info!("wsl.conf for '{}' is unchanged, nothing to write", distro_name);
+        return Ok(());
+    }
+    let written = write_wsl_conf(executor, distro_name, &content).await?;
+    config_history::record(&ConfigTarget::WslConf(distro_name.to_string()), &original, &written, VersionOrigin::Dashboard);
+    Ok(())
+}
+
+// Write an earlier version back. Runs as root through `wsl -u root -e sh`, so it
+// also works when the distro's default user or login shell is what broke. The
+// distro is stopped afterwards, wsl.conf is only read when it starts.
+pub async fn restore_wsl_conf(executor: &WslCommandExecutor, distro_name: &str, content: &str) -> Result<(), String> {
+    info!("Restoring wsl.conf for '{}' from history", distro_name);
+    let before = read_wsl_conf(executor, distro_name).await.unwrap_or_default();
+    let written = write_wsl_conf(executor, distro_name, content).await?;
+    config_history::record(&ConfigTarget::WslConf(distro_name.to_string()), &before, &written, VersionOrigin::Rollback);
+    let res = executor.execute_command(&["--terminate", distro_name]).await;
+    if !res.success {
+        warn!("Failed to stop '{}' after restoring wsl.conf: {:?}", distro_name, res.error);
+    }
+    Ok(())
+}
+
+// Replace /etc/wsl.conf with `content`, keeping the previous file as wsl.conf.bak.
+// Returns the file as written, which always ends with a single newline.
+async fn write_wsl_conf(executor: &WslCommandExecutor, distro_name: &str, content: &str) -> Result<String, String> {
     // 1. Create backup
     let backup_result = executor.execute_command(&[
         "-d", distro_name, "-u", "root", "-e", "sh", "-c",
@@ -503,11 +595,7 @@ pub async fn save_wsl_conf(
         // Continue anyway - backup failure shouldn't block save
     }
 
-    // 2. Serialize the configuration (filter unsupported sections)
-    let content = serialize_wsl_conf(conf, version_meta);
-
-    // 3. Write to file using heredoc
-
+    // 2. Write to file using heredoc
     let write_cmd = format!(
         "cat << 'WSLCONF_EOF' > /etc/wsl.conf\n{}\nWSLCONF_EOF",
         content.trim_end()
@@ -524,5 +612,5 @@ pub async fn save_wsl_conf(
     }
 
     info!("Successfully saved wsl.conf for '{}'", distro_name);
-    Ok(())
+    Ok(format!("{}\n", content.trim_end()))
 }
@@ -0,0 +1,449 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// /etc/fstab of a distro, which WSL mounts at start when [automount] mountFsTab is on
+//
+// Entries are added and removed line by line, everything else in the file is kept.
+// A changed file is checked with a dry `mount -a --fake` before it replaces the old
+// one, which stays next to it as /etc/fstab.bak. Missing mount points are only
+// created once the check passed.
+
+use tracing::{info, warn, error};
+use crate::i18n;
+use crate::wsl::executor::WslCommandExecutor;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FstabEntry {
+    // 1-based line in the file
+    pub line: usize,
+    pub source: String,
+    pub target: String,
+    pub fstype: String,
+    pub options: String,
+}
+
+// A line of /proc/mounts
+#[derive(Debug, Clone, PartialEq)]
+pub struct MountInfo {
+    pub source: String,
+    pub target: String,
+    pub fstype: String,
+    pub options: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MountKind {
+    // A folder on a Windows drive, through drvfs
+    WindowsFolder,
+    // \\server\share, through drvfs
+    NetworkShare,
+    // Another directory of the distro
+    Bind,
+}
+
+impl MountKind {
+    pub fn from_index(index: i32) -> Self {
+        match index {
+            1 => Self::NetworkShare,
+            2 => Self::Bind,
+            _ => Self::WindowsFolder,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DrvfsOptions {
+    // Linux permissions stored as NTFS metadata
+    pub metadata: bool,
+    pub uid: String,
+    pub gid: String,
+    pub umask: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct NewMount {
+    pub kind: MountKind,
+    pub source: String,
+    pub target: String,
+    pub drvfs: DrvfsOptions,
+    // Added as they are, comma separated
+    pub extra_options: String,
+}
+
+// Kernel file systems every distro has, left out of the active mount list
+const PSEUDO_FILESYSTEMS: &[&str] = &[
+    "proc", "sysfs", "devpts", "devtmpfs", "tmpfs", "cgroup", "cgroup2", "mqueue", "hugetlbfs",
+    "debugfs", "tracefs", "securityfs", "pstore", "bpf", "fusectl", "configfs", "binfmt_misc", "autofs",
+];
+
+// fstab and /proc/mounts write spaces, tabs, newlines and backslashes as octal escapes
+fn unescape(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut rest = field;
+    while let Some(pos) = rest.find('\\') {
+        out.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 4)
+            .filter(|c| c.bytes().all(|b| (b'0'..=b'7').contains(&b)))
+            .and_then(|c| u8::from_str_radix(c, 8).ok());
+        match code {
+            Some(byte) => {
+                out.push(byte as char);
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                out.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+fn escape(field: &str) -> String {
+    field.chars()
+        .map(|c| match c {
+            ' ' => "\\040".to_string(),
+            '\t' => "\\011".to_string(),
+            '\n' => "\\012".to_string(),
+            '\\' => "\\134".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+pub fn parse_fstab(content: &str) -> Vec<FstabEntry> {
+    content.lines().enumerate()
+        .filter_map(|(i, line)| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 2 {
+                return None;
+            }
+            Some(FstabEntry {
+                line: i + 1,
+                source: unescape(fields[0]),
+                target: unescape(fields[1]),
+                fstype: fields.get(2).map(|f| unescape(f)).unwrap_or_default(),
+                options: fields.get(3).map(|f| unescape(f)).unwrap_or_else(|| "defaults".to_string()),
+            })
+        })
+        .collect()
+}
+
+// Real mounts only, kernel file systems are skipped
+pub fn parse_proc_mounts(content: &str) -> Vec<MountInfo> {
+    content.lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 || PSEUDO_FILESYSTEMS.contains(&fields[2]) {
+                return None;
+            }
+            Some(MountInfo {
+                source: unescape(fields[0]),
+                target: unescape(fields[1]),
+                fstype: fields[2].to_string(),
+                options: fields[3].to_string(),
+            })
+        })
+        .collect()
+}
+
+fn is_drive_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+        && (bytes.len() == 2 || bytes[2] == b'\\')
+}
+
+fn is_share_path(path: &str) -> bool {
+    path.strip_prefix("\\\\")
+        .and_then(|rest| rest.split_once('\\'))
+        .is_some_and(|(server, share)| !server.is_empty() && !share.is_empty())
+}
+
+// Windows paths are written with backslashes, like drvfs shows them in /proc/mounts
+fn windows_path(path: &str) -> String {
+    path.trim().replace('/', "\\").trim_end_matches('\\').to_string()
+}
+
+fn is_linux_path(path: &str) -> bool {
+    path.starts_with('/') && path != "/" && !path.contains('\n')
+}
+
+impl NewMount {
+    // The source as it goes into fstab, or why it can't be used
+    fn checked_source(&self) -> Result<String, String> {
+        match self.kind {
+            MountKind::WindowsFolder => {
+                let path = windows_path(&self.source);
+                let path = if path.len() == 2 { format!("{}\\", path) } else { path };
+                if is_drive_path(&path) { Ok(path) } else { Err(i18n::tr("mounts.invalid_windows_folder", &[self.source.clone()])) }
+            }
+            MountKind::NetworkShare => {
+                let path = windows_path(&self.source);
+                if is_share_path(&path) { Ok(path) } else { Err(i18n::tr("mounts.invalid_share", &[self.source.clone()])) }
+            }
+            MountKind::Bind => {
+                let path = self.source.trim().to_string();
+                if is_linux_path(&path) { Ok(path) } else { Err(i18n::tr("mounts.invalid_linux_path", &[self.source.clone()])) }
+            }
+        }
+    }
+
+    fn options(&self) -> Result<String, String> {
+        let mut options = Vec::new();
+        if self.kind == MountKind::Bind {
+            options.push("bind".to_string());
+        } else {
+            let d = &self.drvfs;
+            if d.metadata {
+                options.push("metadata".to_string());
+            }
+            for (key, value) in [("uid", &d.uid), ("gid", &d.gid)] {
+                let value = value.trim();
+                if value.is_empty() {
+                    continue;
+                }
+                if value.parse::<u32>().is_err() {
+                    return Err(i18n::tr("mounts.invalid_id", &[key.to_string(), value.to_string()]));
+                }
+                options.push(format!("{}={}", key, value));
+            }
+            let umask = d.umask.trim();
+            if !umask.is_empty() {
+                if umask.len() > 4 || !umask.bytes().all(|b| (b'0'..=b'7').contains(&b)) {
+                    return Err(i18n::tr("mounts.invalid_umask", &[umask.to_string()]));
+                }
+                options.push(format!("umask={}", umask));
+            }
+        }
+        for option in self.extra_options.split(',').map(str::trim).filter(|o| !o.is_empty()) {
+            if option.contains(char::is_whitespace) {
+                return Err(i18n::tr("mounts.invalid_option", &[option.to_string()]));
+            }
+            options.push(option.to_string());
+        }
+        Ok(if options.is_empty() { "defaults".to_string() } else { options.join(",") })
+    }
+
+    // The fstab line for this mount, checked against the entries already in the file
+    pub fn to_line(&self, existing: &[FstabEntry]) -> Result<String, String> {
+        let source = self.checked_source()?;
+        let target = self.target.trim().trim_end_matches('/').to_string();
+        if !is_linux_path(&target) {
+            return Err(i18n::tr("mounts.invalid_target", &[self.target.clone()]));
+        }
+        if existing.iter().any(|e| e.target.trim_end_matches('/') == target) {
+            return Err(i18n::tr("mounts.duplicate_target", &[target]));
+        }
+        let fstype = if self.kind == MountKind::Bind { "none" } else { "drvfs" };
+        Ok(format!("{} {} {} {} 0 0", escape(&source), escape(&target), fstype, self.options()?))
+    }
+
+    pub fn target(&self) -> String {
+        self.target.trim().trim_end_matches('/').to_string()
+    }
+}
+
+// The file with one more entry at the end
+pub fn add_line(content: &str, line: &str) -> String {
+    let content = content.trim_end();
+    if content.is_empty() { format!("{}\n", line) } else { format!("{}\n{}\n", content, line) }
+}
+
+// The file without the entry at `line`. `target` has to match, so an entry that
+// moved since the file was read is never removed by mistake.
+pub fn remove_line(content: &str, line: usize, target: &str) -> Result<String, String> {
+    let matches = parse_fstab(content).iter().any(|e| e.line == line && e.target == target);
+    if !matches {
+        return Err(i18n::t("mounts.entry_changed"));
+    }
+    let lines: Vec<&str> = content.lines().enumerate()
+        .filter(|(i, _)| i + 1 != line)
+        .map(|(_, l)| l)
+        .collect();
+    Ok(format!("{}\n", lines.join("\n").trim_end()))
+}
+
+async fn run_as_root(executor: &WslCommandExecutor, distro_name: &str, script: &str) -> Result<String, String> {
+    let result = executor.execute_command(&["-d", distro_name, "-u", "root", "-e", "sh", "-c", script]).await;
+    if result.success {
+        Ok(result.output)
+    } else {
+        let output = result.output.trim().to_string();
+        let error = result.error.unwrap_or_default();
+        Err(if output.is_empty() { error } else { output })
+    }
+}
+
+pub async fn read_fstab(executor: &WslCommandExecutor, distro_name: &str) -> Result<String, String> {
+    run_as_root(executor, distro_name, "if [ -f /etc/fstab ]; then cat /etc/fstab; fi").await
+}
+
+pub async fn read_mounts(executor: &WslCommandExecutor, distro_name: &str) -> Result<Vec<MountInfo>, String> {
+    run_as_root(executor, distro_name, "cat /proc/mounts").await.map(|c| parse_proc_mounts(&c))
+}
+
+// The file without the entries mounted at one of `targets`
+fn without_targets(content: &str, targets: &[String]) -> String {
+    let skipped: Vec<usize> = parse_fstab(content).into_iter()
+        .filter(|e| targets.iter().any(|t| t.trim_end_matches('/') == e.target.trim_end_matches('/')))
+        .map(|e| e.line)
+        .collect();
+    content.lines().enumerate()
+        .filter(|(i, _)| !skipped.contains(&(i + 1)))
+        .map(|(_, l)| format!("{}\n", l))
+        .collect()
+}
+
+fn quote(path: &str) -> String {
+    format!("'{}'", path.replace('\'', "'\\''"))
+}
+
+// Which of `targets` don't exist yet as directories
+async fn missing_targets(executor: &WslCommandExecutor, distro_name: &str, targets: &[String]) -> Result<Vec<String>, String> {
+    if targets.is_empty() {
+        return Ok(Vec::new());
+    }
+    let script: String = targets.iter()
+        .map(|t| format!("[ -d {0} ] || echo {0}; ", quote(t)))
+        .collect();
+    let output = run_as_root(executor, distro_name, &script).await?;
+    Ok(targets.iter()
+        .filter(|t| output.lines().any(|l| l.trim_end() == t.as_str()))
+        .cloned()
+        .collect())
+}
+
+// Dry run of the whole file: nothing is mounted, no mount helper is called and nothing
+// is created. `mount -a` fails on a missing mount point, so entries for those of `targets`
+// that don't exist yet are left out of the run and returned, write_fstab creates them.
+pub async fn check_fstab(executor: &WslCommandExecutor, distro_name: &str, content: &str, targets: &[String]) -> Result<Vec<String>, String> {
+    let missing = missing_targets(executor, distro_name, targets).await?;
+    let checked = without_targets(content, &missing);
+    if checked.trim().is_empty() {
+        info!("Nothing left for the fstab dry run of '{}'", distro_name);
+        return Ok(missing);
+    }
+    let script = format!(
+        "f=$(mktemp) && cat << 'FSTAB_EOF' > \"$f\"\n{}\nFSTAB_EOF\nmount -a --fake --internal-only --verbose -T \"$f\" 2>&1; r=$?; rm -f \"$f\"; exit $r",
+        checked.trim_end()
+    );
+    run_as_root(executor, distro_name, &script).await.map(|_| missing).map_err(|e| {
+        warn!("Dry run of fstab for '{}' failed: {}", distro_name, e);
+        e
+    })
+}
+
+// Replace /etc/fstab, keeping the previous file as /etc/fstab.bak.
+// The mount points in `create` are made first, call it only after check_fstab passed.
+pub async fn write_fstab(executor: &WslCommandExecutor, distro_name: &str, content: &str, create: &[String]) -> Result<(), String> {
+    let mkdirs: String = create.iter()
+        .map(|t| format!("mkdir -p {} && ", quote(t)))
+        .collect();
+    let script = format!(
+        "{}([ -f /etc/fstab ] && cp -p /etc/fstab /etc/fstab.bak || true) && cat << 'FSTAB_EOF' > /etc/fstab.new\n{}\nFSTAB_EOF\nchmod 644 /etc/fstab.new && mv /etc/fstab.new /etc/fstab",
+        mkdirs,
+        content.trim_end()
+    );
+    match run_as_root(executor, distro_name, &script).await {
+        Ok(_) => {
+            info!("Saved /etc/fstab for '{}'", distro_name);
+            Ok(())
+        }
+        Err(e) => {
+            error!("Failed to save /etc/fstab for '{}': {}", distro_name, e);
+            Err(e)
+        }
+    }
+}
+
+// Mount one fstab entry now instead of at the next start
+pub async fn mount_entry(executor: &WslCommandExecutor, distro_name: &str, target: &str) -> Result<(), String> {
+    info!("Mounting {} in '{}'", target, distro_name);
+    let script = format!("mount {} 2>&1", quote(target));
+    run_as_root(executor, distro_name, &script).await.map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mount(kind: MountKind, source: &str, target: &str) -> NewMount {
+        NewMount {
+            kind,
+            source: source.to_string(),
+            target: target.to_string(),
+            drvfs: DrvfsOptions::default(),
+            extra_options: String::new(),
+        }
+    }
+
+    #[test]
+    fn escaped_spaces_are_unescaped() {
+        let entries = parse_fstab("C:\\My\\040Files /mnt/my\\040files drvfs metadata 0 0\n");
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].source, "C:\\My Files");
+        assert_eq!(entries[0].target, "/mnt/my files");
+        // A backslash that starts no octal escape stays as it is
+        assert_eq!(unescape("C:\\Users"), "C:\\Users");
+        assert_eq!(unescape("a\\134b\\011c"), "a\\b\tc");
+    }
+
+    #[test]
+    fn escape_round_trips() {
+        for field in ["/mnt/my files", "C:\\Tab\there", "plain"] {
+            assert_eq!(unescape(&escape(field)), field);
+        }
+        assert_eq!(escape("/mnt/a b"), "/mnt/a\\040b");
+    }
+
+    #[test]
+    fn comments_and_short_lines_are_skipped() {
+        let content = "# /etc/fstab\n\n  # indented comment\nbroken\nnone /data none bind 0 0\n/dev/sdb1 /mnt/b\n";
+        let entries = parse_fstab(content);
+        assert_eq!(entries.iter().map(|e| (e.line, e.target.as_str())).collect::<Vec<_>>(), vec![(5, "/data"), (6, "/mnt/b")]);
+        assert_eq!(entries[1].fstype, "");
+        assert_eq!(entries[1].options, "defaults");
+    }
+
+    #[test]
+    fn proc_mounts_skip_kernel_file_systems() {
+        let mounts = parse_proc_mounts("proc /proc proc rw 0 0\nC:\\040x /mnt/c drvfs rw,noatime 0 0\nshort line\n");
+        assert_eq!(mounts, vec![MountInfo {
+            source: "C: x".to_string(),
+            target: "/mnt/c".to_string(),
+            fstype: "drvfs".to_string(),
+            options: "rw,noatime".to_string(),
+        }]);
+    }
+
+    #[test]
+    fn new_lines_are_escaped_and_checked() {
+        let line = mount(MountKind::WindowsFolder, "D:/My Data/", "/mnt/my data/").to_line(&[]).unwrap();
+        assert_eq!(line, "D:\\134My\\040Data /mnt/my\\040data drvfs defaults 0 0");
+        assert_eq!(mount(MountKind::WindowsFolder, "d:", "/mnt/d").to_line(&[]).unwrap(), "d:\\134 /mnt/d drvfs defaults 0 0");
+
+        let existing = parse_fstab("none /mnt/my\\040data none bind 0 0\n");
+        assert_eq!(mount(MountKind::Bind, "/srv", "/mnt/my data").to_line(&existing), Err("mounts.duplicate_target".to_string()));
+        assert_eq!(mount(MountKind::NetworkShare, "\\\\server", "/mnt/s").to_line(&[]), Err("mounts.invalid_share".to_string()));
+        assert_eq!(mount(MountKind::Bind, "/srv", "/").to_line(&[]), Err("mounts.invalid_target".to_string()));
+    }
+
+    #[test]
+    fn remove_line_needs_a_matching_target() {
+        let content = "# header\nnone /a none bind 0 0\nnone /b none bind 0 0\n";
+        assert_eq!(remove_line(content, 2, "/a").unwrap(), "# header\nnone /b none bind 0 0\n");
+        assert_eq!(remove_line(content, 2, "/b"), Err("mounts.entry_changed".to_string()));
+    }
+
+    #[test]
+    fn dry_run_leaves_out_missing_targets() {
+        let content = "# header\nnone /a none bind 0 0\nnone /new\\040dir none bind 0 0\n";
+        assert_eq!(without_targets(content, &["/new dir/".to_string()]), "# header\nnone /a none bind 0 0\n");
+        assert_eq!(without_targets(content, &[]), content);
+    }
+}
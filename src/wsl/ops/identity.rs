@@ -0,0 +1,123 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use tracing::{info, warn};
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::models::WslCommandResult;
+
+// Identity changes applied to a freshly cloned distro
+#[derive(Debug, Clone, Default)]
+pub struct IdentityReset {
+    pub hostname: Option<String>,
+    pub regenerate_machine_id: bool,
+    pub regenerate_ssh_keys: bool,
+    pub default_user: Option<String>,
+}
+
+impl IdentityReset {
+    pub fn is_empty(&self) -> bool {
+        self.hostname.is_none() && !self.regenerate_machine_id && !self.regenerate_ssh_keys && self.default_user.is_none()
+    }
+}
+
+// RFC 1123 host name label: 1-63 chars of [A-Za-z0-9-], not starting or ending with '-'
+pub fn is_valid_hostname(hostname: &str) -> bool {
+    !hostname.is_empty()
+        && hostname.len() <= 63
+        && !hostname.starts_with('-')
+        && !hostname.ends_with('-')
+        && hostname.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+// Linux user name: lowercase letter or '_' first, then [a-z0-9_-], at most 32 chars
+pub fn is_valid_username(username: &str) -> bool {
+    let mut chars = username.chars();
+    match chars.next() {
+        Some(c) if c.is_ascii_lowercase() || c == '_' => {}
+        _ => return false,
+    }
+    username.len() <= 32 && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '-')
+}
+
+async fn run_as_root(executor: &WslCommandExecutor, distro_name: &str, cmd: &str) -> WslCommandResult<String> {
+    executor.execute_command(&["-d", distro_name, "-u", "root", "-e", "sh", "-c", cmd]).await
+}
+
+// Apply the identity reset as root inside the target distro.
+// Each step is attempted; failures are collected and reported together.
+pub async fn apply_identity_reset(
+    executor: &WslCommandExecutor,
+    distro_name: &str,
+    reset: &IdentityReset,
+) -> WslCommandResult<String> {
+    if reset.is_empty() {
+        return WslCommandResult::success(String::new(), None);
+    }
+    info!("Applying identity reset to '{}': {:?}", distro_name, reset);
+    let mut errors = Vec::new();
+
+    if reset.regenerate_machine_id {
+        let cmd = "rm -f /etc/machine-id /var/lib/dbus/machine-id; \
+            if command -v systemd-machine-id-setup >/dev/null 2>&1; then systemd-machine-id-setup >/dev/null; \
+            elif command -v dbus-uuidgen >/dev/null 2>&1; then dbus-uuidgen --ensure=/etc/machine-id; \
+            else cat /proc/sys/kernel/random/uuid | tr -d '-' > /etc/machine-id; fi; \
+            [ -d /var/lib/dbus ] && ln -sf /etc/machine-id /var/lib/dbus/machine-id; true";
+        let res = run_as_root(executor, distro_name, cmd).await;
+        if !res.success {
+            errors.push(format!("machine-id: {}", res.error.unwrap_or_default()));
+        }
+    }
+
+    if reset.regenerate_ssh_keys {
+        // Only distros with an SSH server have host keys to regenerate
+        let cmd = "if [ -d /etc/ssh ] && command -v ssh-keygen >/dev/null 2>&1; then \
+            rm -f /etc/ssh/ssh_host_*; ssh-keygen -A; fi";
+        let res = run_as_root(executor, distro_name, cmd).await;
+        if !res.success {
+            errors.push(format!("ssh host keys: {}", res.error.unwrap_or_default()));
+        }
+    }
+
+    if let Some(ref username) = reset.default_user {
+        let check = executor.execute_command(&["-d", distro_name, "-e", "id", "-u", username]).await;
+        if !check.success {
+            info!("Creating default user '{}' in '{}'", username, distro_name);
+            let cmd = format!(
+                "if command -v useradd >/dev/null 2>&1; then useradd -m -s /bin/bash {0}; else adduser -D {0}; fi",
+                username
+            );
+            let res = run_as_root(executor, distro_name, &cmd).await;
+            if !res.success {
+                errors.push(format!("user '{}': {}", username, res.error.unwrap_or_default()));
+            }
+        }
+    }
+
+    // Hostname and default user both live in /etc/wsl.conf
+    if reset.hostname.is_some() || reset.default_user.is_some() {
+        // Only these keys are edited, through the same path as the settings dialog so
+        // the change shows up in the config history
+        let mut conf = super::config::WslConf::default();
+        if let Some(ref hostname) = reset.hostname {
+            conf.network.hostname = Some(hostname.clone());
+        }
+        if let Some(ref username) = reset.default_user {
+            conf.user.default = Some(username.clone());
+        }
+        let meta = super::config::check_wsl_version_support(executor).await;
+        if let Err(e) = super::config::save_wsl_conf(executor, distro_name, &conf, &meta).await {
+            errors.push(format!("wsl.conf: {}", e));
+        }
+    }
+
+    // Restart so wsl.conf changes take effect on next launch
+    let _ = executor.execute_command(&["--terminate", distro_name]).await;
+
+    if errors.is_empty() {
+        WslCommandResult::success("Identity reset applied".into(), None)
+    } else {
+        let err = errors.join("; ");
+        warn!("Identity reset for '{}' partially failed: {}", distro_name, err);
+        WslCommandResult::error(String::new(), err)
+    }
+}
@@ -0,0 +1,254 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Local cache of downloaded rootfs images
+//
+// Images live in ~/.wsldashboard/images, named after distro, version and hash, and
+// are indexed in images/index.toml. An image is found again by its hash when the
+// catalog publishes one, otherwise by download URL and catalog, never by name and
+// version alone. All functions here block on file I/O and hashing, call them through
+// spawn_blocking.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::api::models::MirrorSource;
+
+const INDEX_FILE_NAME: &str = "index.toml";
+
+// Serializes index read-modify-write cycles between installs and the settings page
+static INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedImage {
+    pub name: String,
+    pub version: String,
+    pub sha256: String,
+    // File name inside the cache directory
+    pub file: String,
+    #[serde(default)]
+    pub format: String,
+    #[serde(default)]
+    pub size: u64,
+    // Mirror the image was downloaded from
+    #[serde(default)]
+    pub source: String,
+    // Download URL and catalog label of that mirror
+    #[serde(default)]
+    pub url: String,
+    #[serde(default)]
+    pub catalog: String,
+    #[serde(rename = "added-time")]
+    pub added_time: String,
+    #[serde(rename = "last-used")]
+    pub last_used: String,
+}
+
+impl CachedImage {
+    pub fn path(&self) -> PathBuf {
+        cache_dir().join(&self.file)
+    }
+
+    fn is_intact(&self) -> bool {
+        std::fs::metadata(self.path()).map(|m| m.len() == self.size).unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CacheIndex {
+    #[serde(default)]
+    images: Vec<CachedImage>,
+}
+
+pub fn cache_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".wsldashboard").join("images")
+}
+
+fn load_index() -> CacheIndex {
+    std::fs::read_to_string(cache_dir().join(INDEX_FILE_NAME))
+        .ok()
+        .and_then(|content| toml::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+fn save_index(index: &CacheIndex) -> Result<(), String> {
+    let dir = cache_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let content = toml::to_string_pretty(index).map_err(|e| e.to_string())?;
+    std::fs::write(dir.join(INDEX_FILE_NAME), content).map_err(|e| e.to_string())
+}
+
+fn now_ms() -> String {
+    chrono::Utc::now().timestamp_millis().to_string()
+}
+
+fn sanitize(s: &str) -> String {
+    s.chars().map(|c| if c.is_ascii_alphanumeric() || c == '.' || c == '-' { c } else { '_' }).collect()
+}
+
+// Keep the archive extension so `wsl --import` and the file pickers recognize it
+fn extension_for(format: &str) -> &'static str {
+    match format.to_lowercase().as_str() {
+        "tar.xz" | "xz" => "tar.xz",
+        "tar" => "tar",
+        "wsl" => "wsl",
+        "appx" => "appx",
+        _ => "tar.gz",
+    }
+}
+
+// All intact images, most recently used first. Entries whose file is gone are dropped.
+pub fn list() -> Vec<CachedImage> {
+    let _lock = INDEX_LOCK.lock().unwrap();
+    let mut index = load_index();
+    let before = index.images.len();
+    index.images.retain(|i| i.is_intact());
+    if index.images.len() != before {
+        let _ = save_index(&index);
+    }
+    let mut images = index.images;
+    images.sort_by_key(|i| std::cmp::Reverse(i.last_used.parse::<i64>().unwrap_or(0)));
+    images
+}
+
+fn matches(image: &CachedImage, source: &MirrorSource) -> bool {
+    match source.sha256.as_deref().map(str::trim).filter(|h| !h.is_empty()) {
+        Some(hash) => image.sha256.eq_ignore_ascii_case(hash),
+        None => !image.url.is_empty() && image.url == source.url && image.catalog == source.catalog,
+    }
+}
+
+// A cached image for one of `sources`, without hashing it. Call `verify` before using it.
+pub fn find(sources: &[MirrorSource]) -> Option<CachedImage> {
+    list().into_iter().find(|i| sources.iter().any(|s| matches(i, s)))
+}
+
+// Hash the file again; a damaged or replaced image is dropped from the cache
+pub fn verify(image: CachedImage) -> Option<CachedImage> {
+    match crate::utils::hash::sha256_file(&image.path(), |_| {}) {
+        Ok(actual) if actual.eq_ignore_ascii_case(&image.sha256) => Some(image),
+        Ok(actual) => {
+            warn!("Image cache: '{}' hashes to {} instead of {}, removing it", image.file, actual, image.sha256);
+            let _ = remove(&image.sha256);
+            None
+        }
+        Err(e) => {
+            warn!("Image cache: '{}' could not be hashed: {}", image.file, e);
+            None
+        }
+    }
+}
+
+pub fn find_by_id(sha256: &str) -> Option<CachedImage> {
+    list().into_iter().find(|i| i.sha256 == sha256)
+}
+
+// Mark an image as used so LRU pruning keeps it
+pub fn touch(sha256: &str) {
+    let _lock = INDEX_LOCK.lock().unwrap();
+    let mut index = load_index();
+    if let Some(image) = index.images.iter_mut().find(|i| i.sha256 == sha256) {
+        image.last_used = now_ms();
+        let _ = save_index(&index);
+    }
+}
+
+// Move a finished download into the cache. An identical image already in the cache
+// is reused and the download removed.
+pub fn store(name: &str, version: &str, source: &MirrorSource, downloaded: &Path) -> Result<CachedImage, String> {
+    let sha256 = crate::utils::hash::sha256_file(downloaded, |_| {})?;
+    let size = std::fs::metadata(downloaded).map_err(|e| e.to_string())?.len();
+
+    let _lock = INDEX_LOCK.lock().unwrap();
+    let mut index = load_index();
+    if let Some(existing) = index.images.iter_mut().find(|i| i.sha256 == sha256 && i.is_intact()) {
+        existing.last_used = now_ms();
+        // Found again through this mirror from now on
+        existing.url = source.url.clone();
+        existing.catalog = source.catalog.clone();
+        let image = existing.clone();
+        save_index(&index)?;
+        let _ = std::fs::remove_file(downloaded);
+        info!("Image cache: '{} {}' already cached as '{}'", name, version, image.file);
+        return Ok(image);
+    }
+
+    let dir = cache_dir();
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    let file = format!("{}-{}-{}.{}", sanitize(name), sanitize(version), &sha256[..12.min(sha256.len())], extension_for(&source.format));
+    let target = dir.join(&file);
+    if std::fs::rename(downloaded, &target).is_err() {
+        // Temp folder on another volume
+        std::fs::copy(downloaded, &target).map_err(|e| e.to_string())?;
+        let _ = std::fs::remove_file(downloaded);
+    }
+
+    let now = now_ms();
+    let image = CachedImage {
+        name: name.to_string(),
+        version: version.to_string(),
+        sha256,
+        file,
+        format: source.format.clone(),
+        size,
+        source: source.mirror.clone(),
+        url: source.url.clone(),
+        catalog: source.catalog.clone(),
+        added_time: now.clone(),
+        last_used: now,
+    };
+    index.images.retain(|i| i.file != image.file);
+    index.images.push(image.clone());
+    save_index(&index)?;
+    info!("Image cache: stored '{} {}' as '{}' ({} bytes)", name, version, image.file, size);
+    Ok(image)
+}
+
+pub fn remove(sha256: &str) -> Result<(), String> {
+    let _lock = INDEX_LOCK.lock().unwrap();
+    let mut index = load_index();
+    if let Some(pos) = index.images.iter().position(|i| i.sha256 == sha256) {
+        let image = index.images.remove(pos);
+        match std::fs::remove_file(image.path()) {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.to_string()),
+        }
+        info!("Image cache: removed '{}'", image.file);
+        save_index(&index)?;
+    }
+    Ok(())
+}
+
+// Evict least recently used images until the cache fits `max_bytes`.
+// Images referenced by templates (`pinned` file paths) and the image with the `keep`
+// hash (the one just installed) are never evicted.
+pub fn prune(max_bytes: u64, pinned: &[String], keep: &str) -> Vec<String> {
+    let mut images = list();
+    let is_pinned = |image: &CachedImage| {
+        let path = image.path();
+        image.sha256 == keep || pinned.iter().any(|p| Path::new(p) == path)
+    };
+
+    let mut total: u64 = images.iter().map(|i| i.size).sum();
+    let mut removed = Vec::new();
+    // Oldest last
+    while total > max_bytes {
+        let Some(pos) = images.iter().rposition(|i| !is_pinned(i)) else { break; };
+        let image = images.remove(pos);
+        match remove(&image.sha256) {
+            Ok(_) => {
+                total = total.saturating_sub(image.size);
+                removed.push(image.file);
+            }
+            Err(e) => {
+                warn!("Image cache: failed to evict '{}': {}", image.file, e);
+                break;
+            }
+        }
+    }
+    removed
+}
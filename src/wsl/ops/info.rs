@@ -32,7 +32,7 @@ pub async fn detect_fastest_source(_executor: &WslCommandExecutor) -> bool {
 
     let result = task::spawn_blocking(|| {
         // Check GitHub domain with 5 seconds timeout
-        match ureq::head(crate::app::GITHUB_DOMAIN)
+        match crate::network::http::head(crate::app::GITHUB_DOMAIN)
             .timeout(std::time::Duration::from_secs(5))
             .call() 
         {
@@ -0,0 +1,42 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// The kernel the WSL 2 VM is running. Every distro shares the VM, so asking any
+// running distro is enough.
+
+use tracing::{debug, warn};
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::models::{WslStatus, WslVersion};
+
+#[derive(Debug, Clone)]
+pub struct RunningKernel {
+    // `uname -r`, e.g. 6.6.87.2-microsoft-standard-WSL2
+    pub release: String,
+    // Changes every time the VM boots
+    pub boot_id: String,
+    // Distro the values were read from
+    pub distro: String,
+}
+
+// None when no WSL 2 distro is running. With `start_default` the default distro
+// is started to find out, like opening a terminal would.
+pub async fn running_kernel(executor: &WslCommandExecutor, start_default: bool) -> Option<RunningKernel> {
+    let distros = executor.list_distros().await.data.unwrap_or_default();
+    let wsl2 = || distros.iter().filter(|d| d.version == WslVersion::V2);
+    let distro = wsl2().find(|d| d.status == WslStatus::Running)
+        .or_else(|| if start_default { wsl2().find(|d| d.is_default).or_else(|| wsl2().next()) } else { None })?;
+
+    let result = executor.execute_command(&[
+        "-d", &distro.name, "-u", "root", "-e", "sh", "-c",
+        "uname -r; cat /proc/sys/kernel/random/boot_id",
+    ]).await;
+    if !result.success {
+        warn!("Failed to read the kernel version in '{}': {:?}", distro.name, result.error);
+        return None;
+    }
+    let mut lines = result.output.lines().map(str::trim).filter(|l| !l.is_empty());
+    let release = lines.next()?.to_string();
+    let boot_id = lines.next().unwrap_or_default().to_string();
+    debug!("Running kernel {} (boot {}) read from '{}'", release, boot_id, distro.name);
+    Some(RunningKernel { release, boot_id, distro: distro.name.clone() })
+}
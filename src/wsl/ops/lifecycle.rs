@@ -63,6 +63,12 @@ pub async fn shutdown_wsl(executor: &WslCommandExecutor) -> WslCommandResult<Str
 
 pub async fn delete_distro(executor: &WslCommandExecutor, config_manager: &ConfigManager, distro_name: &str) -> WslCommandResult<String> {
     info!("Operation: Delete WSL distribution - {}", distro_name);
+
+    // Checked before any cleanup, the instance config holding the flag is removed below
+    if ConfigManager::is_protected(distro_name) {
+        warn!("Refusing to delete protected distribution '{}'", distro_name);
+        return WslCommandResult::error(String::new(), crate::i18n::tr("protect.blocked", &[distro_name.to_string()]));
+    }
     
     // 1. Determine PackageFamilyName and if it's the only instance before unregistering
     // Use native registry access instead of slow PowerShell
@@ -0,0 +1,617 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Linter for /etc/wsl.conf and ~/.wslconfig
+//
+// Both files are checked against a schema of the keys WSL knows: value types,
+// allowed values, deprecated locations, keys newer than the installed WSL and
+// settings that cancel each other out. Unknown names get a "did you mean"
+// suggestion. Every diagnostic points at a line of the file.
+
+use std::collections::{HashMap, HashSet};
+use std::net::Ipv4Addr;
+use std::path::Path;
+use std::sync::Mutex;
+use once_cell::sync::Lazy;
+use tracing::{info, warn};
+use crate::i18n;
+use crate::utils::ini_doc::{IniDocument, IniEntry};
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::models::WslStatus;
+use crate::config::WslConfPreset;
+use super::config::{check_wsl_version_support, read_wsl_conf, update_wsl_conf, WslVersionMeta};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Info,
+}
+
+impl Severity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Error => "error",
+            Self::Warning => "warning",
+            Self::Info => "info",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    // 1-based, 0 when it concerns the whole file
+    pub line: usize,
+    pub message: String,
+}
+
+// Diagnostics of one file, `file` is what the user sees, e.g. `Ubuntu: /etc/wsl.conf`
+#[derive(Debug, Clone)]
+pub struct FileReport {
+    pub file: String,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum ValueKind {
+    Bool,
+    Number,
+    // Number with an optional B/KB/MB/GB/TB unit
+    Size,
+    Choice(&'static [&'static str]),
+    Text,
+    LinuxPath,
+    WindowsPath,
+    // Windows path of a file that has to exist
+    WindowsFile,
+    Hostname,
+    Ports,
+    Ipv4,
+}
+
+struct KeySpec {
+    section: &'static str,
+    key: &'static str,
+    kind: ValueKind,
+    // First WSL release that reads the key
+    since: Option<&'static str>,
+    // Where the key lives now, for keys that moved
+    moved_to: Option<&'static str>,
+}
+
+const fn key(section: &'static str, key: &'static str, kind: ValueKind) -> KeySpec {
+    KeySpec { section, key, kind, since: None, moved_to: None }
+}
+
+const fn since(section: &'static str, key: &'static str, kind: ValueKind, version: &'static str) -> KeySpec {
+    KeySpec { section, key, kind, since: Some(version), moved_to: None }
+}
+
+const fn moved(section: &'static str, key: &'static str, kind: ValueKind, to: &'static str) -> KeySpec {
+    KeySpec { section, key, kind, since: Some("2.0.0"), moved_to: Some(to) }
+}
+
+const NETWORKING_MODES: &[&str] = &crate::utils::wsl_config::NETWORKING_MODES;
+const MEMORY_RECLAIM_MODES: &[&str] = &crate::utils::wsl_config::MEMORY_RECLAIM_MODES;
+
+const WSL_CONF_SCHEMA: &[KeySpec] = &[
+    key("automount", "enabled", ValueKind::Bool),
+    key("automount", "mountFsTab", ValueKind::Bool),
+    key("automount", "root", ValueKind::LinuxPath),
+    key("automount", "options", ValueKind::Text),
+    key("automount", "ldconfig", ValueKind::Bool),
+    key("network", "generateHosts", ValueKind::Bool),
+    key("network", "generateResolvConf", ValueKind::Bool),
+    key("network", "hostname", ValueKind::Hostname),
+    key("interop", "enabled", ValueKind::Bool),
+    key("interop", "appendWindowsPath", ValueKind::Bool),
+    key("user", "default", ValueKind::Text),
+    since("boot", "systemd", ValueKind::Bool, "0.67.6"),
+    since("boot", "command", ValueKind::Text, "0.67.6"),
+    since("boot", "protectBinfmt", ValueKind::Bool, "0.67.6"),
+    since("gpu", "enabled", ValueKind::Bool, "1.0.0"),
+    since("time", "useWindowsTimezone", ValueKind::Bool, "1.0.0"),
+];
+
+const WSL_CONFIG_SCHEMA: &[KeySpec] = &[
+    key("wsl2", "memory", ValueKind::Size),
+    key("wsl2", "processors", ValueKind::Number),
+    key("wsl2", "swap", ValueKind::Size),
+    key("wsl2", "swapFile", ValueKind::WindowsPath),
+    key("wsl2", "kernel", ValueKind::WindowsFile),
+    since("wsl2", "kernelModules", ValueKind::WindowsFile, "2.0.0"),
+    key("wsl2", "kernelCommandLine", ValueKind::Text),
+    key("wsl2", "localhostForwarding", ValueKind::Bool),
+    key("wsl2", "nestedVirtualization", ValueKind::Bool),
+    key("wsl2", "vmIdleTimeout", ValueKind::Number),
+    key("wsl2", "pageReporting", ValueKind::Bool),
+    key("wsl2", "guiApplications", ValueKind::Bool),
+    key("wsl2", "debugConsole", ValueKind::Bool),
+    key("wsl2", "safeMode", ValueKind::Bool),
+    key("wsl2", "defaultVhdSize", ValueKind::Size),
+    key("wsl2", "dnsProxy", ValueKind::Bool),
+    key("wsl2", "maxCrashDumpCount", ValueKind::Number),
+    since("wsl2", "networkingMode", ValueKind::Choice(NETWORKING_MODES), "2.0.0"),
+    since("wsl2", "dnsTunneling", ValueKind::Bool, "2.0.0"),
+    since("wsl2", "firewall", ValueKind::Bool, "2.0.0"),
+    since("wsl2", "autoProxy", ValueKind::Bool, "2.0.0"),
+    since("experimental", "autoMemoryReclaim", ValueKind::Choice(MEMORY_RECLAIM_MODES), "2.0.0"),
+    since("experimental", "sparseVhd", ValueKind::Bool, "2.0.0"),
+    since("experimental", "hostAddressLoopback", ValueKind::Bool, "2.0.0"),
+    since("experimental", "bestEffortDnsParsing", ValueKind::Bool, "2.0.0"),
+    since("experimental", "ignoredPorts", ValueKind::Ports, "2.0.0"),
+    since("experimental", "dnsTunnelingIpAddress", ValueKind::Ipv4, "2.0.0"),
+    since("experimental", "initialAutoProxyTimeout", ValueKind::Number, "2.0.0"),
+    since("experimental", "useWindowsDnsCache", ValueKind::Bool, "2.0.0"),
+    // These started out under [experimental]; WSL still reads them there, for now
+    moved("experimental", "networkingMode", ValueKind::Choice(NETWORKING_MODES), "wsl2"),
+    moved("experimental", "dnsTunneling", ValueKind::Bool, "wsl2"),
+    moved("experimental", "firewall", ValueKind::Bool, "wsl2"),
+    moved("experimental", "autoProxy", ValueKind::Bool, "wsl2"),
+];
+
+// ===== Helpers =====
+
+fn parse_version(version: &str) -> Vec<u64> {
+    version.split('.').map(|p| p.parse().unwrap_or(0)).collect()
+}
+
+fn version_at_least(version: &str, min: &str) -> bool {
+    let (v, m) = (parse_version(version), parse_version(min));
+    for i in 0..v.len().max(m.len()) {
+        let (a, b) = (v.get(i).copied().unwrap_or(0), m.get(i).copied().unwrap_or(0));
+        if a != b {
+            return a > b;
+        }
+    }
+    true
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.to_lowercase().chars().collect();
+    let b: Vec<char> = b.to_lowercase().chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        let mut cur = vec![i; b.len() + 1];
+        for j in 1..=b.len() {
+            let cost = if a[i - 1] == b[j - 1] { 0 } else { 1 };
+            cur[j] = (prev[j] + 1).min(cur[j - 1] + 1).min(prev[j - 1] + cost);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+// Closest known name, only when it is close enough to be a typo
+fn suggest<'a>(name: &str, candidates: impl Iterator<Item = &'a str>) -> Option<&'a str> {
+    let limit = (name.len() / 3).clamp(1, 3);
+    candidates
+        .map(|c| (edit_distance(name, c), c))
+        .filter(|(d, _)| *d <= limit)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+fn is_bool(value: &str) -> bool {
+    ["true", "false", "1", "0", "yes", "no"].iter().any(|v| value.eq_ignore_ascii_case(v))
+}
+
+fn is_true(value: &str) -> bool {
+    value.eq_ignore_ascii_case("true") || value == "1" || value.eq_ignore_ascii_case("yes")
+}
+
+// Dotted names are allowed, each label is checked like a clone's host name and the
+// whole name keeps to the same 63 characters
+fn is_hostname(value: &str) -> bool {
+    value.len() <= 63 && value.split('.').all(super::identity::is_valid_hostname)
+}
+
+// WSL matches section and key names regardless of case, e.g. `[Boot]` and `Systemd=true`
+fn same_name(a: &str, b: &str) -> bool {
+    a.eq_ignore_ascii_case(b)
+}
+
+fn is_windows_absolute(value: &str) -> bool {
+    let bytes = value.as_bytes();
+    (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && (bytes[2] == b'\\' || bytes[2] == b'/'))
+        || value.starts_with("\\\\")
+}
+
+// ===== Linter =====
+
+struct Linter<'a> {
+    schema: &'a [KeySpec],
+    meta: &'a WslVersionMeta,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> Linter<'a> {
+    fn push(&mut self, severity: Severity, line: usize, message: String) {
+        self.diagnostics.push(Diagnostic { severity, line, message });
+    }
+
+    fn known_sections(&self) -> Vec<&'static str> {
+        let mut sections: Vec<&'static str> = self.schema.iter().map(|s| s.section).collect();
+        sections.dedup();
+        sections
+    }
+
+    fn is_known_section(&self, section: &str) -> bool {
+        self.schema.iter().any(|s| same_name(s.section, section))
+    }
+
+    fn spec(&self, section: &str, key: &str) -> Option<&'a KeySpec> {
+        self.schema.iter().find(|s| same_name(s.section, section) && same_name(s.key, key))
+    }
+
+    fn check_sections(&mut self, doc: &IniDocument) {
+        let known = self.known_sections();
+        for (name, line) in doc.sections() {
+            if self.is_known_section(&name) {
+                continue;
+            }
+            let message = match suggest(&name, known.iter().copied()) {
+                Some(s) => i18n::tr("lint.unknown_section_suggest", &[name.clone(), s.to_string()]),
+                None => i18n::tr("lint.unknown_section", &[name.clone()]),
+            };
+            self.push(Severity::Warning, line, message);
+        }
+        for line in doc.invalid_lines() {
+            self.push(Severity::Warning, line, i18n::t("lint.invalid_line"));
+        }
+    }
+
+    fn check_entry(&mut self, entry: &IniEntry) {
+        if entry.section.is_empty() {
+            self.push(Severity::Warning, entry.line, i18n::tr("lint.outside_section", &[entry.key.clone()]));
+            return;
+        }
+        if !self.is_known_section(&entry.section) {
+            // Already reported on the section header
+            return;
+        }
+
+        let Some(spec) = self.spec(&entry.section, &entry.key) else {
+            self.check_unknown_key(entry);
+            return;
+        };
+
+        if let Some(to) = spec.moved_to {
+            self.push(Severity::Warning, entry.line, i18n::tr("lint.deprecated_location", &[
+                entry.key.clone(), entry.section.clone(), to.to_string(),
+            ]));
+        }
+        if let Some(min) = spec.since {
+            if !self.meta.detection_failed && !self.meta.version_string.is_empty()
+                && !version_at_least(&self.meta.version_string, min)
+            {
+                self.push(Severity::Warning, entry.line, i18n::tr("lint.unsupported", &[
+                    entry.key.clone(), min.to_string(), self.meta.version_string.clone(),
+                ]));
+            }
+        }
+        self.check_value(spec, entry);
+    }
+
+    fn check_unknown_key(&mut self, entry: &IniEntry) {
+        // A key that exists, just in another section
+        if let Some(other) = self.schema.iter().find(|s| same_name(s.key, &entry.key) && s.moved_to.is_none()) {
+            self.push(Severity::Warning, entry.line, i18n::tr("lint.wrong_section", &[
+                entry.key.clone(), other.section.to_string(), entry.section.clone(),
+            ]));
+            return;
+        }
+        let in_section = self.schema.iter().filter(|s| same_name(s.section, &entry.section)).map(|s| s.key);
+        let message = match suggest(&entry.key, in_section) {
+            Some(s) => i18n::tr("lint.unknown_key_suggest", &[entry.key.clone(), entry.section.clone(), s.to_string()]),
+            None => i18n::tr("lint.unknown_key", &[entry.key.clone(), entry.section.clone()]),
+        };
+        self.push(Severity::Warning, entry.line, message);
+    }
+
+    fn check_value(&mut self, spec: &KeySpec, entry: &IniEntry) {
+        let value = entry.value.trim();
+        let key = entry.key.clone();
+        let error = match spec.kind {
+            ValueKind::Bool if !is_bool(value) => Some(i18n::tr("lint.invalid_bool", &[key, value.to_string()])),
+            ValueKind::Number if value.parse::<u64>().is_err() => Some(i18n::tr("lint.invalid_number", &[key, value.to_string()])),
+            ValueKind::Size if crate::utils::wsl_config::parse_size(value).is_none() => {
+                Some(i18n::tr("lint.invalid_size", &[key, value.to_string()]))
+            }
+            ValueKind::Choice(choices) if !choices.iter().any(|c| c.eq_ignore_ascii_case(value)) => {
+                Some(i18n::tr("lint.invalid_choice", &[key, value.to_string(), choices.join(", ")]))
+            }
+            ValueKind::LinuxPath if !value.starts_with('/') => Some(i18n::tr("lint.invalid_linux_path", &[key, value.to_string()])),
+            ValueKind::WindowsPath | ValueKind::WindowsFile if !is_windows_absolute(value) => {
+                Some(i18n::tr("lint.invalid_windows_path", &[key, value.to_string()]))
+            }
+            ValueKind::WindowsFile if !Path::new(value).is_file() => Some(i18n::tr("lint.file_not_found", &[key, value.to_string()])),
+            ValueKind::Hostname if !is_hostname(value) => Some(i18n::tr("lint.invalid_hostname", &[key, value.to_string()])),
+            ValueKind::Ports if !value.split(',').all(|p| p.trim().parse::<u16>().is_ok_and(|p| p > 0)) => {
+                Some(i18n::tr("lint.invalid_ports", &[key, value.to_string()]))
+            }
+            ValueKind::Ipv4 if value.parse::<Ipv4Addr>().is_err() => Some(i18n::tr("lint.invalid_ip", &[key, value.to_string()])),
+            _ => None,
+        };
+        if let Some(message) = error {
+            self.push(Severity::Error, entry.line, message);
+        }
+    }
+
+    // Keys that are set more than once, WSL reads the last one
+    fn check_duplicates(&mut self, entries: &[IniEntry]) {
+        for (i, entry) in entries.iter().enumerate() {
+            if let Some(later) = entries[i + 1..].iter().find(|e| same_name(&e.section, &entry.section) && same_name(&e.key, &entry.key)) {
+                self.push(Severity::Warning, entry.line, i18n::tr("lint.duplicate_key", &[entry.key.clone(), later.line.to_string()]));
+            }
+        }
+    }
+
+    fn run(mut self, content: &str, conflicts: fn(&mut Linter, &[IniEntry])) -> Vec<Diagnostic> {
+        let doc = IniDocument::parse(content);
+        let entries = doc.entries();
+        self.check_sections(&doc);
+        for entry in &entries {
+            self.check_entry(entry);
+        }
+        self.check_duplicates(&entries);
+        conflicts(&mut self, &entries);
+        self.diagnostics.sort_by_key(|d| (d.line, d.severity));
+        self.diagnostics
+    }
+}
+
+// Last value of a key, like WSL reads it
+fn value_of<'e>(entries: &'e [IniEntry], section: &str, key: &str) -> Option<&'e IniEntry> {
+    entries.iter().rev().find(|e| same_name(&e.section, section) && same_name(&e.key, key))
+}
+
+fn wsl_conf_conflicts(linter: &mut Linter, entries: &[IniEntry]) {
+    let disabled = |section: &str, key: &str| value_of(entries, section, key).is_some_and(|e| !is_true(&e.value));
+
+    if disabled("automount", "enabled") {
+        if let Some(root) = value_of(entries, "automount", "root") {
+            linter.push(Severity::Warning, root.line, i18n::tr("lint.no_effect", &["root".to_string(), "[automount] enabled=false".to_string()]));
+        }
+    }
+    if disabled("interop", "enabled") {
+        if let Some(path) = value_of(entries, "interop", "appendWindowsPath").filter(|e| is_true(&e.value)) {
+            linter.push(Severity::Warning, path.line, i18n::tr("lint.no_effect", &["appendWindowsPath".to_string(), "[interop] enabled=false".to_string()]));
+        }
+    }
+    if disabled("network", "generateHosts") {
+        if let Some(hostname) = value_of(entries, "network", "hostname") {
+            linter.push(Severity::Info, hostname.line, i18n::t("lint.hostname_without_hosts"));
+        }
+    }
+}
+
+fn wsl_config_conflicts(linter: &mut Linter, entries: &[IniEntry]) {
+    let mode = value_of(entries, "wsl2", "networkingMode")
+        .or_else(|| value_of(entries, "experimental", "networkingMode"))
+        .map(|e| e.value.to_lowercase())
+        .unwrap_or_else(|| "nat".to_string());
+
+    if mode == "mirrored" {
+        if let Some(e) = value_of(entries, "wsl2", "localhostForwarding") {
+            linter.push(Severity::Warning, e.line, i18n::t("lint.localhost_forwarding_mirrored"));
+        }
+    } else {
+        for key in ["hostAddressLoopback", "ignoredPorts"] {
+            if let Some(e) = value_of(entries, "experimental", key) {
+                linter.push(Severity::Warning, e.line, i18n::tr("lint.requires_mirrored", &[key.to_string()]));
+            }
+        }
+    }
+
+    let dns_tunneling = value_of(entries, "wsl2", "dnsTunneling")
+        .or_else(|| value_of(entries, "experimental", "dnsTunneling"))
+        .map(|e| is_true(&e.value))
+        .unwrap_or(true);
+    if !dns_tunneling {
+        for key in ["dnsTunnelingIpAddress", "bestEffortDnsParsing"] {
+            if let Some(e) = value_of(entries, "experimental", key) {
+                linter.push(Severity::Warning, e.line, i18n::tr("lint.requires_dns_tunneling", &[key.to_string()]));
+            }
+        }
+    }
+
+    if let Some(memory) = value_of(entries, "wsl2", "memory") {
+        let total = crate::utils::system::get_total_memory();
+        if let Some(bytes) = crate::utils::wsl_config::parse_size(&memory.value) {
+            if total > 0 && bytes > total {
+                linter.push(Severity::Warning, memory.line, i18n::tr("lint.memory_too_large", &[
+                    memory.value.clone(), crate::wsl::ops::preflight::format_bytes(total),
+                ]));
+            }
+        }
+    }
+    if let Some(processors) = value_of(entries, "wsl2", "processors") {
+        if let (Ok(count), Ok(available)) = (processors.value.parse::<usize>(), std::thread::available_parallelism()) {
+            if count == 0 || count > available.get() {
+                linter.push(Severity::Warning, processors.line, i18n::tr("lint.too_many_processors", &[
+                    count.to_string(), available.get().to_string(),
+                ]));
+            }
+        }
+    }
+}
+
+pub fn lint_wsl_conf(content: &str, meta: &WslVersionMeta) -> Vec<Diagnostic> {
+    Linter { schema: WSL_CONF_SCHEMA, meta, diagnostics: Vec::new() }.run(content, wsl_conf_conflicts)
+}
+
+pub fn lint_wsl_config(content: &str, meta: &WslVersionMeta) -> Vec<Diagnostic> {
+    Linter { schema: WSL_CONFIG_SCHEMA, meta, diagnostics: Vec::new() }.run(content, wsl_config_conflicts)
+}
+
+// Distros whose wsl.conf was last seen out of line with their preset, shown in the distro list
+static DRIFTED: Lazy<Mutex<HashSet<String>>> = Lazy::new(|| Mutex::new(HashSet::new()));
+
+pub fn drifted_distros() -> HashSet<String> {
+    DRIFTED.lock().unwrap().clone()
+}
+
+// Returns whether the distro's state changed
+pub fn record_drift(distro: &str, drifted: bool) -> bool {
+    let mut set = DRIFTED.lock().unwrap();
+    if drifted { set.insert(distro.to_string()) } else { set.remove(distro) }
+}
+
+// A distro drifted when applying its preset again would change the file
+pub fn preset_drift(content: &str, preset: &WslConfPreset, meta: &WslVersionMeta) -> Option<Diagnostic> {
+    (update_wsl_conf(content, &preset.settings, meta) != content).then(|| Diagnostic {
+        severity: Severity::Warning,
+        line: 0,
+        message: i18n::tr("lint.preset_drift", &[preset.name.clone()]),
+    })
+}
+
+// ~/.wslconfig and the wsl.conf of every running distro, `presets` maps distros to the
+// preset they follow. Stopped distros are reported as not checked, reading would start them.
+pub async fn lint_all(executor: &WslCommandExecutor, presets: &HashMap<String, WslConfPreset>) -> Vec<FileReport> {
+    let meta = check_wsl_version_support(executor).await;
+    let mut reports = Vec::new();
+
+    match crate::utils::wsl_config::read_wsl_config() {
+        Ok(content) => reports.push(FileReport {
+            file: "~/.wslconfig".to_string(),
+            diagnostics: lint_wsl_config(&content.unwrap_or_default(), &meta),
+        }),
+        Err(e) => reports.push(FileReport {
+            file: "~/.wslconfig".to_string(),
+            diagnostics: vec![Diagnostic { severity: Severity::Error, line: 0, message: e }],
+        }),
+    }
+
+    let distros = executor.list_distros().await.data.unwrap_or_default();
+    for distro in distros {
+        let file = format!("{}: /etc/wsl.conf", distro.name);
+        if distro.status != WslStatus::Running {
+            reports.push(FileReport {
+                file,
+                diagnostics: vec![Diagnostic { severity: Severity::Info, line: 0, message: i18n::t("lint.not_running") }],
+            });
+            continue;
+        }
+        let diagnostics = match read_wsl_conf(executor, &distro.name).await {
+            Ok(content) => {
+                let mut diagnostics = lint_wsl_conf(&content, &meta);
+                let drift = presets.get(&distro.name).and_then(|p| preset_drift(&content, p, &meta));
+                record_drift(&distro.name, drift.is_some());
+                if let Some(drift) = drift {
+                    diagnostics.insert(0, drift);
+                }
+                diagnostics
+            }
+            Err(e) => {
+                warn!("Lint: could not read wsl.conf of '{}': {}", distro.name, e);
+                vec![Diagnostic { severity: Severity::Info, line: 0, message: i18n::tr("lint.read_failed", &[e]) }]
+            }
+        };
+        reports.push(FileReport { file, diagnostics });
+    }
+
+    for name in drifted_distros() {
+        if !presets.contains_key(&name) {
+            record_drift(&name, false);
+        }
+    }
+    let total: usize = reports.iter().map(|r| r.diagnostics.len()).sum();
+    info!("Lint: checked {} files, {} findings", reports.len(), total);
+    reports
+}
+
+// Re-check the running distros that follow a preset, stopped ones keep their last state.
+// Returns whether any distro's drift state changed.
+pub async fn check_preset_drift(executor: &WslCommandExecutor, presets: &HashMap<String, WslConfPreset>) -> bool {
+    let mut changed = false;
+    for name in drifted_distros() {
+        if !presets.contains_key(&name) {
+            changed |= record_drift(&name, false);
+        }
+    }
+
+    let running: Vec<String> = executor.list_distros().await.data.unwrap_or_default()
+        .into_iter()
+        .filter(|d| d.status == WslStatus::Running && presets.contains_key(&d.name))
+        .map(|d| d.name)
+        .collect();
+    if running.is_empty() {
+        return changed;
+    }
+    let meta = check_wsl_version_support(executor).await;
+    for name in running {
+        match read_wsl_conf(executor, &name).await {
+            Ok(content) => changed |= record_drift(&name, preset_drift(&content, &presets[&name], &meta).is_some()),
+            Err(e) => warn!("Preset drift: could not read wsl.conf of '{}': {}", name, e),
+        }
+    }
+    changed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Translations are not loaded in tests, so messages are the bare keys
+    fn messages(content: &str) -> Vec<(usize, String)> {
+        lint_wsl_conf(content, &WslVersionMeta::default()).into_iter().map(|d| (d.line, d.message)).collect()
+    }
+
+    #[test]
+    fn clean_file_has_no_findings() {
+        assert!(messages("[boot]\nsystemd=true\n\n# comment\n[user]\ndefault=dev\n").is_empty());
+    }
+
+    #[test]
+    fn names_match_regardless_of_case() {
+        assert!(messages("[Boot]\nSystemd=true\n[NETWORK]\nHostName=box\n").is_empty());
+        assert_eq!(messages("[boot]\nsystemd=true\n[Boot]\nSYSTEMD=false\n"), vec![(2, "lint.duplicate_key".to_string())]);
+    }
+
+    #[test]
+    fn unknown_names_are_reported() {
+        assert_eq!(messages("[bot]\nsystemd=true\n"), vec![(1, "lint.unknown_section_suggest".to_string())]);
+        assert_eq!(messages("[boot]\nsystemdd=true\n"), vec![(2, "lint.unknown_key_suggest".to_string())]);
+        assert_eq!(messages("[interop]\nhostname=box\n"), vec![(2, "lint.wrong_section".to_string())]);
+        assert_eq!(messages("systemd=true\n"), vec![(1, "lint.outside_section".to_string())]);
+    }
+
+    #[test]
+    fn values_are_checked_against_their_kind() {
+        assert_eq!(messages("[boot]\nsystemd=maybe\n"), vec![(2, "lint.invalid_bool".to_string())]);
+        assert_eq!(messages("[automount]\nroot=mnt\n"), vec![(2, "lint.invalid_linux_path".to_string())]);
+        assert_eq!(messages("[network]\nhostname=my_box\n"), vec![(2, "lint.invalid_hostname".to_string())]);
+        assert!(messages("[network]\nhostname=box.local\n").is_empty());
+        assert!(messages("[boot]\nsystemd=Yes\n").is_empty());
+    }
+
+    #[test]
+    fn conflicting_settings_are_reported() {
+        assert_eq!(messages("[automount]\nenabled=false\nroot=/mnt/\n"), vec![(3, "lint.no_effect".to_string())]);
+        assert_eq!(messages("[network]\ngenerateHosts=false\nhostname=box\n"), vec![(3, "lint.hostname_without_hosts".to_string())]);
+    }
+
+    #[test]
+    fn keys_newer_than_wsl_are_reported() {
+        let meta = WslVersionMeta { version_string: "0.60.0".to_string(), ..WslVersionMeta::default() };
+        let found = lint_wsl_conf("[boot]\nsystemd=true\n", &meta);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].message, "lint.unsupported");
+
+        let meta = WslVersionMeta { version_string: "1.2.5".to_string(), ..WslVersionMeta::default() };
+        let found = lint_wsl_config("[wsl2]\nnetworkingMode=mirrored\n[experimental]\nfirewall=true\n", &meta);
+        let found: Vec<(usize, String)> = found.into_iter().map(|d| (d.line, d.message)).collect();
+        assert!(found.contains(&(2, "lint.unsupported".to_string())));
+        assert!(found.contains(&(4, "lint.deprecated_location".to_string())));
+    }
+
+    #[test]
+    fn versions_compare_by_component() {
+        assert!(version_at_least("2.0.0", "2.0.0"));
+        assert!(version_at_least("2.10", "2.9.1"));
+        assert!(!version_at_least("0.67", "0.67.6"));
+    }
+}
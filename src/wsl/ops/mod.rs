@@ -6,5 +6,20 @@ pub mod transfer;
 pub mod info;
 pub mod ui;
 pub mod config;
+pub mod lint;
+pub mod kernel;
+pub mod fstab;
 pub mod compress;
 pub mod sparse;
+pub mod preflight;
+pub mod vhdx_copy;
+pub mod identity;
+pub mod provision;
+pub mod cloud_init;
+pub mod sandbox;
+pub mod recycle;
+pub mod move_journal;
+pub mod image_cache;
+pub mod appx;
+pub mod oci;
+pub mod wsl_package;
@@ -0,0 +1,306 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Journaled distro moves
+//
+// Every step of a move is recorded in ~/.wsldashboard/move-journal/<distro>.toml
+// before it is carried out. The source is only cleaned up once the target has been
+// verified, so an interrupted or failed move can always be rolled back: on failure
+// right away, after a crash on the next launch.
+
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::wsl::dashboard::WslDashboard;
+use crate::wsl::ops::vhdx_copy;
+
+const JOURNAL_DIR_NAME: &str = "move-journal";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum MoveStep {
+    // Nothing changed yet
+    Started,
+    // WSL2: `wsl --manage --move` issued
+    Moving,
+    // WSL2: VHDX being copied to the target
+    Copying,
+    // WSL2: registry BasePath switched to the target, source disk still in place
+    Registered,
+    // WSL1: source exported to the backup tar
+    Exported,
+    // WSL1: source unregistered
+    Unregistered,
+    // WSL1: backup imported at the target
+    Imported,
+    // Target checked, only source cleanup left
+    Verified,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MoveJournal {
+    #[serde(rename = "distro-name")]
+    pub distro_name: String,
+    #[serde(rename = "target-name")]
+    pub target_name: String,
+    pub version: u32,
+    // Registry BasePath before the move, written back on rollback
+    #[serde(rename = "source-path")]
+    pub source_path: String,
+    #[serde(rename = "target-path")]
+    pub target_path: String,
+    #[serde(rename = "source-vhdx", default)]
+    pub source_vhdx: String,
+    #[serde(rename = "target-vhdx", default)]
+    pub target_vhdx: String,
+    #[serde(rename = "backup-file", default)]
+    pub backup_file: String,
+    pub step: MoveStep,
+    #[serde(rename = "started-time")]
+    pub started_time: String,
+    #[serde(skip)]
+    file: PathBuf,
+}
+
+// How a pending journal was resolved on startup
+pub enum Recovered {
+    RolledBack,
+    Completed,
+}
+
+fn journal_dir() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".wsldashboard").join(JOURNAL_DIR_NAME)
+}
+
+fn normalize(path: &str) -> String {
+    path.trim_start_matches(r"\\?\").replace('/', "\\").trim_end_matches('\\').to_lowercase()
+}
+
+fn same_path(a: &str, b: &str) -> bool {
+    normalize(a) == normalize(b)
+}
+
+fn registered_base_path(name: &str) -> Option<String> {
+    crate::utils::registry::get_wsl_distros_from_reg()
+        .into_iter()
+        .find(|d| d.name == name)
+        .map(|d| d.base_path)
+}
+
+impl MoveJournal {
+    // Record the source location and open the journal; the move must not start if this fails
+    pub fn begin(distro_name: &str, target_name: &str, target_path: &str, version: u32) -> Result<Self, String> {
+        let source_path = crate::utils::registry::get_distro_base_path(distro_name)
+            .ok_or_else(|| format!("Distro '{}' not found in registry", distro_name))?;
+        let dir = journal_dir();
+        std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+        let journal = Self {
+            distro_name: distro_name.to_string(),
+            target_name: target_name.to_string(),
+            version,
+            source_path,
+            target_path: target_path.to_string(),
+            source_vhdx: String::new(),
+            target_vhdx: String::new(),
+            backup_file: String::new(),
+            step: MoveStep::Started,
+            started_time: chrono::Utc::now().timestamp_millis().to_string(),
+            file: dir.join(format!("{}.toml", distro_name)),
+        };
+        journal.save()?;
+        info!("Move journal: started for '{}' ({} -> {})", distro_name, journal.source_path, target_path);
+        Ok(journal)
+    }
+
+    // Write to a temp file and rename it, so a crash never leaves a torn journal behind
+    fn save(&self) -> Result<(), String> {
+        let content = toml::to_string_pretty(self).map_err(|e| e.to_string())?;
+        let tmp = self.file.with_extension("toml.tmp");
+        std::fs::write(&tmp, content).map_err(|e| e.to_string())?;
+        std::fs::rename(&tmp, &self.file).map_err(|e| e.to_string())
+    }
+
+    pub fn set_step(&mut self, step: MoveStep) {
+        self.step = step;
+        if let Err(e) = self.save() {
+            warn!("Move journal: failed to record step {:?} for '{}': {}", step, self.distro_name, e);
+        }
+    }
+
+    // Record the disk paths of a copy-based move before the copy starts
+    pub fn set_vhdx(&mut self, source: &Path, target: &Path) {
+        self.source_vhdx = source.to_string_lossy().to_string();
+        self.target_vhdx = target.to_string_lossy().to_string();
+        self.set_step(MoveStep::Copying);
+    }
+
+    // Record the WSL1 backup path before the export starts, so a partial file gets cleaned up too
+    pub fn set_backup(&mut self, backup_file: &str) {
+        self.backup_file = backup_file.to_string();
+        self.set_step(self.step);
+    }
+
+    // The transaction is over, drop the journal
+    pub fn finish(self) {
+        let _ = std::fs::remove_file(&self.file);
+    }
+
+    // Check that the distro is registered at the target and boots from there
+    pub async fn verify_target(&self, dashboard: &WslDashboard) -> Result<(), String> {
+        let name = if self.version == 1 { &self.target_name } else { &self.distro_name };
+        let base_path = registered_base_path(name)
+            .ok_or_else(|| format!("Distro '{}' is not registered after the move", name))?;
+        if !same_path(&base_path, &self.target_path) {
+            return Err(format!("Distro '{}' is registered at '{}' instead of '{}'", name, base_path, self.target_path));
+        }
+        if self.version == 2 && crate::wsl::ops::info::get_vhdx_path(&base_path).is_none() {
+            return Err(format!("No virtual disk found in '{}'", base_path));
+        }
+
+        let executor = dashboard.executor();
+        let boot = executor.execute_command(&["-d", name, "--exec", "true"]).await;
+        let _ = executor.execute_command(&["--terminate", name]).await;
+        if !boot.success {
+            return Err(boot.error.unwrap_or_else(|| format!("Distro '{}' failed to start at the target", name)));
+        }
+        info!("Move journal: target of '{}' verified at '{}'", name, base_path);
+        Ok(())
+    }
+
+    // Remove what is left at the source once the target is verified
+    pub fn cleanup_source(&self) {
+        if !self.source_vhdx.is_empty() && !same_path(&self.source_vhdx, &self.target_vhdx) {
+            let source = Path::new(&self.source_vhdx);
+            if source.exists() {
+                if let Err(e) = std::fs::remove_file(source) {
+                    warn!("Move journal: old disk '{}' could not be removed: {}", self.source_vhdx, e);
+                }
+            }
+        }
+        if !self.backup_file.is_empty() {
+            let _ = std::fs::remove_file(&self.backup_file);
+        }
+    }
+
+    // Put the distro back where it was before the move.
+    // On error the journal should be kept so the next launch can try again.
+    pub async fn rollback(&self, dashboard: &WslDashboard) -> Result<(), String> {
+        info!("Move journal: rolling back move of '{}' at step {:?}", self.distro_name, self.step);
+        if self.version == 1 {
+            self.rollback_wsl1(dashboard).await
+        } else {
+            self.rollback_wsl2(dashboard).await
+        }
+    }
+
+    async fn rollback_wsl2(&self, dashboard: &WslDashboard) -> Result<(), String> {
+        let set_base_path = |path: &str| {
+            crate::utils::registry::set_distro_base_path(&self.distro_name, path).map_err(|e| e.to_string())
+        };
+
+        match self.step {
+            MoveStep::Started => Ok(()),
+            MoveStep::Moving => {
+                // WSL moves the disk before it updates the registry, so the disk location decides
+                let source_disk = crate::wsl::ops::info::get_vhdx_path(&self.source_path);
+                let target_disk = crate::wsl::ops::info::get_vhdx_path(&self.target_path);
+                match (source_disk, target_disk) {
+                    (Some(_), target_disk) => {
+                        set_base_path(&self.source_path)?;
+                        if let Some(target_disk) = target_disk {
+                            vhdx_copy::discard_partial(&target_disk);
+                        }
+                        Ok(())
+                    }
+                    (None, Some(_)) => {
+                        // The disk only exists at the target, keep the distro usable first
+                        set_base_path(&self.target_path)?;
+                        let source = self.source_path.trim_start_matches(r"\\?\").to_string();
+                        let result = dashboard.move_distro(&self.distro_name, &source).await;
+                        if result.success {
+                            Ok(())
+                        } else {
+                            Err(result.error.unwrap_or_else(|| format!("Distro '{}' remains at '{}'", self.distro_name, self.target_path)))
+                        }
+                    }
+                    (None, None) => Err(format!("No virtual disk of '{}' found at the source or the target", self.distro_name)),
+                }
+            }
+            _ => {
+                // Copy-based move: the source disk is untouched until verification
+                if !self.source_vhdx.is_empty() && !Path::new(&self.source_vhdx).exists() {
+                    return Err(format!("Source disk '{}' is missing", self.source_vhdx));
+                }
+                set_base_path(&self.source_path)?;
+                if !self.target_vhdx.is_empty() {
+                    vhdx_copy::discard_partial(Path::new(&self.target_vhdx));
+                }
+                Ok(())
+            }
+        }
+    }
+
+    async fn rollback_wsl1(&self, dashboard: &WslDashboard) -> Result<(), String> {
+        let executor = dashboard.executor();
+
+        // Drop a partial or unverified import at the target
+        if self.step >= MoveStep::Unregistered && registered_base_path(&self.target_name).is_some_and(|p| same_path(&p, &self.target_path)) {
+            let result = executor.execute_command(&["--unregister", &self.target_name]).await;
+            if !result.success {
+                return Err(result.error.unwrap_or_else(|| format!("Failed to unregister '{}'", self.target_name)));
+            }
+        }
+
+        // Unregister may have gone through before the step was recorded, so check the registry
+        if self.step >= MoveStep::Exported && registered_base_path(&self.distro_name).is_none() {
+            let backup_ok = std::fs::metadata(&self.backup_file).map(|m| m.len() > 0).unwrap_or(false);
+            if !backup_ok {
+                return Err(format!("Backup '{}' of '{}' is missing", self.backup_file, self.distro_name));
+            }
+            let source = self.source_path.trim_start_matches(r"\\?\").to_string();
+            let _ = std::fs::create_dir_all(&source);
+            let result = executor.execute_command(&["--import", &self.distro_name, &source, &self.backup_file, "--version", "1"]).await;
+            if !result.success {
+                return Err(result.error.unwrap_or_else(|| format!("Failed to import '{}' back", self.distro_name)));
+            }
+        }
+
+        if !self.backup_file.is_empty() {
+            let _ = std::fs::remove_file(&self.backup_file);
+        }
+        let _ = dashboard.refresh_distros().await;
+        Ok(())
+    }
+}
+
+// Journals left behind by moves that never finished
+pub fn list_pending() -> Vec<MoveJournal> {
+    std::fs::read_dir(journal_dir())
+        .map(|rd| rd.flatten()
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|p| {
+                let content = std::fs::read_to_string(&p).ok()?;
+                let mut journal: MoveJournal = toml::from_str(&content).ok()?;
+                journal.file = p;
+                Some(journal)
+            })
+            .collect())
+        .unwrap_or_default()
+}
+
+// Resolve an interrupted move: finish it when the target was already verified, roll it back otherwise
+pub async fn recover(dashboard: &WslDashboard, journal: MoveJournal) -> Result<Recovered, String> {
+    if journal.step == MoveStep::Verified {
+        journal.cleanup_source();
+        info!("Move journal: completed cleanup of verified move of '{}'", journal.distro_name);
+        journal.finish();
+        return Ok(Recovered::Completed);
+    }
+    journal.rollback(dashboard).await?;
+    info!("Move journal: move of '{}' rolled back", journal.distro_name);
+    journal.finish();
+    Ok(Recovered::RolledBack)
+}
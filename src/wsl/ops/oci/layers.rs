@@ -0,0 +1,194 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Flattening image layers into one rootfs tarball
+//
+// Pass 1 walks the layers top-down and decides which entries survive: an entry is
+// dropped when a higher layer redefines the path, deletes it with a `.wh.<name>`
+// whiteout, hides its directory with an opaque `.wh..wh..opq` marker, or replaces
+// a parent directory with a file. Pass 2 writes the survivors bottom-up. A hard link
+// whose target is not kept from its own layer (a higher layer redefined or deleted
+// it) is written as a copy of the original target, as overlayfs would show it.
+// Blocking, call from spawn_blocking.
+
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::{Path, PathBuf};
+use tracing::{info, warn};
+
+const WHITEOUT_PREFIX: &str = ".wh.";
+const OPAQUE_MARKER: &str = ".wh..wh..opq";
+
+fn open_layer(path: &Path) -> Result<tar::Archive<Box<dyn Read>>, String> {
+    let mut magic = [0u8; 4];
+    let read = File::open(path)
+        .and_then(|mut f| f.read(&mut magic))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let file = BufReader::new(File::open(path).map_err(|e| e.to_string())?);
+    let reader: Box<dyn Read> = match &magic[..read] {
+        [0x1F, 0x8B, ..] => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        [0x28, 0xB5, 0x2F, 0xFD] => return Err("zstd compressed layers are not supported".to_string()),
+        _ => Box::new(file),
+    };
+    Ok(tar::Archive::new(reader))
+}
+
+// "./usr/bin/" -> "usr/bin"
+fn normalize(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    path.trim_start_matches("./").trim_start_matches('/').trim_end_matches('/').to_string()
+}
+
+fn split_parent(path: &str) -> (&str, &str) {
+    match path.rsplit_once('/') {
+        Some((parent, name)) => (parent, name),
+        None => ("", path),
+    }
+}
+
+// Path itself or one of its parents is in `set`
+fn covered_by(path: &str, set: &HashSet<String>) -> bool {
+    if set.contains(path) {
+        return true;
+    }
+    path.match_indices('/').any(|(i, _)| set.contains(&path[..i]))
+}
+
+// Strict parent directory of `path` is in `set`
+fn below(path: &str, set: &HashSet<String>) -> bool {
+    (set.contains("") && !path.is_empty()) || path.match_indices('/').any(|(i, _)| set.contains(&path[..i]))
+}
+
+#[derive(Default, Clone)]
+struct LayerPlan {
+    // Entry indexes to write
+    keep: HashSet<usize>,
+    // Kept hard links (entry index) to write as a copy of their target path
+    copies: HashMap<usize, String>,
+}
+
+// Pass 1: for every layer, the entries to keep and the hard links to copy
+fn plan(layers: &[PathBuf]) -> Result<Vec<LayerPlan>, String> {
+    let mut defined: HashSet<String> = HashSet::new();
+    // Paths (and everything below) removed or replaced by a file in a higher layer
+    let mut removed: HashSet<String> = HashSet::new();
+    // Directories whose lower content is hidden
+    let mut opaque: HashSet<String> = HashSet::new();
+    let mut plans = vec![LayerPlan::default(); layers.len()];
+
+    for (layer_idx, layer) in layers.iter().enumerate().rev() {
+        let mut archive = open_layer(layer)?;
+        let mut kept: HashMap<String, (usize, bool)> = HashMap::new();
+        let mut new_removed = Vec::new();
+        let mut new_opaque = Vec::new();
+        let mut hard_links = Vec::new();
+
+        for (idx, entry) in archive.entries().map_err(|e| e.to_string())?.enumerate() {
+            let entry = entry.map_err(|e| format!("{}: {}", layer.display(), e))?;
+            let path = normalize(&entry.path().map_err(|e| e.to_string())?);
+            let (parent, name) = split_parent(&path);
+
+            if name == OPAQUE_MARKER {
+                new_opaque.push(parent.to_string());
+                continue;
+            }
+            if let Some(target) = name.strip_prefix(WHITEOUT_PREFIX) {
+                let target = if parent.is_empty() { target.to_string() } else { format!("{}/{}", parent, target) };
+                new_removed.push(target);
+                continue;
+            }
+            if path.is_empty() || defined.contains(&path) || covered_by(&path, &removed) || below(&path, &opaque) {
+                continue;
+            }
+            if entry.header().entry_type().is_hard_link() {
+                let target = entry.link_name().map_err(|e| e.to_string())?.unwrap_or_default();
+                hard_links.push((idx, path.clone(), normalize(&target)));
+            }
+            // Duplicates within a layer: the last one wins
+            kept.insert(path, (idx, entry.header().entry_type().is_dir()));
+        }
+
+        let plan = &mut plans[layer_idx];
+        for (idx, path, target) in hard_links {
+            if kept.get(&path).map(|(i, _)| *i) == Some(idx) && !kept.contains_key(&target) {
+                plan.copies.insert(idx, target);
+            }
+        }
+        for (path, (idx, is_dir)) in kept {
+            plan.keep.insert(idx);
+            if !is_dir {
+                removed.insert(path.clone());
+            }
+            defined.insert(path);
+        }
+        removed.extend(new_removed);
+        opaque.extend(new_opaque);
+    }
+    Ok(plans)
+}
+
+// Merge `layers` (bottom layer first) into an uncompressed tar at `output`
+pub fn flatten(layers: &[PathBuf], output: &Path) -> Result<u64, String> {
+    let plans = plan(layers)?;
+    let out = File::create(output).map_err(|e| format!("{}: {}", output.display(), e))?;
+    let mut builder = tar::Builder::new(std::io::BufWriter::new(out));
+    let mut written = 0u64;
+
+    for (layer_idx, layer) in layers.iter().enumerate() {
+        let plan = &plans[layer_idx];
+        let copy_targets: HashSet<&String> = plan.copies.values().collect();
+        // Content of targets that detached hard links copy, they precede the links
+        let mut copy_data: HashMap<String, (tar::Header, Vec<u8>)> = HashMap::new();
+        let mut archive = open_layer(layer)?;
+        for (idx, entry) in archive.entries().map_err(|e| e.to_string())?.enumerate() {
+            let mut entry = entry.map_err(|e| format!("{}: {}", layer.display(), e))?;
+            let path = normalize(&entry.path().map_err(|e| e.to_string())?);
+            let mut header = entry.header().clone();
+            let entry_type = header.entry_type();
+
+            if copy_targets.contains(&path) && entry_type.is_file() {
+                let mut data = Vec::new();
+                entry.read_to_end(&mut data).map_err(|e| format!("{}: {}", path, e))?;
+                header.set_size(data.len() as u64);
+                if plan.keep.contains(&idx) {
+                    builder.append_data(&mut header.clone(), &path, data.as_slice()).map_err(|e| format!("{}: {}", path, e))?;
+                    written += 1;
+                }
+                copy_data.insert(path, (header, data));
+                continue;
+            }
+            if !plan.keep.contains(&idx) {
+                continue;
+            }
+
+            if let Some(target) = plan.copies.get(&idx) {
+                if let Some((target_header, data)) = copy_data.get(target) {
+                    let mut header = target_header.clone();
+                    builder.append_data(&mut header, &path, data.as_slice()).map_err(|e| format!("{}: {}", path, e))?;
+                    written += 1;
+                    continue;
+                }
+                warn!("OCI: hard link '{}' -> '{}' has no regular file to copy, keeping the link", path, target);
+            }
+
+            if entry_type.is_symlink() || entry_type.is_hard_link() {
+                let target = entry.link_name().map_err(|e| e.to_string())?.unwrap_or_default().into_owned();
+                // Hard link targets are archive paths and need the same normalization
+                let target = if entry_type.is_hard_link() { PathBuf::from(normalize(&target)) } else { target };
+                builder.append_link(&mut header, &path, &target).map_err(|e| format!("{}: {}", path, e))?;
+            } else {
+                // Sizes above 8GB live in a PAX record, not the copied header
+                header.set_size(entry.size());
+                builder.append_data(&mut header, &path, &mut entry).map_err(|e| format!("{}: {}", path, e))?;
+            }
+            written += 1;
+        }
+    }
+
+    builder.into_inner()
+        .and_then(|mut w| std::io::Write::flush(&mut w))
+        .map_err(|e| e.to_string())?;
+    info!("OCI: flattened {} layers into '{}' ({} entries)", layers.len(), output.display(), written);
+    Ok(written)
+}
@@ -0,0 +1,152 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Distros from OCI container images
+//
+// The image is pulled straight from the registry (no Docker needed), its layers are
+// flattened into one rootfs tarball for `wsl --import`, and the container config is
+// mapped onto the distro: ENV goes to /etc/profile.d, USER becomes the wsl.conf
+// default user and WORKDIR the instance's terminal directory.
+
+mod reference;
+mod registry;
+mod layers;
+
+pub use reference::ImageReference;
+pub use registry::{docker_config_credentials, ContainerConfig, Credentials, RegistryClient};
+pub use layers::flatten;
+
+use crate::wsl::executor::WslCommandExecutor;
+
+const ENV_SCRIPT_PATH: &str = "/etc/profile.d/oci-env.sh";
+
+// Variables WSL sets itself on every launch
+const SKIPPED_ENV: [&str; 4] = ["PATH", "HOME", "HOSTNAME", "TERM"];
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+// Profile script exporting the image's ENV, None when nothing carries over
+pub fn env_script(config: &ContainerConfig) -> Option<String> {
+    let lines: Vec<String> = config.env.iter().flatten()
+        .filter_map(|pair| pair.split_once('='))
+        .filter(|(key, _)| {
+            !key.is_empty()
+                && !SKIPPED_ENV.contains(key)
+                && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        })
+        .map(|(key, value)| format!("export {}={}", key, shell_quote(value)))
+        .collect();
+    if lines.is_empty() {
+        None
+    } else {
+        Some(format!("# Environment of the OCI image this distro was created from\n{}\n", lines.join("\n")))
+    }
+}
+
+// USER may be "name", "name:group", "uid" or "uid:gid". Only names map onto
+// wsl.conf, numeric ids other than root are ignored.
+pub fn default_user(config: &ContainerConfig) -> Option<String> {
+    let user = config.user.as_deref()?.trim();
+    let user = user.split(':').next().unwrap_or(user);
+    if user.is_empty() {
+        None
+    } else if user == "0" {
+        Some("root".to_string())
+    } else if user.chars().all(|c| c.is_ascii_digit()) || !crate::wsl::ops::identity::is_valid_username(user) {
+        None
+    } else {
+        Some(user.to_string())
+    }
+}
+
+pub fn working_dir(config: &ContainerConfig) -> Option<String> {
+    config.working_dir.as_deref()
+        .map(str::trim)
+        .filter(|d| d.starts_with('/') && *d != "/")
+        .map(str::to_string)
+}
+
+// Apply ENV and USER inside the freshly imported distro. WORKDIR is an instance
+// setting and handled by the caller.
+pub async fn apply_image_config(
+    executor: &WslCommandExecutor,
+    distro_name: &str,
+    config: &ContainerConfig,
+) -> Result<(), String> {
+    let mut errors = Vec::new();
+
+    if let Some(script) = env_script(config) {
+        let cmd = format!(
+            "mkdir -p /etc/profile.d && cat << 'OCIENV_EOF' > {}\n{}\nOCIENV_EOF",
+            ENV_SCRIPT_PATH,
+            script.trim_end()
+        );
+        let res = executor.execute_command(&["-d", distro_name, "-u", "root", "-e", "sh", "-c", &cmd]).await;
+        if !res.success {
+            errors.push(format!("ENV: {}", res.error.unwrap_or_default()));
+        }
+    }
+
+    if let Some(user) = default_user(config) {
+        // The user has to exist in the image, wsl.conf does not create it
+        let check = executor.execute_command(&["-d", distro_name, "-u", "root", "-e", "id", "-u", &user]).await;
+        if check.success {
+            let mut conf = super::config::WslConf::default();
+            conf.user.default = Some(user.clone());
+            let meta = super::config::check_wsl_version_support(executor).await;
+            if let Err(e) = super::config::save_wsl_conf(executor, distro_name, &conf, &meta).await {
+                errors.push(format!("USER: {}", e));
+            }
+        } else {
+            errors.push(format!("USER: '{}' does not exist in the image", user));
+        }
+    }
+
+    if errors.is_empty() { Ok(()) } else { Err(errors.join("; ")) }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Pulls from a local registry, run it with `cargo test -- --ignored` after
+    //   docker run -d -p 5000:5000 registry:2
+    //   docker pull alpine && docker tag alpine localhost:5000/alpine && docker push localhost:5000/alpine
+    // OCI_TEST_IMAGE overrides the image reference.
+    #[tokio::test]
+    #[ignore]
+    async fn pull_and_flatten_from_local_registry() {
+        let reference = std::env::var("OCI_TEST_IMAGE").unwrap_or_else(|_| "localhost:5000/alpine:latest".to_string());
+        let image = ImageReference::parse(&reference).unwrap();
+        assert!(image.insecure);
+
+        let mut client = RegistryClient::new(image, None).unwrap();
+        let manifest = client.fetch_manifest().await.unwrap();
+        client.fetch_config(&manifest.config).await.unwrap();
+
+        let dir = std::env::temp_dir().join(format!("oci_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let _cleanup = scopeguard::guard(dir.clone(), |dir| {
+            let _ = std::fs::remove_dir_all(dir);
+        });
+
+        let mut layers = Vec::new();
+        for (i, layer) in manifest.layers.iter().enumerate() {
+            let dest = dir.join(format!("layer{}", i));
+            client.download_blob(layer, &dest, |_| {}).await.unwrap();
+            layers.push(dest);
+        }
+
+        let rootfs = dir.join("rootfs.tar");
+        let written = flatten(&layers, &rootfs).unwrap();
+        assert!(written > 0);
+
+        let mut archive = tar::Archive::new(std::fs::File::open(&rootfs).unwrap());
+        let has_etc = archive.entries().unwrap()
+            .filter_map(Result::ok)
+            .any(|e| e.path().map(|p| p.starts_with("etc")).unwrap_or(false));
+        assert!(has_etc);
+    }
+}
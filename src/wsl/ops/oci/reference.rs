@@ -0,0 +1,112 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Image references in the usual docker form:
+//   alpine                      -> registry-1.docker.io/library/alpine:latest
+//   ghcr.io/owner/image:tag
+//   localhost:5000/image@sha256:...
+//   http://192.168.1.10:5000/image:tag   (plain HTTP registry)
+
+const DOCKER_HUB: &str = "docker.io";
+const DOCKER_HUB_API: &str = "registry-1.docker.io";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageReference {
+    // Registry host as written by the user, "docker.io" for Docker Hub
+    pub registry: String,
+    pub repository: String,
+    // Tag or digest
+    pub reference: String,
+    pub insecure: bool,
+}
+
+fn is_local_host(host: &str) -> bool {
+    let name = host.rsplit_once(':').map(|(h, _)| h).unwrap_or(host);
+    name == "localhost" || name == "127.0.0.1" || name == "[::1]"
+}
+
+impl ImageReference {
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut rest = input.trim();
+        let mut insecure = false;
+        if let Some(r) = rest.strip_prefix("docker://") {
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("https://") {
+            rest = r;
+        } else if let Some(r) = rest.strip_prefix("http://") {
+            rest = r;
+            insecure = true;
+        }
+        if rest.is_empty() || rest.contains(char::is_whitespace) {
+            return Err(format!("Invalid image reference '{}'", input.trim()));
+        }
+
+        let (name, reference) = match rest.split_once('@') {
+            Some((name, digest)) => {
+                if !digest.contains(':') {
+                    return Err(format!("Invalid digest '{}'", digest));
+                }
+                (name, digest.to_string())
+            }
+            None => {
+                let last_slash = rest.rfind('/').map(|i| i + 1).unwrap_or(0);
+                match rest[last_slash..].rfind(':') {
+                    Some(i) => (&rest[..last_slash + i], rest[last_slash + i + 1..].to_string()),
+                    None => (rest, "latest".to_string()),
+                }
+            }
+        };
+
+        // The first component is a registry when it looks like a host name
+        let (registry, repository) = match name.split_once('/') {
+            Some((first, repo)) if first.contains('.') || first.contains(':') || first == "localhost" => {
+                (first.to_string(), repo.to_string())
+            }
+            _ => (DOCKER_HUB.to_string(), name.to_string()),
+        };
+
+        let repository = if registry == DOCKER_HUB && !repository.contains('/') {
+            format!("library/{}", repository)
+        } else {
+            repository
+        };
+
+        if repository.is_empty() || reference.is_empty() || repository != repository.to_lowercase() {
+            return Err(format!("Invalid image reference '{}'", input.trim()));
+        }
+
+        let insecure = insecure || is_local_host(&registry);
+        Ok(Self { registry, repository, reference, insecure })
+    }
+
+    // Host serving the registry API
+    pub fn api_host(&self) -> &str {
+        if self.registry == DOCKER_HUB { DOCKER_HUB_API } else { &self.registry }
+    }
+
+    pub fn base_url(&self) -> String {
+        let scheme = if self.insecure { "http" } else { "https" };
+        format!("{}://{}/v2/{}", scheme, self.api_host(), self.repository)
+    }
+
+    pub fn is_docker_hub(&self) -> bool {
+        self.registry == DOCKER_HUB
+    }
+
+    // Short name for the new instance: "library/ubuntu:24.04" -> "ubuntu-24.04"
+    pub fn suggested_name(&self) -> String {
+        let image = self.repository.rsplit('/').next().unwrap_or(&self.repository);
+        if self.reference.contains(':') || self.reference == "latest" {
+            image.to_string()
+        } else {
+            format!("{}-{}", image, self.reference)
+        }
+    }
+}
+
+impl std::fmt::Display for ImageReference {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let sep = if self.reference.contains(':') { '@' } else { ':' };
+        write!(f, "{}/{}{}{}", self.registry, self.repository, sep, self.reference)
+    }
+}
@@ -0,0 +1,321 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// Minimal OCI distribution client: manifests, image config and layer blobs.
+// Handles anonymous and authenticated pulls (Bearer token or Basic) and goes
+// through the configured HTTP proxy.
+
+use std::path::Path;
+use std::time::Duration;
+use base64::Engine;
+use futures_util::StreamExt;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, info};
+use super::reference::ImageReference;
+
+const MANIFEST_ACCEPT: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+#[derive(Debug, Clone, Default)]
+pub struct Credentials {
+    pub username: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Platform {
+    #[serde(default)]
+    architecture: String,
+    #[serde(default)]
+    os: String,
+}
+
+// Layer compression is detected from the blob itself, so the media type is not kept
+#[derive(Debug, Clone, Deserialize)]
+pub struct Descriptor {
+    pub digest: String,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(default)]
+    platform: Option<Platform>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ManifestDocument {
+    // Image index / manifest list
+    #[serde(default)]
+    manifests: Vec<Descriptor>,
+    #[serde(default)]
+    config: Option<Descriptor>,
+    #[serde(default)]
+    layers: Vec<Descriptor>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ImageManifest {
+    pub config: Descriptor,
+    pub layers: Vec<Descriptor>,
+}
+
+impl ImageManifest {
+    pub fn total_size(&self) -> u64 {
+        self.layers.iter().map(|l| l.size).sum()
+    }
+}
+
+// The parts of the image config that carry over into the distro
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerConfig {
+    #[serde(default)]
+    pub env: Option<Vec<String>>,
+    #[serde(default)]
+    pub user: Option<String>,
+    #[serde(default)]
+    pub working_dir: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ImageConfig {
+    #[serde(default)]
+    config: Option<ContainerConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    access_token: Option<String>,
+}
+
+fn target_architecture() -> &'static str {
+    if std::env::consts::ARCH == "aarch64" { "arm64" } else { "amd64" }
+}
+
+// `Bearer realm="https://auth.docker.io/token",service="registry.docker.io",scope="..."`
+fn parse_challenge(header: &str) -> (String, Vec<(String, String)>) {
+    let header = header.trim();
+    let (scheme, rest) = header.split_once(' ').unwrap_or((header, ""));
+    let mut params = Vec::new();
+    let mut rest = rest.trim();
+    while let Some(eq) = rest.find('=') {
+        let key = rest[..eq].trim().trim_start_matches(',').trim().to_lowercase();
+        let after = &rest[eq + 1..];
+        let (value, remaining) = if let Some(quoted) = after.strip_prefix('"') {
+            match quoted.find('"') {
+                Some(end) => (&quoted[..end], &quoted[end + 1..]),
+                None => (quoted, ""),
+            }
+        } else {
+            match after.find(',') {
+                Some(end) => (&after[..end], &after[end..]),
+                None => (after, ""),
+            }
+        };
+        params.push((key, value.to_string()));
+        rest = remaining.trim_start_matches(',').trim();
+    }
+    (scheme.to_lowercase(), params)
+}
+
+fn basic_header(credentials: &Credentials) -> String {
+    let raw = format!("{}:{}", credentials.username, credentials.password);
+    format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(raw))
+}
+
+// Credentials stored by `docker login` in ~/.docker/config.json.
+// Credential helpers (credsStore) are not consulted.
+pub fn docker_config_credentials(image: &ImageReference) -> Option<Credentials> {
+    let path = dirs::home_dir()?.join(".docker").join("config.json");
+    let value: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(path).ok()?).ok()?;
+    let auths = value.get("auths")?.as_object()?;
+
+    let wanted: &[&str] = if image.is_docker_hub() {
+        &["index.docker.io", "docker.io", "registry-1.docker.io"]
+    } else {
+        &[image.registry.as_str()]
+    };
+    auths.iter().find_map(|(key, entry)| {
+        let host = key.trim_start_matches("https://").trim_start_matches("http://");
+        let host = host.split('/').next().unwrap_or(host);
+        if !wanted.iter().any(|w| w.eq_ignore_ascii_case(host)) {
+            return None;
+        }
+        let encoded = entry.get("auth")?.as_str()?;
+        let decoded = base64::engine::general_purpose::STANDARD.decode(encoded).ok()?;
+        let decoded = String::from_utf8(decoded).ok()?;
+        let (username, password) = decoded.split_once(':')?;
+        Some(Credentials { username: username.to_string(), password: password.to_string() })
+    })
+}
+
+pub struct RegistryClient {
+    client: reqwest::Client,
+    image: ImageReference,
+    credentials: Option<Credentials>,
+    authorization: Option<String>,
+}
+
+impl RegistryClient {
+    pub fn new(image: ImageReference, credentials: Option<Credentials>) -> Result<Self, String> {
+        let client = crate::network::http::client_builder()
+            .connect_timeout(Duration::from_secs(15))
+            .user_agent(concat!("wsldashboard/", env!("CARGO_PKG_VERSION")))
+            // Blobs are verified against their digest, keep them byte-exact
+            .no_gzip()
+            .no_brotli()
+            .no_deflate()
+            .build()
+            .map_err(|e| e.to_string())?;
+        Ok(Self { client, image, credentials, authorization: None })
+    }
+
+    async fn send(&self, url: &str, accept: Option<&str>) -> Result<reqwest::Response, String> {
+        let mut request = self.client.get(url);
+        if let Some(accept) = accept {
+            request = request.header(reqwest::header::ACCEPT, accept);
+        }
+        if let Some(ref authorization) = self.authorization {
+            request = request.header(reqwest::header::AUTHORIZATION, authorization);
+        }
+        request.send().await.map_err(|e| format!("{}: {}", url, e))
+    }
+
+    // GET with one re-authentication on 401, tokens expire during long pulls
+    async fn get(&mut self, url: &str, accept: Option<&str>) -> Result<reqwest::Response, String> {
+        let mut response = self.send(url, accept).await?;
+        if response.status() == reqwest::StatusCode::UNAUTHORIZED {
+            let challenge = response.headers()
+                .get(reqwest::header::WWW_AUTHENTICATE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            self.authenticate(&challenge).await?;
+            response = self.send(url, accept).await?;
+        }
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            let body: String = body.trim().chars().take(200).collect();
+            return Err(match status {
+                reqwest::StatusCode::UNAUTHORIZED | reqwest::StatusCode::FORBIDDEN => {
+                    format!("Access to '{}' denied ({}), check the registry credentials", self.image, status)
+                }
+                reqwest::StatusCode::NOT_FOUND => format!("'{}' not found in the registry ({})", self.image, url),
+                _ => format!("{} {}: {}", status, url, body),
+            });
+        }
+        Ok(response)
+    }
+
+    async fn authenticate(&mut self, challenge: &str) -> Result<(), String> {
+        let (scheme, params) = parse_challenge(challenge);
+        match scheme.as_str() {
+            "basic" => {
+                let credentials = self.credentials.as_ref()
+                    .ok_or_else(|| format!("'{}' requires a username and password", self.image.registry))?;
+                self.authorization = Some(basic_header(credentials));
+                Ok(())
+            }
+            "bearer" => {
+                let param = |name: &str| params.iter().find(|(k, _)| k == name).map(|(_, v)| v.clone());
+                let realm = param("realm").ok_or("Bearer challenge without realm")?;
+                let scope = param("scope").unwrap_or_else(|| format!("repository:{}:pull", self.image.repository));
+                let mut query = vec![("scope", scope)];
+                if let Some(service) = param("service") {
+                    query.push(("service", service));
+                }
+
+                let mut request = self.client.get(&realm).query(&query);
+                if let Some(ref credentials) = self.credentials {
+                    request = request.basic_auth(&credentials.username, Some(&credentials.password));
+                }
+                let response = request.send().await.map_err(|e| format!("{}: {}", realm, e))?;
+                if !response.status().is_success() {
+                    return Err(format!("Registry login failed: {}", response.status()));
+                }
+                let token: TokenResponse = response.json().await.map_err(|e| e.to_string())?;
+                let token = token.token.or(token.access_token).ok_or("Registry returned no token")?;
+                debug!("Authenticated against '{}' for '{}'", realm, self.image.repository);
+                self.authorization = Some(format!("Bearer {}", token));
+                Ok(())
+            }
+            _ => Err(format!("Unsupported registry authentication '{}'", challenge)),
+        }
+    }
+
+    // Resolve the reference to a single-platform image manifest
+    pub async fn fetch_manifest(&mut self) -> Result<ImageManifest, String> {
+        let arch = target_architecture();
+        let mut reference = self.image.reference.clone();
+        // An index points at per-platform manifests, follow it once
+        for _ in 0..2 {
+            let url = format!("{}/manifests/{}", self.image.base_url(), reference);
+            let document: ManifestDocument = self.get(&url, Some(MANIFEST_ACCEPT)).await?
+                .json()
+                .await
+                .map_err(|e| format!("Invalid manifest: {}", e))?;
+
+            if !document.manifests.is_empty() {
+                let entry = document.manifests.iter()
+                    .find(|m| m.platform.as_ref().is_some_and(|p| p.os == "linux" && p.architecture == arch))
+                    .ok_or_else(|| format!("'{}' has no linux/{} image", self.image, arch))?;
+                info!("OCI: '{}' resolved to {} for linux/{}", self.image, entry.digest, arch);
+                reference = entry.digest.clone();
+                continue;
+            }
+
+            let config = document.config.ok_or("Unsupported manifest format (schema 1?)")?;
+            if document.layers.is_empty() {
+                return Err(format!("'{}' has no layers", self.image));
+            }
+            return Ok(ImageManifest { config, layers: document.layers });
+        }
+        Err(format!("'{}' resolves to a nested image index", self.image))
+    }
+
+    pub async fn fetch_config(&mut self, config: &Descriptor) -> Result<ContainerConfig, String> {
+        let url = format!("{}/blobs/{}", self.image.base_url(), config.digest);
+        let config: ImageConfig = self.get(&url, None).await?
+            .json()
+            .await
+            .map_err(|e| format!("Invalid image config: {}", e))?;
+        Ok(config.config.unwrap_or_default())
+    }
+
+    // Stream a blob to `dest` and verify it against its digest
+    pub async fn download_blob<F>(&mut self, layer: &Descriptor, dest: &Path, mut on_progress: F) -> Result<(), String>
+    where
+        F: FnMut(u64),
+    {
+        let url = format!("{}/blobs/{}", self.image.base_url(), layer.digest);
+        let response = self.get(&url, None).await?;
+        let mut file = tokio::fs::File::create(dest).await.map_err(|e| e.to_string())?;
+        let mut stream = response.bytes_stream();
+        let mut downloaded = 0u64;
+        while let Some(chunk) = stream.next().await {
+            let chunk = chunk.map_err(|e| format!("{}: {}", layer.digest, e))?;
+            file.write_all(&chunk).await.map_err(|e| e.to_string())?;
+            downloaded += chunk.len() as u64;
+            on_progress(downloaded);
+        }
+        file.flush().await.map_err(|e| e.to_string())?;
+        drop(file);
+
+        if let Some(expected) = layer.digest.strip_prefix("sha256:") {
+            let path = dest.to_path_buf();
+            let actual = tokio::task::spawn_blocking(move || crate::utils::hash::sha256_file(&path, |_| {}))
+                .await
+                .map_err(|e| e.to_string())??;
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(format!("Digest mismatch for {}: got sha256:{}", layer.digest, actual));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,251 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use tracing::{info, warn};
+use crate::i18n;
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::models::WslCommandResult;
+
+const GB: u64 = 1024 * 1024 * 1024;
+// Safety margin reserved on every volume touched by an operation
+const SAFETY_MARGIN: u64 = GB;
+// Rough growth factor of a compressed rootfs archive once it is imported
+const ARCHIVE_EXPANSION_RATIO: u64 = 3;
+
+#[derive(Debug, Clone)]
+pub struct VolumeRequirement {
+    pub volume: String,
+    pub required: u64,
+    pub free: u64,
+}
+
+impl VolumeRequirement {
+    pub fn fits(&self) -> bool {
+        self.free >= self.required
+    }
+}
+
+// Per-volume disk space requirements of a heavy operation
+#[derive(Debug, Clone, Default)]
+pub struct SpacePlan {
+    pub volumes: Vec<VolumeRequirement>,
+    // Paths whose volume could not be resolved, the plan cannot vouch for them
+    pub unresolved: Vec<String>,
+    // The size of the data was not known, only the safety margin is reserved
+    pub size_unknown: bool,
+}
+
+impl SpacePlan {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Reserve `bytes` on the volume holding `path`; requirements on the same volume add up
+    pub fn require(&mut self, path: &str, bytes: u64) {
+        let volume = match volume_root(path) {
+            Some(v) => v,
+            None => {
+                warn!("Preflight: cannot resolve volume for '{}'", path);
+                self.unresolved.push(path.to_string());
+                return;
+            }
+        };
+
+        if let Some(existing) = self.volumes.iter_mut().find(|v| v.volume.eq_ignore_ascii_case(&volume)) {
+            existing.required += bytes;
+        } else {
+            let free = crate::utils::system::get_disk_free_space(&volume);
+            self.volumes.push(VolumeRequirement {
+                volume,
+                required: bytes + SAFETY_MARGIN,
+                free,
+            });
+        }
+    }
+
+    pub fn fits(&self) -> bool {
+        self.unresolved.is_empty() && self.volumes.iter().all(|v| v.fits())
+    }
+
+    // Human readable plan, one line per volume
+    pub fn summary(&self) -> String {
+        let mut lines = vec![i18n::t("dialog.space_plan_title")];
+        for v in &self.volumes {
+            let key = if v.fits() { "dialog.space_plan_line" } else { "dialog.space_plan_line_short" };
+            lines.push(i18n::tr(key, &[v.volume.clone(), format_bytes(v.required), format_bytes(v.free)]));
+        }
+        if self.size_unknown {
+            lines.push(i18n::t("dialog.space_plan_unknown_size"));
+        }
+        lines.join("\n")
+    }
+
+    // Error message describing the first volume that cannot fit or be resolved, if any
+    pub fn shortage(&self) -> Option<String> {
+        if let Some(path) = self.unresolved.first() {
+            return Some(i18n::tr("dialog.space_unresolved", &[path.clone()]));
+        }
+        self.volumes.iter().find(|v| !v.fits()).map(|v| {
+            i18n::tr("dialog.space_insufficient", &[v.volume.clone(), format_bytes(v.required), format_bytes(v.free)])
+        })
+    }
+
+    pub fn into_result(self) -> WslCommandResult<SpacePlan> {
+        match self.shortage() {
+            Some(err) => WslCommandResult::error(self.summary(), err),
+            None => WslCommandResult::success(self.summary(), Some(self)),
+        }
+    }
+}
+
+// Resolve the root of the volume a path lives on ("D:\" or "\\server\share\")
+pub fn volume_root(path: &str) -> Option<String> {
+    let clean = if let Some(rest) = path.strip_prefix(r"\\?\UNC\") {
+        format!(r"\\{}", rest)
+    } else if let Some(rest) = path.strip_prefix(r"\\?\") {
+        rest.to_string()
+    } else {
+        path.to_string()
+    };
+
+    if let Some(rest) = clean.strip_prefix(r"\\") {
+        let mut parts = rest.split(['\\', '/']).filter(|s| !s.is_empty());
+        let server = parts.next()?;
+        let share = parts.next()?;
+        return Some(format!(r"\\{}\{}\", server, share));
+    }
+
+    let bytes = clean.as_bytes();
+    if bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' {
+        return Some(format!("{}:\\", (bytes[0] as char).to_ascii_uppercase()));
+    }
+    None
+}
+
+// Parse the "12.34 GB" / "512.00 MB" strings produced by get_distro_information
+pub fn parse_size_string(size_str: &str) -> u64 {
+    if size_str.is_empty() || size_str.contains("Unknown") {
+        return 0;
+    }
+    let parts: Vec<&str> = size_str.split_whitespace().collect();
+    if parts.len() < 2 {
+        return 0;
+    }
+    let val = match parts[0].parse::<f64>() {
+        Ok(v) => v,
+        Err(_) => return 0,
+    };
+    let unit = parts[1].to_uppercase();
+    if unit.contains("GB") {
+        (val * 1024.0 * 1024.0 * 1024.0) as u64
+    } else if unit.contains("MB") {
+        (val * 1024.0 * 1024.0) as u64
+    } else {
+        (val * 1024.0) as u64
+    }
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    if bytes >= GB {
+        format!("{:.2} GB", bytes as f64 / GB as f64)
+    } else {
+        format!("{:.2} MB", bytes as f64 / (1024.0 * 1024.0))
+    }
+}
+
+// Size of an existing distro: (is_wsl2, footprint in bytes, VHDX path when WSL2)
+async fn distro_footprint(executor: &WslCommandExecutor, distro_name: &str) -> (bool, u64, String) {
+    let info_res = crate::wsl::ops::info::get_distro_information(executor, distro_name).await;
+    let info = match info_res.data {
+        Some(info) if info_res.success => info,
+        _ => return (false, 0, String::new()),
+    };
+
+    let is_wsl2 = info.wsl_version == "WSL2";
+    if is_wsl2 && !info.vhdx_path.is_empty() {
+        // The on-disk file size is what a copy actually has to write
+        let on_disk = std::fs::metadata(&info.vhdx_path).map(|m| m.len()).unwrap_or(0);
+        let bytes = if on_disk > 0 { on_disk } else { parse_size_string(&info.vhdx_size) };
+        return (true, bytes, info.vhdx_path);
+    }
+
+    let used = parse_size_string(&info.actual_used);
+    let bytes = if used > 0 { used } else { parse_size_string(&info.vhdx_size) };
+    (is_wsl2, bytes, String::new())
+}
+
+// Clone: WSL2 copies the VHDX to temp and imports it into the target,
+// WSL1 exports a tar to temp and extracts it into the target
+pub async fn plan_clone(executor: &WslCommandExecutor, source: &str, temp_dir: &str, target_path: &str) -> SpacePlan {
+    let (_, bytes, _) = distro_footprint(executor, source).await;
+    let mut plan = SpacePlan::new();
+    plan.require(temp_dir, bytes);
+    plan.require(target_path, bytes);
+    info!("Preflight clone '{}': {:?}", source, plan.volumes);
+    plan
+}
+
+// Move: WSL2 relocates the VHDX, which is a rename within the same volume,
+// WSL1 goes through a temporary tar export
+pub async fn plan_move(executor: &WslCommandExecutor, source: &str, temp_dir: &str, target_path: &str) -> SpacePlan {
+    let (is_wsl2, bytes, vhdx_path) = distro_footprint(executor, source).await;
+    let mut plan = SpacePlan::new();
+    if !is_wsl2 {
+        plan.require(temp_dir, bytes);
+    }
+    let same_volume = is_wsl2 && volume_root(&vhdx_path).is_some_and(|v| {
+        volume_root(target_path).is_some_and(|t| t.eq_ignore_ascii_case(&v))
+    });
+    plan.require(target_path, if same_volume { 0 } else { bytes });
+    info!("Preflight move '{}': {:?}", source, plan.volumes);
+    plan
+}
+
+// Import of a local rootfs archive or VHDX into `target_path`
+pub fn plan_import(file_path: &str, target_path: &str, is_vhd: bool) -> SpacePlan {
+    let file_size = std::fs::metadata(file_path).map(|m| m.len()).unwrap_or(0);
+    let lower = file_path.to_lowercase();
+    let is_compressed = lower.ends_with(".gz") || lower.ends_with(".xz") || lower.ends_with(".wsl")
+        || super::appx::PACKAGE_EXTENSIONS.iter().any(|ext| lower.ends_with(&format!(".{}", ext)));
+    let bytes = if !is_vhd && is_compressed {
+        file_size * ARCHIVE_EXPANSION_RATIO
+    } else {
+        file_size
+    };
+
+    let mut plan = SpacePlan::new();
+    plan.require(target_path, bytes);
+    plan
+}
+
+// Mirror install: the archive is downloaded to temp, then extracted into the target.
+// Without a known download size only the safety margin is checked.
+pub fn plan_mirror_install(download_size: Option<u64>, temp_dir: &str, target_path: &str) -> SpacePlan {
+    let mut plan = SpacePlan::new();
+    let size = download_size.unwrap_or(0);
+    plan.require(temp_dir, size);
+    plan.require(target_path, size * ARCHIVE_EXPANSION_RATIO);
+    plan.size_unknown = download_size.is_none();
+    plan
+}
+
+// OCI install: the layers and the flattened rootfs tar both sit in temp before the import
+pub fn plan_oci_install(layers_size: u64, temp_dir: &str, target_path: &str) -> SpacePlan {
+    let mut plan = SpacePlan::new();
+    plan.require(temp_dir, layers_size + layers_size * ARCHIVE_EXPANSION_RATIO);
+    plan.require(target_path, layers_size * ARCHIVE_EXPANSION_RATIO);
+    plan
+}
+
+// Compression exports a tar next to the VHDX before re-importing it
+pub fn plan_compress(location: &str, vhdx_bytes: u64) -> SpacePlan {
+    let vhdx_bytes = if vhdx_bytes > 0 {
+        vhdx_bytes
+    } else {
+        std::fs::metadata(location).map(|m| m.len()).unwrap_or(0)
+    };
+    let mut plan = SpacePlan::new();
+    // 1x VHDX size for the backup copy or exported tar plus an extra 1GB on top of the safety margin
+    plan.require(location, vhdx_bytes + GB);
+    plan
+}
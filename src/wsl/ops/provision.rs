@@ -0,0 +1,90 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::collections::HashMap;
+use tracing::{info, warn};
+use crate::config::{ProvisionStep, TemplateParameter};
+use crate::wsl::executor::WslCommandExecutor;
+use crate::wsl::models::WslCommandResult;
+
+// Parameter values end up inside shell scripts, so only allow a conservative character set
+pub fn is_safe_value(value: &str) -> bool {
+    value.chars().all(|c| c.is_ascii_alphanumeric() || " ._@+:/=-,".contains(c))
+}
+
+// Resolve the final parameter values, falling back to template defaults for empty input.
+// Returns the key of the first unsafe value as error.
+pub fn resolve_values(
+    parameters: &[TemplateParameter],
+    input: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, String> {
+    let mut values = HashMap::new();
+    for p in parameters {
+        let value = input.get(&p.key)
+            .map(|v| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+            .unwrap_or_else(|| p.default.clone());
+        if !is_safe_value(&value) {
+            return Err(p.key.clone());
+        }
+        values.insert(p.key.clone(), value);
+    }
+    Ok(values)
+}
+
+// Replace every `{{key}}` placeholder; unknown placeholders become empty
+pub fn render_script(script: &str, values: &HashMap<String, String>) -> String {
+    let mut out = String::with_capacity(script.len());
+    let mut rest = script;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        match rest[start + 2..].find("}}") {
+            Some(end) => {
+                let key = rest[start + 2..start + 2 + end].trim();
+                if let Some(v) = values.get(key) {
+                    out.push_str(v);
+                }
+                rest = &rest[start + 2 + end + 2..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+// Run a single provisioning step inside the distro, streaming its output
+pub async fn run_step<F>(
+    executor: &WslCommandExecutor,
+    distro_name: &str,
+    step: &ProvisionStep,
+    values: &HashMap<String, String>,
+    on_output: F,
+) -> WslCommandResult<String>
+where
+    F: FnMut(String) + Send + 'static,
+{
+    let script = render_script(&step.script, values);
+    let user = if step.user.is_empty() { "root" } else { step.user.as_str() };
+    info!("Provisioning '{}': running step '{}' as {}", distro_name, step.name, user);
+
+    let result = executor
+        .execute_command_streaming(&["-d", distro_name, "-u", user, "-e", "sh", "-c", &script], on_output)
+        .await;
+    if !result.success {
+        warn!("Provisioning step '{}' failed: {:?}", step.name, result.error);
+    }
+    result
+}
+
+// Make `user` the login user. wsl.conf is edited in place, like the settings dialog does,
+// so an existing [user] section is updated instead of skipped.
+pub async fn set_default_user(executor: &WslCommandExecutor, distro_name: &str, user: &str) -> Result<(), String> {
+    let mut conf = crate::wsl::ops::config::WslConf::default();
+    conf.user.default = Some(user.to_string());
+    let meta = crate::wsl::ops::config::check_wsl_version_support(executor).await;
+    crate::wsl::ops::config::save_wsl_conf(executor, distro_name, &conf, &meta).await
+}
@@ -0,0 +1,323 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use crate::config::{ConfigManager, DistroInstanceConfig, UsbAutoAttachDevice};
+use crate::i18n;
+use crate::network::models::PortProxyRule;
+use crate::wsl::dashboard::WslDashboard;
+
+const BIN_DIR_NAME: &str = ".recycle";
+const ENTRY_FILE_NAME: &str = "entry.toml";
+
+// Everything needed to bring a deleted distro back, stored as `entry.toml` in its bin folder
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecycleEntry {
+    pub name: String,
+    #[serde(rename = "deleted-time")]
+    pub deleted_time: String,
+    #[serde(rename = "original-location")]
+    pub original_location: String,
+    #[serde(rename = "image-file")]
+    pub image_file: String,
+    #[serde(rename = "is-vhdx")]
+    pub is_vhdx: bool,
+    #[serde(default)]
+    pub version: u32,
+    #[serde(default)]
+    pub size: u64,
+    #[serde(rename = "port-proxies", default)]
+    pub port_proxies: Vec<PortProxyRule>,
+    #[serde(rename = "usb-auto-attach", default)]
+    pub usb_auto_attach: Vec<UsbAutoAttachDevice>,
+    #[serde(default)]
+    pub instance: Option<DistroInstanceConfig>,
+    // WSL keeps the default user only in the registration, which unregister drops
+    #[serde(rename = "default-uid", default)]
+    pub default_uid: Option<u32>,
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+impl RecycleEntry {
+    // The bin folder name doubles as the entry id used by the UI
+    pub fn id(&self) -> String {
+        self.dir.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default()
+    }
+
+    fn image_path(&self) -> PathBuf {
+        self.dir.join(&self.image_file)
+    }
+}
+
+// Fixed under ~/.wsldashboard so changing the distro location does not orphan entries
+pub fn bin_root() -> PathBuf {
+    let home_dir = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home_dir.join(".wsldashboard").join("recycle")
+}
+
+// Older versions kept the bin next to the distros, entries left there are still listed
+fn legacy_bin_root(config_manager: &ConfigManager) -> Option<PathBuf> {
+    let location = PathBuf::from(&config_manager.get_settings().distro_location);
+    location.is_absolute().then(|| location.join(BIN_DIR_NAME))
+}
+
+fn write_entry(entry: &RecycleEntry) -> Result<(), String> {
+    let content = toml::to_string_pretty(entry).map_err(|e| e.to_string())?;
+    std::fs::write(entry.dir.join(ENTRY_FILE_NAME), content).map_err(|e| e.to_string())
+}
+
+fn read_entry(dir: &Path) -> Option<RecycleEntry> {
+    let content = std::fs::read_to_string(dir.join(ENTRY_FILE_NAME)).ok()?;
+    let mut entry: RecycleEntry = toml::from_str(&content).ok()?;
+    entry.dir = dir.to_path_buf();
+    Some(entry)
+}
+
+// Rename with a few retries, WSL may hold the disk for a moment after terminate
+async fn rename_with_retry(from: &Path, to: &Path) -> std::io::Result<()> {
+    let mut last_err = None;
+    for _ in 0..5 {
+        match std::fs::rename(from, to) {
+            Ok(_) => return Ok(()),
+            Err(e) => {
+                last_err = Some(e);
+                tokio::time::sleep(Duration::from_secs(1)).await;
+            }
+        }
+    }
+    Err(last_err.unwrap())
+}
+
+// Move a file, falling back to copy + delete when crossing volumes
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    std::fs::copy(from, to)?;
+    std::fs::remove_file(from)
+}
+
+// Snapshot a distro into the bin before it gets unregistered.
+// WSL2 disks are moved (or exported as VHDX across volumes), WSL1 distros are exported as tar.
+pub async fn move_to_bin(dashboard: &WslDashboard, config_manager: &ConfigManager, name: &str) -> Result<RecycleEntry, String> {
+    let reg = tokio::task::spawn_blocking(crate::utils::registry::get_wsl_distros_from_reg)
+        .await
+        .unwrap_or_default()
+        .into_iter()
+        .find(|d| d.name == name)
+        .ok_or_else(|| i18n::tr("recycle.not_registered", &[name.to_string()]))?;
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let dir = bin_root().join(format!("{}_{}", name, now));
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+
+    let network = config_manager.get_network_config();
+    let mut entry = RecycleEntry {
+        name: name.to_string(),
+        deleted_time: now.to_string(),
+        original_location: reg.base_path.clone(),
+        image_file: String::new(),
+        is_vhdx: reg.version == 2,
+        version: reg.version,
+        size: 0,
+        port_proxies: network.port_proxies.into_iter().filter(|r| r.distro_name == name).collect(),
+        usb_auto_attach: config_manager.get_usb_config().auto_attach_list.iter()
+            .filter(|d| d.distribution == name)
+            .cloned()
+            .collect(),
+        instance: Some(config_manager.get_instance_config(name)),
+        default_uid: reg.default_uid,
+        dir: dir.clone(),
+    };
+
+    let executor = dashboard.executor();
+    let _ = executor.execute_command(&["--terminate", name]).await;
+
+    let vhdx = if entry.is_vhdx { crate::wsl::ops::info::get_vhdx_path(&reg.base_path) } else { None };
+    let mut moved = false;
+    if let Some(vhdx) = vhdx.as_ref() {
+        let file_name = vhdx.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_else(|| "ext4.vhdx".to_string());
+        entry.original_location = vhdx.parent().map(|p| p.to_string_lossy().to_string()).unwrap_or(reg.base_path.clone());
+        entry.image_file = file_name.clone();
+        match rename_with_retry(vhdx, &dir.join(&file_name)).await {
+            Ok(_) => {
+                info!("Recycle: moved disk of '{}' into '{}'", name, dir.display());
+                moved = true;
+            }
+            Err(e) => warn!("Recycle: cannot move disk of '{}' ({}), exporting instead", name, e),
+        }
+    }
+
+    if !moved {
+        entry.image_file = if entry.is_vhdx { "ext4.vhdx".to_string() } else { "rootfs.tar".to_string() };
+        let target = entry.image_path().to_string_lossy().to_string();
+        let mut args = vec!["--export", name, target.as_str()];
+        if entry.is_vhdx {
+            args.push("--vhd");
+        }
+        let result = executor.execute_command(&args).await;
+        if !result.success {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(result.error.unwrap_or_else(|| i18n::t("recycle.export_failed")));
+        }
+    }
+
+    entry.size = std::fs::metadata(entry.image_path()).map(|m| m.len()).unwrap_or(0);
+    if let Err(e) = write_entry(&entry) {
+        undo_move(&entry);
+        return Err(e);
+    }
+    Ok(entry)
+}
+
+// Put a moved disk back when the unregister step failed, and drop the bin folder
+pub fn undo_move(entry: &RecycleEntry) {
+    let original = Path::new(&entry.original_location).join(&entry.image_file);
+    if entry.is_vhdx && !original.exists() && entry.image_path().exists() {
+        if let Err(e) = move_file(&entry.image_path(), &original) {
+            warn!("Recycle: failed to move disk of '{}' back: {}", entry.name, e);
+            return;
+        }
+    }
+    let _ = std::fs::remove_dir_all(&entry.dir);
+}
+
+// All entries in the bin, newest first
+pub fn list_entries(config_manager: &ConfigManager) -> Vec<RecycleEntry> {
+    let mut roots = vec![bin_root()];
+    roots.extend(legacy_bin_root(config_manager));
+    let mut entries: Vec<RecycleEntry> = roots.iter()
+        .filter_map(|root| std::fs::read_dir(root).ok())
+        .flat_map(|rd| rd.flatten().filter_map(|e| read_entry(&e.path())).collect::<Vec<_>>())
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.deleted_time.parse::<i64>().unwrap_or(0)));
+    entries
+}
+
+pub fn find_entry(config_manager: &ConfigManager, id: &str) -> Option<RecycleEntry> {
+    list_entries(config_manager).into_iter().find(|e| e.id() == id)
+}
+
+pub fn purge_entry(entry: &RecycleEntry) -> Result<(), String> {
+    info!("Recycle: permanently removing '{}' ({})", entry.name, entry.id());
+    std::fs::remove_dir_all(&entry.dir).map_err(|e| e.to_string())
+}
+
+pub struct PurgeResult {
+    pub purged: Vec<String>,
+    // The newest entry alone is larger than the cap, it is kept anyway
+    pub over_cap: bool,
+}
+
+// Drop entries past the retention period, then the oldest ones until the bin fits the size cap.
+// The newest entry is never evicted for size.
+pub fn purge_expired(config_manager: &ConfigManager) -> PurgeResult {
+    let settings = config_manager.get_settings();
+    let retention_ms = settings.recycle_days as i64 * 86_400_000;
+    let cap = settings.recycle_max_gb as u64 * 1024 * 1024 * 1024;
+    let now = chrono::Utc::now().timestamp_millis();
+
+    let mut purged = Vec::new();
+    let mut kept = Vec::new();
+    for entry in list_entries(config_manager) {
+        let age = now - entry.deleted_time.parse::<i64>().unwrap_or(0);
+        if age > retention_ms && purge_entry(&entry).is_ok() {
+            purged.push(entry.id());
+        } else {
+            kept.push(entry);
+        }
+    }
+
+    let mut total: u64 = kept.iter().map(|e| e.size).sum();
+    if cap > 0 {
+        // Newest first, so walk from the back and stop before the newest one
+        while total > cap && kept.len() > 1 {
+            let entry = kept.pop().unwrap();
+            if purge_entry(&entry).is_ok() {
+                purged.push(entry.id());
+                total -= entry.size;
+            }
+        }
+    }
+    PurgeResult { purged, over_cap: cap > 0 && total > cap }
+}
+
+// Register the distro again from its bin entry and restore its default user, instance config
+// and port rules. USB rules live in the main config and are restored by the caller.
+pub async fn restore_entry(dashboard: &WslDashboard, config_manager: &ConfigManager, entry: &RecycleEntry) -> Result<(), String> {
+    if dashboard.get_distros().await.iter().any(|d| d.name == entry.name) {
+        return Err(i18n::tr("recycle.name_exists", &[entry.name.clone()]));
+    }
+
+    let _heavy_lock = dashboard.heavy_op_lock().lock().await;
+    let executor = dashboard.executor();
+    let image = entry.image_path();
+    if !image.exists() {
+        return Err(i18n::tr("recycle.image_missing", &[image.to_string_lossy().to_string()]));
+    }
+
+    // Store packages may be gone by now, fall back to the default install folder
+    let mut location = PathBuf::from(&entry.original_location);
+    if !location.is_dir() || location.join(&entry.image_file).exists() {
+        location = PathBuf::from(&config_manager.get_settings().distro_location).join(&entry.name);
+    }
+    std::fs::create_dir_all(&location).map_err(|e| e.to_string())?;
+    let location_str = location.to_string_lossy().to_string();
+
+    let result = if entry.is_vhdx {
+        let target = location.join(&entry.image_file);
+        move_file(&image, &target).map_err(|e| e.to_string())?;
+        let target_str = target.to_string_lossy().to_string();
+        let res = executor.execute_command(&["--import-in-place", &entry.name, &target_str]).await;
+        if !res.success {
+            let _ = move_file(&target, &image);
+        }
+        res
+    } else {
+        let image_str = image.to_string_lossy().to_string();
+        let version = entry.version.max(1).to_string();
+        executor.execute_command(&["--import", &entry.name, &location_str, &image_str, "--version", &version]).await
+    };
+
+    if !result.success {
+        return Err(result.error.unwrap_or_else(|| i18n::t("install.import_failed")));
+    }
+    info!("Recycle: restored '{}' into '{}'", entry.name, location_str);
+
+    if let Some(uid) = entry.default_uid {
+        if let Err(e) = crate::utils::registry::set_distro_default_uid(&entry.name, uid) {
+            warn!("Recycle: failed to restore the default user of '{}': {}", entry.name, e);
+        }
+    }
+
+    if let Some(instance) = entry.instance.clone() {
+        let _ = config_manager.update_instance_config(&entry.name, instance);
+    }
+    if !entry.port_proxies.is_empty() {
+        let mut network = config_manager.get_network_config();
+        for rule in &entry.port_proxies {
+            if !network.port_proxies.iter().any(|r| r.id == rule.id) {
+                network.port_proxies.push(rule.clone());
+            }
+        }
+        let _ = config_manager.update_network_config(network);
+
+        // While the distro is stopped this only clears stale netsh entries,
+        // the startup task adds the rules again on its next run
+        let name = entry.name.clone();
+        let rules = entry.port_proxies.clone();
+        let synced = tokio::task::spawn_blocking(move || crate::network::port_proxy::sync_port_proxies(&name, &rules)).await;
+        if let Ok(Err(e)) = synced {
+            warn!("Recycle: failed to sync the port rules of '{}': {}", entry.name, e);
+        }
+    }
+
+    let _ = std::fs::remove_dir_all(&entry.dir);
+    drop(_heavy_lock);
+    let _ = dashboard.refresh_distros().await;
+    Ok(())
+}
@@ -0,0 +1,145 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::path::Path;
+use tracing::{info, warn};
+use crate::config::ConfigManager;
+use crate::i18n;
+use crate::wsl::dashboard::WslDashboard;
+use crate::wsl::models::WslStatus;
+
+// Lifetime choices offered in the UI; the index is what the UI passes back
+const EXPIRY_HOURS: [u64; 5] = [0, 1, 8, 24, 168];
+
+// Labels for the expiry combo box, index-aligned with EXPIRY_HOURS (0 = delete on stop)
+pub fn expiry_labels() -> Vec<String> {
+    EXPIRY_HOURS.iter().map(|&h| match h {
+        0 => i18n::t("sandbox.expire_on_stop"),
+        h if h % 24 == 0 && h >= 48 => i18n::tr("sandbox.expire_days", &[(h / 24).to_string()]),
+        h => i18n::tr("sandbox.expire_hours", &[h.to_string()]),
+    }).collect()
+}
+
+// Mark a freshly created distro as sandbox with the chosen lifetime.
+// `created_dir` is the install folder when the dashboard created it for this distro.
+pub fn mark_sandbox(config_manager: &ConfigManager, distro_name: &str, expiry_idx: i32, created_dir: Option<&str>) -> Result<(), String> {
+    let hours = EXPIRY_HOURS.get(expiry_idx.max(0) as usize).copied().unwrap_or(0);
+    let mut config = config_manager.get_instance_config(distro_name);
+    config.sandbox = true;
+    config.sandbox_started = false;
+    config.sandbox_dir = created_dir.unwrap_or_default().to_string();
+    if hours == 0 {
+        config.delete_on_stop = true;
+        config.expire_time = String::new();
+    } else {
+        config.delete_on_stop = false;
+        let expire = chrono::Utc::now().timestamp_millis() + (hours * 3_600_000) as i64;
+        config.expire_time = expire.to_string();
+    }
+    info!("Marking '{}' as sandbox (delete-on-stop: {}, expire-time: '{}')", distro_name, config.delete_on_stop, config.expire_time);
+    config_manager.update_instance_config(distro_name, config).map_err(|e| e.to_string())
+}
+
+// Record that a delete-on-stop sandbox has been launched, so it is removed once it stops
+// again even when it ran for less than a cleanup tick
+pub fn mark_sandbox_started(config_manager: &ConfigManager, distro_name: &str) {
+    let mut config = config_manager.get_instance_config(distro_name);
+    if !config.sandbox || !config.delete_on_stop || config.sandbox_started {
+        return;
+    }
+    config.sandbox_started = true;
+    if let Err(e) = config_manager.update_instance_config(distro_name, config) {
+        warn!("Failed to record the start of sandbox '{}': {}", distro_name, e);
+    }
+}
+
+// The folder is removed as a whole only when the dashboard created it for the sandbox.
+// A folder the user picked may hold other distros, so only the sandbox's own disk goes
+// and the folder follows when nothing else is left in it.
+fn remove_install_files(location: &str, created_dir: &str) {
+    let p = Path::new(location);
+    if !p.is_dir() {
+        return;
+    }
+    if !created_dir.is_empty() && location.to_lowercase() == created_dir.to_lowercase() {
+        match std::fs::remove_dir_all(p) {
+            Ok(_) => info!("Sandbox folder '{}' removed", location),
+            Err(e) => warn!("Failed to remove sandbox folder '{}': {}", location, e),
+        }
+        return;
+    }
+    let vhdx = p.join("ext4.vhdx");
+    if vhdx.is_file() {
+        if let Err(e) = std::fs::remove_file(&vhdx) {
+            warn!("Failed to remove sandbox disk '{}': {}", vhdx.display(), e);
+            return;
+        }
+    }
+    // Fails while the folder still has other content
+    if std::fs::remove_dir(p).is_ok() {
+        info!("Empty sandbox folder '{}' removed", location);
+    }
+}
+
+// The folder a new sandbox will be installed to, when it does not exist yet
+pub fn new_install_dir(path: &str) -> Option<String> {
+    (!path.is_empty() && !Path::new(path).exists()).then(|| path.to_string())
+}
+
+// Remove expired sandboxes and those stopped after first use.
+// Returns the names of the removed distros.
+pub async fn cleanup_sandboxes(dashboard: &WslDashboard, config_manager: &ConfigManager) -> Vec<String> {
+    let sandboxes = config_manager.get_sandbox_instances();
+    if sandboxes.is_empty() {
+        return Vec::new();
+    }
+
+    let distros = dashboard.get_distros().await;
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut removed = Vec::new();
+
+    for (name, mut config) in sandboxes {
+        let Some(distro) = distros.iter().find(|d| d.name == name) else {
+            // Unregistered by other means, drop the stale entry
+            let _ = config_manager.remove_instance_config(&name);
+            continue;
+        };
+        // Protection wins over the sandbox lifetime
+        if config.protected {
+            continue;
+        }
+        let running = distro.status == WslStatus::Running;
+
+        // Started outside the dashboard, e.g. from a terminal
+        if running && config.delete_on_stop && !config.sandbox_started {
+            config.sandbox_started = true;
+            let _ = config_manager.update_instance_config(&name, config.clone());
+        }
+
+        let expire_time = config.expire_time.parse::<i64>().unwrap_or(0);
+        let expired = expire_time > 0 && now >= expire_time;
+        let stopped_after_use = config.delete_on_stop && config.sandbox_started && !running;
+        if !expired && !stopped_after_use {
+            continue;
+        }
+
+        // Never interfere with a running operation; retry on the next tick
+        if dashboard.get_active_op(&name).await.is_some() || dashboard.heavy_op_lock().try_lock().is_err() {
+            continue;
+        }
+
+        info!("Sandbox '{}' is due for removal (expired: {}, stopped after use: {})", name, expired, stopped_after_use);
+        let location = dashboard.executor().get_distro_install_location(&name).await.data;
+        let result = dashboard.delete_distro(config_manager, &name).await;
+        if result.success {
+            if let Some(location) = location {
+                let created_dir = config.sandbox_dir.clone();
+                let _ = tokio::task::spawn_blocking(move || remove_install_files(&location, &created_dir)).await;
+            }
+            removed.push(name);
+        } else {
+            warn!("Failed to remove sandbox '{}': {:?}", name, result.error);
+        }
+    }
+    removed
+}
@@ -0,0 +1,313 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::os::windows::io::AsRawHandle;
+use std::path::{Path, PathBuf};
+use std::time::{Instant, UNIX_EPOCH};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use windows::Win32::Foundation::{ERROR_MORE_DATA, HANDLE};
+use windows::Win32::System::IO::DeviceIoControl;
+use windows::Win32::System::Ioctl::{FILE_ALLOCATED_RANGE_BUFFER, FSCTL_QUERY_ALLOCATED_RANGES, FSCTL_SET_SPARSE};
+
+const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+// Flush and persist a checkpoint roughly every 256MB of processed source data
+const CHECKPOINT_INTERVAL: u64 = 256 * 1024 * 1024;
+// Minimum interval between two progress callbacks
+const PROGRESS_INTERVAL_MS: u128 = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CopyPhase {
+    Copying,
+    Verifying,
+}
+
+#[derive(Debug, Clone)]
+pub struct CopyProgress {
+    pub phase: CopyPhase,
+    pub done: u64,
+    pub total: u64,
+    pub bytes_per_sec: u64,
+}
+
+impl CopyProgress {
+    // "1024 MB / 4096 MB (85.3 MB/s)"
+    pub fn display(&self) -> String {
+        format!(
+            "{} MB / {} MB ({:.1} MB/s)",
+            self.done / 1024 / 1024,
+            self.total / 1024 / 1024,
+            self.bytes_per_sec as f64 / (1024.0 * 1024.0)
+        )
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CopyOptions {
+    // Hash source and target after copying and fail on mismatch
+    pub verify: bool,
+    // Keep the partial target and its checkpoint on failure so the next attempt can resume
+    pub resume: bool,
+}
+
+impl Default for CopyOptions {
+    fn default() -> Self {
+        Self { verify: true, resume: true }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CopyOutcome {
+    pub total: u64,
+    pub written: u64,
+    pub resumed_from: u64,
+    pub sha256: Option<String>,
+}
+
+// Persisted next to the target as "<target>.copystate"
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct CopyCheckpoint {
+    source: String,
+    source_size: u64,
+    source_modified: u64,
+    offset: u64,
+}
+
+pub fn checkpoint_path(target: &Path) -> PathBuf {
+    let mut name = target.as_os_str().to_os_string();
+    name.push(".copystate");
+    PathBuf::from(name)
+}
+
+// Remove a partial target together with its checkpoint
+pub fn discard_partial(target: &Path) {
+    let _ = fs::remove_file(target);
+    let _ = fs::remove_file(checkpoint_path(target));
+}
+
+fn load_checkpoint(target: &Path) -> Option<CopyCheckpoint> {
+    let content = fs::read_to_string(checkpoint_path(target)).ok()?;
+    serde_json::from_str(&content).ok()
+}
+
+fn save_checkpoint(target: &Path, checkpoint: &CopyCheckpoint) -> Result<(), String> {
+    let content = serde_json::to_string(checkpoint).map_err(|e| e.to_string())?;
+    fs::write(checkpoint_path(target), content).map_err(|e| format!("Write checkpoint failed: {}", e))
+}
+
+fn handle_of(file: &File) -> HANDLE {
+    HANDLE(file.as_raw_handle())
+}
+
+// Mark the target as sparse so skipped regions do not consume disk space
+fn set_sparse(file: &File) -> bool {
+    let mut returned = 0u32;
+    unsafe {
+        DeviceIoControl(handle_of(file), FSCTL_SET_SPARSE, None, 0, None, 0, Some(&mut returned), None).is_ok()
+    }
+}
+
+// Allocated (offset, length) ranges of the source; falls back to the whole file
+fn allocated_ranges(file: &File, total: u64) -> Vec<(u64, u64)> {
+    let entry_size = std::mem::size_of::<FILE_ALLOCATED_RANGE_BUFFER>();
+    let mut query = FILE_ALLOCATED_RANGE_BUFFER { FileOffset: 0, Length: total as i64 };
+    let mut out = vec![FILE_ALLOCATED_RANGE_BUFFER::default(); 1024];
+    let mut ranges = Vec::new();
+
+    loop {
+        let mut returned = 0u32;
+        let res = unsafe {
+            DeviceIoControl(
+                handle_of(file),
+                FSCTL_QUERY_ALLOCATED_RANGES,
+                Some(&query as *const _ as *const std::ffi::c_void),
+                entry_size as u32,
+                Some(out.as_mut_ptr() as *mut std::ffi::c_void),
+                (out.len() * entry_size) as u32,
+                Some(&mut returned),
+                None,
+            )
+        };
+        let count = returned as usize / entry_size;
+        for r in &out[..count] {
+            ranges.push((r.FileOffset as u64, r.Length as u64));
+        }
+
+        match res {
+            Ok(()) => break,
+            Err(e) if e.code() == ERROR_MORE_DATA.to_hresult() && count > 0 => {
+                let last = out[count - 1];
+                let next = (last.FileOffset + last.Length) as u64;
+                query.FileOffset = next as i64;
+                query.Length = total.saturating_sub(next) as i64;
+            }
+            Err(e) => {
+                warn!("FSCTL_QUERY_ALLOCATED_RANGES failed ({}), copying the whole file", e);
+                return vec![(0, total)];
+            }
+        }
+    }
+
+    if ranges.is_empty() && total > 0 {
+        // Fully allocated files on some filesystems report nothing useful
+        return vec![(0, total)];
+    }
+    ranges
+}
+
+fn modified_secs(meta: &fs::Metadata) -> u64 {
+    meta.modified().ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn throughput(bytes: u64, started: Instant) -> u64 {
+    let secs = started.elapsed().as_secs_f64();
+    if secs > 0.0 { (bytes as f64 / secs) as u64 } else { 0 }
+}
+
+// Sparse-aware, resumable and verified copy of a (stopped) VHDX file.
+// Blocking: run it inside spawn_blocking.
+pub fn copy_vhdx<F>(source: &Path, target: &Path, options: &CopyOptions, mut on_progress: F) -> Result<CopyOutcome, String>
+where
+    F: FnMut(&CopyProgress),
+{
+    let result = copy_inner(source, target, options, &mut on_progress);
+    if result.is_err() && !options.resume {
+        discard_partial(target);
+    }
+    result
+}
+
+fn copy_inner<F>(source: &Path, target: &Path, options: &CopyOptions, on_progress: &mut F) -> Result<CopyOutcome, String>
+where
+    F: FnMut(&CopyProgress),
+{
+    let mut src = File::open(source).map_err(|e| format!("Open source failed: {}", e))?;
+    let src_meta = src.metadata().map_err(|e| e.to_string())?;
+    let total = src_meta.len();
+
+    let fresh = CopyCheckpoint {
+        source: source.to_string_lossy().to_string(),
+        source_size: total,
+        source_modified: modified_secs(&src_meta),
+        offset: 0,
+    };
+
+    // Resume only if the checkpoint belongs to the same, unchanged source
+    let resume_from = match load_checkpoint(target) {
+        Some(cp) if options.resume
+            && cp.source == fresh.source
+            && cp.source_size == fresh.source_size
+            && cp.source_modified == fresh.source_modified
+            && fs::metadata(target).map(|m| m.len() == total).unwrap_or(false) => cp.offset.min(total),
+        _ => 0,
+    };
+
+    let mut dst = if resume_from > 0 {
+        info!("VHDX copy: resuming '{}' at {} MB", target.display(), resume_from / 1024 / 1024);
+        OpenOptions::new().write(true).open(target).map_err(|e| format!("Open target failed: {}", e))?
+    } else {
+        let _ = fs::remove_file(checkpoint_path(target));
+        let f = File::create(target).map_err(|e| format!("Create target failed: {}", e))?;
+        if !set_sparse(&f) {
+            warn!("VHDX copy: target '{}' could not be marked sparse", target.display());
+        }
+        f.set_len(total).map_err(|e| format!("Allocate target failed: {}", e))?;
+        f
+    };
+
+    let ranges = allocated_ranges(&src, total);
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut written = 0u64;
+    let mut processed = 0u64;
+    let mut last_checkpoint = resume_from;
+    let mut last_report = Instant::now();
+    let started = Instant::now();
+    let mut checkpoint = CopyCheckpoint { offset: resume_from, ..fresh.clone() };
+    save_checkpoint(target, &checkpoint)?;
+
+    for (start, len) in ranges {
+        let end = start + len;
+        if end <= resume_from {
+            continue;
+        }
+        let mut pos = start.max(resume_from);
+        src.seek(SeekFrom::Start(pos)).map_err(|e| e.to_string())?;
+
+        while pos < end {
+            let n = ((end - pos) as usize).min(CHUNK_SIZE);
+            src.read_exact(&mut buffer[..n]).map_err(|e| format!("Read source failed: {}", e))?;
+
+            // Zero blocks stay holes in the sparse target
+            if buffer[..n].iter().any(|&b| b != 0) {
+                dst.seek(SeekFrom::Start(pos)).map_err(|e| e.to_string())?;
+                dst.write_all(&buffer[..n]).map_err(|e| format!("Write target failed: {}", e))?;
+                written += n as u64;
+            }
+            pos += n as u64;
+            processed += n as u64;
+
+            if pos - last_checkpoint >= CHECKPOINT_INTERVAL {
+                dst.sync_data().map_err(|e| e.to_string())?;
+                checkpoint.offset = pos;
+                save_checkpoint(target, &checkpoint)?;
+                last_checkpoint = pos;
+            }
+
+            if last_report.elapsed().as_millis() >= PROGRESS_INTERVAL_MS {
+                last_report = Instant::now();
+                on_progress(&CopyProgress {
+                    phase: CopyPhase::Copying,
+                    done: pos,
+                    total,
+                    bytes_per_sec: throughput(processed, started),
+                });
+            }
+        }
+    }
+
+    dst.sync_all().map_err(|e| e.to_string())?;
+    drop(dst);
+    on_progress(&CopyProgress {
+        phase: CopyPhase::Copying,
+        done: total,
+        total,
+        bytes_per_sec: throughput(processed, started),
+    });
+    info!(
+        "VHDX copy: '{}' -> '{}' done ({} MB written, {} MB resumed)",
+        source.display(), target.display(), written / 1024 / 1024, resume_from / 1024 / 1024
+    );
+
+    let mut sha256 = None;
+    if options.verify {
+        let verify_started = Instant::now();
+        let mut report = |base: u64, done: u64| {
+            if last_report.elapsed().as_millis() >= PROGRESS_INTERVAL_MS {
+                last_report = Instant::now();
+                on_progress(&CopyProgress {
+                    phase: CopyPhase::Verifying,
+                    done: base + done,
+                    total: total * 2,
+                    bytes_per_sec: throughput(base + done, verify_started),
+                });
+            }
+        };
+        let src_hash = crate::utils::hash::sha256_file(source, |d| report(0, d))?;
+        let dst_hash = crate::utils::hash::sha256_file(target, |d| report(total, d))?;
+        if src_hash != dst_hash {
+            // A corrupt copy must never be resumed
+            discard_partial(target);
+            return Err(format!("Verification failed: source {} != target {}", src_hash, dst_hash));
+        }
+        info!("VHDX copy verified: sha256 {}", src_hash);
+        sha256 = Some(src_hash);
+    }
+
+    let _ = fs::remove_file(checkpoint_path(target));
+    Ok(CopyOutcome { total, written, resumed_from: resume_from, sha256 })
+}
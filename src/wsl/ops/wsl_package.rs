@@ -0,0 +1,75 @@
+// SPDX-FileCopyrightText: Copyright (c) 2026 owu <wqh@live.com>
+// SPDX-License-Identifier: GPL-3.0-only
+
+// .wsl distribution packages are rootfs tarballs that may describe themselves in
+// /etc/wsl-distribution.conf. WSL 2.4.4+ installs them with `wsl --install
+// --from-file`, which runs the package's OOBE on first launch. Older versions only
+// have `wsl --import`, after which the default UID is applied here. Reading the
+// metadata blocks, call it from spawn_blocking.
+
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use ini::Ini;
+
+const DISTRIBUTION_CONF: &str = "etc/wsl-distribution.conf";
+// Packages put /etc near the start, give up on the conf after this much of the tarball
+const MAX_SCAN_BYTES: u64 = 256 * 1024 * 1024;
+// First WSL release with `wsl --install --from-file`
+const INSTALL_FROM_FILE_MIN: [u64; 3] = [2, 4, 4];
+
+pub fn is_wsl_package(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("wsl"))
+}
+
+fn read_distribution_conf(path: &Path) -> Option<String> {
+    let mut magic = [0u8; 2];
+    File::open(path).ok()?.read_exact(&mut magic).ok()?;
+    let file = BufReader::new(File::open(path).ok()?);
+    // xz packages have no decoder here, they simply get no suggested name
+    let reader: Box<dyn Read> = match magic {
+        [0x1F, 0x8B] => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        [0xFD, 0x37] => return None,
+        _ => Box::new(file),
+    };
+
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries().ok()? {
+        let mut entry = entry.ok()?;
+        if entry.raw_header_position() > MAX_SCAN_BYTES {
+            return None;
+        }
+        let entry_path = entry.path().ok()?.to_string_lossy().replace('\\', "/");
+        if entry_path.trim_start_matches("./").trim_start_matches('/') == DISTRIBUTION_CONF {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).ok()?;
+            return Some(content);
+        }
+    }
+    None
+}
+
+fn oobe_value(path: &Path, key: &str) -> Option<String> {
+    let content = read_distribution_conf(path)?;
+    let conf = Ini::load_from_str(&content).ok()?;
+    conf.get_from(Some("oobe"), key)
+        .map(|value| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+// `[oobe] defaultName` of the package, e.g. "Ubuntu-24.04"
+pub fn default_name(path: &Path) -> Option<String> {
+    oobe_value(path, "defaultName")
+}
+
+// `[oobe] defaultUid`, the user WSL logs in as once the OOBE created it
+pub fn default_uid(path: &Path) -> Option<u32> {
+    oobe_value(path, "defaultUid")?.parse().ok()
+}
+
+// `wsl --version` number, e.g. "2.4.4.0"
+pub fn supports_install_from_file(wsl_version: &str) -> bool {
+    let parts: Vec<u64> = wsl_version.split('.').map(|p| p.parse().unwrap_or(0)).collect();
+    let version = [0, 1, 2].map(|i| parts.get(i).copied().unwrap_or(0));
+    version >= INSTALL_FROM_FILE_MIN
+}